@@ -0,0 +1,106 @@
+//  ENGINES.rs
+//    by Lut99
+//
+//  Description:
+//!   Benchmarks the inference engines of the supported policy languages against each other.
+//!
+//!   Since the example messages are only available in Slick, the engines are compared on
+//!   synthetic programs that are the same logically; i.e., a chain where every message derives
+//!   the next fact from the previous one. The section 6 examples are then benchmarked for Slick
+//!   alone.
+//
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use justact_prototype::policy::{PolicyEngine, datalog, slick};
+
+
+/***** CONSTANTS *****/
+/// The message counts to benchmark with.
+const MESSAGE_COUNTS: [usize; 4] = [1, 10, 100, 1000];
+
+/// The section 6 example messages.
+const SECTION6: [&str; 16] = [
+    include_str!("../examples/section6/slick/consortium_1.slick"),
+    include_str!("../examples/section6/slick/st-antonius_1.slick"),
+    include_str!("../examples/section6/slick/surf_1.slick"),
+    include_str!("../examples/section6/slick/amy_1.slick"),
+    include_str!("../examples/section6/slick/st-antonius_2.slick"),
+    include_str!("../examples/section6/slick/surf_2.slick"),
+    include_str!("../examples/section6/slick/amy_2.slick"),
+    include_str!("../examples/section6/slick/st-antonius_3.slick"),
+    include_str!("../examples/section6/slick/bob_1.slick"),
+    include_str!("../examples/section6/slick/st-antonius_4.slick"),
+    include_str!("../examples/section6/slick/consortium_2.slick"),
+    include_str!("../examples/section6/slick/surf_3.slick"),
+    include_str!("../examples/section6/slick/st-antonius_5.slick"),
+    include_str!("../examples/section6/slick/dan_1.slick"),
+    include_str!("../examples/section6/slick/st-antonius_6.slick"),
+    include_str!("../examples/section6/slick/st-antonius_7.slick"),
+];
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Generates a chain of `n` messages in Slick.
+///
+/// # Arguments
+/// - `n`: The number of messages (i.e., rules) in the chain.
+///
+/// # Returns
+/// A program of the shape `f0. f1 if f0. ...`.
+fn slick_chain(n: usize) -> String {
+    let mut res = String::from("f0.\n");
+    for i in 1..n {
+        res.push_str(&format!("f{i} if f{}.\n", i - 1));
+    }
+    res
+}
+
+/// Generates a chain of `n` messages in Datalog.
+///
+/// # Arguments
+/// - `n`: The number of messages (i.e., rules) in the chain.
+///
+/// # Returns
+/// A program of the shape `f0. f1 :- f0. ...`.
+fn datalog_chain(n: usize) -> String {
+    let mut res = String::from("f0.\n");
+    for i in 1..n {
+        res.push_str(&format!("f{i} :- f{}.\n", i - 1));
+    }
+    res
+}
+
+
+
+
+
+/***** BENCHMARKS *****/
+/// Compares the engines on equivalent chains of increasing length.
+fn bench_chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chain");
+    for n in MESSAGE_COUNTS {
+        let program: String = slick_chain(n);
+        group.bench_with_input(BenchmarkId::new("slick", n), &program, |b, program| b.iter(|| slick::Engine.denote(program).unwrap()));
+        let program: String = datalog_chain(n);
+        group.bench_with_input(BenchmarkId::new("datalog", n), &program, |b, program| b.iter(|| datalog::Engine.denote(program).unwrap()));
+    }
+    group.finish();
+}
+
+/// Benchmarks Slick on increasingly large prefixes of the section 6 example messages.
+fn bench_section6(c: &mut Criterion) {
+    let mut group = c.benchmark_group("section6");
+    for n in [1, 4, 8, SECTION6.len()] {
+        let program: String = SECTION6[..n].concat();
+        group.bench_with_input(BenchmarkId::new("slick", n), &program, |b, program| b.iter(|| slick::Engine.denote(program).unwrap()));
+    }
+    group.finish();
+}
+
+
+
+criterion_group!(benches, bench_chain, bench_section6);
+criterion_main!(benches);
@@ -0,0 +1,126 @@
+//  INTERN.rs
+//    by Lut99
+//
+//  Description:
+//!   Benchmarks parsing and extracting Slick policy with and without pre-interning the names
+//!   involved.
+//!
+//!   Next to timings, this counts the number of allocations done by a single extraction through
+//!   a counting global allocator, and reports those before the timings.
+//
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use justact_prototype::policy::slick::{self, Extractor, Program};
+use justact_prototype::wire::Message;
+
+
+/***** CONSTANTS *****/
+/// The number of facts in the benchmarked message.
+const N_FACTS: usize = 1000;
+
+/// The author of the benchmarked message.
+const AUTHOR: &str = "st-antonius";
+
+
+
+
+
+/***** GLOBALS *****/
+/// Counts the allocations done so far.
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocator that counts allocations before deferring to the system one.
+struct CountingAllocator;
+unsafe impl GlobalAlloc for CountingAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) { System.dealloc(ptr, layout) }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Generates the payload of a message with many facts, all of which will get a `says`-head.
+///
+/// # Returns
+/// Unparsed Slick by [`AUTHOR`] of the shape `(AUTHOR owns x0). (AUTHOR owns x1). ...`.
+fn heavy_payload() -> String {
+    let mut payload = String::new();
+    for i in 0..N_FACTS {
+        payload.push_str(&format!("({AUTHOR} owns x{i}).\n"));
+    }
+    payload
+}
+
+/// Parses a payload as a message and extracts it.
+///
+/// The payload is only parsed here, such that the names in it aren't known to Slick before the
+/// first call.
+///
+/// # Arguments
+/// - `payload`: The unparsed payload of the message.
+///
+/// # Returns
+/// The extracted [`Policy`](slick::Policy).
+fn parse_and_extract(payload: &str) -> slick::Policy {
+    let payload: Program = slick::parse_payload(AUTHOR, "heavy", payload.as_bytes()).unwrap();
+    let msg = Message { human_id: "heavy".into(), author_id: AUTHOR.into(), payload, meta: BTreeMap::new() };
+    Extractor::new().extract_with_actor(AUTHOR, &msg).unwrap()
+}
+
+/// Counts the allocations done by parsing and extracting a message once.
+///
+/// # Arguments
+/// - `payload`: The unparsed payload of the message.
+///
+/// # Returns
+/// The number of allocations done.
+fn count_allocs(payload: &str) -> usize {
+    let start: usize = ALLOCS.load(Ordering::Relaxed);
+    let pol = parse_and_extract(payload);
+    let end: usize = ALLOCS.load(Ordering::Relaxed);
+    drop(pol);
+    end - start
+}
+
+
+
+
+
+/***** BENCHMARKS *****/
+/// Compares extraction before and after pre-interning.
+fn bench_intern(c: &mut Criterion) {
+    let payload: String = heavy_payload();
+
+    // Nothing parsed or interned yet
+    let cold: usize = count_allocs(&payload);
+    let mut group = c.benchmark_group("extract");
+    group.bench_function("cold", |b| b.iter(|| parse_and_extract(&payload)));
+
+    // Interned
+    slick::intern_all(&[AUTHOR, "says", "actor"]);
+    let warm: usize = count_allocs(&payload);
+    group.bench_function("interned", |b| b.iter(|| parse_and_extract(&payload)));
+    group.finish();
+
+    println!("Allocations per parse and extraction of {N_FACTS} facts: {cold} cold, {warm} interned ({} names cached)", slick::cache_size());
+}
+
+
+
+criterion_group!(benches, bench_intern);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+//  PROVENANCE.rs
+//    by Lut99
+//
+//  Description:
+//!   Benchmarks Slick policy extraction and validation with and without the
+//!   `author says ...`-reflection (see `Extractor::with_says()`).
+//
+
+use std::collections::BTreeMap;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use justact::policies::Policy as _;
+use justact_prototype::policy::slick::{Extractor, Program};
+use justact_prototype::wire::Message;
+
+
+/***** CONSTANTS *****/
+/// The number of rules in the benchmarked message.
+const N_RULES: usize = 1000;
+
+/// The author of the benchmarked message.
+const AUTHOR: &str = "amy";
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Generates a message with a long chain of rules.
+///
+/// # Returns
+/// A [`Message`] by [`AUTHOR`] of the shape `fact0. fact1 if fact0. ...`.
+fn chain_message() -> Message<Program> {
+    let mut payload = String::from("fact0.\n");
+    for i in 1..N_RULES {
+        payload.push_str(&format!("fact{i} if fact{}.\n", i - 1));
+    }
+    Message { human_id: "chain".into(), author_id: AUTHOR.into(), payload: ::slick::parse::program(&payload).unwrap().1, meta: BTreeMap::new() }
+}
+
+
+
+
+
+/***** BENCHMARKS *****/
+/// Compares extracting and validating a chain with and without `says`-reflection.
+fn bench_provenance(c: &mut Criterion) {
+    let msg: Message<Program> = chain_message();
+
+    let mut group = c.benchmark_group("provenance");
+    for says in [true, false] {
+        let extractor: Extractor = Extractor::new().with_says(says);
+        group.bench_with_input(BenchmarkId::new("says", says), &extractor, |b, extractor| {
+            b.iter(|| <Extractor as justact::Extractor<str, Program>>::extract(extractor, &msg).unwrap().is_valid())
+        });
+    }
+    group.finish();
+}
+
+
+
+criterion_group!(benches, bench_provenance);
+criterion_main!(benches);
@@ -13,19 +13,20 @@
 //
 
 use std::borrow::Cow;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Display;
-use std::ops::ControlFlow;
+use std::ops::{ControlFlow, Range};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crossterm::event::EventStream;
 use error_trace::toplevel;
 use futures::{FutureExt as _, StreamExt as _};
 use justact::collections::Recipient;
 use justact::collections::set::InfallibleSet;
-use justact_prototype::auditing::{Audit, Event, EventControl, EventData, Permission};
-use justact_prototype::policy::PolicySerialize;
-use justact_prototype::policy::slick::{GroundAtom, Program, Text as SlickText};
+use justact_prototype::auditing::{ActionOutcome, Event, EventControl, EventData, Permission, SlickAudit};
+use justact_prototype::policy::slick::{Effect, EffectKind, GroundAtom, PolicyStats, Program, effect_deletes, effect_reads, effect_writes};
 use justact_prototype::wire::Message;
 use log::{debug, error};
 use parking_lot::{Mutex, MutexGuard};
@@ -36,11 +37,16 @@ use ratatui::style::{Color, Style, Stylize as _};
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Block, List, ListState, Paragraph};
 use thiserror::Error;
+use tokio::fs::File;
 use tokio::io::AsyncRead;
 use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::event_iter::EventIter;
+use crate::template::{RowField, RowPiece, RowTemplate};
+use crate::time_format::TimeFormat;
 use crate::widgets::scroll_area::{ScrollArea, ScrollState};
 
 
@@ -53,6 +59,12 @@ pub enum Error {
         #[source]
         err: crate::event_iter::Error,
     },
+    #[error("Failed to open trace file {path:?}")]
+    FileOpen {
+        path: String,
+        #[source]
+        err:  std::io::Error,
+    },
     #[error("Failed to render the terminal UI")]
     Render {
         #[source]
@@ -63,12 +75,31 @@ pub enum Error {
         #[source]
         err: std::io::Error,
     },
+    #[error("Failed to write to stdout")]
+    Stdout {
+        #[source]
+        err: std::io::Error,
+    },
 }
 
 
 
 
 
+/***** CONSTANTS *****/
+/// The maximum number of characters of a message's payload shown in the list of events.
+const MAX_SUMMARY_LEN: usize = 48;
+
+/// The colors that agents are assigned in the inspector.
+///
+/// Green, yellow and red are missing on purpose, as they're already used for identifiers.
+const AGENT_PALETTE: [Color; 8] =
+    [Color::Cyan, Color::LightBlue, Color::LightMagenta, Color::LightCyan, Color::Blue, Color::Magenta, Color::LightGreen, Color::LightYellow];
+
+
+
+
+
 /***** HELPER FUNCTIONS *****/
 /// Will either wait on the given channel, or, if it's closed, wait indefinitely.
 ///
@@ -90,50 +121,6 @@ async fn wait_for_event_or_forever(channel: &mut Receiver<()>) -> Option<()> {
 
 
 
-/// Constructs a [`GroundAtom`] representing the effect for reading.
-///
-/// # Arguments
-/// - `who`: The name of the person doing an action for which the effect needs to be generated.
-///   Essentially, "who reads".
-/// - `var`: The identifier of the variable in question. Give as a tuple of
-///   `((<WORKER> <TASK>) <VARIABLE)`.
-///
-/// # Returns
-/// A [`GroundAtom`] encoding `<who> reads <var>`.
-fn gen_read_effect(who: &str, var: &((String, String), String)) -> GroundAtom {
-    GroundAtom::Tuple(vec![
-        GroundAtom::Constant(SlickText::from_str(who)),
-        GroundAtom::Constant(SlickText::from_str("reads")),
-        GroundAtom::Tuple(vec![
-            GroundAtom::Tuple(vec![GroundAtom::Constant(SlickText::from_str(&var.0.0)), GroundAtom::Constant(SlickText::from_str(&var.0.1))]),
-            GroundAtom::Constant(SlickText::from_str(&var.1)),
-        ]),
-    ])
-}
-
-/// Constructs a [`GroundAtom`] representing the effect for writing.
-///
-/// # Arguments
-/// - `who`: The name of the person doing an action for which the effect needs to be generated.
-///   Essentially, "who writes".
-/// - `var`: The identifier of the variable in question. Give as a tuple of
-///   `((<WORKER> <TASK>) <VARIABLE>)`.
-///
-/// # Returns
-/// A [`GroundAtom`] encoding `<who> writes <var>`.
-fn gen_write_effect(who: &str, var: &((String, String), String)) -> GroundAtom {
-    GroundAtom::Tuple(vec![
-        GroundAtom::Constant(SlickText::from_str(who)),
-        GroundAtom::Constant(SlickText::from_str("writes")),
-        GroundAtom::Tuple(vec![
-            GroundAtom::Tuple(vec![GroundAtom::Constant(SlickText::from_str(&var.0.0)), GroundAtom::Constant(SlickText::from_str(&var.0.1))]),
-            GroundAtom::Constant(SlickText::from_str(&var.1)),
-        ]),
-    ])
-}
-
-
-
 /// Centers an area for something.
 ///
 /// # Arguments
@@ -158,7 +145,79 @@ fn center(horizontal: Constraint, vertical: Constraint, area: Rect) -> Rect {
 /// # Returns
 /// A [`Rect`] that can make an element center.
 #[inline]
-fn center_text(text: &Text, area: Rect) -> Rect { center(Constraint::Length(text.width() as u16), Constraint::Length(1), area) }
+fn center_text(text: &Text, area: Rect) -> Rect { center(Constraint::Length(display_width(text)), Constraint::Length(1), area) }
+
+/// Computes the number of terminal columns needed to display some text.
+///
+/// This uses the Unicode display width of every line, such that wide characters (e.g., CJK) take
+/// up two columns and combining characters none.
+///
+/// # Arguments
+/// - `text`: Some [`Text`] to measure.
+///
+/// # Returns
+/// The width of the widest line, saturated to fit in a [`u16`].
+fn display_width(text: &Text) -> u16 {
+    let width: usize =
+        text.lines.iter().map(|line| line.spans.iter().map(|span| UnicodeWidthStr::width(span.content.as_ref())).sum::<usize>()).max().unwrap_or(0);
+    u16::try_from(width).unwrap_or(u16::MAX)
+}
+
+/// Computes the width of the inner area of a scroll area showing some text.
+///
+/// # Arguments
+/// - `text`: Some [`Text`] that will be shown in a bordered block.
+/// - `min`: The minimum width of the area.
+///
+/// # Returns
+/// The [display width](display_width()) of the text plus its borders, or `min` if that's larger.
+#[inline]
+fn inner_width(text: &Text, min: u16) -> u16 { std::cmp::max(min, display_width(text).saturating_add(2)) }
+
+/// Soft-wraps some text such that no line is wider than the given width.
+///
+/// Lines are broken at exactly `width` characters (not at word boundaries), such that the height
+/// of the result is simply its number of lines. The styling of lines and spans is preserved.
+///
+/// # Arguments
+/// - `text`: Some [`Text`] to wrap.
+/// - `width`: The maximum [display width](display_width()) of any line. If it's `0`, the text is
+///   returned unchanged.
+///
+/// # Returns
+/// A new [`Text`] with the wrapped lines.
+fn soft_wrap<'a>(text: Text<'a>, width: u16) -> Text<'a> {
+    if width == 0 {
+        return text;
+    }
+    let width: usize = width as usize;
+
+    let mut lines: Vec<Line<'a>> = Vec::with_capacity(text.lines.len());
+    for Line { style, alignment, spans } in text.lines {
+        let mut wrapped: Line<'a> = Line { style, alignment, spans: Vec::new() };
+        let mut used: usize = 0;
+        for span in spans {
+            let mut chunk: String = String::new();
+            for c in span.content.chars() {
+                let w: usize = UnicodeWidthChar::width(c).unwrap_or(0);
+                if used > 0 && used + w > width {
+                    if !chunk.is_empty() {
+                        wrapped.spans.push(Span::styled(std::mem::take(&mut chunk), span.style));
+                    }
+                    lines.push(std::mem::replace(&mut wrapped, Line { style, alignment, spans: Vec::new() }));
+                    used = 0;
+                }
+                chunk.push(c);
+                used += w;
+            }
+            if !chunk.is_empty() {
+                wrapped.spans.push(Span::styled(chunk, span.style));
+            }
+        }
+        lines.push(wrapped);
+    }
+    Text { lines, ..text }
+}
 
 /// Renders some text centered in the given area.
 ///
@@ -205,6 +264,148 @@ fn press_or_to(key1: impl Display, key2: impl Display, what: impl Display) -> Te
     text
 }
 
+/// Picks the color of an agent.
+///
+/// This is based on a 64-bit [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function)
+/// hash of its identifier. Unlike the [`DefaultHasher`](std::collections::hash_map::DefaultHasher),
+/// this is fixed, such that an agent has the same color in every run and build of the inspector
+/// (and across traces).
+///
+/// # Arguments
+/// - `id`: The identifier of the agent.
+///
+/// # Returns
+/// One of the [`AGENT_PALETTE`]'s colors.
+#[inline]
+fn agent_color(id: &str) -> Color {
+    let hash: u64 = id.bytes().fold(0xCBF29CE484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001B3));
+    AGENT_PALETTE[(hash % AGENT_PALETTE.len() as u64) as usize]
+}
+
+/// Generates a [`Span`] with the name of an agent in its color.
+///
+/// # Arguments
+/// - `who`: The identifier of the agent.
+/// - `dimmed`: Whether the pane it's rendered in isn't focused. If so, the span isn't colored,
+///   such that it's dimmed like the rest of the pane.
+///
+/// # Returns
+/// A bold [`Span`] with the agent's identifier.
+fn agent_span(who: &str, dimmed: bool) -> Span<'static> {
+    let span = Span::from(who.to_string()).bold();
+    if dimmed { span } else { span.fg(agent_color(who)) }
+}
+
+/// Generates a [`Span`] with an icon showing who received a statement or action.
+///
+/// Private statements matter for whether actions are sourced, so they should stand out.
+///
+/// # Arguments
+/// - `to`: The [`Recipient`] of the statement or action.
+///
+/// # Returns
+/// A [`Span`] with a megaphone for broadcasts, or a lock for anything sent to a single agent.
+#[inline]
+fn recipient_icon(to: &Recipient<Cow<str>>) -> Span<'static> {
+    match to {
+        Recipient::All => Span::from("📢"),
+        Recipient::One(_) => Span::from("🔒").yellow(),
+    }
+}
+
+/// Summarizes what an action does to the dataplane.
+///
+/// # Arguments
+/// - `effects`: The [`Effect`]s of the action.
+///
+/// # Returns
+/// A string like `(2 writes, 1 read)`, or `(no effects)` if there are none. Effects that aren't
+/// reads, writes or deletes are counted as `other`.
+fn effect_summary(effects: &[Effect]) -> String {
+    if effects.is_empty() {
+        return "(no effects)".into();
+    }
+    let count = |kind: Option<EffectKind>| -> usize { effects.iter().filter(|effect| effect.kind() == kind).count() };
+    let parts: Vec<String> = [
+        (count(Some(EffectKind::Write)), "write"),
+        (count(Some(EffectKind::Read)), "read"),
+        (count(Some(EffectKind::Delete)), "delete"),
+        (count(None), "other"),
+    ]
+    .into_iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, what)| if n == 1 || what == "other" { format!("{n} {what}") } else { format!("{n} {what}s") })
+    .collect();
+    format!("({})", parts.join(", "))
+}
+
+/// Renders a line of a Slick payload with basic syntax highlighting.
+///
+/// This is a tiny tokenizer that only looks at word boundaries, so it also copes with lines that
+/// aren't valid Slick on their own (e.g., halfway a rule). It colours:
+/// - comments (everything after `//`) dark gray;
+/// - the keywords `if`, `and`, `not` and `says` magenta;
+/// - variables (words starting with an uppercase letter or `_`) cyan; and
+/// - all other words (constants) green.
+///
+/// Punctuation and whitespace are left unstyled.
+///
+/// # Arguments
+/// - `line`: The line to highlight.
+///
+/// # Returns
+/// A [`Line`] with a styled [`Span`] per token.
+fn highlight_slick(line: &str) -> Line<'static> {
+    let (code, comment): (&str, Option<&str>) = match line.find("//") {
+        Some(pos) => (&line[..pos], Some(&line[pos..])),
+        None => (line, None),
+    };
+
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut rest: &str = code;
+    while let Some(c) = rest.chars().next() {
+        // Find the end of the current token
+        let is_word = |c: char| !c.is_whitespace() && !matches!(c, '(' | ')' | '.' | ',');
+        let len: usize = if is_word(c) {
+            rest.find(|c: char| !is_word(c)).unwrap_or(rest.len())
+        } else if c.is_whitespace() {
+            rest.find(|c: char| !c.is_whitespace()).unwrap_or(rest.len())
+        } else {
+            c.len_utf8()
+        };
+        let (token, tail) = rest.split_at(len);
+        rest = tail;
+
+        // Style it
+        let span = Span::raw(token.to_string());
+        spans.push(if !is_word(c) {
+            span
+        } else if matches!(token, "if" | "and" | "not" | "says") {
+            span.fg(Color::Magenta).bold()
+        } else if c.is_uppercase() || c == '_' {
+            span.fg(Color::Cyan)
+        } else {
+            span.fg(Color::Green)
+        });
+    }
+    if let Some(comment) = comment {
+        spans.push(Span::raw(comment.to_string()).fg(Color::DarkGray).italic());
+    }
+    Line::from(spans)
+}
+
+/// Renders the lines of a Slick payload as [`Text`].
+///
+/// # Arguments
+/// - `lines`: The lines of the payload to render.
+/// - `highlight`: Whether to [syntax highlight](highlight_slick()) them or leave them plain.
+///
+/// # Returns
+/// A [`Text`] with one [`Line`] per payload line.
+fn payload_text<'l>(lines: impl Iterator<Item = &'l str>, highlight: bool) -> Text<'static> {
+    Text::from(lines.map(|l| if highlight { highlight_slick(l) } else { Line::raw(l.to_string()) }).collect::<Vec<Line>>())
+}
+
 
 
 
@@ -219,6 +420,17 @@ pub enum Focus {
     Event,
 }
 
+/// Defines what kind of event is referred to by an identifier.
+///
+/// See [`StateGuard::find_event_by_id()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum IdKind {
+    /// The identifier is of a message, which is stated or agreed.
+    Message,
+    /// The identifier is of an action, which is enacted.
+    Action,
+}
+
 /// Defines the state of the app.
 ///
 /// This isn't worked on directly. Usually, it will be accessed through a `StateGuard` which has
@@ -228,7 +440,7 @@ struct State {
     /// A queue of errors to show.
     errors: Arc<Mutex<VecDeque<Error>>>,
     /// An audit happening live on the trace that provides us with validity.
-    audit: Arc<Mutex<Audit>>,
+    audit: Arc<Mutex<SlickAudit>>,
     /// Which part of the window is focused.
     focus: Focus,
     /// The currently collected list of trace.
@@ -239,6 +451,26 @@ struct State {
     opened_event: Option<usize>,
     /// The scroll state of the right pane.
     right_scroll: ScrollState,
+    /// If the user is typing a path of a new trace file to open, this is what they typed so far.
+    open_prompt: Option<String>,
+    /// A path of a trace file that the user wants to open, to be picked up by the [`App`].
+    to_open: Option<String>,
+    /// If the user is typing a path to export the opened event's payload to, this is what they typed so far.
+    export_prompt: Option<String>,
+    /// A short-lived message to show in the footer (e.g., where a yanked event went).
+    status: Option<String>,
+    /// Whether the trace reader is still (possibly) producing new events.
+    live: bool,
+    /// Whether to syntax highlight Slick payloads.
+    highlight: bool,
+    /// Whether to soft-wrap long lines in the right pane instead of scrolling horizontally.
+    wrap: bool,
+    /// If given, the template used to render the rows in the list of events.
+    format: Option<RowTemplate>,
+    /// How to render timestamps.
+    time_format: TimeFormat,
+    /// The index (in the full trace) of the first event in `trace`.
+    offset: usize,
 }
 impl State {
     /// Constructor for the State that initializes it to default.
@@ -246,11 +478,17 @@ impl State {
     /// # Arguments
     /// - `errors`: The shared queue of errors with the trace reader thread.
     /// - `trace`: The shared buffer of parsed [`Event`]s with the trace reader thread.
-    /// - `audit`: Some shared [`Audit`] with the trace reader such that we can obtain action validities.
+    /// - `audit`: Some shared [`SlickAudit`] with the trace reader such that we can obtain action validities.
+    /// - `offset`: The index (in the full trace) of the first event that ends up in `trace`.
     ///
     /// # Returns
     /// A new State reading for state'ing.
-    fn new(errors: Arc<Mutex<VecDeque<Error>>>, trace: Arc<Mutex<Vec<Event<'static, Program>>>>, audit: Arc<Mutex<Audit>>) -> Self {
+    fn new(
+        errors: Arc<Mutex<VecDeque<Error>>>,
+        trace: Arc<Mutex<Vec<Event<'static, Program>>>>,
+        audit: Arc<Mutex<SlickAudit>>,
+        offset: usize,
+    ) -> Self {
         Self {
             errors,
             trace,
@@ -259,6 +497,16 @@ impl State {
             selected_event: ListState::default(),
             opened_event: None,
             right_scroll: ScrollState::default(),
+            open_prompt: None,
+            to_open: None,
+            export_prompt: None,
+            status: None,
+            live: true,
+            highlight: true,
+            wrap: false,
+            format: None,
+            time_format: TimeFormat::Logical,
+            offset,
         }
     }
 
@@ -277,6 +525,16 @@ impl State {
             selected_event: &mut self.selected_event,
             opened_event: &mut self.opened_event,
             right_scroll: &mut self.right_scroll,
+            open_prompt: &mut self.open_prompt,
+            to_open: &mut self.to_open,
+            export_prompt: &mut self.export_prompt,
+            status: &mut self.status,
+            live: self.live,
+            highlight: &mut self.highlight,
+            wrap: &mut self.wrap,
+            format: self.format.as_ref(),
+            time_format: self.time_format,
+            offset: self.offset,
         }
     }
 }
@@ -286,7 +544,7 @@ struct StateGuard<'s> {
     /// A queue of errors to show.
     _errors: MutexGuard<'s, VecDeque<Error>>,
     /// An audit happening live on the trace that provides us with validity.
-    audit: MutexGuard<'s, Audit>,
+    audit: MutexGuard<'s, SlickAudit>,
     /// Which part of the window is focused.
     focus: &'s mut Focus,
     /// The currently collected list of trace.
@@ -297,6 +555,28 @@ struct StateGuard<'s> {
     opened_event: &'s mut Option<usize>,
     /// The scroll state of the right pane.
     right_scroll: &'s mut ScrollState,
+    /// If the user is typing a path of a new trace file to open, this is what they typed so far.
+    open_prompt: &'s mut Option<String>,
+    /// A path of a trace file that the user wants to open, to be picked up by the [`App`].
+    to_open: &'s mut Option<String>,
+    /// If the user is typing a path to export the opened event's payload to, this is what they typed so far.
+    export_prompt: &'s mut Option<String>,
+    /// A short-lived message to show in the footer (e.g., where a yanked event went).
+    status: &'s mut Option<String>,
+    /// Whether the trace reader is still (possibly) producing new events.
+    live: bool,
+    /// Whether to syntax highlight Slick payloads.
+    highlight: &'s mut bool,
+    /// Whether to soft-wrap long lines in the right pane instead of scrolling horizontally.
+    wrap: &'s mut bool,
+    /// If given, the template used to render the rows in the list of events.
+    format: Option<&'s RowTemplate>,
+    /// How to render timestamps.
+    time_format: TimeFormat,
+    /// The index (in the full trace) of the first event in `trace`.
+    ///
+    /// Events are shown with their index in the full trace, and the audit is indexed by it too.
+    offset: usize,
 }
 
 
@@ -315,37 +595,181 @@ pub struct App {
     receiver: Receiver<()>,
     /// The thread handle responsible for generating new trace.
     handle:   JoinHandle<()>,
+    /// Used to tell the trace thread to stop once it's read everything readily available.
+    shutdown: Option<oneshot::Sender<()>>,
+    /// The range of event indices (in the full trace) that are shown.
+    window:   Range<usize>,
+    /// If given, only the actions of these actors are audited.
+    actors:   Option<HashSet<String>>,
+    /// If given, the trace is replayed with this delay between events.
+    pace:     Option<Duration>,
 }
 
 // Constructors & Destructors
 impl App {
     /// Creates a new App.
     ///
+    /// The App may only show a part of the trace. Events before this window are still audited,
+    /// such that the permissions of the actions in it are correct, but they aren't kept. Events
+    /// after the window aren't read at all.
+    ///
     /// # Arguments
     /// - `what`: Some name (path or otherwise) that describes the `input` (used for debugging purposes only).
     /// - `input`: Some [`Read`]er from which to read [`Event`]s.
+    /// - `window`: The range of (zero-indexed) events to show. Use `0..usize::MAX` to show all.
+    /// - `actors`: If given, only the actions of these actors are audited, which saves time on
+    ///   large traces. Other actions are still shown, but not checked. This also holds for traces
+    ///   opened later.
+    /// - `pace`: If given, waits this long between showing events, to replay a recorded trace as
+    ///   if it were a live run. Otherwise, events are shown as fast as they can be read.
     ///
     /// # Returns
     /// An App that is ready for drawing.
-    #[inline]
-    pub fn new(what: impl Into<String>, input: impl 'static + Send + AsyncRead + Unpin) -> Self {
+    pub fn new(
+        what: impl Into<String>,
+        input: impl 'static + Send + AsyncRead + Unpin,
+        window: Range<usize>,
+        actors: Option<HashSet<String>>,
+        pace: Option<Duration>,
+    ) -> Self {
         let what: String = what.into();
         let errors = Arc::new(Mutex::new(VecDeque::new()));
         let trace = Arc::new(Mutex::new(Vec::new()));
-        let audit = Arc::new(Mutex::new(Audit::new()));
+        let audit = Arc::new(Mutex::new(Self::new_audit(actors.as_ref())));
         let (sender, receiver) = channel(3);
+        let (shutdown, stop) = oneshot::channel();
         Self {
-            state: State::new(errors.clone(), trace.clone(), audit.clone()),
+            state: State::new(errors.clone(), trace.clone(), audit.clone(), window.start),
             events: EventStream::new(),
             receiver,
-            handle: tokio::spawn(Self::trace_reader(errors, trace, audit, sender, what, input)),
+            handle: tokio::spawn(Self::trace_reader(errors, trace, audit, sender, stop, what, input, window.clone(), pace)),
+            shutdown: Some(shutdown),
+            window,
+            actors,
+            pace,
+        }
+    }
+
+    /// Creates the audit that a new trace is checked with.
+    ///
+    /// # Arguments
+    /// - `actors`: If given, only the actions of these actors are audited.
+    ///
+    /// # Returns
+    /// A fresh [`SlickAudit`].
+    pub(crate) fn new_audit(actors: Option<&HashSet<String>>) -> SlickAudit {
+        match actors {
+            Some(actors) => SlickAudit::new().with_actors(actors.clone()),
+            None => SlickAudit::new(),
         }
     }
 }
+impl App {
+    /// Sets whether Slick payloads are syntax highlighted initially.
+    ///
+    /// Users can still toggle this at runtime by pressing `h`.
+    ///
+    /// # Arguments
+    /// - `highlight`: Whether to highlight payloads.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_highlight(mut self, highlight: bool) -> Self {
+        self.state.highlight = highlight;
+        self
+    }
+
+    /// Sets how timestamps (of time advancements and agreement windows) are rendered.
+    ///
+    /// # Arguments
+    /// - `time_format`: The [`TimeFormat`] to render timestamps with.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_time_format(mut self, time_format: TimeFormat) -> Self {
+        self.state.time_format = time_format;
+        self
+    }
+
+    /// Sets a template for the rows in the list of events, replacing the default rendering.
+    ///
+    /// # Arguments
+    /// - `format`: The [`RowTemplate`] to render every row with.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_format(mut self, format: RowTemplate) -> Self {
+        self.state.format = Some(format);
+        self
+    }
+}
 impl Drop for App {
     fn drop(&mut self) {
         // Attempt to drop the handle
+        // NOTE: This is only a fallback for when the reader wasn't [stopped](App::stop_reader())
+        self.handle.abort();
+    }
+}
+
+// Trace management
+impl App {
+    /// Replaces the trace currently inspected with the one in the given file.
+    ///
+    /// The reader of the current trace is stopped, and the trace, audit and selection are all
+    /// reset. If the file could not be opened, the current trace is kept instead. The new trace is
+    /// shown in the same window as the old one.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the trace file to open.
+    async fn open(&mut self, path: String) {
+        debug!("Opening input file {path:?}...");
+        let input: File = match File::open(&path).await {
+            Ok(input) => input,
+            Err(err) => {
+                let err = Error::FileOpen { path, err };
+                error!("{}", toplevel!(("Failed to open new trace"), err));
+                self.state.errors.lock().push_back(err);
+                return;
+            },
+        };
+
+        // Stop the old reader before we start the new one
         self.handle.abort();
+        let errors = Arc::new(Mutex::new(VecDeque::new()));
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(Self::new_audit(self.actors.as_ref())));
+        let (sender, receiver) = channel(3);
+        let (shutdown, stop) = oneshot::channel();
+        let (highlight, wrap, format, time_format): (bool, bool, Option<RowTemplate>, TimeFormat) =
+            (self.state.highlight, self.state.wrap, self.state.format.take(), self.state.time_format);
+        self.state = State::new(errors.clone(), trace.clone(), audit.clone(), self.window.start);
+        self.state.highlight = highlight;
+        self.state.wrap = wrap;
+        self.state.format = format;
+        self.state.time_format = time_format;
+        self.receiver = receiver;
+        self.handle =
+            tokio::spawn(Self::trace_reader(errors, trace, audit, sender, stop, format!("{path:?}"), input, self.window.clone(), self.pace));
+        self.shutdown = Some(shutdown);
+    }
+
+    /// Cooperatively stops the reader of the current trace.
+    ///
+    /// Unlike aborting it, this lets the reader finish processing whatever input is readily
+    /// available, such that the trace and audit are complete up to that point.
+    async fn stop_reader(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            // NOTE: If this fails, the reader has already quit by itself
+            let _ = shutdown.send(());
+        }
+        if let Err(err) = (&mut self.handle).await {
+            if !err.is_cancelled() {
+                error!("Trace reader thread panicked: {err}");
+            }
+        }
     }
 }
 
@@ -364,6 +788,8 @@ impl App {
             // Render the new UI state (immediate mode and all that)
             {
                 log::trace!("Rendering terminal UI");
+                // NOTE: The reader drops its end of the channel once it's done
+                self.state.live = !self.receiver.is_closed();
                 let mut state: StateGuard = self.state.lock();
                 if let Err(err) = term.draw(|frame| state.render(frame)) {
                     ratatui::restore();
@@ -379,19 +805,37 @@ impl App {
                         Some(Ok(event)) => {
                             let mut state: StateGuard = self.state.lock();
                             match state.handle_event(event) {
-                                Ok(ControlFlow::Continue(_)) => continue,
+                                Ok(ControlFlow::Continue(_)) => {
+                                    // The user may have asked for another trace
+                                    let to_open: Option<String> = state.to_open.take();
+                                    drop(state);
+                                    if let Some(path) = to_open {
+                                        self.open(path).await;
+                                    }
+                                    continue;
+                                },
                                 Ok(ControlFlow::Break(_)) => {
+                                    drop(state);
                                     ratatui::restore();
+                                    self.stop_reader().await;
                                     return Ok(());
                                 },
                                 Err(err) => {
+                                    drop(state);
                                     ratatui::restore();
+                                    self.stop_reader().await;
                                     return Err(err);
                                 },
                             }
                         }
-                        Some(Err(err)) => return Err(Error::TuiEvent { err }),
-                        None => return Ok(()),
+                        Some(Err(err)) => {
+                            self.stop_reader().await;
+                            return Err(Error::TuiEvent { err });
+                        },
+                        None => {
+                            self.stop_reader().await;
+                            return Ok(());
+                        },
                     }
                 },
 
@@ -402,6 +846,205 @@ impl App {
     }
 }
 
+// Auditing
+impl<'s> StateGuard<'s> {
+    /// Finds the audited outcome of the action with the given identifier.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the action to find the outcome of.
+    ///
+    /// # Returns
+    /// An [`ActionOutcome`] describing what the audit concluded. If no action with the given
+    /// identifier is (yet) in the trace, returns [`ActionOutcome::NotAnAction`].
+    fn outcome_of_action(&self, id: &str) -> ActionOutcome {
+        match self.find_event_by_id(IdKind::Action, id) {
+            Some(i) => self.audit.outcome_of(self.offset + i),
+            None => ActionOutcome::NotAnAction,
+        }
+    }
+}
+
+// Row templates
+impl<'s> StateGuard<'s> {
+    /// Renders a row in the list of events using a user-given template.
+    ///
+    /// # Arguments
+    /// - `format`: The [`RowTemplate`] to render.
+    /// - `i`: The index of the `event` in the (windowed) trace.
+    /// - `event`: The [`Event`] to render the row of.
+    /// - `width`: The number of characters to pad the event's number to.
+    /// - `color`: The [`Color`] of the list.
+    ///
+    /// # Returns
+    /// A single-line [`Text`] with the row.
+    fn render_row(&self, format: &RowTemplate, i: usize, event: &Event<'static, Program>, width: usize, color: Color) -> Text<'static> {
+        let mut text = Text::default().fg(color);
+        for piece in &format.pieces {
+            match piece {
+                RowPiece::Literal(lit) => text.push_span(lit.clone()),
+                RowPiece::Field(field) => text.push_span(self.resolve_field(*field, i, event, width)),
+            }
+        }
+        text
+    }
+
+    /// Finds the value of a field in a [`RowTemplate`] for a particular event.
+    ///
+    /// Fields that don't apply to the event (e.g., `{to}` for a read) resolve to nothing.
+    ///
+    /// # Arguments
+    /// - `field`: The [`RowField`] to resolve.
+    /// - `i`: The index of the `event` in the (windowed) trace.
+    /// - `event`: The [`Event`] to resolve the field for.
+    /// - `width`: The number of characters to pad the event's number to.
+    ///
+    /// # Returns
+    /// A [`Span`] with the (styled) value of the field.
+    fn resolve_field(&self, field: RowField, i: usize, event: &Event<'static, Program>, width: usize) -> Span<'static> {
+        let status = |permitted: bool| -> Span<'static> {
+            if permitted { Span::from("✓").bold().green() } else { Span::from("✘").bold().white().on_red() }
+        };
+        match (field, event) {
+            (RowField::Idx, _) => Span::from(format!("{:>width$}", self.offset + i + 1)).dark_gray(),
+
+            (RowField::Kind, Event::Control { event }) => Span::from(match event {
+                EventControl::SetAgreements { .. } | EventControl::SetAgreementsWithin { .. } => "agree",
+                EventControl::RetractAgreement { .. } => "retract",
+                EventControl::AdvanceTime { .. } => "time",
+                EventControl::EnactAction { .. } => "enact",
+                EventControl::StateMessage { .. } => "state",
+            })
+            .italic(),
+            (RowField::Kind, Event::Data { event }) => Span::from(match event {
+                EventData::Read { .. } => "read",
+                EventData::Write { .. } => "write",
+                EventData::Delete { .. } => "delete",
+            })
+            .italic(),
+
+            (RowField::Actor, Event::Control { event: EventControl::EnactAction { who, .. } | EventControl::StateMessage { who, .. } }) => {
+                agent_span(who, *self.focus != Focus::List)
+            },
+            (
+                RowField::Actor,
+                Event::Data { event: EventData::Read { who, .. } | EventData::Write { who, .. } | EventData::Delete { who, .. } },
+            ) => agent_span(who, *self.focus != Focus::List),
+            (RowField::Actor, _) => Span::default(),
+
+            (RowField::Id, Event::Control { event: EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } }) => {
+                Span::from(agrees.iter().map(|agree| format!("{:?}", agree.human_id)).collect::<Vec<String>>().join(", ")).green()
+            },
+            (RowField::Id, Event::Control { event: EventControl::RetractAgreement { id, .. } }) => Span::from(format!("{id:?}")).green(),
+            (RowField::Id, Event::Control { event: EventControl::AdvanceTime { timestamp } }) => Span::from(self.time_format.format(*timestamp)).bold(),
+            (RowField::Id, Event::Control { event: EventControl::EnactAction { action, .. } }) => Span::from(format!("{:?}", action.human_id)).yellow(),
+            (RowField::Id, Event::Control { event: EventControl::StateMessage { msg, .. } }) => Span::from(format!("{:?}", msg.human_id)).red(),
+            (RowField::Id, Event::Data { event: EventData::Read { id, .. } | EventData::Write { id, .. } | EventData::Delete { id, .. } }) => {
+                Span::from(format!("\"{id}\"")).bold().dark_gray()
+            },
+
+            (RowField::To, Event::Control { event: EventControl::EnactAction { to, .. } | EventControl::StateMessage { to, .. } }) => match to {
+                Recipient::All => Span::from("<everyone>"),
+                Recipient::One(to) => agent_span(to, *self.focus != Focus::List),
+            },
+            (RowField::To, _) => Span::default(),
+
+            (RowField::Status, Event::Control { event: EventControl::EnactAction { .. } }) => match self.audit.outcome_of(self.offset + i) {
+                ActionOutcome::NotAnAction => Span::from("-").dark_gray(),
+                ActionOutcome::Rejected(perm) if perm.inference_failed => Span::from("?").bold().black().on_yellow(),
+                outcome => status(outcome.is_permitted()),
+            },
+            (RowField::Status, Event::Control { .. }) => Span::default(),
+            (RowField::Status, Event::Data { event: EventData::Read { contents, .. } }) => {
+                status(contents.is_some() && !self.audit.is_data_violation(self.offset + i))
+            },
+            (RowField::Status, Event::Data { .. }) => status(!self.audit.is_data_violation(self.offset + i)),
+
+            (RowField::Summary, Event::Control { event: EventControl::StateMessage { msg, .. } }) => {
+                Span::from(msg.serialize().summary(MAX_SUMMARY_LEN)).dark_gray()
+            },
+            (RowField::Summary, _) => Span::default(),
+        }
+    }
+}
+
+// Time
+impl<'s> StateGuard<'s> {
+    /// Finds the time at which an event happened, i.e., the one last advanced to before it.
+    ///
+    /// # Arguments
+    /// - `i`: The index (in `trace`) of the event to find the time of.
+    ///
+    /// # Returns
+    /// The timestamp, or [`None`] if time was never advanced before the event but earlier events
+    /// were skipped (so we can't tell).
+    fn time_at(&self, i: usize) -> Option<u64> {
+        for event in self.trace[..i.min(self.trace.len())].iter().rev() {
+            if let Event::Control { event: EventControl::AdvanceTime { timestamp } } = event {
+                return Some(*timestamp);
+            }
+        }
+        if self.offset == 0 { Some(0) } else { None }
+    }
+}
+
+// Navigation
+impl<'s> StateGuard<'s> {
+    /// Finds the first event in the trace that carries the message or action with the given
+    /// identifier.
+    ///
+    /// # Arguments
+    /// - `kind`: Whether to look for a message or an action.
+    /// - `id`: The identifier of the message or action to find.
+    ///
+    /// # Returns
+    /// The index of the first [`EventControl::StateMessage`] or [`EventControl::SetAgreements`]
+    /// carrying the message, or of the first [`EventControl::EnactAction`] carrying the action.
+    /// If there is no such event (yet), returns [`None`].
+    fn find_event_by_id(&self, kind: IdKind, id: &str) -> Option<usize> {
+        self.trace.iter().position(|e| match (kind, e) {
+            (IdKind::Message, Event::Control { event: EventControl::StateMessage { msg, .. } }) => msg.human_id == id,
+            (IdKind::Message, Event::Control { event: EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } }) => {
+                agrees.iter().any(|msg| msg.human_id == id)
+            },
+            (IdKind::Action, Event::Control { event: EventControl::EnactAction { action, .. } }) => action.human_id == id,
+            _ => false,
+        })
+    }
+
+    /// Returns the messages referred to by the opened event, if it's an action.
+    ///
+    /// # Returns
+    /// The identifier of the basis and those of the extra messages (sorted, like they are shown),
+    /// or [`None`] if no action is opened.
+    fn opened_action_refs(&self) -> Option<(String, Vec<String>)> {
+        let Some(Event::Control { event: EventControl::EnactAction { action, .. } }) = self.opened_event.and_then(|i| self.trace.get(i)) else {
+            return None;
+        };
+        let mut extra: Vec<String> = action.extra.iter().map(|msg| msg.human_id.clone()).collect();
+        extra.sort();
+        Some((action.basis.human_id.clone(), extra))
+    }
+
+    /// Selects and opens the first event carrying the message or action with the given identifier.
+    ///
+    /// If there is no such event, the [status](StateGuard::status) is updated to say so instead.
+    ///
+    /// # Arguments
+    /// - `kind`: Whether to jump to a message or an action.
+    /// - `id`: The identifier of the message or action to jump to.
+    fn jump_to(&mut self, kind: IdKind, id: &str) {
+        match self.find_event_by_id(kind, id) {
+            Some(i) => {
+                self.selected_event.select(Some(i));
+                *self.opened_event = Some(i);
+                *self.focus = Focus::Event;
+                self.right_scroll.reset();
+            },
+            None => *self.status = Some(format!("{id:?} is not (yet) in the trace")),
+        }
+    }
+}
+
 // Rendering
 impl<'s> StateGuard<'s> {
     /// Renders the application's current window.
@@ -418,25 +1061,40 @@ impl<'s> StateGuard<'s> {
         let vrects = Layout::vertical([Constraint::Length(3), Constraint::Fill(1), Constraint::Length(1)]).split(frame.area());
 
         // Title bar
+        let mut position: Line = Line::from(match self.selected_event.selected() {
+            Some(i) => format!("Event {} / {}", self.offset + i + 1, self.offset + self.trace.len()),
+            None => format!("{} events", self.trace.len()),
+        });
+        if self.offset > 0 {
+            position.push_span(" ");
+            position.push_span(Span::from(format!("(from event {})", self.offset + 1)).italic().dark_gray());
+        }
+        if self.live {
+            position.push_span(" ");
+            position.push_span(Span::from("(receiving...)").italic().dark_gray());
+        }
+        let block = Block::bordered();
+        let hrects = Layout::horizontal([Constraint::Fill(1), Constraint::Length(display_width(&Text::from(position.clone())))]).split(block.inner(vrects[0]));
+        frame.render_widget(&block, vrects[0]);
         frame.render_widget(
-            Paragraph::new(format!("JustAct Prototype Event Inspector - v{}", env!("CARGO_PKG_VERSION")))
-                .style(Style::new().bold())
-                .block(Block::bordered()),
-            vrects[0],
+            Paragraph::new(format!("JustAct Prototype Event Inspector - v{}", env!("CARGO_PKG_VERSION"))).style(Style::new().bold()),
+            hrects[0],
         );
+        frame.render_widget(position, hrects[1]);
 
 
 
         // Events (left plane)
-        let max_trace_width: usize = (self.trace.len().checked_ilog10().unwrap_or(0) + 1) as usize;
+        let max_trace_width: usize = ((self.offset + self.trace.len()).checked_ilog10().unwrap_or(0) + 1) as usize;
         let body_rects =
             Layout::horizontal(if self.opened_event.is_some() { [Constraint::Fill(1); 2].as_slice() } else { [Constraint::Fill(1); 1].as_slice() })
                 .split(vrects[1]);
-        let titles = self.trace.iter().enumerate().map(|(i, t)| match t {
-            Event::Control { event } => match event {
-                EventControl::SetAgreements { agrees } => {
+        let titles = self.trace.iter().enumerate().map(|(i, t)| match (self.format, t) {
+            (Some(format), t) => self.render_row(format, i, t, max_trace_width, left_color),
+            (None, Event::Control { event }) => match event {
+                EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } => {
                     let mut text = Text::default().fg(left_color);
-                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", i + 1)).dark_gray());
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
                     text.push_span(Span::from("[JUSTACT]").italic());
                     text.push_span(" Updated agreements ");
                     for (i, agree) in agrees.iter().enumerate() {
@@ -445,112 +1103,132 @@ impl<'s> StateGuard<'s> {
                         }
                         text.push_span(Span::from(format!("{:?}", agree.human_id)).green());
                     }
+                    if let EventControl::SetAgreementsWithin { at, until, .. } = event {
+                        text.push_span(
+                            Span::from(format!(" (valid from {} until {})", self.time_format.format(*at), self.time_format.format(*until))).dark_gray(),
+                        );
+                    }
+                    text
+                },
+                EventControl::RetractAgreement { author: _, id } => {
+                    let mut text = Text::default().fg(left_color);
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
+                    text.push_span(Span::from("[JUSTACT]").italic());
+                    text.push_span(" Retracted agreement ");
+                    text.push_span(Span::from(format!("{id:?}")).green());
+                    text
+                },
+                EventControl::AdvanceTime { timestamp } => {
+                    let mut text = Text::default().fg(left_color);
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
+                    text.push_span(Span::from("[JUSTACT]").italic());
+                    text.push_span(" Advanced time to ");
+                    text.push_span(Span::from(self.time_format.format(*timestamp)).bold());
                     text
                 },
-                EventControl::EnactAction { who, to: _, action } => {
+                EventControl::EnactAction { who, to, action } => {
                     // Then render
                     let mut text = Text::default().fg(left_color);
-                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", i + 1)).dark_gray());
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
                     text.push_span(Span::from("[JUSTACT]").italic());
+                    text.push_span(" ");
+                    text.push_span(recipient_icon(to));
                     text.push_span(" Agent ");
-                    text.push_span(Span::from(format!("{who}")).bold());
+                    text.push_span(agent_span(who, left_color == inactive));
                     text.push_span(" enacted action ");
                     text.push_span(Span::from(format!("{:?}", action.human_id)).yellow());
+                    if let Recipient::One(a) = to {
+                        text.push_span(" to ");
+                        text.push_span(agent_span(a, left_color == inactive));
+                    }
+                    if action.extra.is_empty() {
+                        text.push_span(" ");
+                        text.push_span(Span::from("(unjustified)").italic().magenta());
+                    } else if action.is_self_justified() {
+                        text.push_span(" ");
+                        text.push_span(Span::from("(self-justified)").italic().dark_gray());
+                    }
+                    let outcome: ActionOutcome = self.audit.outcome_of(self.offset + i);
+                    if let Some(perm) = outcome.permission() {
+                        text.push_span(" ");
+                        text.push_span(Span::from(effect_summary(&perm.effects)).dark_gray());
+                    }
                     text.push_span(" ");
-                    text.push_span({
-                        if self.audit.permission_of(i).and_then(|res| res.as_ref().map(|a| a.is_permitted()).ok()).unwrap_or(false) {
-                            Span::from("✓").bold().green()
-                        } else {
-                            Span::from("✘").bold().white().on_red()
-                        }
+                    text.push_span(match outcome {
+                        // Not audited because of `--actor`
+                        ActionOutcome::NotAnAction => Span::from("-").dark_gray(),
+                        outcome if outcome.is_permitted() => Span::from("✓").bold().green(),
+                        // The policy couldn't be computed, which is not the same as it being violated
+                        ActionOutcome::Rejected(perm) if perm.inference_failed => Span::from("?").bold().black().on_yellow(),
+                        _ => Span::from("✘").bold().white().on_red(),
                     });
                     text
                 },
                 EventControl::StateMessage { who, to, msg } => {
                     let mut text = Text::default().fg(left_color);
-                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", i + 1)).dark_gray());
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
                     text.push_span(Span::from("[JUSTACT]").italic());
+                    text.push_span(" ");
+                    text.push_span(recipient_icon(to));
                     text.push_span(" Agent ");
-                    text.push_span(Span::from(format!("{who}")).bold());
+                    text.push_span(agent_span(who, left_color == inactive));
                     text.push_span(" stated message ");
                     text.push_span(Span::from(format!("{:?}", msg.human_id)).red());
                     if let Recipient::One(a) = to {
                         text.push_span(" to ");
-                        text.push_span(Span::from(format!("{a}")).bold());
+                        text.push_span(agent_span(a, left_color == inactive));
                     }
+                    text.push_span(Span::from(format!(" {}", msg.serialize().summary(MAX_SUMMARY_LEN))).dark_gray());
                     text
                 },
             },
 
-            Event::Data { event } => match event {
-                EventData::Read { who, id, context, contents } => {
+            (None, Event::Data { event }) => match event {
+                EventData::Read { who, id, context: _, contents } => {
                     let mut text = Text::default().fg(left_color);
-                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", i + 1)).dark_gray());
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
                     text.push_span(Span::from("[DATAPLN]").italic().black().bg(left_color));
                     text.push_span(" Agent ");
-                    text.push_span(Span::from(format!("{who}")).bold());
+                    text.push_span(agent_span(who, left_color == inactive));
                     text.push_span(" read variable ");
-                    text.push_span(Span::from(format!("\"({} {}) {}\"", id.0.0, id.0.1, id.1)).bold().dark_gray());
+                    text.push_span(Span::from(format!("\"{id}\"")).bold().dark_gray());
                     text.push_span(" ");
-                    match (
-                        self.trace
-                            .iter()
-                            .position(|e| {
-                                if let Event::Control { event: EventControl::EnactAction { action, .. } } = e {
-                                    &action.human_id == context
-                                } else {
-                                    false
-                                }
-                            })
-                            .and_then(|i| self.audit.permission_of(i)),
-                        contents.is_some(),
-                    ) {
-                        (Some(Ok(perm)), true) => {
-                            let effect: GroundAtom = gen_read_effect(who, id);
-                            if perm.is_permitted() && <[_]>::iter(&perm.effects).find(|e| e.fact == effect).is_some() {
-                                text.push_span(Span::from("✓").bold().green());
-                            } else {
-                                text.push_span(Span::from("!!!").bold().white().on_red());
-                            }
-                        },
-                        (_, _) => {
-                            text.push_span(Span::from("!!!").bold().white().on_red());
-                        },
+                    if contents.is_some() && !self.audit.is_data_violation(self.offset + i) {
+                        text.push_span(Span::from("✓").bold().green());
+                    } else {
+                        text.push_span(Span::from("!!!").bold().white().on_red());
                     }
                     text
                 },
-                EventData::Write { who, id, context, new, contents: _ } => {
+                EventData::Write { who, id, context: _, new, contents: _, labels: _ } => {
                     let mut text = Text::default().fg(left_color);
-                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", i + 1)).dark_gray());
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
                     text.push_span(Span::from("[DATAPLN]").italic().black().bg(left_color));
                     text.push_span(" Agent ");
-                    text.push_span(Span::from(format!("{who}")).bold());
+                    text.push_span(agent_span(who, left_color == inactive));
                     text.push_span(format!(" wrote to{} variable ", if *new { " new" } else { "" }));
-                    text.push_span(Span::from(format!("\"({} {}) {}\"", id.0.0, id.0.1, id.1)).bold().dark_gray());
+                    text.push_span(Span::from(format!("\"{id}\"")).bold().dark_gray());
                     text.push_span(" ");
-                    match self
-                        .trace
-                        .iter()
-                        .position(|e| {
-                            if let Event::Control { event: EventControl::EnactAction { action, .. } } = e {
-                                &action.human_id == context
-                            } else {
-                                false
-                            }
-                        })
-                        .and_then(|i| self.audit.permission_of(i))
-                    {
-                        Some(Ok(perm)) => {
-                            let effect: GroundAtom = gen_write_effect(who, id);
-                            if perm.is_permitted() && <[_]>::iter(&perm.effects).find(|e| e.fact == effect).is_some() {
-                                text.push_span(Span::from("✓").bold().green());
-                            } else {
-                                text.push_span(Span::from("!!!").bold().white().on_red());
-                            }
-                        },
-                        _ => {
-                            text.push_span(Span::from("!!!").bold().white().on_red());
-                        },
+                    if !self.audit.is_data_violation(self.offset + i) {
+                        text.push_span(Span::from("✓").bold().green());
+                    } else {
+                        text.push_span(Span::from("!!!").bold().white().on_red());
+                    }
+                    text
+                },
+                EventData::Delete { who, id, context: _ } => {
+                    let mut text = Text::default().fg(left_color);
+                    text.push_span(Span::from(format!("{:>max_trace_width$}) ", self.offset + i + 1)).dark_gray());
+                    text.push_span(Span::from("[DATAPLN]").italic().black().bg(left_color));
+                    text.push_span(" Agent ");
+                    text.push_span(agent_span(who, left_color == inactive));
+                    text.push_span(" deleted variable ");
+                    text.push_span(Span::from(format!("\"{id}\"")).bold().dark_gray());
+                    text.push_span(" ");
+                    if !self.audit.is_data_violation(self.offset + i) {
+                        text.push_span(Span::from("✓").bold().green());
+                    } else {
+                        text.push_span(Span::from("!!!").bold().white().on_red());
                     }
                     text
                 },
@@ -565,31 +1243,36 @@ impl<'s> StateGuard<'s> {
 
 
         // Opened trace (right plane)
-        if let Some(i) = self.opened_event {
+        if let Some(i) = &*self.opened_event {
             let trace: &Event<Program> = &self.trace[*i];
 
             // Render the block
-            let block = Block::bordered().title(format!("Event {}", *i + 1)).fg(right_color);
+            let block = Block::bordered().title(format!("Event {}", self.offset + *i + 1)).fg(right_color);
             frame.render_widget(&block, body_rects[1]);
 
             // Render the components
             match trace {
                 Event::Control { event } => match event {
-                    EventControl::SetAgreements { agrees } => {
+                    EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } => {
+                        let window: Option<(u64, u64)> =
+                            if let EventControl::SetAgreementsWithin { at, until, .. } = event { Some((*at, *until)) } else { None };
+
                         // Let's just assume there's only ever 1
                         let [agree] = agrees.as_slice() else { panic!("Only one agreement update per time is supported") };
 
                         // Compute the size of the inner area of the scroll area
-                        let spayload = agree.payload.serialize();
-                        let text = Text::from(spayload.lines().map(|l| Line::raw(l)).collect::<Vec<Line>>());
-                        let inner: Rect = Rect::new(0, 0, std::cmp::max(40, 2 + text.width() as u16), 4 + 2 + text.height() as u16);
+                        let smsg = agree.serialize();
+                        let area: Rect = block.inner(body_rects[1]);
+                        let text = payload_text(smsg.payload_lines(), *self.highlight);
+                        let text = if *self.wrap { soft_wrap(text, area.width.saturating_sub(2)) } else { text };
+                        let inner: Rect = Rect::new(0, 0, if *self.wrap { area.width } else { inner_width(&text, 40) }, 5 + 2 + text.height() as u16);
 
                         // Render with the scroll area
                         frame.render_stateful_widget(
                             ScrollArea::new(inner).render_inner(move |mut frame| {
                                 // Prepare the layout
                                 let vrects = Layout::vertical(
-                                    Some(Constraint::Length(1)).into_iter().cycle().take(3).chain(Some(Constraint::Length(2 + text.height() as u16))),
+                                    Some(Constraint::Length(1)).into_iter().cycle().take(4).chain(Some(Constraint::Length(2 + text.height() as u16))),
                                 )
                                 .split(frame.area());
 
@@ -606,59 +1289,108 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Agreement author    : ");
-                                        text.push_span(Span::from(&agree.author_id).bold());
+                                        text.push_span(agent_span(&agree.author_id, right_color == inactive));
                                         text
                                     })
                                     .fg(right_color),
                                     vrects[1],
                                 );
+                                frame.render_widget(
+                                    Paragraph::new({
+                                        let mut text = Text::from("Agreement validity  : ");
+                                        match window {
+                                            Some((at, until)) => text.push_span(
+                                                Span::from(format!("from {} until {}", self.time_format.format(at), self.time_format.format(until))).bold(),
+                                            ),
+                                            None => text.push_span(Span::from("<always>").bold()),
+                                        }
+                                        text
+                                    })
+                                    .fg(right_color),
+                                    vrects[2],
+                                );
 
                                 // Render the payload
                                 frame.render_widget(
-                                    Paragraph::new(text).fg(right_color).block(Block::bordered().title("Payload").fg(right_color)),
-                                    vrects[3],
+                                    Paragraph::new(text)
+                                        .fg(right_color)
+                                        .block(Block::bordered().title(format!("Payload ({})", PolicyStats::of(&agree.payload))).fg(right_color)),
+                                    vrects[4],
                                 );
                             }),
                             block.inner(body_rects[1]),
                             &mut self.right_scroll,
                         );
                     },
+                    EventControl::RetractAgreement { author, id } => {
+                        frame.render_widget(
+                            Paragraph::new(vec![
+                                Line::from(vec![Span::from("Retracted agreement: "), Span::from(format!("{id:?}")).bold()]),
+                                Line::from(vec![Span::from("Agreement author   : "), Span::from(author.to_string()).bold()]),
+                            ])
+                            .fg(right_color),
+                            block.inner(body_rects[1]),
+                        );
+                    },
+                    EventControl::AdvanceTime { timestamp } => {
+                        frame.render_widget(
+                            Paragraph::new({
+                                let mut text = Text::from("Advanced time to: ");
+                                text.push_span(Span::from(self.time_format.format(*timestamp)).bold());
+                                text
+                            })
+                            .fg(right_color),
+                            block.inner(body_rects[1]),
+                        );
+                    },
                     EventControl::EnactAction { who, to, action } => {
                         // First, compute the denotation and decide if this was permitted
-                        let denot: Result<(&Permission, Text<'static>), _> = self
-                            .audit
-                            .permission_of(*i)
-                            .unwrap_or_else(|| panic!("Failed to find action {} {:?} in audit after list construction!", i, action.human_id))
-                            .as_ref()
-                            .map(|p| {
-                                (p, {
-                                    let mut text = Text::default();
-                                    for t in &p.truths {
-                                        let mut line = Line::from(format!("{t:?}"));
-                                        if match &t {
-                                            GroundAtom::Constant(t) if format!("{t:?}") == "error" => true,
-                                            GroundAtom::Tuple(ts) if !ts.is_empty() && format!("{:?}", ts[0]) == "error" => true,
-                                            _ => false,
-                                        } {
-                                            line = line.bold().white().on_red();
+                        let outcome: ActionOutcome = self.audit.outcome_of(self.offset + *i);
+                        let taken_at: Option<u64> = self.time_at(*i);
+                        let missing: &[(String, String)] = outcome.permission().map(|p| p.missing_justifications.as_slice()).unwrap_or(&[]);
+                        let denot: Result<(&Permission, Text<'static>), &str> = match &outcome {
+                            ActionOutcome::Permitted(p) | ActionOutcome::Rejected(p) => Ok((p, {
+                                let mut text = Text::default();
+                                for t in &p.truths {
+                                    let mut line = Line::from(format!("{t:?}"));
+                                    if p.inference_failed {
+                                        line = line.italic().yellow();
+                                    } else if match &t {
+                                        GroundAtom::Constant(t) if format!("{t:?}") == "error" => true,
+                                        GroundAtom::Tuple(ts) if !ts.is_empty() && format!("{:?}", ts[0]) == "error" => true,
+                                        _ => false,
+                                    } {
+                                        line = line.bold().white().on_red();
+                                    }
+                                    text.push_line(line);
+
+                                    // Expand what derived the error, if known
+                                    if let Some((_, supports)) = p.error_supports.iter().find(|(error, _)| error == t) {
+                                        for support in supports {
+                                            text.push_line(Line::from(format!("  ⤷ {support:?}")).italic().red());
                                         }
-                                        text.push_line(line);
                                     }
-                                    text
-                                })
-                            });
+                                }
+                                text
+                            })),
+                            ActionOutcome::PolicyError(err) => Err(err.as_str()),
+                            ActionOutcome::NotAnAction => Err("NOT AUDITED (not enacted by one of the --actor actors)"),
+                        };
 
                         // Then compute the total size of the needed inner area
+                        let area: Rect = block.inner(body_rects[1]);
+                        let denot = if *self.wrap { denot.map(|(p, text)| (p, soft_wrap(text, area.width.saturating_sub(2)))) } else { denot };
                         let effect_height: usize = std::cmp::max(denot.as_ref().map(|(p, _)| p.effects.len()).unwrap_or(0), 1);
                         let (denot_width, denot_height): (u16, u16) =
-                            denot.as_ref().map(|(_, text)| (2 + text.width() as u16, 2 + text.height() as u16)).unwrap_or((0, 0));
-                        let inner: Rect = Rect::new(0, 0, std::cmp::max(40, denot_width), 12 + effect_height as u16 + denot_height);
+                            denot.as_ref().map(|(_, text)| (inner_width(text, 0), 2 + text.height() as u16)).unwrap_or((0, 0));
+                        let inner: Rect =
+                            Rect::new(0, 0, if *self.wrap { area.width } else { std::cmp::max(40, denot_width) }, 13 + effect_height as u16 + denot_height);
 
                         // Render the information scrolled
                         frame.render_stateful_widget(
                             ScrollArea::new(inner).render_inner(|mut frame| {
                                 let vrects = Layout::vertical(
-                                    [Constraint::Length(1); 12]
+                                    [Constraint::Length(1); 13]
                                         .into_iter()
                                         .chain([Constraint::Length(1)].into_iter().cycle().take(effect_height))
                                         .chain([Constraint::Length(denot_height)]),
@@ -670,7 +1402,7 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Enacted by: ");
-                                        text.push_span(Span::from(who.as_ref()).bold());
+                                        text.push_span(agent_span(who, right_color == inactive));
                                         text
                                     })
                                     .fg(right_color),
@@ -680,13 +1412,10 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Enacted to: ");
-                                        text.push_span(
-                                            Span::from(match to {
-                                                Recipient::All => "<everyone>",
-                                                Recipient::One(agent) => agent.as_ref(),
-                                            })
-                                            .bold(),
-                                        );
+                                        text.push_span(match to {
+                                            Recipient::All => Span::from("<everyone>").bold(),
+                                            Recipient::One(agent) => agent_span(agent, right_color == inactive),
+                                        });
                                         text
                                     })
                                     .fg(right_color),
@@ -708,7 +1437,20 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Action actor     : ");
-                                        text.push_span(Span::from(&action.actor_id).bold());
+                                        text.push_span(agent_span(&action.actor_id, right_color == inactive));
+                                        text
+                                    })
+                                    .fg(right_color),
+                                    vrects[i],
+                                );
+                                i += 1;
+                                frame.render_widget(
+                                    Paragraph::new({
+                                        let mut text = Text::from("Action taken at  : ");
+                                        match taken_at {
+                                            Some(timestamp) => text.push_span(Span::from(self.time_format.format(timestamp)).bold()),
+                                            None => text.push_span(Span::from("<unknown>").bold()),
+                                        }
                                         text
                                     })
                                     .fg(right_color),
@@ -739,10 +1481,17 @@ impl<'s> StateGuard<'s> {
                                                 } else if i > 0 {
                                                     text.push_span(" and ");
                                                 }
-                                                text.push_span(Span::from(format!("{:?}", msg.human_id)).bold());
+                                                let span: Span = Span::from(format!("[{}] {:?}", i + 1, msg.human_id)).bold();
+                                                if missing.iter().any(|(author, id)| *author == msg.author_id && *id == msg.human_id) {
+                                                    // Never stated, so call it out
+                                                    text.push_span(span.white().on_red());
+                                                    text.push_span(Span::from(" (never stated)").italic().red());
+                                                } else {
+                                                    text.push_span(span);
+                                                }
                                             }
                                         } else {
-                                            text.push_span(" <empty>");
+                                            text.push_span(Span::from("<empty> (unjustified, hence not based)").italic().magenta());
                                         }
                                         text
                                     })
@@ -759,29 +1508,11 @@ impl<'s> StateGuard<'s> {
                                             Paragraph::new({
                                                 let mut text = Text::from("Permission : ");
                                                 if perm.is_permitted() {
-                                                    text.push_span(Span::from("OK").bold().green());
+                                                    text.push_span(Span::from(perm.explain()).bold().green());
+                                                } else if perm.inference_failed {
+                                                    text.push_span(Span::from(perm.explain()).bold().yellow());
                                                 } else {
-                                                    text.push_span(Span::from("ILLEGAL").bold().red());
-                                                    text.push_span(" (");
-                                                    let mut first: bool = true;
-                                                    if !perm.valid_act {
-                                                        text.push_span(Span::from("not valid").red());
-                                                        first = false;
-                                                    }
-                                                    if !perm.sourced {
-                                                        if !first {
-                                                            text.push_span(", ");
-                                                        }
-                                                        text.push_span(Span::from("not sourced").red());
-                                                        first = false;
-                                                    }
-                                                    if !perm.based {
-                                                        if !first {
-                                                            text.push_span(", ");
-                                                        }
-                                                        text.push_span(Span::from("not based").red());
-                                                    }
-                                                    text.push_span(")");
+                                                    text.push_span(Span::from(perm.explain()).bold().red());
                                                 }
                                                 text
                                             })
@@ -797,7 +1528,7 @@ impl<'s> StateGuard<'s> {
                                                 frame.render_widget(
                                                     Paragraph::new({
                                                         let mut text = Text::from(" - ");
-                                                        text.push_span(Span::from(format!("{:?}", effect.fact)).bold());
+                                                        text.push_span(Span::from(effect.to_string()).bold());
                                                         text
                                                     })
                                                     .fg(right_color),
@@ -819,7 +1550,18 @@ impl<'s> StateGuard<'s> {
                                             vrects[i],
                                         );
                                     },
-                                    Err(_) => todo!(),
+                                    Err(err) => {
+                                        frame.render_widget(
+                                            Paragraph::new({
+                                                let mut text = Text::from("Permission : ");
+                                                text.push_span(Span::from("FAILED TO EXTRACT POLICY").bold().white().on_red());
+                                                text.push_span(format!(" ({err})"));
+                                                text
+                                            })
+                                            .fg(right_color),
+                                            vrects[i],
+                                        );
+                                    },
                                 }
                             }),
                             block.inner(body_rects[1]),
@@ -828,16 +1570,49 @@ impl<'s> StateGuard<'s> {
                     },
                     EventControl::StateMessage { who, to, msg } => {
                         // Compute the size of the total info area
-                        let spayload = msg.payload.serialize();
-                        let text = Text::from(spayload.lines().map(|l| Line::raw(l)).collect::<Vec<Line>>());
-                        let inner: Rect = Rect::new(0, 0, std::cmp::max(40, 2 + text.width() as u16), 6 + 2 + text.height() as u16);
+                        let smsg = msg.serialize();
+                        let area: Rect = block.inner(body_rects[1]);
+                        let text = payload_text(smsg.payload_lines(), *self.highlight);
+                        let text = if *self.wrap { soft_wrap(text, area.width.saturating_sub(2)) } else { text };
+                        let mtext: Option<Text> = if !msg.meta.is_empty() {
+                            Some(Text::from(
+                                msg.meta
+                                    .iter()
+                                    .map(|(key, value)| {
+                                        let mut line = Line::from(Span::from(key.as_str()).bold());
+                                        line.push_span(" = ");
+                                        line.push_span(value.as_str());
+                                        line
+                                    })
+                                    .collect::<Vec<Line>>(),
+                            ))
+                        } else {
+                            None
+                        };
+                        let mtext: Option<Text> = if *self.wrap { mtext.map(|mtext| soft_wrap(mtext, area.width.saturating_sub(2))) } else { mtext };
+                        let mheight: u16 = mtext.as_ref().map(|mtext| 2 + mtext.height() as u16).unwrap_or(0);
+                        let inner: Rect = Rect::new(
+                            0,
+                            0,
+                            if *self.wrap {
+                                area.width
+                            } else {
+                                std::cmp::max(inner_width(&text, 40), mtext.as_ref().map(|mtext| inner_width(mtext, 40)).unwrap_or(0))
+                            },
+                            6 + 2 + text.height() as u16 + mheight,
+                        );
 
                         // Render in a scrolled area
                         frame.render_stateful_widget(
                             ScrollArea::new(inner).render_inner(|mut frame| {
                                 // Prepare the layout
                                 let vrects = Layout::vertical(
-                                    Some(Constraint::Length(1)).into_iter().cycle().take(6).chain(Some(Constraint::Length(2 + text.height() as u16))),
+                                    Some(Constraint::Length(1))
+                                        .into_iter()
+                                        .cycle()
+                                        .take(6)
+                                        .chain(Some(Constraint::Length(2 + text.height() as u16)))
+                                        .chain(Some(Constraint::Length(mheight))),
                                 )
                                 .split(frame.area());
 
@@ -845,7 +1620,7 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Stated by: ");
-                                        text.push_span(Span::from(who.as_ref()).bold());
+                                        text.push_span(agent_span(who, right_color == inactive));
                                         text
                                     })
                                     .fg(right_color),
@@ -854,13 +1629,10 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Stated to: ");
-                                        text.push_span(
-                                            Span::from(match to {
-                                                Recipient::All => "<everyone>",
-                                                Recipient::One(agent) => agent.as_ref(),
-                                            })
-                                            .bold(),
-                                        );
+                                        text.push_span(match to {
+                                            Recipient::All => Span::from("<everyone>").bold(),
+                                            Recipient::One(agent) => agent_span(agent, right_color == inactive),
+                                        });
                                         text
                                     })
                                     .fg(right_color),
@@ -880,7 +1652,7 @@ impl<'s> StateGuard<'s> {
                                 frame.render_widget(
                                     Paragraph::new({
                                         let mut text = Text::from("Message author    : ");
-                                        text.push_span(Span::from(&msg.author_id).bold());
+                                        text.push_span(agent_span(&msg.author_id, right_color == inactive));
                                         text
                                     })
                                     .fg(right_color),
@@ -889,9 +1661,19 @@ impl<'s> StateGuard<'s> {
 
                                 // Render the basis payload
                                 frame.render_widget(
-                                    Paragraph::new(text).fg(right_color).block(Block::bordered().title("Payload").fg(right_color)),
+                                    Paragraph::new(text)
+                                        .fg(right_color)
+                                        .block(Block::bordered().title(format!("Payload ({})", PolicyStats::of(&msg.payload))).fg(right_color)),
                                     vrects[6],
                                 );
+
+                                // Render the metadata, if any
+                                if let Some(mtext) = mtext {
+                                    frame.render_widget(
+                                        Paragraph::new(mtext).fg(right_color).block(Block::bordered().title("Metadata").fg(right_color)),
+                                        vrects[7],
+                                    );
+                                }
                             }),
                             block.inner(body_rects[1]),
                             &mut self.right_scroll,
@@ -910,24 +1692,15 @@ impl<'s> StateGuard<'s> {
                             .collect::<Vec<Line>>();
                         let lines = if !lines.is_empty() { lines } else { vec![Line::from("<no content>")] };
                         let text = Text::from(lines);
-                        let perm: Result<&Permission, &str> = match self
-                            .trace
-                            .iter()
-                            .position(|e| {
-                                if let Event::Control { event: EventControl::EnactAction { action, .. } } = e {
-                                    &action.human_id == context
-                                } else {
-                                    false
-                                }
-                            })
-                            .and_then(|i| self.audit.permission_of(i))
-                        {
-                            Some(Ok(perm)) => Ok(perm),
-                            Some(Err(_)) => Err("FAILED TO EXTRACT POLICY!!!"),
-                            None => Err("NOT FOUND!!!"),
+                        let outcome: ActionOutcome = self.outcome_of_action(context);
+                        let perm: Result<&Permission, &str> = match &outcome {
+                            ActionOutcome::Permitted(perm) | ActionOutcome::Rejected(perm) => Ok(perm),
+                            ActionOutcome::PolicyError(_) => Err("FAILED TO EXTRACT POLICY!!!"),
+                            ActionOutcome::NotAnAction => Err("NOT FOUND!!!"),
                         };
+                        let source: Option<usize> = self.audit.read_source(self.offset + *i);
                         let vrects = Layout::vertical(
-                            [Constraint::Length(1); 5]
+                            [Constraint::Length(1); 6]
                                 .into_iter()
                                 .chain(if perm.is_ok() { Some(Constraint::Length(1)) } else { None }.into_iter())
                                 .chain([Constraint::Length(2 + text.height() as u16)]),
@@ -938,7 +1711,7 @@ impl<'s> StateGuard<'s> {
                         frame.render_widget(
                             Paragraph::new({
                                 let mut text = Text::from("Reader   : ");
-                                text.push_span(Span::from(who.as_ref()).bold());
+                                text.push_span(agent_span(who, right_color == inactive));
                                 text
                             })
                             .fg(right_color),
@@ -947,7 +1720,7 @@ impl<'s> StateGuard<'s> {
                         frame.render_widget(
                             Paragraph::new({
                                 let mut text = Text::from("Variable : ");
-                                text.push_span(Span::from(format!("({} {}) {}", id.0.0, id.0.1, id.1)).bold());
+                                text.push_span(Span::from(id.to_string()).bold());
                                 if contents.is_none() {
                                     text.push_span(" ");
                                     text.push_span(Span::from("NON-EXISTING!!!").bold().white().on_red());
@@ -957,6 +1730,21 @@ impl<'s> StateGuard<'s> {
                             .fg(right_color),
                             vrects[1],
                         );
+                        frame.render_widget(
+                            Paragraph::new({
+                                let mut text = Text::from("Source   : ");
+                                match source {
+                                    Some(source) => {
+                                        text.push_span("reading value written at event ");
+                                        text.push_span(Span::from(format!("{}", source + 1)).bold());
+                                    },
+                                    None => text.push_span(Span::from("<never written>").bold()),
+                                }
+                                text
+                            })
+                            .fg(right_color),
+                            vrects[2],
+                        );
 
                         // Render the context
                         frame.render_widget(
@@ -979,13 +1767,13 @@ impl<'s> StateGuard<'s> {
                                 text
                             })
                             .fg(right_color),
-                            vrects[3],
+                            vrects[4],
                         );
                         if let Ok(perm) = perm {
                             frame.render_widget(
                                 Paragraph::new({
                                     let mut text = Text::from(" - Effect : ");
-                                    let effect: GroundAtom = gen_read_effect(who, id);
+                                    let effect: GroundAtom = effect_reads(who, id);
                                     text.push_span(Span::from(format!("{effect:?}")).bold());
                                     text.push_span(" ");
                                     if <[_]>::iter(&perm.effects).find(|e| e.fact == effect).is_some() {
@@ -995,7 +1783,7 @@ impl<'s> StateGuard<'s> {
                                     }
                                     text
                                 }),
-                                vrects[4],
+                                vrects[5],
                             );
                         }
 
@@ -1003,37 +1791,40 @@ impl<'s> StateGuard<'s> {
                         if contents.is_some() {
                             frame.render_widget(
                                 Paragraph::new(text).block(Block::bordered().title("Contents read").fg(right_color)).fg(right_color),
-                                vrects[if perm.is_ok() { 6 } else { 5 }],
+                                vrects[if perm.is_ok() { 7 } else { 6 }],
                             );
                         }
                     },
-                    EventData::Write { who, id, context, new, contents } => {
+                    EventData::Write { who, id, context, new, contents, labels } => {
                         // Prepare the layout
                         let scontents: Cow<str> = String::from_utf8_lossy(contents);
                         let lines = scontents.lines().map(|l| Line::raw(l.to_string())).collect::<Vec<Line>>();
                         let lines = if !lines.is_empty() { lines } else { vec![Line::from("<no content>")] };
                         let text = Text::from(lines);
-                        let perm: Result<&Permission, &str> = match self
-                            .trace
-                            .iter()
-                            .position(|e| {
-                                if let Event::Control { event: EventControl::EnactAction { action, .. } } = e {
-                                    &action.human_id == context
-                                } else {
-                                    false
-                                }
+                        let mut slabels: Vec<(&String, &String)> = labels.iter().collect();
+                        slabels.sort();
+                        let lines = slabels
+                            .into_iter()
+                            .map(|(key, value)| {
+                                let mut line = Line::from(Span::from(key.as_str()).bold());
+                                line.push_span(" = ");
+                                line.push_span(value.as_str());
+                                line
                             })
-                            .and_then(|i| self.audit.permission_of(i))
-                        {
-                            Some(Ok(perm)) => Ok(perm),
-                            Some(Err(_)) => Err("FAILED TO EXTRACT POLICY!!!"),
-                            None => Err("NOT FOUND!!!"),
+                            .collect::<Vec<Line>>();
+                        let lines = if !lines.is_empty() { lines } else { vec![Line::from("<no labels>")] };
+                        let ltext = Text::from(lines);
+                        let outcome: ActionOutcome = self.outcome_of_action(context);
+                        let perm: Result<&Permission, &str> = match &outcome {
+                            ActionOutcome::Permitted(perm) | ActionOutcome::Rejected(perm) => Ok(perm),
+                            ActionOutcome::PolicyError(_) => Err("FAILED TO EXTRACT POLICY!!!"),
+                            ActionOutcome::NotAnAction => Err("NOT FOUND!!!"),
                         };
                         let vrects = Layout::vertical(
                             [Constraint::Length(1); 5]
                                 .into_iter()
                                 .chain(if perm.is_ok() { Some(Constraint::Length(1)) } else { None }.into_iter())
-                                .chain([Constraint::Length(2 + text.height() as u16)]),
+                                .chain([Constraint::Length(2 + text.height() as u16), Constraint::Length(2 + ltext.height() as u16)]),
                         )
                         .split(block.inner(body_rects[1]));
 
@@ -1041,7 +1832,7 @@ impl<'s> StateGuard<'s> {
                         frame.render_widget(
                             Paragraph::new({
                                 let mut text = Text::from("Writer   : ");
-                                text.push_span(Span::from(who.as_ref()).bold());
+                                text.push_span(agent_span(who, right_color == inactive));
                                 text
                             })
                             .fg(right_color),
@@ -1050,7 +1841,7 @@ impl<'s> StateGuard<'s> {
                         frame.render_widget(
                             Paragraph::new({
                                 let mut text = Text::from("Variable : ");
-                                text.push_span(Span::from(format!("({} {}) {}", id.0.0, id.0.1, id.1)).bold());
+                                text.push_span(Span::from(id.to_string()).bold());
                                 if *new {
                                     text.push_span(" ");
                                     text.push_span(Span::from("(NEW)").bold().cyan());
@@ -1088,7 +1879,7 @@ impl<'s> StateGuard<'s> {
                             frame.render_widget(
                                 Paragraph::new({
                                     let mut text = Text::from(" - Effect : ");
-                                    let effect: GroundAtom = gen_write_effect(who, id);
+                                    let effect: GroundAtom = effect_writes(who, id);
                                     text.push_span(Span::from(format!("{effect:?}")).bold());
                                     text.push_span(" ");
                                     if <[_]>::iter(&perm.effects).find(|e| e.fact == effect).is_some() {
@@ -1102,11 +1893,89 @@ impl<'s> StateGuard<'s> {
                             );
                         }
 
-                        // Render the payload
+                        // Render the payload & its labels
                         frame.render_widget(
                             Paragraph::new(text).block(Block::bordered().title("Contents written").fg(right_color)).fg(right_color),
                             vrects[if perm.is_ok() { 6 } else { 5 }],
                         );
+                        frame.render_widget(
+                            Paragraph::new(ltext).block(Block::bordered().title("Labels").fg(right_color)).fg(right_color),
+                            vrects[if perm.is_ok() { 7 } else { 6 }],
+                        );
+                    },
+                    EventData::Delete { who, id, context } => {
+                        // Prepare the layout
+                        let outcome: ActionOutcome = self.outcome_of_action(context);
+                        let perm: Result<&Permission, &str> = match &outcome {
+                            ActionOutcome::Permitted(perm) | ActionOutcome::Rejected(perm) => Ok(perm),
+                            ActionOutcome::PolicyError(_) => Err("FAILED TO EXTRACT POLICY!!!"),
+                            ActionOutcome::NotAnAction => Err("NOT FOUND!!!"),
+                        };
+                        let vrects = Layout::vertical(
+                            [Constraint::Length(1); 4].into_iter().chain(if perm.is_ok() { Some(Constraint::Length(1)) } else { None }.into_iter()),
+                        )
+                        .split(block.inner(body_rects[1]));
+
+                        // Write the info first
+                        frame.render_widget(
+                            Paragraph::new({
+                                let mut text = Text::from("Deleter  : ");
+                                text.push_span(agent_span(who, right_color == inactive));
+                                text
+                            })
+                            .fg(right_color),
+                            vrects[0],
+                        );
+                        frame.render_widget(
+                            Paragraph::new({
+                                let mut text = Text::from("Variable : ");
+                                text.push_span(Span::from(id.to_string()).bold());
+                                text
+                            })
+                            .fg(right_color),
+                            vrects[1],
+                        );
+
+                        // Render the context
+                        frame.render_widget(
+                            Paragraph::new({
+                                let mut text = Text::from("Justified by : ");
+                                text.push_span(Span::from(format!("{:?}", context)).yellow());
+                                text.push_span(" ");
+                                match perm {
+                                    Ok(perm) => {
+                                        if perm.is_permitted() {
+                                            text.push_span(Span::from("✓").bold().green());
+                                        } else {
+                                            text.push_span(Span::from("✘").bold().white().on_red());
+                                        }
+                                    },
+                                    Err(err) => {
+                                        text.push_span(Span::from(err).bold().white().on_red());
+                                    },
+                                }
+                                text
+                            })
+                            .fg(right_color),
+                            vrects[3],
+                        );
+                        if let Ok(perm) = perm {
+                            frame.render_widget(
+                                Paragraph::new({
+                                    let mut text = Text::from(" - Effect : ");
+                                    let effect: GroundAtom = effect_deletes(who, id);
+                                    text.push_span(Span::from(format!("{effect:?}")).bold());
+                                    text.push_span(" ");
+                                    if <[_]>::iter(&perm.effects).find(|e| e.fact == effect).is_some() {
+                                        text.push_span(Span::from("✓").bold().green());
+                                    } else {
+                                        text.push_span(Span::from("NOT IN ACTION!!!").bold().white().on_red());
+                                    }
+                                    text
+                                }),
+                                vrects[4],
+                            );
+                        }
                     },
                 },
             }
@@ -1115,14 +1984,52 @@ impl<'s> StateGuard<'s> {
 
 
         // Footer
-        if *self.focus == Focus::Event {
-            let hrects = Layout::horizontal([Constraint::Fill(1); 3].as_slice()).split(vrects[2]);
+        if let Some(status) = self.status.as_ref() {
+            render_centered_text(frame, Text::from(status.as_str()).italic(), vrects[2]);
+        } else if let Some(input) = self.open_prompt.as_ref() {
+            let hrects = Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1), Constraint::Fill(1)]).split(vrects[2]);
+
+            frame.render_widget(
+                {
+                    let mut text = Text::from("Open trace file: ");
+                    text.push_span(Span::from(input.as_str()).bold());
+                    text.push_span(Span::from("█").slow_blink());
+                    text
+                },
+                hrects[0],
+            );
+            render_centered_text(frame, press_to("Enter", "open"), hrects[1]);
+            render_centered_text(frame, press_to("Esc", "cancel"), hrects[2]);
+        } else if let Some(input) = self.export_prompt.as_ref() {
+            let hrects = Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1), Constraint::Fill(1)]).split(vrects[2]);
+
+            frame.render_widget(
+                {
+                    let mut text = Text::from("Export payload to: ");
+                    text.push_span(Span::from(input.as_str()).bold());
+                    text.push_span(Span::from("█").slow_blink());
+                    text
+                },
+                hrects[0],
+            );
+            render_centered_text(frame, press_to("Enter", "export"), hrects[1]);
+            render_centered_text(frame, press_to("Esc", "cancel"), hrects[2]);
+        } else if *self.focus == Focus::Event {
+            let is_action: bool = self.opened_action_refs().is_some();
+            let hrects = Layout::horizontal(Some(Constraint::Fill(1)).into_iter().cycle().take(7 + is_action as usize)).split(vrects[2]);
 
             render_centered_text(frame, press_to("Q", "quit"), hrects[0]);
             render_centered_text(frame, press_to("Esc", "close event"), hrects[1]);
-            render_centered_text(frame, press_or_to("Shift+←", "Tab", "switch to list"), hrects[2]);
+            render_centered_text(frame, press_to("Y", "copy as JSON"), hrects[2]);
+            render_centered_text(frame, press_to("E", "export payload"), hrects[3]);
+            render_centered_text(frame, press_to("H", if *self.highlight { "plain payloads" } else { "highlight payloads" }), hrects[4]);
+            render_centered_text(frame, press_to("W", if *self.wrap { "scroll long lines" } else { "wrap long lines" }), hrects[5]);
+            render_centered_text(frame, press_or_to("Shift+←", "Tab", "switch to list"), hrects[6]);
+            if is_action {
+                render_centered_text(frame, press_or_to("B", "1-9", "jump to basis or extra"), hrects[7]);
+            }
         } else {
-            let n_boxes: usize = 2 + self.selected_event.selected().map(|_| 2).unwrap_or(0) + self.opened_event.map(|_| 1).unwrap_or(0);
+            let n_boxes: usize = 3 + self.selected_event.selected().map(|_| 2).unwrap_or(0) + self.opened_event.map(|_| 1).unwrap_or(0);
             let hrects = Layout::horizontal(Some(Constraint::Fill(1)).into_iter().cycle().take(n_boxes)).split(vrects[2]);
 
             let mut i: usize = 0;
@@ -1132,6 +2039,8 @@ impl<'s> StateGuard<'s> {
                 hrects[i],
             );
             i += 1;
+            render_centered_text(frame, press_to("O", "open another trace"), hrects[i]);
+            i += 1;
             if self.selected_event.selected().is_some() {
                 render_centered_text(frame, press_to("Esc", "unselect"), hrects[i]);
                 i += 1;
@@ -1163,6 +2072,50 @@ impl<'s> StateGuard<'s> {
     /// # Errors
     /// This function may error if we failed to handle them properly.
     fn handle_event(&mut self, event: CEvent) -> Result<ControlFlow<()>, Error> {
+        // Any key press dismisses the status message
+        if let CEvent::Key(_) = event {
+            *self.status = None;
+        }
+
+        // If we're prompting for a file to open, then that takes all the input
+        if let Some(input) = self.open_prompt.as_mut() {
+            match event {
+                CEvent::Key(KeyEvent { code: KeyCode::Enter, modifiers: _, kind: KeyEventKind::Press, state: _ }) => {
+                    debug!(target: "Main", "Opening new trace {input:?}");
+                    *self.to_open = self.open_prompt.take();
+                },
+                CEvent::Key(KeyEvent { code: KeyCode::Esc, modifiers: _, kind: KeyEventKind::Press, state: _ }) => *self.open_prompt = None,
+                CEvent::Key(KeyEvent { code: KeyCode::Backspace, modifiers: _, kind: KeyEventKind::Press, state: _ }) => {
+                    input.pop();
+                },
+                CEvent::Key(KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, kind: KeyEventKind::Press, state: _ }) => {
+                    input.push(c)
+                },
+                _ => {},
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        // Same for when we're prompting for a file to export a payload to
+        if let Some(input) = self.export_prompt.as_mut() {
+            match event {
+                CEvent::Key(KeyEvent { code: KeyCode::Enter, modifiers: _, kind: KeyEventKind::Press, state: _ }) => {
+                    debug!(target: "Main", "Exporting payload to {input:?}");
+                    let path: String = self.export_prompt.take().unwrap_or_default();
+                    self.export_opened_payload(&path);
+                },
+                CEvent::Key(KeyEvent { code: KeyCode::Esc, modifiers: _, kind: KeyEventKind::Press, state: _ }) => *self.export_prompt = None,
+                CEvent::Key(KeyEvent { code: KeyCode::Backspace, modifiers: _, kind: KeyEventKind::Press, state: _ }) => {
+                    input.pop();
+                },
+                CEvent::Key(KeyEvent { code: KeyCode::Char(c), modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT, kind: KeyEventKind::Press, state: _ }) => {
+                    input.push(c)
+                },
+                _ => {},
+            }
+            return Ok(ControlFlow::Continue(()));
+        }
+
         match event {
             // List management (Enter, Up, Down, Esc)
             CEvent::Key(KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
@@ -1218,13 +2171,31 @@ impl<'s> StateGuard<'s> {
                 Ok(ControlFlow::Continue(()))
             },
             CEvent::Key(KeyEvent { code: KeyCode::Left, modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
-                if *self.focus == Focus::Event {
+                if *self.focus == Focus::Event && !*self.wrap {
                     self.right_scroll.scroll_left();
                 }
                 Ok(ControlFlow::Continue(()))
             },
-            CEvent::Key(KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+            CEvent::Key(KeyEvent { code: KeyCode::Left, modifiers: KeyModifiers::CONTROL, kind: KeyEventKind::Press, state: _ }) => {
+                if *self.focus == Focus::Event && !*self.wrap {
+                    self.right_scroll.scroll_to_start();
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+            CEvent::Key(KeyEvent { code: KeyCode::Home, modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
                 if *self.focus == Focus::Event {
+                    self.right_scroll.scroll_to_top();
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+            CEvent::Key(KeyEvent { code: KeyCode::End, modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                if *self.focus == Focus::Event {
+                    self.right_scroll.scroll_to_bottom();
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+            CEvent::Key(KeyEvent { code: KeyCode::Right, modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                if *self.focus == Focus::Event && !*self.wrap {
                     self.right_scroll.scroll_right();
                 }
                 Ok(ControlFlow::Continue(()))
@@ -1270,6 +2241,64 @@ impl<'s> StateGuard<'s> {
                 Ok(ControlFlow::Continue(()))
             },
 
+            // (O)pen another trace
+            CEvent::Key(KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event 'o'");
+                *self.open_prompt = Some(String::new());
+                Ok(ControlFlow::Continue(()))
+            },
+
+            // Jump to the (b)asis or one of the extra messages of the opened action
+            CEvent::Key(KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event 'b'");
+                if let Some((basis, _)) = self.opened_action_refs() {
+                    self.jump_to(IdKind::Message, &basis);
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+            CEvent::Key(KeyEvent { code: KeyCode::Char(c @ '1'..='9'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event '{c}'");
+                if let Some((_, extra)) = self.opened_action_refs() {
+                    match extra.get((c as usize) - ('1' as usize)) {
+                        Some(id) => self.jump_to(IdKind::Message, id),
+                        None => *self.status = Some(format!("The action only has {} extra message(s)", extra.len())),
+                    }
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+
+            // Toggle syntax (h)ighlighting of payloads
+            CEvent::Key(KeyEvent { code: KeyCode::Char('h'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event 'h'");
+                *self.highlight = !*self.highlight;
+                Ok(ControlFlow::Continue(()))
+            },
+
+            // Toggle soft-(w)rapping of the right pane
+            CEvent::Key(KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event 'w'");
+                *self.wrap = !*self.wrap;
+                // Any horizontal scroll no longer makes sense (and vertical ones change meaning)
+                self.right_scroll.reset();
+                Ok(ControlFlow::Continue(()))
+            },
+
+            // (Y)ank the opened event
+            CEvent::Key(KeyEvent { code: KeyCode::Char('y'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event 'y'");
+                self.yank_opened_event();
+                Ok(ControlFlow::Continue(()))
+            },
+
+            // (E)xport the payload of the opened event
+            CEvent::Key(KeyEvent { code: KeyCode::Char('e'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
+                debug!(target: "Main", "Received key event 'e'");
+                if self.opened_event.is_some() {
+                    *self.export_prompt = Some(String::new());
+                }
+                Ok(ControlFlow::Continue(()))
+            },
+
             // (Q)uit
             CEvent::Key(KeyEvent { code: KeyCode::Char('q'), modifiers: KeyModifiers::NONE, kind: KeyEventKind::Press, state: _ }) => {
                 debug!(target: "Main", "Quitting...");
@@ -1282,6 +2311,94 @@ impl<'s> StateGuard<'s> {
     }
 }
 
+// Yanking
+impl<'s> StateGuard<'s> {
+    /// Copies the currently opened event as JSON to the system clipboard.
+    ///
+    /// If there is no clipboard available, then the event is written to a file in the temporary
+    /// directory instead. Either way, the [status](StateGuard::status) is updated to tell the user
+    /// where it went. If no event is opened, this function does nothing.
+    fn yank_opened_event(&mut self) {
+        let Some(i) = *self.opened_event else { return };
+        let Some(event) = self.trace.get(i) else { return };
+        let n: usize = self.offset + i + 1;
+        let json: String = match serde_json::to_string_pretty(&event.serialize()) {
+            Ok(json) => json,
+            Err(err) => {
+                error!("{}", toplevel!(("Failed to serialize event {n}"), err));
+                *self.status = Some(format!("Failed to serialize event {n}"));
+                return;
+            },
+        };
+
+        // Try the clipboard first
+        let err = match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(json.as_str())) {
+            Ok(()) => {
+                *self.status = Some(format!("Copied event {n} to clipboard"));
+                return;
+            },
+            Err(err) => err,
+        };
+        debug!(target: "Main", "{}", toplevel!(("Failed to copy event {n} to clipboard, writing it to a file instead"), err));
+
+        // Else, fall back to a file
+        let path: PathBuf = std::env::temp_dir().join(format!("justact-event-{n}.json"));
+        match std::fs::write(&path, json) {
+            Ok(()) => *self.status = Some(format!("Wrote event {n} to {}", path.display())),
+            Err(err) => {
+                error!("{}", toplevel!(("Failed to write event {n} to {:?}", path.display()), err));
+                *self.status = Some(format!("Failed to write event {n} to {}", path.display()));
+            },
+        }
+    }
+}
+
+// Exporting
+impl<'s> StateGuard<'s> {
+    /// Returns the payload of the currently opened event.
+    ///
+    /// # Returns
+    /// The raw bytes of the payload; i.e., the Slick program of a stated message or agreement, or
+    /// the contents of a data read or write. If no event is opened or it doesn't have a payload
+    /// (e.g., it's an action), returns [`None`].
+    fn opened_payload(&self) -> Option<Vec<u8>> {
+        match self.opened_event.and_then(|i| self.trace.get(i))? {
+            Event::Control { event: EventControl::StateMessage { msg, .. } } => Some(msg.serialize().payload.into_bytes()),
+            Event::Control { event: EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } } => {
+                Some(agrees.iter().map(|agree| agree.serialize().payload).collect::<Vec<String>>().join("\n").into_bytes())
+            },
+            Event::Control { .. } => None,
+            Event::Data { event: EventData::Read { contents, .. } } => contents.as_ref().map(|contents| contents.to_vec()),
+            Event::Data { event: EventData::Write { contents, .. } } => Some(contents.to_vec()),
+            Event::Data { event: EventData::Delete { .. } } => None,
+        }
+    }
+
+    /// Writes the payload of the currently opened event to a file.
+    ///
+    /// Payloads are written as-is, so Slick programs end up as text and data contents as whatever
+    /// bytes they were. Either way, the [status](StateGuard::status) is updated to tell the user
+    /// how it went. If no event is opened, this function does nothing.
+    ///
+    /// # Arguments
+    /// - `path`: The path of the file to write the payload to.
+    fn export_opened_payload(&mut self, path: &str) {
+        let Some(i) = *self.opened_event else { return };
+        let n: usize = self.offset + i + 1;
+        let Some(payload) = self.opened_payload() else {
+            *self.status = Some(format!("Event {n} has no payload to export"));
+            return;
+        };
+        match std::fs::write(path, payload) {
+            Ok(()) => *self.status = Some(format!("Exported the payload of event {n} to {path}")),
+            Err(err) => {
+                error!("{}", toplevel!(("Failed to export the payload of event {n} to {path:?}"), err));
+                *self.status = Some(format!("Failed to export the payload of event {n} to {path}"));
+            },
+        }
+    }
+}
+
 // Collecting trace
 impl App {
     /// Thread that will push to the given list of trace once they become available.
@@ -1292,22 +2409,62 @@ impl App {
     /// - `audit`: A shared, running audit that is used to cache validity of actions as they come
     ///   in.
     /// - `sender`: A [`Sender`] used to prompt redraws.
+    /// - `stop`: A [`oneshot::Receiver`] that, once triggered (or once its sender is dropped),
+    ///   makes this thread quit as soon as it would have to wait for new input. Input that is
+    ///   already available is still read (and audited) first, and pacing is skipped for it.
     /// - `what`: Some description of the `input`. Used for debugging only.
     /// - `input`: Some kind of [`Read`]able handle to read new [`Event`]s from.
+    /// - `window`: The range of event indices to push to the `output`. Events before it are only
+    ///   audited, and reading stops once the end of it is reached.
+    /// - `pace`: If given, the time to wait between pushing events to the `output`. Events before
+    ///   the `window` are not paced. Triggering `stop` also cuts the wait short.
     ///
     /// # Returns
-    /// This function will only return once the given `input` closes.
-    async fn trace_reader(
+    /// This function will only return once the given `input` closes, `stop` is triggered and no
+    /// more input is ready, or the end of the `window` is reached.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn trace_reader(
         errors: Arc<Mutex<VecDeque<Error>>>,
         output: Arc<Mutex<Vec<Event<'static, Program>>>>,
-        audit: Arc<Mutex<Audit>>,
+        audit: Arc<Mutex<SlickAudit>>,
         sender: Sender<()>,
+        mut stop: oneshot::Receiver<()>,
         what: String,
         input: impl AsyncRead + Unpin,
+        window: Range<usize>,
+        pace: Option<Duration>,
     ) {
         // Simply iterate over the input stream to collect trace
         let mut stream = EventIter::new(what.clone(), input);
-        while let Some(event) = stream.next().await {
+        let mut i: usize = 0;
+        let mut stopped: bool = false;
+        loop {
+            // Don't bother reading what we won't show
+            if i >= window.end {
+                debug!("Reached the end of the window in {what}");
+                break;
+            }
+
+            // Once stopped, we only read what is available without waiting for it
+            let event = if stopped {
+                match stream.next().now_or_never() {
+                    Some(Some(event)) => event,
+                    Some(None) | None => break,
+                }
+            } else {
+                tokio::select! {
+                    _ = &mut stop => {
+                        debug!("Stopping reading from {what} once no more input is ready");
+                        stopped = true;
+                        continue;
+                    },
+                    event = stream.next() => match event {
+                        Some(event) => event,
+                        None => break,
+                    },
+                }
+            };
+
             // Unwrap it
             match event {
                 Ok(event) => {
@@ -1315,9 +2472,24 @@ impl App {
 
                     // Perform an audit on the trace
                     {
-                        let mut audit: MutexGuard<Audit> = audit.lock();
+                        let mut audit: MutexGuard<SlickAudit> = audit.lock();
                         audit.audit(&event);
                     }
+                    i += 1;
+                    if i <= window.start {
+                        continue;
+                    }
+
+                    // Pretend it's a live run by waiting before every event but the first
+                    if let Some(pace) = pace.filter(|_| !stopped && i > window.start + 1) {
+                        tokio::select! {
+                            _ = tokio::time::sleep(pace) => {},
+                            _ = &mut stop => {
+                                debug!("Stopping reading from {what} while paced");
+                                stopped = true;
+                            },
+                        }
+                    }
 
                     // Add the trace to the output
                     {
@@ -1326,8 +2498,9 @@ impl App {
                     }
 
                     // NOTE: We ignore the result of polling the interface to redraw, because worst
-                    //       case, it simply won't be redrawn
-                    let _ = sender.send(()).await;
+                    //       case, it simply won't be redrawn. This also means we don't block on it
+                    //       when the interface is no longer listening.
+                    let _ = sender.try_send(());
                 },
                 Err(err) => {
                     error!("{}", toplevel!(("Failed to read event from {what}"), err));
@@ -1339,10 +2512,284 @@ impl App {
                     }
 
                     // NOTE: We ignore the result of polling the interface to redraw, because worst
-                    //       case, it simply won't be redrawn
-                    let _ = sender.send(()).await;
+                    //       case, it simply won't be redrawn. This also means we don't block on it
+                    //       when the interface is no longer listening.
+                    let _ = sender.try_send(());
                 },
             }
         }
     }
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::time::{Duration, SystemTime};
+
+    use justact_prototype::auditing::TimedEvent;
+    use justact_prototype::dataplane::VarId;
+    use justact_prototype::policy::slick::text;
+    use justact_prototype::wire::Action;
+
+    use super::*;
+
+
+    #[test]
+    fn test_display_width() {
+        // ASCII is one column per character
+        assert_eq!(display_width(&Text::from("hello")), 5);
+        assert_eq!(inner_width(&Text::from("hello"), 40), 40);
+
+        // Wide characters take up two
+        let text = Text::from(vec![Line::raw("short"), Line::raw("日本語のペイロードはとても長いです、本当に長いです")]);
+        assert_eq!(display_width(&text), 50);
+        assert_eq!(inner_width(&text, 40), 52);
+
+        // Absurdly long lines don't overflow
+        assert_eq!(inner_width(&Text::from("x".repeat(100_000)), 40), u16::MAX);
+    }
+
+    #[test]
+    fn test_soft_wrap() {
+        // Lines are broken at exactly the width, keeping span styles
+        let text = soft_wrap(Text::from(Line::from(vec![Span::from("amy says "), Span::from("hello").bold()])), 6);
+        assert_eq!(text.lines.len(), 3);
+        assert_eq!(text.lines.iter().map(|l| l.to_string()).collect::<Vec<String>>(), ["amy sa", "ys hel", "lo"]);
+        assert_eq!(text.lines[1].spans[1].style, Style::new().bold());
+        assert!(text.lines.iter().all(|l| display_width(&Text::from(l.clone())) <= 6));
+
+        // Wide characters are never split
+        let text = soft_wrap(Text::from("日本語"), 5);
+        assert_eq!(text.lines.iter().map(|l| l.to_string()).collect::<Vec<String>>(), ["日本", "語"]);
+
+        // Short lines and empty ones are untouched
+        let text = soft_wrap(Text::from(vec![Line::raw("foo."), Line::raw("")]), 40);
+        assert_eq!(text.lines.iter().map(|l| l.to_string()).collect::<Vec<String>>(), ["foo.", ""]);
+        assert_eq!(soft_wrap(Text::from("foo."), 0).lines.len(), 1);
+    }
+
+    #[test]
+    fn test_highlight_slick() {
+        let line = highlight_slick("amy says (X ready) if not Y. // Comment");
+        let tokens: Vec<(&str, Option<Color>)> = line.spans.iter().map(|s| (s.content.as_ref(), s.style.fg)).collect();
+        assert_eq!(tokens, vec![
+            ("amy", Some(Color::Green)),
+            (" ", None),
+            ("says", Some(Color::Magenta)),
+            (" ", None),
+            ("(", None),
+            ("X", Some(Color::Cyan)),
+            (" ", None),
+            ("ready", Some(Color::Green)),
+            (")", None),
+            (" ", None),
+            ("if", Some(Color::Magenta)),
+            (" ", None),
+            ("not", Some(Color::Magenta)),
+            (" ", None),
+            ("Y", Some(Color::Cyan)),
+            (".", None),
+            (" ", None),
+            ("// Comment", Some(Color::DarkGray)),
+        ]);
+
+        // Highlighting never changes the text itself
+        assert_eq!(payload_text("(a b).\n// c".lines(), true).lines.len(), 2);
+        assert_eq!(display_width(&payload_text(["(a b)."].into_iter(), true)), display_width(&payload_text(["(a b)."].into_iter(), false)));
+    }
+
+
+    #[tokio::test]
+    async fn test_trace_reader_stop() {
+        let input: String = (0..100)
+            .map(|timestamp| serde_json::to_string(&Event::<str>::Control { event: EventControl::AdvanceTime { timestamp } }).unwrap() + "\n")
+            .collect();
+        let errors = Arc::new(Mutex::new(VecDeque::new()));
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(SlickAudit::new()));
+        let (sender, receiver) = channel(3);
+        let (shutdown, stop) = oneshot::channel();
+
+        // Quit before the reader even started, and without ever listening to redraws
+        shutdown.send(()).unwrap();
+        App::trace_reader(errors.clone(), trace.clone(), audit.clone(), sender, stop, "<test>".into(), input.as_bytes(), 0..usize::MAX, None).await;
+        drop(receiver);
+
+        // Everything that was already available should have made it
+        assert!(errors.lock().is_empty());
+        assert_eq!(trace.lock().len(), 100);
+        assert_eq!(audit.lock().len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_trace_reader_window() {
+        let input: String = (0..100)
+            .map(|timestamp| serde_json::to_string(&Event::<str>::Control { event: EventControl::AdvanceTime { timestamp } }).unwrap() + "\n")
+            .collect();
+        let errors = Arc::new(Mutex::new(VecDeque::new()));
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(SlickAudit::new()));
+        let (sender, _receiver) = channel(100);
+        let (_shutdown, stop) = oneshot::channel();
+        App::trace_reader(errors.clone(), trace.clone(), audit.clone(), sender, stop, "<test>".into(), input.as_bytes(), 10..20, None).await;
+
+        // Only the window is kept, but everything up to it is audited
+        assert!(errors.lock().is_empty());
+        let trace = trace.lock();
+        assert_eq!(trace.len(), 10);
+        assert!(matches!(trace[0], Event::Control { event: EventControl::AdvanceTime { timestamp: 10 } }));
+        assert!(matches!(trace[9], Event::Control { event: EventControl::AdvanceTime { timestamp: 19 } }));
+        assert_eq!(audit.lock().len(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_trace_reader_timed() {
+        let input: String = (0..10)
+            .map(|timestamp| {
+                let event = Event::<str>::Control { event: EventControl::AdvanceTime { timestamp } };
+                serde_json::to_string(&TimedEvent { emitted_at: SystemTime::UNIX_EPOCH + Duration::from_secs(timestamp), event }).unwrap() + "\n"
+            })
+            .collect();
+        let errors = Arc::new(Mutex::new(VecDeque::new()));
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(SlickAudit::new()));
+        let (sender, _receiver) = channel(10);
+        let (_shutdown, stop) = oneshot::channel();
+        App::trace_reader(errors.clone(), trace.clone(), audit.clone(), sender, stop, "<test>".into(), input.as_bytes(), 0..usize::MAX, None).await;
+
+        // The timestamps are simply skipped over
+        assert!(errors.lock().is_empty());
+        let trace = trace.lock();
+        assert_eq!(trace.len(), 10);
+        assert!(matches!(trace[9], Event::Control { event: EventControl::AdvanceTime { timestamp: 9 } }));
+    }
+
+
+    #[tokio::test]
+    async fn test_trace_reader_paced() {
+        let input: String = (0..5)
+            .map(|timestamp| serde_json::to_string(&Event::<str>::Control { event: EventControl::AdvanceTime { timestamp } }).unwrap() + "\n")
+            .collect();
+        let errors = Arc::new(Mutex::new(VecDeque::new()));
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let audit = Arc::new(Mutex::new(SlickAudit::new()));
+        let (sender, _receiver) = channel(10);
+        let (_shutdown, stop) = oneshot::channel();
+
+        // Every event but the first is delayed
+        let start = std::time::Instant::now();
+        App::trace_reader(
+            errors.clone(),
+            trace.clone(),
+            audit.clone(),
+            sender,
+            stop,
+            "<test>".into(),
+            input.as_bytes(),
+            0..usize::MAX,
+            Some(Duration::from_millis(20)),
+        )
+        .await;
+        assert!(start.elapsed() >= Duration::from_millis(80), "Reader finished after only {:?}", start.elapsed());
+        assert_eq!(trace.lock().len(), 5);
+
+        // Stopping doesn't wait for the delay to pass
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        let (sender, _receiver) = channel(10);
+        let (shutdown, stop) = oneshot::channel();
+        let handle = tokio::spawn(App::trace_reader(
+            errors.clone(),
+            trace.clone(),
+            Arc::new(Mutex::new(SlickAudit::new())),
+            sender,
+            stop,
+            "<test>".into(),
+            std::io::Cursor::new(input.into_bytes()),
+            0..usize::MAX,
+            Some(Duration::from_secs(3600)),
+        ));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        shutdown.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), handle).await.expect("Paced reader did not stop promptly").unwrap();
+        assert_eq!(trace.lock().len(), 1);
+    }
+
+
+    #[test]
+    fn test_effect_summary() {
+        let effect = |fact: GroundAtom| -> Effect { Effect { fact, affector: GroundAtom::Constant(text("amy")) } };
+        let (x, y) = (VarId::new("amy", "data", "x"), VarId::new("amy", "data", "y"));
+        let eats = GroundAtom::Tuple(vec![GroundAtom::Constant(text("amy")), GroundAtom::Constant(text("eats")), GroundAtom::Constant(text("x"))]);
+
+        assert_eq!(effect_summary(&[]), "(no effects)");
+        assert_eq!(effect_summary(&[effect(effect_reads("amy", &x))]), "(1 read)");
+        assert_eq!(
+            effect_summary(&[effect(effect_reads("amy", &x)), effect(effect_writes("amy", &x)), effect(effect_writes("amy", &y)), effect(eats)]),
+            "(2 writes, 1 read, 1 other)"
+        );
+    }
+
+
+    #[test]
+    fn test_find_event_by_id() {
+        let msg = |author: &str, id: &str| -> Arc<Message<Program>> {
+            Arc::new(Message { human_id: id.into(), author_id: author.into(), payload: Program { rules: Vec::new() }, meta: BTreeMap::new() })
+        };
+        let agree = msg("consortium", "consortium 1");
+        let stmt = msg("amy", "amy 1");
+        let trace = vec![
+            Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } },
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: Recipient::All, msg: stmt.clone() } },
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     Recipient::All,
+                    action: Action {
+                        human_id: "amy 2".into(),
+                        actor_id: "amy".into(),
+                        basis:    agree.clone(),
+                        extra:    justact::messages::MessageSet::from_iter([stmt.clone(), agree.clone()]),
+                    },
+                },
+            },
+        ];
+        let mut state = State::new(Arc::new(Mutex::new(VecDeque::new())), Arc::new(Mutex::new(trace)), Arc::new(Mutex::new(SlickAudit::new())), 0);
+        let mut state = state.lock();
+
+        // Finding by ID
+        assert_eq!(state.find_event_by_id(IdKind::Message, "consortium 1"), Some(0));
+        assert_eq!(state.find_event_by_id(IdKind::Message, "amy 1"), Some(1));
+        assert_eq!(state.find_event_by_id(IdKind::Action, "amy 2"), Some(2));
+        assert_eq!(state.find_event_by_id(IdKind::Action, "amy 1"), None);
+
+        // Jumping from the action
+        *state.opened_event = Some(2);
+        assert_eq!(state.opened_action_refs(), Some(("consortium 1".into(), vec!["amy 1".into(), "consortium 1".into()])));
+        state.handle_event(CEvent::Key(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE))).unwrap();
+        assert_eq!(*state.opened_event, Some(1));
+        assert_eq!(state.opened_action_refs(), None);
+        *state.opened_event = Some(2);
+        state.handle_event(CEvent::Key(KeyEvent::new(KeyCode::Char('b'), KeyModifiers::NONE))).unwrap();
+        assert_eq!(*state.opened_event, Some(0));
+        assert_eq!(state.selected_event.selected(), Some(0));
+    }
+
+
+    #[test]
+    fn test_agent_color() {
+        // Colors are fixed across runs and builds
+        assert_eq!(agent_color("amy"), Color::LightGreen);
+        assert_eq!(agent_color("bob"), Color::Blue);
+        assert_eq!(agent_color("cho"), Color::LightCyan);
+        assert!(AGENT_PALETTE.contains(&agent_color("consortium")));
+
+        // Focused panes color agents, unfocused ones leave them dimmed
+        assert_eq!(agent_span("amy", false).style.fg, Some(agent_color("amy")));
+        assert_eq!(agent_span("amy", true).style.fg, None);
+    }
+}
@@ -14,17 +14,33 @@
 
 use std::io::ErrorKind;
 
-use justact_prototype::auditing::Event;
-use justact_prototype::policy::slick::Program;
+use justact_prototype::auditing::{BINARY_TRACE_MAGIC, BinaryTraceError, Event, TimedEvent, decode_event_binary};
+use justact_prototype::policy::slick::{Program, SyntaxError};
 use log::debug;
 use thiserror::Error;
-use tokio::io::{AsyncRead, AsyncReadExt as _, BufReader};
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, BufReader};
 
 
 /***** ERRORS *****/
 /// Defines errors yielded by the [`BraceIter`].
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("Frame {frame}: Failed to decode binary event")]
+    BinaryDeserialize {
+        frame: usize,
+        #[source]
+        err:   BinaryTraceError,
+    },
+    #[error("Input looks like a binary trace, but its magic bytes are invalid")]
+    BinaryMagic,
+    #[error("Frame {frame}: Illegal Slick policy string")]
+    BinaryParse {
+        frame: usize,
+        #[source]
+        err:   SyntaxError,
+    },
+    #[error("Frame {frame}: Binary trace ends in the middle of a frame")]
+    BinaryTruncated { frame: usize },
     #[error("{}:{}: Failed to deserialize event", pos.0, pos.1)]
     EventDeserialize {
         pos: (usize, usize),
@@ -35,7 +51,7 @@ pub enum Error {
     EventParse {
         pos: (usize, usize),
         #[source]
-        err: nom::Err<nom::error::VerboseError<String>>,
+        err: SyntaxError,
     },
     #[error("{}:{}: Expected closing brance '}}' for opening brace at {}:{}", close.0, close.1, open.0, open.1 )]
     MissingClosingBrace { open: (usize, usize), close: (usize, usize) },
@@ -53,11 +69,32 @@ pub enum Error {
 
 
 
+/***** HELPER FUNCTIONS *****/
+/// Parses the Slick policy in a raw event.
+///
+/// # Arguments
+/// - `trace`: The [`Event`] with serialized policy.
+///
+/// # Returns
+/// An [`Event`] with parsed policy.
+///
+/// # Errors
+/// This function errors if any of the policy in the `trace` was not valid Slick.
+#[inline]
+fn parse_event(trace: &Event<str>) -> Result<Event<'static, Program>, SyntaxError> { trace.deserialize_slick().map(Event::into_owned) }
+
+
+
+
+
 /***** LIBRARY *****/
 /// Iterator that will read chunks wrapped in `{}` from a given `R`eader.
 ///
 /// Will generate errors if other things were found in between that aren't whitespaces, or those
 /// things in braces aren't [`Event`]s.
+///
+/// Alternatively, if the input starts with the [`BINARY_TRACE_MAGIC`], it is read as a binary
+/// trace instead (see [`write_events_binary()`](justact_prototype::auditing::write_events_binary())).
 pub struct EventIter<R> {
     /// Some description of what we're reading.
     what:   String,
@@ -65,6 +102,12 @@ pub struct EventIter<R> {
     reader: BufReader<R>,
     /// The current line/col pos.
     pos:    (usize, usize),
+    /// Whether we're reading a binary trace, or [`None`] if we don't know yet.
+    binary: Option<bool>,
+    /// The number of binary frames read so far.
+    frame:  usize,
+    /// Whether we gave up reading the input, in which case we yield nothing anymore.
+    done:   bool,
 }
 
 // Constructors
@@ -82,7 +125,7 @@ where
     /// A new BraceIter that will yield every pair of curly braces in the input text, or errors
     /// otherwise.
     #[inline]
-    pub fn new(what: String, input: R) -> Self { Self { what, reader: BufReader::new(input), pos: (1, 0) } }
+    pub fn new(what: String, input: R) -> Self { Self { what, reader: BufReader::new(input), pos: (1, 0), binary: None, frame: 0, done: false } }
 }
 
 // Reading
@@ -117,6 +160,73 @@ where
             Err(err) => return Err(Error::ReaderRead { what: self.what.clone(), err }),
         }
     }
+
+    /// Decides whether we're reading a binary trace, consuming its magic bytes if so.
+    ///
+    /// # Returns
+    /// True if the input is a binary trace, or false if it's (supposed to be) JSON.
+    ///
+    /// # Errors
+    /// This function may error if it failed to read using the backend `R`eader, or if the input
+    /// looked binary but did not start with the [`BINARY_TRACE_MAGIC`].
+    async fn detect_binary(&mut self) -> Result<bool, Error> {
+        // NOTE: JSON traces can only start with whitespace or `{`, so one byte suffices to tell
+        let first: Option<u8> = self.reader.fill_buf().await.map_err(|err| Error::ReaderRead { what: self.what.clone(), err })?.first().copied();
+        if first != Some(BINARY_TRACE_MAGIC[0]) {
+            return Ok(false);
+        }
+        let mut magic: [u8; 8] = [0; 8];
+        match self.reader.read_exact(&mut magic).await {
+            Ok(_) if &magic == BINARY_TRACE_MAGIC => Ok(true),
+            Ok(_) => Err(Error::BinaryMagic),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => Err(Error::BinaryMagic),
+            Err(err) => Err(Error::ReaderRead { what: self.what.clone(), err }),
+        }
+    }
+
+    /// Reads the next frame from a binary trace.
+    ///
+    /// # Returns
+    /// The next [`Event`], or [`None`] if the input ended.
+    ///
+    /// # Errors
+    /// This function may error if it failed to read using the backend `R`eader, if the input
+    /// ended in the middle of the frame or if the frame did not contain a valid event.
+    async fn next_binary(&mut self) -> Option<Result<Event<'static, Program>, Error>> {
+        let frame: usize = self.frame;
+
+        // Only running out at the start of a frame is a clean end
+        match self.reader.fill_buf().await {
+            Ok(buf) if buf.is_empty() => return None,
+            Ok(_) => {},
+            Err(err) => return Some(Err(Error::ReaderRead { what: self.what.clone(), err })),
+        }
+        let mut len: [u8; 4] = [0; 4];
+        match self.reader.read_exact(&mut len).await {
+            Ok(_) => {},
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Some(Err(Error::BinaryTruncated { frame })),
+            Err(err) => return Some(Err(Error::ReaderRead { what: self.what.clone(), err })),
+        }
+
+        // NOTE: We don't allocate the length up front, as it may be anything in a corrupt trace
+        let len: u32 = u32::from_le_bytes(len);
+        let mut raw: Vec<u8> = Vec::new();
+        if let Err(err) = (&mut self.reader).take(len as u64).read_to_end(&mut raw).await {
+            return Some(Err(Error::ReaderRead { what: self.what.clone(), err }));
+        }
+        if raw.len() != len as usize {
+            return Some(Err(Error::BinaryTruncated { frame }));
+        }
+        self.frame += 1;
+
+        // Parse it
+        let trace: Event<str> = match decode_event_binary(&raw) {
+            Ok(trace) => trace,
+            Err(err) => return Some(Err(Error::BinaryDeserialize { frame, err })),
+        };
+        debug!("Found binary trace: {trace:?}");
+        Some(parse_event(&trace).map_err(|err| Error::BinaryParse { frame, err }))
+    }
 }
 
 // Iteration
@@ -126,6 +236,29 @@ where
 {
     /// Yields the next item in the iterator, as long as supply lasts.
     pub async fn next(&mut self) -> Option<Result<Event<'static, Program>, Error>> {
+        if self.done {
+            return None;
+        }
+
+        // Find out what kind of trace we're reading
+        let binary: bool = match self.binary {
+            Some(binary) => binary,
+            None => match self.detect_binary().await {
+                Ok(binary) => {
+                    self.binary = Some(binary);
+                    binary
+                },
+                Err(err) => {
+                    // Don't attempt to make sense of the rest
+                    self.done = true;
+                    return Some(Err(err));
+                },
+            },
+        };
+        if binary {
+            return self.next_binary().await;
+        }
+
         // Start to search for the next '{'
         let mut buf: String = String::with_capacity(32);
         loop {
@@ -159,20 +292,13 @@ where
                                     match serde_json::from_str::<Event<str>>(&buf) {
                                         Ok(trace) => {
                                             // Deserialize the slick program
-                                            let trace: Result<Event<Program>, nom::Err<nom::error::VerboseError<String>>> =
-                                                trace.deserialize().map_err(|err: nom::Err<nom::error::VerboseError<&str>>| {
-                                                    err.map(|err| nom::error::VerboseError {
-                                                        errors: err.errors.into_iter().map(|(src, err)| (src.to_string(), err)).collect(),
-                                                    })
-                                                });
-                                            match trace {
-                                                Ok(trace) => return Some(Ok(trace.into_owned())),
-                                                Err(err) => {
-                                                    return Some(Err(Error::EventParse { pos: open_pos, err }));
-                                                },
-                                            }
+                                            return Some(parse_event(&trace).map_err(|err| Error::EventParse { pos: open_pos, err }));
+                                        },
+                                        // Traces written with the `timestamps`-feature wrap every event
+                                        Err(err) => match serde_json::from_str::<TimedEvent>(&buf) {
+                                            Ok(timed) => return Some(parse_event(&timed.event).map_err(|err| Error::EventParse { pos: open_pos, err })),
+                                            Err(_) => return Some(Err(Error::EventDeserialize { pos: open_pos, err })),
                                         },
-                                        Err(err) => return Some(Err(Error::EventDeserialize { pos: open_pos, err })),
                                     }
                                 }
                             },
@@ -203,3 +329,42 @@ where
         }
     }
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[tokio::test]
+    async fn test_event_iter_bad_magic() {
+        // Starts like a binary trace, but the magic is off; whatever follows must not be read as frames
+        let input: &[u8] = b"JUSTACT\x02\xFF\xFF\xFF\xFFgarbage";
+        let mut iter = EventIter::new("<test>".into(), input);
+        assert!(matches!(iter.next().await, Some(Err(Error::BinaryMagic))));
+        assert!(iter.next().await.is_none());
+        assert!(iter.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_event_iter_truncated() {
+        // A frame claiming to be 4 GiB is not taken at its word
+        let mut input: Vec<u8> = BINARY_TRACE_MAGIC.to_vec();
+        input.extend_from_slice(&u32::MAX.to_le_bytes());
+        input.extend_from_slice(b"garbage");
+        let mut iter = EventIter::new("<test>".into(), input.as_slice());
+        assert!(matches!(iter.next().await, Some(Err(Error::BinaryTruncated { frame: 0 }))));
+        assert!(iter.next().await.is_none());
+
+        // Neither is a trace that ends halfway a frame's length
+        let mut input: Vec<u8> = BINARY_TRACE_MAGIC.to_vec();
+        input.extend_from_slice(&[1, 0]);
+        let mut iter = EventIter::new("<test>".into(), input.as_slice());
+        assert!(matches!(iter.next().await, Some(Err(Error::BinaryTruncated { frame: 0 }))));
+        assert!(iter.next().await.is_none());
+    }
+}
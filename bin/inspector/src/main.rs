@@ -14,9 +14,15 @@
 
 mod app;
 mod event_iter;
+mod template;
+mod time_format;
+mod watch;
 mod widgets;
 
+use std::collections::HashSet;
 use std::io::{Result as IResult, Write};
+use std::ops::Range;
+use std::time::Duration;
 
 use app::App;
 use clap::Parser;
@@ -25,6 +31,8 @@ use humanlog::{ColourChoice, DebugMode, HumanLogger, LogWriter};
 use log::{Level, debug, error, info};
 use parking_lot::lock_api::RawMutex as _;
 use parking_lot::{Mutex, RawMutex};
+use template::RowTemplate;
+use time_format::TimeFormat;
 use tokio::fs::File;
 use tokio::io::{AsyncRead, stdin};
 
@@ -58,6 +66,29 @@ impl Write for &'static LockedWriter {
 
 
 
+/***** HELPER FUNCTIONS *****/
+/// Parses a `--replay-rate`, which must be a positive number.
+///
+/// # Arguments
+/// - `raw`: The value given by the user.
+///
+/// # Returns
+/// The number of events per second.
+///
+/// # Errors
+/// This function errors if `raw` is not a number, or if it's not positive.
+fn parse_replay_rate(raw: &str) -> Result<f64, String> {
+    match raw.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        Ok(rate) => Err(format!("Replay rate must be a positive number of events per second, not {rate}")),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+
+
+
+
 /***** ARGUMENTS *****/
 #[derive(Parser)]
 struct Arguments {
@@ -71,6 +102,31 @@ struct Arguments {
     /// If given, denotes the file to read the traces from. Use `-` to read from stdout instead.
     #[clap(name = "PATH", default_value = "-")]
     path: String,
+    /// If given, does not show the interface but instead prints a line for every action as it comes in.
+    #[clap(short, long)]
+    watch: bool,
+    /// If given, shows message payloads as plain text instead of syntax highlighting them. Can be toggled with `h`.
+    #[clap(long)]
+    no_highlight: bool,
+    /// If given, only shows events from the one with this number onwards (as numbered in the interface). Earlier events are still audited.
+    #[clap(long, value_name = "N")]
+    from: Option<usize>,
+    /// If given, only shows events up to and including the one with this number (as numbered in the interface). Later events aren't read.
+    #[clap(long, value_name = "M")]
+    to: Option<usize>,
+    /// If given, renders every row in the list of events with this template instead (e.g., "{idx} {kind} {actor} {id} {status}").
+    /// Available fields are {idx}, {kind}, {actor}, {id}, {to}, {status} and {summary}.
+    #[clap(long, value_name = "TEMPLATE")]
+    format: Option<RowTemplate>,
+    /// If given, only audits the actions of this actor. Can be given multiple times. Statements and agreements by anyone are still taken into account.
+    #[clap(long = "actor", value_name = "AGENT")]
+    actors: Vec<String>,
+    /// If given, replays the trace at this many events per second instead of showing it as fast as it's read. Useful to demo recorded traces.
+    #[clap(long, value_name = "EVENTS_PER_SEC", value_parser = parse_replay_rate)]
+    replay_rate: Option<f64>,
+    /// How to render timestamps: as-is ("logical"), or as seconds since the unix epoch in a readable ("unix") or ISO 8601 ("iso") format.
+    #[clap(long, value_name = "FORMAT", default_value = "logical")]
+    time_format: TimeFormat,
 }
 
 
@@ -115,9 +171,28 @@ async fn main() {
         })
     };
 
+    // Decide which events to show (the interface numbers them from 1)
+    let window: Range<usize> = args.from.unwrap_or(1).saturating_sub(1)..args.to.unwrap_or(usize::MAX);
+    let actors: Option<HashSet<String>> = if !args.actors.is_empty() { Some(args.actors.into_iter().collect()) } else { None };
+    let pace: Option<Duration> = args.replay_rate.map(|rate| Duration::try_from_secs_f64(1.0 / rate).unwrap_or(Duration::MAX));
+
     // Now run the app
+    if args.watch {
+        debug!("Watching {what}...");
+        if let Err(err) = watch::watch(what, handle, window, actors, pace).await {
+            eprintln!("{}", String::from_utf8_lossy(&STDERR_BUF.0.lock()));
+            error!("{}", err.trace());
+            std::process::exit(1);
+        }
+        eprintln!("{}", String::from_utf8_lossy(&STDERR_BUF.0.lock()));
+        return;
+    }
     debug!("Entering main game loop");
-    if let Err(err) = App::new(what, handle).run().await {
+    let mut app: App = App::new(what, handle, window, actors, pace).with_highlight(!args.no_highlight).with_time_format(args.time_format);
+    if let Some(format) = args.format {
+        app = app.with_format(format);
+    }
+    if let Err(err) = app.run().await {
         eprintln!("{}", String::from_utf8_lossy(&STDERR_BUF.0.lock()));
         error!("{}", err.trace());
         std::process::exit(1);
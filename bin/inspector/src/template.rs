@@ -0,0 +1,168 @@
+//  TEMPLATE.rs
+//    by Lut99
+//
+//  Description:
+//!   Defines templates for the rows in the list of events, such that users
+//!   can choose the information shown there.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Defines the errors emitted when parsing a [`RowTemplate`].
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unclosed field starting at character {pos} (missing '}}')")]
+    UnclosedField { pos: usize },
+    #[error("Unexpected '}}' at character {pos} (use '}}}}' to write a literal one)")]
+    UnopenedField { pos: usize },
+    #[error("Unknown field {{{name}}} (expected one of {})", RowField::ALL.iter().map(|f| format!("{{{f}}}")).collect::<Vec<String>>().join(", "))]
+    UnknownField { name: String },
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines the fields that can be used in a [`RowTemplate`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RowField {
+    /// The (one-indexed) number of the event in the trace.
+    Idx,
+    /// What happened (e.g., `state`, `enact` or `read`).
+    Kind,
+    /// The agent doing it.
+    Actor,
+    /// The identifier of the message, action or variable that it happened to.
+    Id,
+    /// Who it happened to (for stated messages and enacted actions).
+    To,
+    /// Whether it was permitted (for enacted actions and dataplane events).
+    Status,
+    /// A short summary of the payload (for stated messages).
+    Summary,
+}
+impl RowField {
+    /// All fields, in the order they are listed to users.
+    pub const ALL: [Self; 7] = [Self::Idx, Self::Kind, Self::Actor, Self::Id, Self::To, Self::Status, Self::Summary];
+
+    /// Returns the name of this field as it is written in templates.
+    ///
+    /// # Returns
+    /// A static string with the name (without braces).
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Idx => "idx",
+            Self::Kind => "kind",
+            Self::Actor => "actor",
+            Self::Id => "id",
+            Self::To => "to",
+            Self::Status => "status",
+            Self::Summary => "summary",
+        }
+    }
+}
+impl Display for RowField {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "{}", self.name()) }
+}
+impl FromStr for RowField {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|f| f.name() == s).ok_or_else(|| Error::UnknownField { name: s.into() })
+    }
+}
+
+
+
+/// Defines a piece of a [`RowTemplate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RowPiece {
+    /// Some text that is copied as-is.
+    Literal(String),
+    /// Some field that is replaced by its value for every event.
+    Field(RowField),
+}
+
+/// Defines a template for a row in the list of events.
+///
+/// Templates are strings with fields in braces (e.g., `"{idx} {kind} {actor} {id} {status}"`).
+/// Literal braces can be written by doubling them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RowTemplate {
+    /// The pieces making up the template, in order.
+    pub pieces: Vec<RowPiece>,
+}
+impl FromStr for RowTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pieces: Vec<RowPiece> = Vec::new();
+        let mut lit: String = String::new();
+        let mut chars = s.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' if chars.peek().map(|(_, c)| *c) == Some('{') => {
+                    chars.next();
+                    lit.push('{');
+                },
+                '}' if chars.peek().map(|(_, c)| *c) == Some('}') => {
+                    chars.next();
+                    lit.push('}');
+                },
+                '{' => {
+                    let Some(len) = s[i + 1..].find('}') else { return Err(Error::UnclosedField { pos: i }) };
+                    let field: RowField = s[i + 1..i + 1 + len].trim().parse()?;
+                    if !lit.is_empty() {
+                        pieces.push(RowPiece::Literal(std::mem::take(&mut lit)));
+                    }
+                    pieces.push(RowPiece::Field(field));
+                    while chars.next_if(|(j, _)| *j <= i + 1 + len).is_some() {}
+                },
+                '}' => return Err(Error::UnopenedField { pos: i }),
+                c => lit.push(c),
+            }
+        }
+        if !lit.is_empty() {
+            pieces.push(RowPiece::Literal(lit));
+        }
+        Ok(Self { pieces })
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_row_template_parse() {
+        let tmpl: RowTemplate = "{idx} {kind}: {{{actor}}}".parse().unwrap();
+        assert_eq!(tmpl.pieces, vec![
+            RowPiece::Field(RowField::Idx),
+            RowPiece::Literal(" ".into()),
+            RowPiece::Field(RowField::Kind),
+            RowPiece::Literal(": {".into()),
+            RowPiece::Field(RowField::Actor),
+            RowPiece::Literal("}".into()),
+        ]);
+        assert_eq!("".parse::<RowTemplate>().unwrap().pieces, vec![]);
+
+        assert!(matches!("{idx".parse::<RowTemplate>(), Err(Error::UnclosedField { pos: 0 })));
+        assert!(matches!("idx}".parse::<RowTemplate>(), Err(Error::UnopenedField { pos: 3 })));
+        assert!(matches!("{foo}".parse::<RowTemplate>(), Err(Error::UnknownField { .. })));
+    }
+}
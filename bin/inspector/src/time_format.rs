@@ -0,0 +1,160 @@
+//  TIME FORMAT.rs
+//    by Lut99
+//
+//  Description:
+//!   Defines how the inspector renders the timestamps of a trace, which
+//!   may be logical times or real (unix) ones.
+//
+
+use std::fmt::{Display, Formatter, Result as FResult};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Defines the errors emitted when parsing a [`TimeFormat`].
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Unknown time format {raw:?} (expected one of {})", TimeFormat::ALL.iter().map(|f| format!("{:?}", f.name())).collect::<Vec<String>>().join(", "))]
+    UnknownFormat { raw: String },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Splits a unix timestamp into a UTC date and time.
+///
+/// Based on Howard Hinnant's `civil_from_days`-algorithm.
+///
+/// # Arguments
+/// - `timestamp`: The number of seconds since the unix epoch.
+///
+/// # Returns
+/// A tuple of the year, month (1-12), day (1-31), hour, minute and second.
+fn civil_from_unix(timestamp: u64) -> (u64, u64, u64, u64, u64, u64) {
+    let (days, secs): (u64, u64) = (timestamp / 86400, timestamp % 86400);
+
+    // Shift the epoch to 0000-03-01, s.t. leap days are at the end of the (400-year) era
+    let z: u64 = days + 719468;
+    let era: u64 = z / 146097;
+    let doe: u64 = z - era * 146097;
+    let yoe: u64 = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy: u64 = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp: u64 = (5 * doy + 2) / 153;
+    let day: u64 = doy - (153 * mp + 2) / 5 + 1;
+    let month: u64 = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year: u64 = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day, secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Defines how to render the (`u64`) timestamps in a trace.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TimeFormat {
+    /// Renders timestamps as-is, i.e., as logical times.
+    #[default]
+    Logical,
+    /// Interprets timestamps as seconds since the unix epoch, and renders them as a readable UTC
+    /// date and time (e.g., `2025-01-29 22:06:25 UTC`).
+    Unix,
+    /// Interprets timestamps as seconds since the unix epoch, and renders them in ISO 8601 (e.g.,
+    /// `2025-01-29T22:06:25Z`).
+    Iso,
+}
+impl TimeFormat {
+    /// All formats, in the order they are listed to users.
+    pub const ALL: [Self; 3] = [Self::Logical, Self::Unix, Self::Iso];
+
+    /// Returns the name of this format as it is given on the command-line.
+    ///
+    /// # Returns
+    /// A static string with the name.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Logical => "logical",
+            Self::Unix => "unix",
+            Self::Iso => "iso",
+        }
+    }
+
+    /// Renders a timestamp in this format.
+    ///
+    /// # Arguments
+    /// - `timestamp`: The timestamp to render.
+    ///
+    /// # Returns
+    /// A [`String`] with the rendered timestamp.
+    pub fn format(&self, timestamp: u64) -> String {
+        match self {
+            Self::Logical => timestamp.to_string(),
+            Self::Unix => {
+                let (year, month, day, hour, min, sec) = civil_from_unix(timestamp);
+                format!("{year:04}-{month:02}-{day:02} {hour:02}:{min:02}:{sec:02} UTC")
+            },
+            Self::Iso => {
+                let (year, month, day, hour, min, sec) = civil_from_unix(timestamp);
+                format!("{year:04}-{month:02}-{day:02}T{hour:02}:{min:02}:{sec:02}Z")
+            },
+        }
+    }
+}
+impl Display for TimeFormat {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "{}", self.name()) }
+}
+impl FromStr for TimeFormat {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|f| f.name() == s).ok_or_else(|| Error::UnknownFormat { raw: s.into() })
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    #[test]
+    fn test_time_format_logical() {
+        assert_eq!(TimeFormat::Logical.format(0), "0");
+        assert_eq!(TimeFormat::Logical.format(1738188385), "1738188385");
+    }
+
+    #[test]
+    fn test_time_format_unix() {
+        assert_eq!(TimeFormat::Unix.format(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(TimeFormat::Unix.format(1738188385), "2025-01-29 22:06:25 UTC");
+        // Leap days are handled
+        assert_eq!(TimeFormat::Unix.format(951782400), "2000-02-29 00:00:00 UTC");
+    }
+
+    #[test]
+    fn test_time_format_iso() {
+        assert_eq!(TimeFormat::Iso.format(0), "1970-01-01T00:00:00Z");
+        assert_eq!(TimeFormat::Iso.format(1738188385), "2025-01-29T22:06:25Z");
+        assert_eq!(TimeFormat::Iso.format(4102444799), "2099-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn test_time_format_parse() {
+        for format in TimeFormat::ALL {
+            assert_eq!(format.name().parse::<TimeFormat>().unwrap(), format);
+        }
+        assert!(matches!("rfc3339".parse::<TimeFormat>(), Err(Error::UnknownFormat { raw }) if raw == "rfc3339"));
+    }
+}
@@ -0,0 +1,99 @@
+//  WATCH.rs
+//    by Lut99
+//
+//  Description:
+//!   Implements a non-TUI mode of the `inspector` that simply prints
+//!   the audit of every action as it arrives.
+//
+
+use std::collections::{HashSet, VecDeque};
+use std::io::Write as _;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use error_trace::ErrorTrace as _;
+use justact_prototype::auditing::{ActionOutcome, Event, EventControl};
+use justact_prototype::policy::slick::Program;
+use log::debug;
+use parking_lot::Mutex;
+use tokio::io::AsyncRead;
+use tokio::sync::mpsc::channel;
+use tokio::sync::oneshot;
+
+use crate::app::{App, Error};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Summarizes an audited action in a single line.
+///
+/// # Arguments
+/// - `event`: The [`Event`] to summarize.
+/// - `outcome`: The [`ActionOutcome`] of auditing the `event`.
+///
+/// # Returns
+/// A [`String`] summarizing the event if it was an action, or [`None`] otherwise.
+fn summarize(event: &Event<Program>, outcome: ActionOutcome) -> Option<String> {
+    let Event::Control { event: EventControl::EnactAction { who, action, .. } } = event else { return None };
+    let verdict: String = match outcome {
+        ActionOutcome::Permitted(perm) | ActionOutcome::Rejected(perm) => perm.explain(),
+        ActionOutcome::PolicyError(err) => format!("ILLEGAL (policy error: {err})"),
+        ActionOutcome::NotAnAction => return None,
+    };
+    let now: f64 = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+    Some(format!("[{now:.3}] {who} enacts {:?}: {verdict}", action.human_id))
+}
+
+
+
+
+
+/***** LIBRARY *****/
+/// Tails the given input and prints a one-line summary for every action that arrives.
+///
+/// # Arguments
+/// - `what`: Some name (path or otherwise) that describes the `input` (used for debugging purposes only).
+/// - `input`: Some [`Read`]er from which to read [`Event`]s.
+/// - `window`: The range of (zero-indexed) events to print actions of. See [`App::new()`].
+/// - `actors`: If given, only prints (and audits) the actions of these actors.
+/// - `pace`: If given, waits this long between events. See [`App::new()`].
+///
+/// # Errors
+/// This function errors if we failed to write to stdout.
+pub async fn watch(
+    what: impl Into<String>,
+    input: impl 'static + Send + AsyncRead + Unpin,
+    window: Range<usize>,
+    actors: Option<HashSet<String>>,
+    pace: Option<Duration>,
+) -> Result<(), Error> {
+    let errors = Arc::new(Mutex::new(VecDeque::new()));
+    let trace = Arc::new(Mutex::new(Vec::new()));
+    let audit = Arc::new(Mutex::new(App::new_audit(actors.as_ref())));
+    let (sender, mut receiver) = channel(3);
+    // NOTE: We never stop the reader early, but have to keep the sender alive for it to know that
+    let (_shutdown, stop) = oneshot::channel::<()>();
+    let offset: usize = window.start;
+    tokio::spawn(App::trace_reader(errors.clone(), trace.clone(), audit.clone(), sender, stop, what.into(), input, window, pace));
+
+    // Every time the reader notifies us, print what's new. It drops the sender once the input closes.
+    let mut next: usize = 0;
+    while receiver.recv().await.is_some() {
+        for err in errors.lock().drain(..) {
+            eprintln!("{}", err.trace());
+        }
+
+        let trace = trace.lock();
+        let audit = audit.lock();
+        let mut stdout = std::io::stdout().lock();
+        for (i, event) in trace.iter().enumerate().skip(next) {
+            if let Some(line) = summarize(event, audit.outcome_of(offset + i)) {
+                writeln!(stdout, "{line}").map_err(|err| Error::Stdout { err })?;
+            }
+        }
+        stdout.flush().map_err(|err| Error::Stdout { err })?;
+        next = trace.len();
+    }
+    debug!("Input closed, stopping watch");
+    Ok(())
+}
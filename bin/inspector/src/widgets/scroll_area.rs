@@ -216,6 +216,42 @@ impl ScrollState {
         self.pos.0 = self.pos.0.saturating_sub(n);
         self
     }
+
+
+
+    /// Scrolls the scroll area to the first line, leaving the horizontal scroll as-is.
+    ///
+    /// # Returns
+    /// A mutable reference to Self for chaining.
+    #[inline]
+    pub const fn scroll_to_top(&mut self) -> &mut Self {
+        self.pos.1 = 0;
+        self
+    }
+
+    /// Scrolls the scroll area to the last line, leaving the horizontal scroll as-is.
+    ///
+    /// Since we don't know the size of the contents until rendering, this scrolls as far as
+    /// possible and lets the render clip it to the contents.
+    ///
+    /// # Returns
+    /// A mutable reference to Self for chaining.
+    #[inline]
+    pub const fn scroll_to_bottom(&mut self) -> &mut Self {
+        self.pos.1 = u16::MAX;
+        self
+    }
+
+    /// Scrolls the scroll area to the first character of the lines, leaving the vertical scroll
+    /// as-is.
+    ///
+    /// # Returns
+    /// A mutable reference to Self for chaining.
+    #[inline]
+    pub const fn scroll_to_start(&mut self) -> &mut Self {
+        self.pos.0 = 0;
+        self
+    }
 }
 
 
@@ -307,12 +343,9 @@ impl<'a, F: for<'f> FnOnce(ScrollFrame<'f>)> StatefulWidget for ScrollArea<'a, F
         (self.render_callback)(ScrollFrame { buffer: &mut state.cache, area: inner });
 
         // Now bound the scroll state to not go beyond the inner frame
-        if state.pos.0 + outer.width > self.inner.width {
-            state.pos.0 = state.pos.0.saturating_sub((state.pos.0 + outer.width) - self.inner.width);
-        }
-        if state.pos.1 + outer.height > self.inner.height {
-            state.pos.1 = state.pos.1.saturating_sub((state.pos.1 + outer.height) - self.inner.height);
-        }
+        // NOTE: Written as a `min()` to avoid overflows when scrolled to the far end
+        state.pos.0 = min(state.pos.0, self.inner.width.saturating_sub(outer.width));
+        state.pos.1 = min(state.pos.1, self.inner.height.saturating_sub(outer.height));
 
         // Run the math
         scroll(state.pos, outer, inner, &state.cache, outer_buf);
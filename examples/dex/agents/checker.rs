@@ -0,0 +1,55 @@
+//  CHECKER.rs
+//    by Lut99
+//
+//  Description:
+//!   Implements a checker agent, which decides who may read the data of
+//!   the `worker`.
+//
+
+use justact::collections::Recipient;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::VarId;
+
+use super::{ground_atom, program, var_atom};
+
+
+/***** LIBRARY *****/
+/// Describes a read that a [`checker()`] is willing to authorise.
+#[derive(Clone, Debug)]
+pub struct Grant {
+    /// The variable that may be read.
+    pub var:    VarId,
+    /// The Slick atom of the task for which it may be read.
+    pub task:   String,
+    /// The agent that may read it.
+    pub reader: String,
+}
+
+
+
+/// Builds a checker agent that authorises a fixed list of reads.
+///
+/// For every [`Grant`], the checker waits until the task has been submitted (i.e., someone stated
+/// that it takes the variable as input) and then authorises the reader to read it. Any read not
+/// granted is simply never authorised.
+///
+/// # Arguments
+/// - `id`: The identifier of the checker.
+/// - `grants`: The [`Grant`]s to hand out, in order.
+///
+/// # Returns
+/// A new [`Agent`] programmed to act as a checker.
+pub fn checker(id: &str, grants: impl IntoIterator<Item = Grant>) -> Agent {
+    let mut checker = Agent::new(id.into());
+    let mut prgm = checker.program();
+    for Grant { var, task, reader } in grants {
+        let var = var_atom(&var);
+        prgm.state_on_truth(
+            ground_atom(&format!("{task} has input {var}")),
+            Recipient::All,
+            program(&format!("authorise read of {var} for {task} by {reader}.\n")),
+        );
+    }
+    drop(prgm);
+    checker
+}
@@ -13,18 +13,60 @@
 //!   execute on the `worker`.
 //
 
+use justact::collections::Recipient;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::ScopedStoreHandle;
 
-/***** AUXILLARY *****/
-/// The definition of tasks in a [`Workflow`].
+use super::{Task, ground_atom, program, var_atom};
 
 
+/***** LIBRARY *****/
+/// Returns the Slick atom of the pseudo-task a [`driver()`] uses to download its results.
+///
+/// # Arguments
+/// - `driver`: The identifier of the driver.
+///
+/// # Returns
+/// A string with `(driver end)`.
+#[inline]
+pub fn end_atom(driver: &str) -> String { format!("({driver} end)") }
 
 
 
-/***** LIBRARY *****/
-pub struct Driver {
-    /// The workflow to execute.
-    workflow: Workflow,
-    /// A counter for allocating message identifiers.
-    next_id:  usize,
+/// Builds a driver agent that submits a task to a worker and downloads the result.
+///
+/// Concretely, the driver goes through the following steps:
+/// 1. It states the `task`, i.e., what it takes as input and what output it produces;
+/// 2. It waits for some worker to state it executed the task;
+/// 3. It states an [`end`](end_atom())-task that takes the output of the `task` as input;
+/// 4. Once it has been authorised to read it, it enacts the `end`-task and reads the result.
+///
+/// # Arguments
+/// - `store`: The driver's [`ScopedStoreHandle`] to the dataplane.
+/// - `task`: The [`Task`] to submit. Its [`driver`](Task::driver) is the identifier of this agent.
+/// - `download_id`: The identifier of the action that downloads the result. Actions are numbered
+///   globally by the order in which they are enacted.
+///
+/// # Returns
+/// A new [`Agent`] programmed to act as a driver.
+pub fn driver(store: ScopedStoreHandle, task: &Task, download_id: &str) -> Agent {
+    let end = end_atom(&task.driver);
+    let output = var_atom(&task.output_var());
+
+    let mut driver = Agent::with_store(task.driver.clone(), store);
+    driver
+        .program()
+        // Submit the task
+        .state(Recipient::All, program(&format!("{} has input {}.\n{} has output {}.\n", task.atom(), var_atom(&task.input), task.atom(), task.output)))
+        // Once it's done, announce we'd like to download the result
+        .state_on_truth(ground_atom(&format!("{} executed", task.atom())), Recipient::All, program(&format!("{end} has input {output}.\n{end} executed.\n")))
+        // Then download it once we're allowed to
+        .enact_on_truths([
+            ground_atom(&format!("{} has output {}", task.atom(), task.output)),
+            ground_atom(&format!("{end} executed")),
+            ground_atom(&format!("{} executed", task.atom())),
+            ground_atom(&format!("authorise read of {output} for {end} by {}", task.driver)),
+        ])
+        .read(task.output_var(), download_id);
+    driver
 }
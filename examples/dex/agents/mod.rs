@@ -16,11 +16,77 @@
 pub mod checker;
 pub mod driver;
 pub mod syncer;
+pub mod verifier;
 pub mod worker;
 
 // Imports
+use justact_prototype::dataplane::VarId;
+use slick::{GroundAtom, Program};
+
+
+/***** HELPER FUNCTIONS *****/
+/// Parses a Slick ground atom, panicking if it's invalid.
+///
+/// # Arguments
+/// - `raw`: The Slick text to parse.
+///
+/// # Returns
+/// The parsed [`GroundAtom`].
+#[inline]
+fn ground_atom(raw: &str) -> GroundAtom { slick::parse::ground_atom(raw).unwrap_or_else(|err| panic!("Invalid ground atom {raw:?}: {err}")).1 }
+
+/// Parses a Slick program, panicking if it's invalid.
+///
+/// # Arguments
+/// - `raw`: The Slick text to parse.
+///
+/// # Returns
+/// The parsed [`Program`].
+#[inline]
+fn program(raw: &str) -> Program { slick::parse::program(raw).unwrap_or_else(|err| panic!("Invalid program {raw:?}: {err}")).1 }
+
+/// Formats a variable as the Slick atom identifying it.
+///
+/// # Arguments
+/// - `var`: The [`VarId`] to format.
+///
+/// # Returns
+/// A string with `((owner dataset) name)`.
+#[inline]
+fn var_atom(var: &VarId) -> String { format!("(({} {}) {})", var.owner, var.dataset, var.name) }
+
+
+
 
 
 /***** LIBRARY *****/
-/// Represents a meta-agent abstracting over the individual agents.
-pub enum Agent {}
+/// Describes a task in a workflow that a [driver](driver::driver()) wants a
+/// [worker](worker::worker()) to execute.
+///
+/// For simplicity, every task has a single input and produces a single output.
+#[derive(Clone, Debug)]
+pub struct Task {
+    /// The agent that submitted the task.
+    pub driver: String,
+    /// The name of the task (unique for the driver).
+    pub name:   String,
+    /// The variable the task reads.
+    pub input:  VarId,
+    /// The name of the output the task produces.
+    pub output: String,
+}
+impl Task {
+    /// Returns the Slick atom identifying this task.
+    ///
+    /// # Returns
+    /// A string with `(driver name)`.
+    #[inline]
+    pub fn atom(&self) -> String { format!("({} {})", self.driver, self.name) }
+
+    /// Returns the variable produced by this task.
+    ///
+    /// # Returns
+    /// A [`VarId`] that is owned by the driver and scoped to this task.
+    #[inline]
+    pub fn output_var(&self) -> VarId { VarId::new(self.driver.clone(), self.name.clone(), self.output.clone()) }
+}
@@ -0,0 +1,29 @@
+//  SYNCER.rs
+//    by Lut99
+//
+//  Description:
+//!   Implements the synchronizer agent, which decides on the agreements
+//!   of the DEX examples.
+//
+
+use justact_prototype::agent::Agent;
+
+use super::program;
+
+
+/***** LIBRARY *****/
+/// Builds a synchronizer that agrees on the DEX policy.
+///
+/// The policy is the same as the one used by the consortium in the examples of section 6 of the
+/// paper. In particular, it requires that reading data is authorised by whoever controls it.
+///
+/// # Arguments
+/// - `id`: The identifier of the synchronizer.
+///
+/// # Returns
+/// A new [`Agent`] programmed to act as a synchronizer.
+pub fn syncer(id: &str) -> Agent {
+    let mut syncer = Agent::new(id.into());
+    syncer.program().agree(program(include_str!("../slick/consortium.slick")));
+    syncer
+}
@@ -0,0 +1,102 @@
+//  VERIFIER.rs
+//    by Lut99
+//
+//  Description:
+//!   Implements a verifier agent, which checks that the outputs in the
+//!   dataplane are what a scenario expects them to be.
+//
+
+use justact::collections::Recipient;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::{ScopedStoreHandle, VarId};
+
+use super::{ground_atom, program, var_atom};
+
+
+/***** LIBRARY *****/
+/// Describes an output that a [`verifier()`] checks.
+pub struct Expectation {
+    /// The variable to check.
+    pub var:      VarId,
+    /// Decides whether the contents of the variable are correct.
+    pub expected: Box<dyn Fn(&[u8]) -> bool>,
+}
+
+
+
+/// Returns the Slick atom of the task with which a [`verifier()`] reads its outputs.
+///
+/// # Arguments
+/// - `verifier`: The identifier of the verifier.
+///
+/// # Returns
+/// A string with `(verifier verify)`.
+#[inline]
+pub fn verify_atom(verifier: &str) -> String { format!("({verifier} verify)") }
+
+
+
+/// Builds a verifier agent that asserts the contents of a fixed list of outputs.
+///
+/// Concretely, the verifier goes through the following steps:
+/// 1. It waits until all of the outputs exist;
+/// 2. It states that its [verification task](verify_atom()) takes all of them as input, and that
+///    it has executed it;
+/// 3. Once the outputs have been produced (i.e., the tasks writing them were executed) and their
+///    checkers authorised it to read them for that task, it enacts the execution; and
+/// 4. It reads every output under that action, stating `VAR verified` if it's correct or
+///    `error (VAR incorrect)` if it isn't.
+///
+/// Because the verdict ends up in the trace, a failing expectation shows up as an error in the
+/// inspector like any other policy violation.
+///
+/// # Arguments
+/// - `id`: The identifier of the verifier.
+/// - `store`: The verifier's [`ScopedStoreHandle`] to the dataplane.
+/// - `expectations`: The [`Expectation`]s to check, in order.
+/// - `verify_id`: The identifier of the action that justifies reading the outputs.
+///
+/// # Returns
+/// A new [`Agent`] programmed to act as a verifier.
+pub fn verifier(id: &str, store: ScopedStoreHandle, expectations: impl IntoIterator<Item = Expectation>, verify_id: &str) -> Agent {
+    let expectations: Vec<Expectation> = expectations.into_iter().collect();
+    let task = verify_atom(id);
+    let inputs: Vec<String> = expectations.iter().map(|exp| var_atom(&exp.var)).collect();
+
+    let mut verifier = Agent::with_store(id.into(), store);
+    let mut prgm = verifier.program();
+    prgm
+        // Wait for the outputs, then claim we'll verify them
+        .wait_for_data(expectations.iter().map(|exp| exp.var.clone()))
+        .state(
+            Recipient::All,
+            program(&format!("{}{task} executed.\n", inputs.iter().map(|input| format!("{task} has input {input}.\n")).collect::<String>())),
+        )
+        // Once we're allowed to read them, enact that claim
+        .enact_on_truths(
+            inputs
+                .iter()
+                .zip(&expectations)
+                .flat_map(|(input, exp)| {
+                    [
+                        ground_atom(&format!("{task} has input {input}")),
+                        ground_atom(&format!("({} {}) executed", exp.var.owner, exp.var.dataset)),
+                        ground_atom(&format!("authorise read of {input} for {task} by {id}")),
+                    ]
+                })
+                .chain([ground_atom(&format!("{task} executed"))]),
+        );
+    // Then check the outputs one-by-one
+    for (Expectation { var, expected }, input) in expectations.into_iter().zip(inputs) {
+        prgm.verify(
+            var,
+            verify_id,
+            expected,
+            Recipient::All,
+            program(&format!("{input} verified.\n")),
+            program(&format!("error ({input} incorrect).\n")),
+        );
+    }
+    drop(prgm);
+    verifier
+}
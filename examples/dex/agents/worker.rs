@@ -0,0 +1,89 @@
+//  WORKER.rs
+//    by Lut99
+//
+//  Description:
+//!   Implements a worker agent, which owns some data and executes tasks
+//!   on it when permitted by its `checker`.
+//
+
+use justact::collections::Recipient;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::{ScopedStoreHandle, VarId};
+
+use super::{Task, ground_atom, program, var_atom};
+
+
+/***** LIBRARY *****/
+/// Describes the dataset that a [`worker()`] publishes and the task it executes on it.
+pub struct Job {
+    /// The identifier of the dataset owned by the worker.
+    pub dataset:    VarId,
+    /// The contents of the `dataset`.
+    pub contents:   Vec<u8>,
+    /// The identifier of the action that publishes the `dataset`.
+    pub publish_id: String,
+    /// The [`Task`] to execute.
+    pub task:       Task,
+    /// The identifier of the action that executes the `task`.
+    pub execute_id: String,
+    /// Computes the `task`'s output from its input.
+    pub compute:    Box<dyn Fn(Vec<u8>) -> Vec<u8>>,
+}
+
+
+
+/// Builds a worker agent that publishes a dataset it owns and then executes a task on it.
+///
+/// Concretely, the worker goes through the following steps:
+/// 1. It states that it has the [dataset](Job::dataset), and that its `checker` controls access
+///    to it;
+/// 2. It enacts that statement and writes the dataset's contents to the dataplane;
+/// 3. It waits until a driver has submitted the [task](Job::task) (i.e., stated that the task
+///    takes the dataset as input) _and_ until the checker has authorised the worker to read the
+///    dataset for it, after which the worker states that it has executed the task;
+/// 4. It enacts the execution, reading the dataset and writing the task's output as the result
+///    of [computing](Job::compute) on it.
+///
+/// Note that the agent does not know the identifiers of its own actions in advance, so these have
+/// to be given in the `job`. Actions are numbered globally by the order in which they are
+/// enacted.
+///
+/// # Arguments
+/// - `id`: The identifier of the worker.
+/// - `store`: The worker's [`ScopedStoreHandle`] to the dataplane.
+/// - `checker`: The identifier of the agent that decides who may read the worker's dataset.
+/// - `job`: The [`Job`] describing what to publish and execute.
+///
+/// # Returns
+/// A new [`Agent`] programmed to act as a worker.
+pub fn worker(id: &str, store: ScopedStoreHandle, checker: &str, job: Job) -> Agent {
+    let Job { dataset, contents, publish_id, task, execute_id, compute } = job;
+    let set = format!("({} {})", dataset.owner, dataset.dataset);
+    let authorisation = format!("authorise read of {} for {} by {id}", var_atom(&task.input), task.atom());
+
+    let mut worker = Agent::with_store(id.into(), store);
+    worker
+        .program()
+        // Publish the dataset. We control it, but delegate who gets access to it to the checker.
+        .state(
+            Recipient::All,
+            program(&format!("{set} has output {}.\n{checker} controls {}.\n\n{set} executed.\n", dataset.name, var_atom(&dataset))),
+        )
+        .enact_on_truth(ground_atom(&format!("{set} executed")))
+        .write(dataset, publish_id, contents)
+        // Wait for the task to be submitted and authorised; then claim we executed it
+        .state_on_truths(
+            [ground_atom(&format!("{} has input {}", task.atom(), var_atom(&task.input))), ground_atom(&authorisation)],
+            Recipient::All,
+            program(&format!("{} executed.\n", task.atom())),
+        )
+        // Actually execute it by enacting the claim and doing the work
+        .enact_on_truths([
+            ground_atom(&format!("{} has input {}", task.atom(), var_atom(&task.input))),
+            ground_atom(&format!("{set} executed")),
+            ground_atom(&format!("{} executed", task.atom())),
+            ground_atom(&authorisation),
+        ])
+        .transform(task.input.clone(), task.output_var(), execute_id, compute);
+    worker
+}
@@ -15,6 +15,93 @@
 
 mod agents;
 
+use agents::Task;
+use agents::checker::{Grant, checker};
+use agents::driver::{driver, end_atom};
+use agents::syncer::syncer;
+use agents::verifier::{Expectation, verifier, verify_atom};
+use agents::worker::{Job, worker};
+use clap::Parser;
+use error_trace::toplevel;
+use humanlog::{DebugMode, HumanLogger};
+use justact::runtime::System as _;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::{StoreHandle, VarId};
+use justact_prototype::runtime::System;
+use log::{error, info};
+
+
+/***** ARGUMENTS *****/
+/// The binary's CLI arguments.
+#[derive(Parser)]
+struct Arguments {
+    /// If given, enables additional INFO- and DEBUG-level statements.
+    #[clap(long, global = true)]
+    debug: bool,
+    /// If given, enables additional TRACE-level statements. Implies `--debug`.
+    #[clap(long, global = true)]
+    trace: bool,
+}
+
+
+
+
 
 /***** ENTRYPOINT *****/
-fn main() {}
+fn main() {
+    // Parse args
+    let args = Arguments::parse();
+
+    // Setup the logger
+    if let Err(err) = HumanLogger::terminal(if args.trace {
+        DebugMode::Full
+    } else if args.debug {
+        DebugMode::Debug
+    } else {
+        DebugMode::HumanFriendly
+    })
+    .init()
+    {
+        eprintln!("WARNING: Failed to setup logger: {err} (no logging this session)");
+    }
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Amy would like to count the number of patients in the hospital's dataset
+    let dataset = VarId::new("hospital", "patients-2024", "patients");
+    let task = Task { driver: "amy".into(), name: "count-patients".into(), input: dataset.clone(), output: "num-patients".into() };
+
+    // Create the agents
+    // NOTE: The hospital enacts first (to publish), then to execute Amy's task; only then can Amy
+    //       download the result, after which the auditor verifies it.
+    let dataplane = StoreHandle::new();
+    let amy = driver(dataplane.scope("amy"), &task, "amy 3");
+    let hospital = worker("hospital", dataplane.scope("hospital"), "hospital-checker", Job {
+        dataset:    dataset.clone(),
+        contents:   b"billy bob jones\ncharlie brown\nanakin skywalker".to_vec(),
+        publish_id: "hospital 1".into(),
+        task:       task.clone(),
+        execute_id: "hospital 2".into(),
+        compute:    Box::new(|patients| patients.split(|b| *b == b'\n').count().to_string().into_bytes()),
+    });
+    let hospital_checker = checker("hospital-checker", [
+        Grant { var: dataset, task: task.atom(), reader: "hospital".into() },
+        Grant { var: task.output_var(), task: end_atom(&task.driver), reader: task.driver.clone() },
+        Grant { var: task.output_var(), task: verify_atom("auditor"), reader: "auditor".into() },
+    ]);
+    let auditor = verifier(
+        "auditor",
+        dataplane.scope("auditor"),
+        [Expectation { var: task.output_var(), expected: Box::new(|num_patients| num_patients == b"3") }],
+        "auditor 4",
+    );
+    let consortium = syncer("consortium");
+
+    // Run the runtime!
+    let mut runtime = System::new().stream_to(std::io::stdout());
+    if let Err(err) = runtime.run::<Agent>([amy, hospital, hospital_checker, auditor], consortium) {
+        error!("{}", toplevel!(("Failed to run runtime"), err));
+        std::process::exit(1);
+    }
+
+    // Done!
+}
@@ -0,0 +1,103 @@
+//  SCRIPTED.rs
+//    by Lut99
+//
+//  Description:
+//!   A small example where an agent is written entirely as a script using
+//!   the [`ScriptBuilder`].
+//!
+//!   The consortium agrees that Amy may say hello. Amy waits until that
+//!   is known to her, then says hello to everyone.
+//
+
+use std::convert::Infallible;
+use std::error;
+
+use clap::Parser;
+use error_trace::toplevel;
+use humanlog::{DebugMode, HumanLogger};
+use justact::collections::Recipient;
+use justact::runtime::System as _;
+use justact_prototype::System;
+use justact_prototype::agent::Agent;
+use justact_prototype::auditing::Event;
+use justact_prototype::events::{Script, ScriptBuilder};
+use justact_prototype::io::EventHandler;
+use log::{error, info};
+
+
+/***** HELPERS *****/
+/// An [`EventHandler`] that writes to stdout.
+pub struct StdoutEventHandler;
+impl EventHandler for StdoutEventHandler {
+    #[inline]
+    fn handle(&mut self, event: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        println!("{}", serde_json::to_string(&event).map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })?);
+        Ok(())
+    }
+}
+
+
+
+
+
+/***** ARGUMENTS *****/
+/// The binary's CLI arguments.
+#[derive(Parser)]
+struct Arguments {
+    /// If given, enables additional INFO- and DEBUG-level statements.
+    #[clap(long, global = true)]
+    debug: bool,
+    /// If given, enables additional TRACE-level statements. Implies `--debug`.
+    #[clap(long, global = true)]
+    trace: bool,
+}
+
+
+
+
+
+/***** ENTRYPOINT *****/
+fn main() {
+    // Parse args
+    let args = Arguments::parse();
+
+    // Setup the logger
+    if let Err(err) = HumanLogger::terminal(if args.trace {
+        DebugMode::Full
+    } else if args.debug {
+        DebugMode::Debug
+    } else {
+        DebugMode::HumanFriendly
+    })
+    .init()
+    {
+        eprintln!("WARNING: Failed to setup logger: {err} (no logging this session)");
+    }
+    info!("{} - v{}", env!("CARGO_BIN_NAME"), env!("CARGO_PKG_VERSION"));
+
+    // Setup the event callback
+    justact_prototype::io::register_event_handler(StdoutEventHandler);
+
+    // Amy is fully scripted
+    let amy = ScriptBuilder::new()
+        .wait_for(slick::parse::ground_atom("amy may say hello").unwrap().1)
+        .run(|| -> Result<(), Infallible> {
+            info!("Amy is allowed to say hello!");
+            Ok(())
+        })
+        .state(Recipient::All, slick::parse::program("amy says hello.").unwrap().1)
+        .finish();
+
+    // The consortium just agrees
+    let mut consortium = Agent::new("consortium".into());
+    consortium.program().agree(slick::parse::program("amy may say hello.").unwrap().1);
+
+    // Run the runtime!
+    let mut runtime = System::new();
+    if let Err(err) = runtime.run([Script::new("amy", amy)], consortium) {
+        error!("{}", toplevel!(("Failed to run runtime"), err));
+        std::process::exit(1);
+    }
+
+    // Done!
+}
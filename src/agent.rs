@@ -5,6 +5,11 @@
 //!   Defines a little interface to conveniently build agent scripts.
 //
 
+use std::collections::BTreeMap;
+#[cfg(feature = "dataplane")]
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::convert::Infallible;
 use std::error;
 use std::task::Poll;
 
@@ -14,8 +19,8 @@ use slick::{GroundAtom, Program};
 use thiserror::Error;
 
 #[cfg(feature = "dataplane")]
-use crate::dataplane::ScopedStoreHandle;
-use crate::io::TracingView;
+use crate::dataplane::{ScopedStoreHandle, VarId};
+use crate::io::{Recipient, TracingView, with_registry};
 use crate::policy::slick::Extractor;
 
 mod justact {
@@ -24,7 +29,7 @@ mod justact {
     pub use ::justact::auxillary::Identifiable;
     pub use ::justact::collections::set::{Set, SetAsync, SetSync};
     pub use ::justact::collections::{Recipient, Singleton};
-    pub use ::justact::messages::ConstructableMessage;
+    pub use ::justact::messages::{ConstructableMessage, MessageSet};
 }
 
 
@@ -41,9 +46,19 @@ pub enum Error {
     Extract(#[source] crate::policy::slick::SyntaxError),
     #[error("Failed to interact with the [`View::agreed`]-set.")]
     SetAgreed(#[source] Box<dyn 'static + Send + error::Error>),
+    #[error("Failed to advance the time")]
+    AdvanceTime(#[source] Box<dyn 'static + Send + error::Error>),
+    #[error("Cannot wait at barrier {name:?} outside of a System")]
+    BarrierWithoutSystem { name: String },
     #[error("Failed to interact with the [`View::stated`]-set.")]
     SetStated(#[source] Box<dyn 'static + Send + error::Error>),
     #[cfg(feature = "dataplane")]
+    #[error("Cannot read from non-existing dataset \"{id}\"")]
+    MissingData { id: VarId },
+    #[cfg(feature = "dataplane")]
+    #[error("Dataset \"{id}\" did not become available within {polls} polls")]
+    DataTimeout { id: VarId, polls: u64 },
+    #[cfg(feature = "dataplane")]
     #[error("Failed to interact with the store")]
     Store(#[source] crate::dataplane::Error),
 }
@@ -52,27 +67,152 @@ pub enum Error {
 
 
 
+/***** HELPER FUNCTIONS *****/
+/// Checks whether a message identifier matches a pattern.
+///
+/// # Arguments
+/// - `pattern`: The pattern to match. Every `*` in it matches any (possibly empty) sequence of
+///   characters; everything else has to match literally.
+/// - `id`: The identifier to match against.
+///
+/// # Returns
+/// True if `id` matches `pattern`, or false otherwise.
+fn id_matches(pattern: &str, id: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first: &str = parts.next().unwrap_or("");
+    let Some(mut rest) = id.strip_prefix(first) else { return false };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else { return rest.is_empty() };
+    for part in parts {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+
+
+
+
 /***** HELPERS *****/
+/// Wraps the function used by a [`Step::Transform`] such that we can still [`Debug`] the steps.
+#[cfg(feature = "dataplane")]
+struct Transformer(Box<dyn Fn(Vec<u8>) -> Vec<u8>>);
+#[cfg(feature = "dataplane")]
+impl std::fmt::Debug for Transformer {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "Transformer(..)") }
+}
+
+/// Wraps the predicate used by a [`Step::Verify`] such that we can still [`Debug`] the steps.
+#[cfg(feature = "dataplane")]
+struct Expectation(Box<dyn Fn(&[u8]) -> bool>);
+#[cfg(feature = "dataplane")]
+impl std::fmt::Debug for Expectation {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "Expectation(..)") }
+}
+
+
+/// Wraps the handler installed by a [`Step::Catch`] such that we can still [`Debug`] the steps.
+struct Catcher(Box<dyn FnMut(&Error) -> Recovery>);
+impl std::fmt::Debug for Catcher {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "Catcher(..)") }
+}
+
+/// Wraps the handler installed by a [`Step::OnMessageOnce`] such that we can still [`Debug`] the
+/// steps.
+struct Listener(Box<dyn FnMut(&str, &Program) -> Option<(justact::Recipient<String>, Program)>>);
+impl std::fmt::Debug for Listener {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "Listener(..)") }
+}
+
+
+
 /// Represents a single script step.
 #[derive(Debug)]
 enum Step {
     /// [`AgentProgrammer::agree()`]
     Agree { msg: Program },
-    /// [`AgentProgrammer::state()`]
-    State { to: justact::Recipient<String>, msg: Program },
+    /// [`AgentProgrammer::agree_and_advance_time()`]
+    AgreeAndAdvanceTime { msg: Program, at: u64 },
+    /// [`AgentProgrammer::agree_within()`]
+    AgreeWithin { msg: Program, at: u64, until: u64 },
+    /// [`AgentProgrammer::retract_agreement()`]
+    RetractAgreement { id: String },
+    /// [`AgentProgrammer::advance_time()`]
+    AdvanceTime { at: u64 },
+    /// [`AgentProgrammer::barrier()`]
+    Barrier { name: String },
+    /// [`AgentProgrammer::catch()`]
+    Catch { handler: Catcher },
+    /// [`AgentProgrammer::on_message_once()`]
+    OnMessageOnce { pattern: String, handler: Listener },
+    /// [`AgentProgrammer::state()`], [`AgentProgrammer::state_to_group()`],
+    /// [`AgentProgrammer::state_with_meta()`]
+    State { to: Recipient, msg: Program, meta: BTreeMap<String, String> },
+    /// [`AgentProgrammer::state_at()`]
+    StateAtTime { at: u64, to: Recipient, msg: Program },
     /// [`AgentProgrammer::enact_on_truth()`], [`AgentProgrammer::enact_on_truths()`]
     EnactOnTruths { truths: Vec<GroundAtom> },
     /// [`AgentProgrammer::wait_for_truth()`], [`AgentProgrammer::wait_for_truths()`]
     WaitForTruths { truths: Vec<GroundAtom> },
-    /// [`AgentProgrammer::wait_for_datum()`], [`AgentProgrammer::wait_for_data()`]
+    /// [`AgentProgrammer::relay_until_truth()`], [`AgentProgrammer::relay_until_truths()`]
+    Relay { until: Vec<GroundAtom>, relayed: HashSet<String> },
+    /// [`AgentProgrammer::wait_for_datum()`], [`AgentProgrammer::wait_for_data()`],
+    /// [`AgentProgrammer::wait_for_data_within()`]
     #[cfg(feature = "dataplane")]
-    WaitForData { data: Vec<((String, String), String)> },
+    WaitForData { data: Vec<VarId>, max_polls: Option<u64>, polls: u64 },
     /// [`AgentProgrammer::read()`]
     #[cfg(feature = "dataplane")]
-    Read { target: ((String, String), String), context: String },
-    /// [`AgentProgrammer::write()`]
+    Read { target: VarId, context: String },
+    /// [`AgentProgrammer::write()`], [`AgentProgrammer::write_labelled()`]
+    #[cfg(feature = "dataplane")]
+    Write { target: VarId, context: String, content: Vec<u8>, labels: HashMap<String, String> },
+    /// [`AgentProgrammer::transform()`]
     #[cfg(feature = "dataplane")]
-    Write { target: ((String, String), String), context: String, content: Vec<u8> },
+    Transform { src: VarId, dst: VarId, context: String, f: Transformer },
+    /// [`AgentProgrammer::verify()`]
+    #[cfg(feature = "dataplane")]
+    Verify { target: VarId, context: String, expected: Expectation, to: Recipient, verified: Program, failed: Program },
+}
+impl Step {
+    /// Returns what kind of step this is.
+    ///
+    /// # Returns
+    /// A [`StepKind`] without any of the step's data.
+    #[inline]
+    fn kind(&self) -> StepKind {
+        match self {
+            Self::Agree { .. } => StepKind::Agree,
+            Self::AgreeAndAdvanceTime { .. } => StepKind::AgreeAndAdvanceTime,
+            Self::AgreeWithin { .. } => StepKind::AgreeWithin,
+            Self::RetractAgreement { .. } => StepKind::RetractAgreement,
+            Self::AdvanceTime { .. } => StepKind::AdvanceTime,
+            Self::Barrier { .. } => StepKind::Barrier,
+            Self::Catch { .. } => StepKind::Catch,
+            Self::OnMessageOnce { .. } => StepKind::OnMessageOnce,
+            Self::State { .. } => StepKind::State,
+            Self::StateAtTime { .. } => StepKind::StateAtTime,
+            Self::EnactOnTruths { .. } => StepKind::EnactOnTruths,
+            Self::WaitForTruths { .. } => StepKind::WaitForTruths,
+            Self::Relay { .. } => StepKind::Relay,
+            #[cfg(feature = "dataplane")]
+            Self::WaitForData { .. } => StepKind::WaitForData,
+            #[cfg(feature = "dataplane")]
+            Self::Read { .. } => StepKind::Read,
+            #[cfg(feature = "dataplane")]
+            Self::Write { .. } => StepKind::Write,
+            #[cfg(feature = "dataplane")]
+            Self::Transform { .. } => StepKind::Transform,
+            #[cfg(feature = "dataplane")]
+            Self::Verify { .. } => StepKind::Verify,
+        }
+    }
 }
 
 
@@ -80,6 +220,70 @@ enum Step {
 
 
 /***** AUXILLARY *****/
+/// Decides how an [`Agent`] recovers from a failing step.
+///
+/// See [`AgentProgrammer::catch()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Recovery {
+    /// Skips the failing step and continues with the next one.
+    Continue,
+    /// Drops all remaining steps, i.e., the agent dies gracefully instead of aborting the system.
+    Stop,
+}
+
+
+
+/// Describes what kind of step an [`Agent`] is at, for introspection.
+///
+/// See [`Agent::peek_next_step()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum StepKind {
+    /// [`AgentProgrammer::agree()`]
+    Agree,
+    /// [`AgentProgrammer::agree_and_advance_time()`]
+    AgreeAndAdvanceTime,
+    /// [`AgentProgrammer::agree_within()`]
+    AgreeWithin,
+    /// [`AgentProgrammer::retract_agreement()`]
+    RetractAgreement,
+    /// [`AgentProgrammer::advance_time()`]
+    AdvanceTime,
+    /// [`AgentProgrammer::barrier()`]
+    Barrier,
+    /// [`AgentProgrammer::catch()`]
+    Catch,
+    /// [`AgentProgrammer::on_message_once()`]
+    OnMessageOnce,
+    /// [`AgentProgrammer::state()`], [`AgentProgrammer::state_with_meta()`]
+    State,
+    /// [`AgentProgrammer::state_at()`]
+    StateAtTime,
+    /// [`AgentProgrammer::enact_on_truth()`], [`AgentProgrammer::enact_on_truths()`]
+    EnactOnTruths,
+    /// [`AgentProgrammer::wait_for_truth()`], [`AgentProgrammer::wait_for_truths()`]
+    WaitForTruths,
+    /// [`AgentProgrammer::relay_until_truth()`], [`AgentProgrammer::relay_until_truths()`]
+    Relay,
+    /// [`AgentProgrammer::wait_for_datum()`], [`AgentProgrammer::wait_for_data()`]
+    #[cfg(feature = "dataplane")]
+    WaitForData,
+    /// [`AgentProgrammer::read()`]
+    #[cfg(feature = "dataplane")]
+    Read,
+    /// [`AgentProgrammer::write()`], [`AgentProgrammer::write_labelled()`]
+    #[cfg(feature = "dataplane")]
+    Write,
+    /// [`AgentProgrammer::transform()`]
+    #[cfg(feature = "dataplane")]
+    Transform,
+    /// [`AgentProgrammer::verify()`]
+    #[cfg(feature = "dataplane")]
+    Verify,
+}
+
+
+
 /// Builder-like interface for an [`Agent`].
 ///
 /// This exists to be able to add steps to the agent while being efficient around the ordering of
@@ -107,6 +311,128 @@ impl<'a> AgentProgrammer<'a> {
         self
     }
 
+    /// States a message as an agreement and then advances the time to `at`, both immediately
+    /// once this step is reached.
+    ///
+    /// Use this over [`AgentProgrammer::agree()`] when the agreement should be current right away,
+    /// as there is no chance for agents to act in between setting it and advancing the time.
+    ///
+    /// This is a Synchronizer-only action.
+    ///
+    /// # Arguments
+    /// - `msg`: The message to state as an agreement.
+    /// - `at`: The timestamp to advance the time to.
+    #[inline]
+    pub fn agree_and_advance_time(&mut self, msg: Program, at: u64) -> &mut Self {
+        self.0.push(Step::AgreeAndAdvanceTime { msg, at });
+        self
+    }
+
+    /// States a message as an agreement that is only valid within a window, immediately once this
+    /// step is reached.
+    ///
+    /// Actions based on it are only considered current from `at` up to (but not including)
+    /// `until`. Note that this doesn't advance the time by itself; use
+    /// [`AgentProgrammer::advance_time()`] for that.
+    ///
+    /// This is a Synchronizer-only action.
+    ///
+    /// # Arguments
+    /// - `msg`: The message to state as an agreement.
+    /// - `at`: The first timestamp at which the agreement is current.
+    /// - `until`: The first timestamp at which the agreement is no longer current.
+    #[inline]
+    pub fn agree_within(&mut self, msg: Program, at: u64, until: u64) -> &mut Self {
+        self.0.push(Step::AgreeWithin { msg, at, until });
+        self
+    }
+
+    /// Retracts an agreement previously made by this synchronizer once this step is reached.
+    ///
+    /// Actions that are based on it will no longer be considered based from then on.
+    ///
+    /// This is a Synchronizer-only action.
+    ///
+    /// # Arguments
+    /// - `id`: The (human) identifier of the agreement to retract.
+    #[inline]
+    pub fn retract_agreement(&mut self, id: impl Into<String>) -> &mut Self {
+        self.0.push(Step::RetractAgreement { id: id.into() });
+        self
+    }
+
+    /// Advances the time to `at` once this step is reached.
+    ///
+    /// This is a Synchronizer-only action.
+    ///
+    /// # Arguments
+    /// - `at`: The timestamp to advance the time to.
+    #[inline]
+    pub fn advance_time(&mut self, at: u64) -> &mut Self {
+        self.0.push(Step::AdvanceTime { at });
+        self
+    }
+
+
+
+    /// Installs a handler for errors in any of the steps after this one.
+    ///
+    /// Without a handler, a failing step (e.g., reading a dataset that doesn't exist) makes the
+    /// agent error, which aborts the whole [`System`](crate::System). With one, the handler is
+    /// given the error instead and decides how to [recover](Recovery). Installing another handler
+    /// later replaces this one.
+    ///
+    /// # Arguments
+    /// - `handler`: Some closure that is called with every error and returns how to recover.
+    #[inline]
+    pub fn catch(&mut self, handler: impl 'static + FnMut(&Error) -> Recovery) -> &mut Self {
+        self.0.push(Step::Catch { handler: Catcher(Box::new(handler)) });
+        self
+    }
+
+    /// Installs a handler that reacts exactly once to every stated message matching a pattern.
+    ///
+    /// Once this step is reached, the handler is called during every subsequent poll for each
+    /// stated message in the agent's view of which the identifier matches `id_pattern` and that
+    /// it hasn't been called with before. This replaces keeping track of which messages were
+    /// already reacted to by hand.
+    ///
+    /// Note that handlers only run while the agent still has steps to complete. Hence, follow
+    /// this step with one that waits for the end of the scenario (e.g.,
+    /// [`AgentProgrammer::wait_for_truth()`]).
+    ///
+    /// # Arguments
+    /// - `id_pattern`: The pattern of the message identifiers to react to (e.g., `"amy *"`).
+    ///   Every `*` matches any sequence of characters.
+    /// - `handler`: Some closure that is called with the identifier and the payload of every
+    ///   matching message. If it returns a message, that is stated to the given [`Recipient`].
+    #[inline]
+    pub fn on_message_once(
+        &mut self,
+        id_pattern: impl Into<String>,
+        handler: impl 'static + FnMut(&str, &Program) -> Option<(justact::Recipient<String>, Program)>,
+    ) -> &mut Self {
+        self.0.push(Step::OnMessageOnce { pattern: id_pattern.into(), handler: Listener(Box::new(handler)) });
+        self
+    }
+
+    /// Waits until every agent with a barrier of the same name has reached it.
+    ///
+    /// Agents declare the barriers they will meet at to the [`System`](crate::System) running
+    /// them every time they are polled, i.e., well before they reach them. No barrier is passed
+    /// before every agent has been polled once. Barriers only exist within one System, and are
+    /// one-off: once passed, a barrier stays open. If every remaining agent ends up waiting at a
+    /// barrier that can't be passed anymore, the System fails with a
+    /// [deadlock](crate::runtime::Error::Deadlock).
+    ///
+    /// # Arguments
+    /// - `name`: The name of the barrier to meet at.
+    #[inline]
+    pub fn barrier(&mut self, name: impl Into<String>) -> &mut Self {
+        self.0.push(Step::Barrier { name: name.into() });
+        self
+    }
+
 
 
     /// States a message immediately once this step is reached.
@@ -116,7 +442,56 @@ impl<'a> AgentProgrammer<'a> {
     /// - `msg`: The message to state.
     #[inline]
     pub fn state(&mut self, to: justact::Recipient<String>, msg: Program) -> &mut Self {
-        self.0.push(Step::State { to, msg });
+        self.0.push(Step::State { to: to.into(), msg, meta: BTreeMap::new() });
+        self
+    }
+
+    /// States a message immediately once this step is reached, attaching some metadata to it.
+    ///
+    /// The metadata gives out-of-band context about the message (e.g., that it is a consent
+    /// form). It is not part of the policy, and only ends up in the trace.
+    ///
+    /// # Arguments
+    /// - `to`: The [`Recipient`] encoding who to state to.
+    /// - `msg`: The message to state.
+    /// - `meta`: Some key/value pairs describing the message (e.g., `("purpose", "consent")`).
+    #[inline]
+    pub fn state_with_meta<S1: Into<String>, S2: Into<String>>(
+        &mut self,
+        to: justact::Recipient<String>,
+        msg: Program,
+        meta: impl IntoIterator<Item = (S1, S2)>,
+    ) -> &mut Self {
+        self.0.push(Step::State { to: to.into(), msg, meta: meta.into_iter().map(|(key, value)| (key.into(), value.into())).collect() });
+        self
+    }
+
+    /// States a message once the agent's [clock](Agent::time()) reaches the given timestamp.
+    ///
+    /// If the timestamp already lies in the past by the time this step is reached, the message is
+    /// stated immediately.
+    ///
+    /// # Arguments
+    /// - `at`: The timestamp at which to state the message.
+    /// - `to`: The [`Recipient`] encoding who to state to.
+    /// - `msg`: The message to state.
+    #[inline]
+    pub fn state_at(&mut self, at: u64, to: justact::Recipient<String>, msg: Program) -> &mut Self {
+        self.0.push(Step::StateAtTime { at, to: to.into(), msg });
+        self
+    }
+
+    /// States a message to every member of a group immediately once this step is reached.
+    ///
+    /// The group is resolved when the message is stated, and must be defined by then (see
+    /// [`System::define_group()`](crate::System::define_group())).
+    ///
+    /// # Arguments
+    /// - `group`: The name of the group to state to.
+    /// - `msg`: The message to state.
+    #[inline]
+    pub fn state_to_group(&mut self, group: impl Into<String>, msg: Program) -> &mut Self {
+        self.0.push(Step::State { to: Recipient::Group(group.into()), msg, meta: BTreeMap::new() });
         self
     }
 
@@ -129,7 +504,7 @@ impl<'a> AgentProgrammer<'a> {
     #[inline]
     pub fn state_on_truth(&mut self, truth: GroundAtom, to: justact::Recipient<String>, msg: Program) -> &mut Self {
         self.0.push(Step::WaitForTruths { truths: Vec::from([truth]) });
-        self.0.push(Step::State { to, msg });
+        self.0.push(Step::State { to: to.into(), msg, meta: BTreeMap::new() });
         self
     }
 
@@ -143,7 +518,7 @@ impl<'a> AgentProgrammer<'a> {
     #[inline]
     pub fn state_on_truths(&mut self, truths: impl IntoIterator<Item = GroundAtom>, to: justact::Recipient<String>, msg: Program) -> &mut Self {
         self.0.push(Step::WaitForTruths { truths: truths.into_iter().collect() });
-        self.0.push(Step::State { to, msg });
+        self.0.push(Step::State { to: to.into(), msg, meta: BTreeMap::new() });
         self
     }
 
@@ -200,14 +575,42 @@ impl<'a> AgentProgrammer<'a> {
         self
     }
 
+
+
+    /// Relays messages until some truth becomes available.
+    ///
+    /// See [`AgentProgrammer::relay_until_truths()`] for more information.
+    ///
+    /// # Arguments
+    /// - `truth`: The truth to watch for.
+    #[inline]
+    pub fn relay_until_truth(&mut self, truth: GroundAtom) -> &mut Self {
+        self.0.push(Step::Relay { until: Vec::from([truth]), relayed: HashSet::new() });
+        self
+    }
+
+    /// Relays messages until some truths become available.
+    ///
+    /// While relaying, the agent acts as a broker: every message that appears in its view is
+    /// gossiped to all other agents. Every message is relayed only once, so relays cannot keep
+    /// bouncing messages between each other.
+    ///
+    /// # Arguments
+    /// - `truths`: The truths to watch for. Relaying stops once all of them are present.
+    #[inline]
+    pub fn relay_until_truths(&mut self, truths: impl IntoIterator<Item = GroundAtom>) -> &mut Self {
+        self.0.push(Step::Relay { until: truths.into_iter().collect(), relayed: HashSet::new() });
+        self
+    }
+
     /// Wait for a dataset to become available.
     ///
     /// # Arguments
     /// - `data`: The data identifier to watch for.
     #[cfg(feature = "dataplane")]
     #[inline]
-    pub fn wait_for_datum(&mut self, ((data_auth, data_id), data_func): ((impl Into<String>, impl Into<String>), impl Into<String>)) -> &mut Self {
-        self.0.push(Step::WaitForData { data: vec![((data_auth.into(), data_id.into()), data_func.into())] });
+    pub fn wait_for_datum(&mut self, data: impl Into<VarId>) -> &mut Self {
+        self.0.push(Step::WaitForData { data: vec![data.into()], max_polls: None, polls: 0 });
         self
     }
 
@@ -217,16 +620,24 @@ impl<'a> AgentProgrammer<'a> {
     /// - `data`: The list of data identifiers to watch for.
     #[cfg(feature = "dataplane")]
     #[inline]
-    pub fn wait_for_data<S1: Into<String>, S2: Into<String>, S3: Into<String>>(
-        &mut self,
-        data: impl IntoIterator<Item = ((S1, S2), S3)>,
-    ) -> &mut Self {
-        self.0.push(Step::WaitForData {
-            data: data
-                .into_iter()
-                .map(|((target_auth, target_id), target_func)| ((target_auth.into(), target_id.into()), target_func.into()))
-                .collect(),
-        });
+    pub fn wait_for_data(&mut self, data: impl IntoIterator<Item = impl Into<VarId>>) -> &mut Self {
+        self.0.push(Step::WaitForData { data: data.into_iter().map(Into::into).collect(), max_polls: None, polls: 0 });
+        self
+    }
+
+    /// Wait for some datasets to become available, but only for so long.
+    ///
+    /// This is like [`AgentProgrammer::wait_for_data()`], except that the agent fails with an
+    /// [`Error::DataTimeout`] if the data is still missing after `max_polls` polls. Use this to
+    /// make scenarios in which the data never appears fail loudly instead of hang.
+    ///
+    /// # Arguments
+    /// - `data`: The list of data identifiers to watch for.
+    /// - `max_polls`: The number of polls to wait at most.
+    #[cfg(feature = "dataplane")]
+    #[inline]
+    pub fn wait_for_data_within(&mut self, data: impl IntoIterator<Item = impl Into<VarId>>, max_polls: u64) -> &mut Self {
+        self.0.push(Step::WaitForData { data: data.into_iter().map(Into::into).collect(), max_polls: Some(max_polls), polls: 0 });
         self
     }
 
@@ -239,12 +650,8 @@ impl<'a> AgentProgrammer<'a> {
     /// - `context`: The ID of the action that justifies this.
     #[cfg(feature = "dataplane")]
     #[inline]
-    pub fn read(
-        &mut self,
-        ((target_auth, target_id), target_func): ((impl Into<String>, impl Into<String>), impl Into<String>),
-        context: impl Into<String>,
-    ) -> &mut Self {
-        self.0.push(Step::Read { target: ((target_auth.into(), target_id.into()), target_func.into()), context: context.into() });
+    pub fn read(&mut self, target: impl Into<VarId>, context: impl Into<String>) -> &mut Self {
+        self.0.push(Step::Read { target: target.into(), context: context.into() });
         self
     }
 
@@ -256,16 +663,101 @@ impl<'a> AgentProgrammer<'a> {
     /// - `content`: Something to write to the dataset.
     #[cfg(feature = "dataplane")]
     #[inline]
-    pub fn write(
+    pub fn write(&mut self, target: impl Into<VarId>, context: impl Into<String>, content: impl Into<Vec<u8>>) -> &mut Self {
+        self.0.push(Step::Write {
+            target:  target.into(),
+            context: context.into(),
+            content: content.into(),
+            labels:  HashMap::new(),
+        });
+        self
+    }
+
+    /// Write something to a dataset, labelling it in the process.
+    ///
+    /// # Arguments
+    /// - `target`: The name of the data to write to.
+    /// - `context`: The ID of the action that justifies this.
+    /// - `content`: Something to write to the dataset.
+    /// - `labels`: Some key/value pairs describing the dataset (e.g., `("sensitivity", "high")`).
+    #[cfg(feature = "dataplane")]
+    #[inline]
+    pub fn write_labelled<S1: Into<String>, S2: Into<String>>(
         &mut self,
-        ((target_auth, target_id), target_func): ((impl Into<String>, impl Into<String>), impl Into<String>),
+        target: impl Into<VarId>,
         context: impl Into<String>,
         content: impl Into<Vec<u8>>,
+        labels: impl IntoIterator<Item = (S1, S2)>,
     ) -> &mut Self {
         self.0.push(Step::Write {
-            target:  ((target_auth.into(), target_id.into()), target_func.into()),
+            target:  target.into(),
             context: context.into(),
             content: content.into(),
+            labels:  labels.into_iter().map(|(key, value)| (key.into(), value.into())).collect(),
+        });
+        self
+    }
+
+    /// Reads a dataset, computes something on it, and writes the result to another dataset.
+    ///
+    /// This is equivalent to a [`AgentProgrammer::read()`] followed by a
+    /// [`AgentProgrammer::write()`], except that the written contents are computed from the read
+    /// ones.
+    ///
+    /// # Arguments
+    /// - `src`: The name of the data to read from.
+    /// - `dst`: The name of the data to write to.
+    /// - `context`: The ID of the action that justifies both the read and the write.
+    /// - `f`: Some function that computes the contents of `dst` from the contents of `src`.
+    #[cfg(feature = "dataplane")]
+    #[inline]
+    pub fn transform(
+        &mut self,
+        src: impl Into<VarId>,
+        dst: impl Into<VarId>,
+        context: impl Into<String>,
+        f: Box<dyn Fn(Vec<u8>) -> Vec<u8>>,
+    ) -> &mut Self {
+        self.0.push(Step::Transform {
+            src:     src.into(),
+            dst:     dst.into(),
+            context: context.into(),
+            f:       Transformer(f),
+        });
+        self
+    }
+
+    /// Reads a dataset and states whether its contents are what we expect.
+    ///
+    /// This is useful to write scenarios with assertions in them, e.g., to have some agent check
+    /// that a worker computed the right thing. Note that the read is audited like any other, so
+    /// the agent still needs an action that justifies it.
+    ///
+    /// # Arguments
+    /// - `target`: The name of the data to read from.
+    /// - `context`: The ID of the action that justifies this.
+    /// - `expected`: Some predicate that decides whether the contents of `target` are correct.
+    /// - `to`: The [`Recipient`] encoding who to state the verdict to.
+    /// - `verified`: The message to state if `expected` holds for the contents.
+    /// - `failed`: The message to state if it doesn't.
+    #[cfg(feature = "dataplane")]
+    #[inline]
+    pub fn verify(
+        &mut self,
+        target: impl Into<VarId>,
+        context: impl Into<String>,
+        expected: Box<dyn Fn(&[u8]) -> bool>,
+        to: justact::Recipient<String>,
+        verified: Program,
+        failed: Program,
+    ) -> &mut Self {
+        self.0.push(Step::Verify {
+            target: target.into(),
+            context: context.into(),
+            expected: Expectation(expected),
+            to: to.into(),
+            verified,
+            failed,
         });
         self
     }
@@ -284,6 +776,16 @@ pub struct Agent {
     ///
     /// Note, reversed for efficient popping!
     steps: Vec<Step>,
+    /// The time as last advanced to by the synchronizer of the System polling this agent.
+    time:  u64,
+    /// The handler installed by the last [`Step::Catch`], if any.
+    catch: Option<Catcher>,
+    /// The handlers installed by [`Step::OnMessageOnce`], with the pattern of the messages they
+    /// react to.
+    listeners: Vec<(String, Listener)>,
+    /// The messages that every listener has already handled, as pairs of the listener's index and
+    /// the message's identifier.
+    handled: HashSet<(usize, String)>,
     /// Defines the store, if any, to listen for events there.
     #[cfg(feature = "dataplane")]
     store: Option<ScopedStoreHandle>,
@@ -302,10 +804,14 @@ impl Agent {
     /// # Returns
     /// A new Agent that can be programmed with steps.
     #[inline]
-    pub const fn new(id: String) -> Self {
+    pub fn new(id: String) -> Self {
         Self {
             id,
             steps: Vec::new(),
+            time: 0,
+            catch: None,
+            listeners: Vec::new(),
+            handled: HashSet::new(),
             #[cfg(feature = "dataplane")]
             store: None,
         }
@@ -324,7 +830,9 @@ impl Agent {
     /// A new Agent that can be programmed with steps.
     #[cfg(feature = "dataplane")]
     #[inline]
-    pub const fn with_store(id: String, store: ScopedStoreHandle) -> Self { Self { id, steps: Vec::new(), store: Some(store) } }
+    pub fn with_store(id: String, store: ScopedStoreHandle) -> Self {
+        Self { id, steps: Vec::new(), time: 0, catch: None, listeners: Vec::new(), handled: HashSet::new(), store: Some(store) }
+    }
 
 
 
@@ -340,46 +848,342 @@ impl Agent {
     }
 }
 
-// Step processing
+// Time
 impl Agent {
-    /// Processes a single step as if this Agent is an [`Agent`](justact::Agent).
+    /// Returns the current time according to this agent.
     ///
-    /// Hence, synchronizers can call this to handle everything except synchronization.
+    /// This is the timestamp that the synchronizer last
+    /// [advanced the time](AgentProgrammer::advance_time()) to, which the agent learns every time
+    /// it is polled by a [`System`](crate::System). It starts at `0`, and stays there for agents
+    /// that are polled by hand.
     ///
-    /// # Arguments
-    /// - `view`: The [`View`] to interact with the world with.
+    /// # Returns
+    /// The current timestamp.
+    #[inline]
+    pub const fn time(&self) -> u64 { self.time }
+}
+
+// Introspection
+impl Agent {
+    /// Returns how many steps this agent still has to complete.
     ///
     /// # Returns
-    /// A [`Poll`] encoding whether to continue or whether this agent is dead.
+    /// The number of remaining steps. If it's `0`, the agent is done.
+    #[inline]
+    pub fn remaining_steps(&self) -> usize { self.steps.len() }
+
+    /// Returns what kind of step the agent is currently at.
     ///
-    /// # Errors
-    /// This function can error, for sure. Jep.
-    fn process_step<A, S, E, SM, SA>(&mut self, mut view: TracingView<A, S, E>) -> Result<Poll<()>, Error>
-    where
-        A: justact::Set<SM>,
-        S: justact::SetAsync<str, SM>,
-        E: justact::SetAsync<str, SA>,
-        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
-        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
-    {
-        let Some(step) = self.steps.last() else { return Ok(Poll::Ready(())) };
-        match step {
-            Step::State { to: _, msg: _ } => {
-                let Step::State { to, msg } = self.steps.pop().unwrap() else { unreachable!() };
-                let msg = SM::new(self.id.clone(), msg.clone());
+    /// Useful to find out what a stuck agent is waiting for.
+    ///
+    /// # Returns
+    /// The [`StepKind`] of the next step to complete, or [`None`] if the agent is done.
+    #[inline]
+    pub fn peek_next_step(&self) -> Option<StepKind> { self.steps.last().map(Step::kind) }
+}
+
+// Step processing
+impl Agent {
+    /// Gives an error produced by a step to the installed [catch handler](AgentProgrammer::catch()), if any.
+    ///
+    /// # Arguments
+    /// - `failed_step`: The number of steps left before the step that produced `res` was processed,
+    ///   or [`None`] if `res` wasn't produced by a step (e.g., by a
+    ///   [listener](AgentProgrammer::on_message_once())).
+    /// - `res`: The result of processing the step.
+    ///
+    /// # Returns
+    /// `res` if it was OK or if there is no handler, or else a [`Poll`] encoding how the handler
+    /// decided to recover.
+    fn recover(&mut self, failed_step: Option<usize>, res: Result<Poll<()>, Error>) -> Result<Poll<()>, Error> {
+        let err = match res {
+            Ok(res) => return Ok(res),
+            Err(err) => err,
+        };
+        let Some(Catcher(handler)) = &mut self.catch else { return Err(err) };
+        match handler(&err) {
+            Recovery::Continue => {
+                // Some steps fail before they are popped, so make sure we don't retry forever
+                if failed_step == Some(self.steps.len()) {
+                    self.steps.pop();
+                }
+            },
+            Recovery::Stop => self.steps.clear(),
+        }
+        if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+    }
+
+    /// Reads a dataset that is expected to exist.
+    ///
+    /// # Arguments
+    /// - `target`: The name of the data to read from.
+    /// - `context`: The ID of the action that justifies this.
+    ///
+    /// # Returns
+    /// The contents of the dataset.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read from the store, or if the dataset did not exist.
+    ///
+    /// # Panics
+    /// This function panics if this agent has no store.
+    #[cfg(feature = "dataplane")]
+    fn read_existing(&self, target: VarId, context: String) -> Result<Vec<u8>, Error> {
+        match self
+            .store
+            .as_ref()
+            .expect("Cannot read without a store!")
+            .read(target.clone(), context)
+            .map_err(Error::Store)?
+        {
+            Some(contents) => Ok(contents),
+            None => Err(Error::MissingData { id: target }),
+        }
+    }
+
+    /// Checks whether the given truths are all derived by at least one of the agreed or stated
+    /// messages in the agent's view.
+    ///
+    /// Note that every message is considered in isolation.
+    ///
+    /// # Arguments
+    /// - `view`: The [`View`] to find the truths in.
+    /// - `truths`: The truths to look for.
+    ///
+    /// # Returns
+    /// True if all `truths` were found, or false otherwise.
+    ///
+    /// # Errors
+    /// This function errors if we failed to iterate over the agreed or stated messages, or if we
+    /// failed to extract policy from any of them.
+    #[inline]
+    pub(crate) fn truths_known<A, S, E, SM>(view: &TracingView<A, S, E>, truths: &[GroundAtom]) -> Result<bool, Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        Ok(Self::truths_missing(view, truths)?.is_empty())
+    }
+
+    /// Finds which of the given truths are not derived by any of the agreed or stated messages in
+    /// the agent's view.
+    ///
+    /// Note that every message is considered in isolation.
+    ///
+    /// # Arguments
+    /// - `view`: The [`View`] to find the truths in.
+    /// - `truths`: The truths to look for.
+    ///
+    /// # Returns
+    /// The `truths` that were not found. If it's empty, all of them are known.
+    ///
+    /// # Errors
+    /// This function errors if we failed to iterate over the agreed or stated messages, or if we
+    /// failed to extract policy from any of them.
+    fn truths_missing<A, S, E, SM>(view: &TracingView<A, S, E>, truths: &[GroundAtom]) -> Result<Vec<GroundAtom>, Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        let mut truths: Vec<GroundAtom> = truths.to_vec();
+
+        // For all the statements in the view...
+        for stmt in view.0.agreed.iter().map_err(cast).map_err(Error::SetAgreed)?.chain(view.0.stated.iter().map_err(cast).map_err(Error::SetStated)?) {
+            // ...extract the truths from this message...
+            let pol = match Extractor::new().extract(&justact::Singleton(stmt)) {
+                Ok(pol) => pol,
+                Err(err) => return Err(Error::Extract(err)),
+            };
+            let denot = pol.truths();
+
+            // ...and then check if they are all contained
+            let mut to_remove = Vec::new();
+            for (i, truth) in <[_]>::iter(&truths).enumerate() {
+                if denot.contains(truth).unwrap() {
+                    // Remove it from the truths
+                    to_remove.push(i);
+                }
+            }
+            truths = truths
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, truth)| if !<[_]>::contains(&to_remove, &i) { Some(truth) } else { None })
+                .collect();
+            if truths.is_empty() {
+                break;
+            }
+        }
+        Ok(truths)
+    }
+
+    /// Calls the installed [listeners](AgentProgrammer::on_message_once()) with every matching
+    /// message that they haven't handled yet.
+    ///
+    /// # Arguments
+    /// - `view`: The [`View`] to find the messages in and to state any responses to.
+    ///
+    /// # Errors
+    /// This function errors if we failed to iterate over the stated messages, or to state any of
+    /// the responses.
+    fn fire_listeners<A, S, E, SM>(&mut self, view: &mut TracingView<A, S, E>) -> Result<(), Error>
+    where
+        S: justact::SetAsync<str, SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        if self.listeners.is_empty() {
+            return Ok(());
+        }
+
+        // Collect the responses first, as we can't state while iterating
+        let mut responses: Vec<(justact::Recipient<String>, Program)> = Vec::new();
+        for stmt in view.0.stated.iter().map_err(cast).map_err(Error::SetStated)? {
+            for (i, (pattern, Listener(handler))) in self.listeners.iter_mut().enumerate() {
+                if !id_matches(pattern, stmt.human_id()) || !self.handled.insert((i, stmt.human_id().into())) {
+                    continue;
+                }
+                #[cfg(feature = "log")]
+                log::debug!(target: &self.id, "OnMessageOnce: handling {:?}", stmt.human_id());
+                responses.extend(handler(stmt.human_id(), stmt.payload()));
+            }
+        }
+        for (to, msg) in responses {
+            let msg = SM::new(self.id.clone(), msg);
+            view.state(msg.clone()).map_err(cast).map_err(Error::SetStated)?;
+            view.gossip(to.into(), msg).map_err(cast).map_err(Error::SetStated)?;
+        }
+        Ok(())
+    }
+
+    /// Processes a single step as if this Agent is an [`Agent`](justact::Agent).
+    ///
+    /// Hence, synchronizers can call this to handle everything except synchronization. Any
+    /// errors are already given to the [catch handler](AgentProgrammer::catch()).
+    ///
+    /// # Arguments
+    /// - `view`: The [`View`] to interact with the world with.
+    ///
+    /// # Returns
+    /// A [`Poll`] encoding whether to continue or whether this agent is dead.
+    ///
+    /// # Errors
+    /// This function can error, for sure. Jep.
+    fn process_step<A, S, E, SM, SA>(&mut self, mut view: TracingView<A, S, E>) -> Result<Poll<()>, Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
+    {
+        if self.steps.is_empty() {
+            return Ok(Poll::Ready(()));
+        }
+        with_registry(|registry| {
+            if let Some(registry) = registry {
+                self.time = registry.now;
+                let barriers = self.steps.iter().filter_map(|step| if let Step::Barrier { name } = step { Some(name.as_str()) } else { None });
+                registry.declare_barriers(&self.id, barriers);
+            }
+        });
+        if let Err(err) = self.fire_listeners(&mut view) {
+            return self.recover(None, Err(err));
+        }
+        let steps: usize = self.steps.len();
+        let res = self.run_step(view);
+        self.recover(Some(steps), res)
+    }
+
+    /// Runs the current step, without giving errors to the [catch handler](AgentProgrammer::catch()).
+    ///
+    /// # Arguments
+    /// - `view`: The [`View`] to interact with the world with.
+    ///
+    /// # Returns
+    /// A [`Poll`] encoding whether to continue or whether this agent is dead.
+    ///
+    /// # Errors
+    /// This function errors if the step failed.
+    fn run_step<A, S, E, SM, SA>(&mut self, mut view: TracingView<A, S, E>) -> Result<Poll<()>, Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
+    {
+        let Some(step) = self.steps.last() else { return Ok(Poll::Ready(())) };
+        #[cfg(feature = "log")]
+        log::debug!(target: &self.id, "Attempting step {:?} (time {})", step.kind(), self.time);
+        match step {
+            Step::Barrier { name } => {
+                // Wait until everybody is there
+                let passed: bool = with_registry(|registry| {
+                    let registry = registry.ok_or_else(|| Error::BarrierWithoutSystem { name: name.clone() })?;
+                    let passed: bool = registry.reach_barrier(name, &self.id);
+                    #[cfg(feature = "log")]
+                    if !passed {
+                        log::debug!(target: &self.id, "Barrier: waiting for {:?} to reach {name:?}", registry.barriers[name].waiting());
+                    }
+                    Ok(passed)
+                })?;
+                if !passed {
+                    return Ok(Poll::Pending);
+                }
+                self.steps.pop();
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            Step::Catch { .. } => {
+                let Step::Catch { handler } = self.steps.pop().unwrap() else { unreachable!() };
+                self.catch = Some(handler);
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            Step::OnMessageOnce { .. } => {
+                let Step::OnMessageOnce { pattern, handler } = self.steps.pop().unwrap() else { unreachable!() };
+                self.listeners.push((pattern, handler));
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            Step::StateAtTime { at, .. } => {
+                if self.time < *at {
+                    #[cfg(feature = "log")]
+                    log::debug!(target: &self.id, "StateAtTime: waiting until time {at}");
+                    return Ok(Poll::Pending);
+                }
+                let Step::StateAtTime { at: _, to, msg } = self.steps.pop().unwrap() else { unreachable!() };
+                let to: Vec<justact::Recipient<String>> = to.resolve::<Infallible>().map_err(cast).map_err(Error::SetStated)?;
+                let msg = SM::new(self.id.clone(), msg);
                 view.state(msg.clone()).map_err(cast).map_err(Error::SetStated)?;
-                view.gossip(to, msg).map_err(cast).map_err(Error::SetStated)?;
+                for to in to {
+                    view.gossip(to.into(), msg.clone()).map_err(cast).map_err(Error::SetStated)?;
+                }
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            Step::State { .. } => {
+                let Step::State { to, msg, meta } = self.steps.pop().unwrap() else { unreachable!() };
+
+                // Find out who to send it to first, such that nothing is stated if that fails
+                let to: Vec<justact::Recipient<String>> = to.resolve::<Infallible>().map_err(cast).map_err(Error::SetStated)?;
+                let msg = SM::new(self.id.clone(), msg.clone());
+                view.state_with_meta(msg.clone(), meta.clone()).map_err(cast).map_err(Error::SetStated)?;
+                for to in to {
+                    view.gossip_with_meta(to.into(), msg.clone(), meta.clone()).map_err(cast).map_err(Error::SetStated)?;
+                }
 
                 // We still might need a next step, though
                 if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
             },
 
             Step::EnactOnTruths { truths } => {
+                #[cfg(feature = "log")]
+                let total: usize = truths.len();
                 let mut truths = truths.clone();
 
                 // Ensure there is at least one basis
                 let basis = view.0.agreed.iter().map_err(cast).map_err(Error::SetAgreed)?.next();
                 if basis.is_none() {
+                    #[cfg(feature = "log")]
+                    log::debug!(target: &self.id, "EnactOnTruths: no agreement to use as basis yet");
                     return Ok(Poll::Pending);
                 }
 
@@ -387,7 +1191,7 @@ impl Agent {
                 let mut msgs: Vec<SM> = Vec::new();
                 for stmt in view.0.stated.iter().map_err(cast).map_err(Error::SetStated)? {
                     // ...extract the truths from this message...
-                    let pol = match Extractor.extract(&justact::Singleton(stmt)) {
+                    let pol = match Extractor::new().extract(&justact::Singleton(stmt)) {
                         Ok(pol) => pol,
                         Err(err) => return Err(Error::Extract(err)),
                     };
@@ -416,66 +1220,72 @@ impl Agent {
 
                 // If not all truths are found, we need to wait
                 if !truths.is_empty() {
+                    #[cfg(feature = "log")]
+                    log::debug!(target: &self.id, "EnactOnTruths: {} of {} truths present", total - truths.len(), total);
                     return Ok(Poll::Pending);
                 }
 
                 // Now build the action and enact it!
+                // NOTE: The basis must be part of the justification for the action to be based
+                let basis: SM = basis.unwrap().clone();
+                let mut extra: justact::MessageSet<SM> = msgs.into_iter().collect();
+                extra.add(basis.clone());
                 self.steps.pop();
-                view.enact(SA::new(self.id.clone(), basis.unwrap().clone(), msgs.into_iter().collect())).map_err(cast).map_err(Error::SetStated)?;
+                view.enact(SA::new(self.id.clone(), basis, extra)).map_err(cast).map_err(Error::SetStated)?;
 
                 // We still might need a next step, though
                 if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
             },
 
             Step::WaitForTruths { truths } => {
-                let mut truths = truths.clone();
-
-                // For all the statements in the view...
-                for stmt in
-                    view.0.agreed.iter().map_err(cast).map_err(Error::SetAgreed)?.chain(view.0.stated.iter().map_err(cast).map_err(Error::SetStated)?)
-                {
-                    // ...extract the truths from this message...
-                    let pol = match Extractor.extract(&justact::Singleton(stmt)) {
-                        Ok(pol) => pol,
-                        Err(err) => return Err(Error::Extract(err)),
-                    };
-                    let denot = pol.truths();
-
-                    // ...and then check if they are all contained
-                    let mut to_remove = Vec::new();
-                    for (i, truth) in <[_]>::iter(&truths).enumerate() {
-                        if denot.contains(truth).unwrap() {
-                            // Remove it from the truths
-                            to_remove.push(i);
-                        }
-                    }
-                    truths = truths
-                        .into_iter()
-                        .enumerate()
-                        .filter_map(|(i, truth)| if !<[_]>::contains(&to_remove, &i) { Some(truth) } else { None })
-                        .collect();
-                    if truths.is_empty() {
-                        break;
+                // If not all truths are found, we need to wait
+                let missing: Vec<GroundAtom> = Self::truths_missing(&view, truths)?;
+                if !missing.is_empty() {
+                    #[cfg(feature = "log")]
+                    log::debug!(target: &self.id, "WaitForTruths: {} of {} truths present", truths.len() - missing.len(), truths.len());
+                    return Ok(Poll::Pending);
+                }
+                self.steps.pop();
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            Step::Relay { .. } => {
+                // Relay anything we haven't relayed yet
+                let Some(Step::Relay { until: _, relayed }) = self.steps.last_mut() else { unreachable!() };
+                let mut to_relay: Vec<SM> = Vec::new();
+                for stmt in view.0.stated.iter().map_err(cast).map_err(Error::SetStated)? {
+                    if relayed.insert(stmt.human_id().into()) {
+                        to_relay.push(stmt.clone());
                     }
                 }
+                for msg in to_relay {
+                    view.gossip(Recipient::All, msg).map_err(cast).map_err(Error::SetStated)?;
+                }
 
-                // If not all truths are found, we need to wait
-                if truths.is_empty() {
-                    self.steps.pop();
-                    if self.steps.is_empty() {
-                        return Ok(Poll::Ready(()));
-                    }
+                // Keep relaying until the truths are there
+                let Some(Step::Relay { until, relayed: _ }) = self.steps.last() else { unreachable!() };
+                let missing: Vec<GroundAtom> = Self::truths_missing(&view, until)?;
+                if !missing.is_empty() {
+                    #[cfg(feature = "log")]
+                    log::debug!(target: &self.id, "Relay: {} of {} truths present", until.len() - missing.len(), until.len());
+                    return Ok(Poll::Pending);
                 }
-                Ok(Poll::Pending)
+                self.steps.pop();
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
             },
 
             #[cfg(feature = "dataplane")]
-            Step::WaitForData { data } => {
+            Step::WaitForData { .. } => {
                 // Cross 'em out
-                for datum in data {
-                    if !self.store.as_ref().expect("Cannot wait for data without a store!").exists(datum) {
-                        return Ok(Poll::Pending);
-                    }
+                let store = self.store.as_ref().expect("Cannot wait for data without a store!");
+                let Some(Step::WaitForData { data, max_polls, polls }) = self.steps.last_mut() else { unreachable!() };
+                if let Some(datum) = data.iter().find(|datum| !store.exists(datum)) {
+                    *polls += 1;
+                    #[cfg(feature = "log")]
+                    log::debug!(target: &self.id, "WaitForData: {datum:?} does not exist yet (poll {polls})");
+                    return match *max_polls {
+                        Some(max_polls) if *polls > max_polls => Err(Error::DataTimeout { id: datum.clone(), polls: max_polls }),
+                        _ => Ok(Poll::Pending),
+                    };
                 }
                 self.steps.pop();
                 if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
@@ -484,27 +1294,45 @@ impl Agent {
             #[cfg(feature = "dataplane")]
             Step::Read { target: _, context: _ } => {
                 let Step::Read { target, context } = self.steps.pop().unwrap() else { unreachable!() };
-                if self
-                    .store
-                    .as_ref()
-                    .expect("Cannot read without a store!")
-                    .read(((&target.0.0, &target.0.1), &target.1), context)
-                    .map_err(Error::Store)?
-                    .is_none()
-                {
-                    panic!("Cannot read from non-existing dataset (({:?}, {:?}), {:?})", target.0.0, target.0.1, target.1);
-                }
+                self.read_existing(target, context)?;
                 if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
             },
             #[cfg(feature = "dataplane")]
-            Step::Write { target: _, context: _, content: _ } => {
-                let Step::Write { target, context, content } = self.steps.pop().unwrap() else { unreachable!() };
-                self.store.as_ref().expect("Cannot write without a store!").write(target, context, content).map_err(Error::Store)?;
+            Step::Write { .. } => {
+                let Step::Write { target, context, content, labels } = self.steps.pop().unwrap() else { unreachable!() };
+                self.store.as_ref().expect("Cannot write without a store!").write_labelled(target, context, content, labels).map_err(Error::Store)?;
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            #[cfg(feature = "dataplane")]
+            Step::Transform { .. } => {
+                let Step::Transform { src, dst, context, f } = self.steps.pop().unwrap() else { unreachable!() };
+                let contents: Vec<u8> = self.read_existing(src, context.clone())?;
+                self.store.as_ref().expect("Cannot write without a store!").write(dst, context, (f.0)(contents)).map_err(Error::Store)?;
+                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+            },
+            #[cfg(feature = "dataplane")]
+            Step::Verify { .. } => {
+                let Step::Verify { target, context, expected, to, verified, failed } = self.steps.pop().unwrap() else { unreachable!() };
+                let ok: bool = (expected.0)(&self.read_existing(target, context)?);
+                #[cfg(feature = "log")]
+                log::debug!(target: &self.id, "Verify: contents are {}", if ok { "as expected" } else { "NOT as expected" });
+                let to: Vec<justact::Recipient<String>> = to.resolve::<Infallible>().map_err(cast).map_err(Error::SetStated)?;
+                let msg = SM::new(self.id.clone(), if ok { verified } else { failed });
+                view.state(msg.clone()).map_err(cast).map_err(Error::SetStated)?;
+                for to in to {
+                    view.gossip(to.into(), msg.clone()).map_err(cast).map_err(Error::SetStated)?;
+                }
                 if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
             },
 
             // Synchronizer-only steps
-            Step::Agree { .. } => panic!("Cannot handle Synchronizer step in agent"),
+            Step::Agree { .. }
+            | Step::AgreeAndAdvanceTime { .. }
+            | Step::AgreeWithin { .. }
+            | Step::RetractAgreement { .. }
+            | Step::AdvanceTime { .. } => {
+                panic!("Cannot handle Synchronizer step in agent")
+            },
         }
     }
 }
@@ -546,18 +1374,771 @@ impl justact::Synchronizer<Program> for Agent {
 
         // Catch any step that is for us
         let Some(step) = self.steps.last() else { return Ok(Poll::Ready(())) };
-        match step {
+        let steps: usize = self.steps.len();
+        let res = match step {
             Step::Agree { msg: _ } => {
                 // Publish the agreement
                 let Step::Agree { msg } = self.steps.pop().unwrap() else { unreachable!() };
-                view.agree([SM::new(self.id.clone(), msg)]).map_err(cast).map_err(Error::SetAgreed)?;
+                let res = view.agree([SM::new(self.id.clone(), msg)]).map_err(cast).map_err(Error::SetAgreed);
 
                 // Done
-                if self.steps.is_empty() { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+                res.map(|_| if self.steps.is_empty() { Poll::Ready(()) } else { Poll::Pending })
+            },
+            Step::AgreeAndAdvanceTime { .. } => {
+                // Publish the agreement, then make it current
+                let Step::AgreeAndAdvanceTime { msg, at } = self.steps.pop().unwrap() else { unreachable!() };
+                let res = view
+                    .agree([SM::new(self.id.clone(), msg)])
+                    .map_err(cast)
+                    .map_err(Error::SetAgreed)
+                    .and_then(|_| view.advance_time(at).map_err(cast).map_err(Error::AdvanceTime));
+
+                // Done
+                res.map(|_| if self.steps.is_empty() { Poll::Ready(()) } else { Poll::Pending })
+            },
+            Step::AgreeWithin { .. } => {
+                // Publish the agreement with its window
+                let Step::AgreeWithin { msg, at, until } = self.steps.pop().unwrap() else { unreachable!() };
+                let res = view.agree_within([SM::new(self.id.clone(), msg)], at, until).map_err(cast).map_err(Error::SetAgreed);
+
+                // Done
+                res.map(|_| if self.steps.is_empty() { Poll::Ready(()) } else { Poll::Pending })
+            },
+            Step::RetractAgreement { .. } => {
+                // Withdraw the agreement
+                let Step::RetractAgreement { id } = self.steps.pop().unwrap() else { unreachable!() };
+                let res = view.retract_agreement::<SM>(&self.id, &id).map_err(cast).map_err(Error::SetAgreed);
+
+                // Done
+                res.map(|_| if self.steps.is_empty() { Poll::Ready(()) } else { Poll::Pending })
+            },
+            Step::AdvanceTime { .. } => {
+                // Move the clock forward
+                let Step::AdvanceTime { at } = self.steps.pop().unwrap() else { unreachable!() };
+                let res = view.advance_time(at).map_err(cast).map_err(Error::AdvanceTime);
+
+                // Done
+                res.map(|_| if self.steps.is_empty() { Poll::Ready(()) } else { Poll::Pending })
             },
 
             // The rest is up to the default step processing
-            _ => self.process_step(view),
+            _ => return self.process_step(view),
+        };
+        self.recover(Some(steps), res)
+    }
+}
+
+
+
+/// Defines a builder for the typical synchronizer of a scenario.
+///
+/// Most scenarios have some consortium that does nothing but publish agreements and advance the
+/// time. This wraps an [`Agent`] that is programmed with only those steps, in the order in which
+/// they are given.
+///
+/// # Example
+/// ```ignore
+/// let mut consortium = Synchronizer::new("consortium");
+/// consortium.publish_agreement(1, slick::parse::program("amy may say hello.").unwrap().1).advance_time(2);
+/// ```
+pub struct Synchronizer {
+    /// The agent doing the actual work.
+    agent: Agent,
+}
+
+// Constructors
+impl Synchronizer {
+    /// Builds a new Synchronizer that doesn't do anything yet.
+    ///
+    /// # Arguments
+    /// - `id`: The name of this synchronizer.
+    ///
+    /// # Returns
+    /// A new Synchronizer that can be given agreements to publish.
+    #[inline]
+    pub fn new(id: impl Into<String>) -> Self { Self { agent: Agent::new(id.into()) } }
+}
+
+// Steps
+impl Synchronizer {
+    /// Publishes a message as the new agreement and makes it current by advancing the time to
+    /// `at`, both in one poll.
+    ///
+    /// # Arguments
+    /// - `at`: The timestamp to advance the time to.
+    /// - `msg`: The message to publish as an agreement.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn publish_agreement(&mut self, at: u64, msg: Program) -> &mut Self {
+        self.agent.program().agree_and_advance_time(msg, at);
+        self
+    }
+
+    /// Publishes a message as the new agreement that is only current from `at` up to (but not
+    /// including) `until`.
+    ///
+    /// Unlike [`Synchronizer::publish_agreement()`], this doesn't advance the time.
+    ///
+    /// # Arguments
+    /// - `at`: The first timestamp at which the agreement is current.
+    /// - `until`: The first timestamp at which the agreement is no longer current.
+    /// - `msg`: The message to publish as an agreement.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn publish_agreement_within(&mut self, at: u64, until: u64, msg: Program) -> &mut Self {
+        self.agent.program().agree_within(msg, at, until);
+        self
+    }
+
+    /// Advances the time to `t` without changing the agreements.
+    ///
+    /// # Arguments
+    /// - `t`: The timestamp to advance the time to.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn advance_time(&mut self, t: u64) -> &mut Self {
+        self.agent.program().advance_time(t);
+        self
+    }
+}
+
+// Introspection
+impl Synchronizer {
+    /// Returns how many agreements and time advances this synchronizer still has to do.
+    ///
+    /// # Returns
+    /// The number of remaining steps. If it's `0`, the synchronizer is done.
+    #[inline]
+    pub fn remaining_steps(&self) -> usize { self.agent.remaining_steps() }
+}
+
+// JustAct
+impl justact::Identifiable for Synchronizer {
+    type Id = str;
+
+    #[inline]
+    fn id(&self) -> &Self::Id { &self.agent.id }
+}
+impl justact::Synchronizer<Program> for Synchronizer {
+    type Error = Error;
+
+    #[inline]
+    fn poll<A, S, E, SM, SA>(&mut self, view: justact::View<Self::Id, A, S, E>) -> Result<Poll<()>, Self::Error>
+    where
+        A: justact::SetSync<SM>,
+        S: justact::SetAsync<Self::Id, SM>,
+        E: justact::SetAsync<Self::Id, SA>,
+        SM: justact::ConstructableMessage<AuthorId = Self::Id, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        <Agent as justact::Synchronizer<Program>>::poll(&mut self.agent, view)
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use slick::parse;
+
+    use super::*;
+    use crate::auditing::{Event, EventControl};
+    use crate::io::{CollectEventHandler, EventHandler, NopEventHandler, register_event_handler, with_scoped_event_handler};
+    use crate::sets::{Actions, Agreements, Statements};
+
+
+    /// An otherwise empty world to poll a single agent (or synchronizer) in.
+    struct EmptyWorld {
+        /// The identifier of the agent polled in this world.
+        id:      String,
+        /// The agreements in this world.
+        agreed:  Agreements<Program>,
+        /// The statements in this world.
+        stated:  Statements<Program>,
+        /// The enactments in this world.
+        enacted: Actions<Program>,
+    }
+    impl EmptyWorld {
+        /// Creates an empty world for the agent with the given identifier.
+        #[inline]
+        fn new(id: &str) -> Self {
+            let mut stated: Statements<Program> = Statements::new();
+            let mut enacted: Actions<Program> = Actions::new();
+            stated.register(id);
+            enacted.register(id);
+            Self { id: id.into(), agreed: Agreements::new(), stated, enacted }
         }
+
+        /// Polls the given agent once as an [`Agent`](justact::Agent).
+        #[inline]
+        fn poll_alone(&mut self, agent: &mut Agent) -> Result<Poll<()>, Error> {
+            <Agent as justact::Agent<Program>>::poll(agent, justact::View {
+                id:      self.id.as_str().into(),
+                agreed:  &self.agreed,
+                stated:  self.stated.scope(&self.id),
+                enacted: self.enacted.scope(&self.id),
+            })
+        }
+
+        /// Polls the given agent once as a [`Synchronizer`](justact::Synchronizer).
+        #[inline]
+        fn poll_sync_alone(&mut self, sync: &mut Agent) -> Result<Poll<()>, Error> {
+            <Agent as justact::Synchronizer<Program>>::poll(sync, justact::View {
+                id:      self.id.as_str().into(),
+                agreed:  &mut self.agreed,
+                stated:  self.stated.scope(&self.id),
+                enacted: self.enacted.scope(&self.id),
+            })
+        }
+
+        /// Returns the payloads of the current agreements.
+        #[inline]
+        fn agreed(&self) -> Vec<Program> { self.agreed.iter().map(|msg| msg.payload.clone()).collect() }
+
+        /// Returns the payloads of the statements known to the agent.
+        #[inline]
+        fn said(&mut self) -> Vec<Program> { justact::Set::iter(&self.stated.scope(&self.id)).unwrap().map(|msg| msg.payload.clone()).collect() }
+    }
+
+
+    #[test]
+    fn test_agent_remaining_steps() {
+        register_event_handler(NopEventHandler);
+
+        let mut agent = Agent::new("amy".into());
+        agent
+            .program()
+            .state(justact::Recipient::All, parse::program("foo.").unwrap().1)
+            .wait_for_truth(parse::ground_atom("foo").unwrap().1)
+            .state(justact::Recipient::All, parse::program("bar.").unwrap().1);
+        assert_eq!(agent.remaining_steps(), 3);
+        assert_eq!(agent.peek_next_step(), Some(StepKind::State));
+
+        // Poll it in an otherwise empty world
+        let mut world = EmptyWorld::new("amy");
+        let mut poll = |agent: &mut Agent| -> Poll<()> { world.poll_alone(agent).unwrap() };
+
+        assert_eq!(poll(&mut agent), Poll::Pending);
+        assert_eq!(agent.remaining_steps(), 2);
+        assert_eq!(agent.peek_next_step(), Some(StepKind::WaitForTruths));
+        assert_eq!(poll(&mut agent), Poll::Pending);
+        assert_eq!(agent.remaining_steps(), 1);
+        assert_eq!(agent.peek_next_step(), Some(StepKind::State));
+        assert_eq!(poll(&mut agent), Poll::Ready(()));
+        assert_eq!(agent.remaining_steps(), 0);
+        assert_eq!(agent.peek_next_step(), None);
+    }
+
+    #[test]
+    fn test_id_matches() {
+        assert!(id_matches("amy 1", "amy 1"));
+        assert!(!id_matches("amy 1", "amy 12"));
+        assert!(id_matches("amy *", "amy 12"));
+        assert!(!id_matches("amy *", "bob 1"));
+        assert!(id_matches("*", ""));
+        assert!(id_matches("* 1", "bob 1"));
+        assert!(id_matches("a*b*c", "abbc"));
+        assert!(!id_matches("ab*bc", "abc"));
+    }
+
+    #[test]
+    fn test_agent_on_message_once() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        register_event_handler(NopEventHandler);
+
+        // Bob says hello, and Amy acknowledges every message of his
+        let mut bob = Agent::new("bob".into());
+        bob.program().state(justact::Recipient::All, parse::program("hello.").unwrap().1);
+        let calls: Rc<Cell<usize>> = Rc::new(Cell::new(0));
+        let mut amy = Agent::new("amy".into());
+        amy.program()
+            .on_message_once("bob *", {
+                let calls = calls.clone();
+                move |_, _| {
+                    calls.set(calls.get() + 1);
+                    Some((justact::Recipient::All, parse::program("ack.").unwrap().1))
+                }
+            })
+            .wait_for_truth(parse::ground_atom("goodbye").unwrap().1);
+        assert_eq!(amy.peek_next_step(), Some(StepKind::OnMessageOnce));
+
+        // Poll them in an otherwise empty world
+        let agreed: Agreements<Program> = Agreements::new();
+        let mut stated: Statements<Program> = Statements::new();
+        let mut enacted: Actions<Program> = Actions::new();
+        for id in ["amy", "bob"] {
+            stated.register(id);
+            enacted.register(id);
+        }
+        let mut poll = |agent: &mut Agent| -> Poll<()> {
+            let id: String = agent.id.clone();
+            <Agent as justact::Agent<Program>>::poll(agent, justact::View {
+                id:      id.as_str().into(),
+                agreed:  &agreed,
+                stated:  stated.scope(&id),
+                enacted: enacted.scope(&id),
+            })
+            .unwrap()
+        };
+
+        // Nothing to react to yet
+        assert_eq!(poll(&mut amy), Poll::Pending);
+        assert_eq!(calls.get(), 0);
+        assert_eq!(poll(&mut bob), Poll::Ready(()));
+
+        // Amy reacts to Bob's message, but only once
+        assert_eq!(poll(&mut amy), Poll::Pending);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(poll(&mut amy), Poll::Pending);
+        assert_eq!(poll(&mut amy), Poll::Pending);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(amy.peek_next_step(), Some(StepKind::WaitForTruths));
+    }
+
+    #[test]
+    fn test_agent_state_with_meta() {
+        let mut agent = Agent::new("amy".into());
+        agent.program().state_with_meta(justact::Recipient::All, parse::program("foo.").unwrap().1, [("purpose", "consent")]);
+        let mut agents: Vec<Agent> = vec![agent];
+        let mut sync: Option<Agent> = None;
+        let mut system = crate::System::<Program>::new();
+
+        // Collect what's emitted while the message is stated
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            while system.poll_once(&mut agents, &mut sync).unwrap().is_pending() {}
+        });
+
+        // Both the statement to Amy herself and the gossip to everyone carry the metadata
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        assert_eq!(trace.len(), 2);
+        for event in &trace {
+            let Event::Control { event: EventControl::StateMessage { msg, .. } } = event else { panic!("Expected a StateMessage, got {event:?}") };
+            assert_eq!(msg.meta, BTreeMap::from([("purpose".into(), "consent".into())]));
+        }
+    }
+
+    #[test]
+    fn test_agent_agree_and_advance_time() {
+        register_event_handler(NopEventHandler);
+
+        let mut sync = Agent::new("consortium".into());
+        sync.program()
+            .agree_and_advance_time(parse::program("foo.").unwrap().1, 1)
+            .agree_and_advance_time(parse::program("bar.").unwrap().1, 2);
+        assert_eq!(sync.peek_next_step(), Some(StepKind::AgreeAndAdvanceTime));
+
+        // Poll it in an otherwise empty world
+        let mut world = EmptyWorld::new("consortium");
+        let mut poll = |sync: &mut Agent| -> (Poll<()>, Vec<Program>) { (world.poll_sync_alone(sync).unwrap(), world.agreed()) };
+
+        // Every poll replaces the agreement in one go
+        assert_eq!(poll(&mut sync), (Poll::Pending, vec![parse::program("foo.").unwrap().1]));
+        assert_eq!(sync.peek_next_step(), Some(StepKind::AgreeAndAdvanceTime));
+        assert_eq!(poll(&mut sync), (Poll::Ready(()), vec![parse::program("bar.").unwrap().1]));
+        assert_eq!(sync.peek_next_step(), None);
+    }
+
+    #[test]
+    fn test_agent_state_at() {
+        // Amy speaks at time 2, and Bob at a time that has already passed by the time he gets to it
+        let mut amy = Agent::new("amy".into());
+        amy.program().state_at(2, justact::Recipient::All, parse::program("late.").unwrap().1);
+        let mut bob = Agent::new("bob".into());
+        bob.program().wait_for_truths([]).state_at(0, justact::Recipient::All, parse::program("early.").unwrap().1);
+        let mut consortium = Agent::new("consortium".into());
+        consortium.program().advance_time(1).advance_time(2);
+        let mut agents: Vec<Agent> = vec![amy, bob];
+        let mut sync: Option<Agent> = Some(consortium);
+        let mut system = crate::System::<Program>::new();
+
+        // However often agents are polled, only advancing the time moves their clocks
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+            assert_eq!(agents[0].time(), 0);
+            while system.poll_once(&mut agents, &mut sync).unwrap().is_pending() {}
+        });
+
+        // Every statement happens at the time it was scheduled for (or as soon as possible after)
+        let mut now: u64 = 0;
+        let mut stated: Vec<(String, u64)> = Vec::new();
+        for event in trace.lock().unwrap().iter() {
+            match event {
+                Event::Control { event: EventControl::AdvanceTime { timestamp } } => now = *timestamp,
+                Event::Control { event: EventControl::StateMessage { who, to: justact::Recipient::All, .. } } => stated.push((who.to_string(), now)),
+                _ => {},
+            }
+        }
+        assert_eq!(stated, [("bob".to_string(), 1), ("amy".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_synchronizer_builder() {
+        register_event_handler(NopEventHandler);
+
+        // Amy can only say hello once the consortium agreed she may
+        let mut amy = Agent::new("amy".into());
+        amy.program()
+            .wait_for_truth(parse::ground_atom("amy may say hello").unwrap().1)
+            .state(justact::Recipient::All, parse::program("hello.").unwrap().1);
+        let mut consortium = Synchronizer::new("consortium");
+        consortium.publish_agreement(1, parse::program("amy may say hello.").unwrap().1).advance_time(2);
+        assert_eq!(consortium.remaining_steps(), 2);
+
+        // Amy is stuck until the synchronizer's first poll
+        let mut system = crate::System::<Program>::new();
+        let mut agents: Vec<Agent> = vec![amy];
+        let mut sync: Option<Synchronizer> = Some(consortium);
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        assert_eq!(agents[0].peek_next_step(), Some(StepKind::WaitForTruths));
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        assert_eq!(sync.as_ref().map(Synchronizer::remaining_steps), Some(1));
+
+        // After which both finish
+        let mut polls: usize = 0;
+        while system.poll_once(&mut agents, &mut sync).unwrap().is_pending() {
+            polls += 1;
+            assert!(polls < 16, "Scenario did not finish");
+        }
+        assert!(agents.is_empty());
+        assert!(sync.is_none());
+    }
+
+    #[test]
+    fn test_synchronizer_agree_within() {
+        use crate::auditing::SlickAudit;
+
+        // Amy acts on the agreement as soon as it's there, and then again every cycle
+        let may_act = parse::ground_atom("amy may act").unwrap().1;
+        let mut amy = Agent::new("amy".into());
+        amy.program().enact_on_truth(may_act.clone()).enact_on_truth(may_act.clone()).enact_on_truth(may_act);
+        let mut consortium = Synchronizer::new("consortium");
+        consortium.publish_agreement_within(1, 3, parse::program("amy may act.").unwrap().1).advance_time(1).advance_time(3);
+
+        // Run it, which should trace the window
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            <crate::System<Program> as ::justact::runtime::System>::run(&mut crate::System::new(), [amy], consortium)
+        })
+        .unwrap();
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        assert!(trace.iter().any(|event| matches!(event, Event::Control { event: EventControl::SetAgreementsWithin { at: 1, until: 3, .. } })));
+
+        // Amy acted before, in and after the window, which the audit should see
+        let mut audit = SlickAudit::new();
+        for event in &trace {
+            audit.audit(&event.deserialize_slick().unwrap());
+        }
+        let perms: Vec<(bool, bool)> = (0..trace.len())
+            .filter_map(|i| audit.permission_of(i))
+            .map(|perm| perm.as_ref().unwrap())
+            .map(|perm| (perm.current, perm.future_basis))
+            .collect();
+        assert_eq!(perms, [(false, true), (true, false), (false, false)]);
+    }
+
+    #[test]
+    fn test_agent_barrier() {
+        register_event_handler(NopEventHandler);
+
+        // Everybody states something, but only after all of them are ready
+        let mut agents: Vec<Agent> = ["amy", "bob", "cho"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, id)| {
+                let mut agent = Agent::new(id.into());
+                let mut prog = agent.program();
+                for _ in 0..i {
+                    prog.wait_for_truths([]);
+                }
+                prog.barrier("ready").state(justact::Recipient::All, parse::program(&format!("{id} ready.")).unwrap().1);
+                drop(prog);
+                agent
+            })
+            .collect();
+        let mut system = crate::System::<Program>::new();
+        let mut sync: Option<Agent> = None;
+
+        // Amy and Bob arrive first, but have to wait for Cho
+        for _ in 0..(2 * (agents.len() + 1)) {
+            assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        }
+        assert_eq!(agents.iter().map(Agent::peek_next_step).collect::<Vec<_>>(), [Some(StepKind::Barrier); 3]);
+
+        // Then they all pass it
+        let mut polls: usize = 0;
+        while system.poll_once(&mut agents, &mut sync).unwrap().is_pending() {
+            polls += 1;
+            assert!(polls < 16, "Agents did not pass the barrier");
+        }
+        assert!(agents.is_empty());
+
+        // Barriers are local to the System, so Amy doesn't pass one just because another System opened it
+        let mut agents: Vec<Agent> = ["amy", "bob"]
+            .into_iter()
+            .map(|id| {
+                let mut agent = Agent::new(id.into());
+                agent.program().barrier("ready");
+                agent
+            })
+            .collect();
+        let mut system = crate::System::<Program>::new();
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        assert_eq!(agents.len(), 2);
+    }
+
+    #[test]
+    fn test_agent_barrier_deadlock() {
+        register_event_handler(NopEventHandler);
+
+        // Amy and Bob meet at the same barriers, but in the opposite order
+        let mut amy = Agent::new("amy".into());
+        amy.program().barrier("left").barrier("right");
+        let mut bob = Agent::new("bob".into());
+        bob.program().barrier("right").barrier("left");
+        let mut agents: Vec<Agent> = vec![amy, bob];
+        let mut sync: Option<Agent> = None;
+        let mut system = crate::System::<Program>::new();
+
+        // So they end up waiting for each other
+        let mut polls: usize = 0;
+        let err = loop {
+            match system.poll_once(&mut agents, &mut sync) {
+                Ok(_) => polls += 1,
+                Err(err) => break err,
+            }
+            assert!(polls < 16, "Deadlock was not detected");
+        };
+        let crate::runtime::Error::Deadlock { waiting } = &err else { panic!("Expected a deadlock, got {err:?}") };
+        assert_eq!(waiting, &[
+            ("amy".to_string(), "left".to_string(), vec!["bob".to_string()]),
+            ("bob".to_string(), "right".to_string(), vec!["amy".to_string()]),
+        ]);
+
+        // Outside of a System, there's nobody to wait for
+        let mut cho = Agent::new("cho".into());
+        cho.program().barrier("left");
+        let res = EmptyWorld::new("cho").poll_alone(&mut cho);
+        assert!(matches!(res, Err(Error::BarrierWithoutSystem { name }) if name == "left"));
+    }
+
+    #[test]
+    fn test_agent_state_to_group() {
+        let mut system = crate::System::<Program>::new()
+            .define_group("hospitals", ["st-antonius", "umc"])
+            .define_group("universities", ["tu-delft", "uva"]);
+        let mut amy = Agent::new("amy".into());
+        amy.program().state_to_group("hospitals", parse::program("foo.").unwrap().1).state_to_group("universities", parse::program("bar.").unwrap().1);
+        let mut agents: Vec<Agent> = ["st-antonius", "umc", "surf", "uva"].into_iter().map(|id| Agent::new(id.into())).collect();
+        agents.insert(0, amy);
+        let mut sync: Option<Agent> = None;
+
+        // Run the system until Amy addresses the universities, of which TU Delft doesn't exist
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        let err = with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            loop {
+                match system.poll_once(&mut agents, &mut sync) {
+                    Ok(Poll::Ready(())) => panic!("Expected stating to an unknown member to fail"),
+                    Ok(Poll::Pending) => continue,
+                    Err(err) => break err,
+                }
+            }
+        });
+        let crate::runtime::Error::Agent { id, err } = &err else { panic!("Expected an agent error, got {err:?}") };
+        assert_eq!(id, "amy");
+        assert_eq!(err.source().unwrap().to_string(), "Member \"tu-delft\" of group \"universities\" is not an agent in the system");
+
+        // Only the hospitals got the first message, and the second one wasn't even stated
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        let recipients: Vec<String> = trace
+            .iter()
+            .map(|event| match event {
+                Event::Control { event: EventControl::StateMessage { to: justact::Recipient::One(id), .. } } => id.to_string(),
+                _ => panic!("Expected a StateMessage to one agent, got {event:?}"),
+            })
+            .collect();
+        assert_eq!(recipients, ["amy", "st-antonius", "umc"]);
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_agent_catch() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        use crate::dataplane::StoreHandle;
+
+        register_event_handler(NopEventHandler);
+
+        // Amy tries to read something that isn't there, but survives it
+        let caught: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut agent = Agent::with_store("amy".into(), StoreHandle::new().scope("amy"));
+        agent
+            .program()
+            .catch({
+                let caught = caught.clone();
+                move |err: &Error| {
+                    caught.borrow_mut().push(err.to_string());
+                    Recovery::Continue
+                }
+            })
+            .read(VarId::new("amy", "data", "x"), "amy 1")
+            .state(justact::Recipient::All, parse::program("foo.").unwrap().1);
+
+        // Poll it in an otherwise empty world
+        let mut world = EmptyWorld::new("amy");
+        let mut poll = |agent: &mut Agent| -> Result<Poll<()>, Error> { world.poll_alone(agent) };
+
+        assert_eq!(poll(&mut agent).unwrap(), Poll::Pending);
+        assert_eq!(agent.peek_next_step(), Some(StepKind::Read));
+        assert_eq!(poll(&mut agent).unwrap(), Poll::Pending);
+        assert_eq!(caught.borrow().as_slice(), ["Cannot read from non-existing dataset \"(amy data) x\""]);
+        assert_eq!(agent.peek_next_step(), Some(StepKind::State));
+        assert_eq!(poll(&mut agent).unwrap(), Poll::Ready(()));
+
+        // Without a handler, it's an error; and when stopping, the rest is skipped
+        let mut agent = Agent::with_store("amy".into(), StoreHandle::new().scope("amy"));
+        agent.program().read(VarId::new("amy", "data", "x"), "amy 1");
+        assert!(matches!(poll(&mut agent), Err(Error::MissingData { .. })));
+        let mut agent = Agent::with_store("amy".into(), StoreHandle::new().scope("amy"));
+        agent
+            .program()
+            .catch(|_| Recovery::Stop)
+            .read(VarId::new("amy", "data", "x"), "amy 1")
+            .state(justact::Recipient::All, parse::program("foo.").unwrap().1);
+        assert_eq!(poll(&mut agent).unwrap(), Poll::Pending);
+        assert_eq!(poll(&mut agent).unwrap(), Poll::Ready(()));
+        assert_eq!(agent.remaining_steps(), 0);
+    }
+
+    #[test]
+    fn test_agent_catch_listener() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        /// Refuses to handle any acknowledgements, but handles everything else.
+        struct RefuseAcks;
+        impl EventHandler for RefuseAcks {
+            #[inline]
+            fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + std::error::Error>> {
+                match trace {
+                    Event::Control { event: EventControl::StateMessage { msg, .. } } if msg.payload.contains("ack") => Err(Box::new(std::fmt::Error)),
+                    _ => Ok(()),
+                }
+            }
+        }
+
+        // Amy's acknowledgement of her own hello fails, which shouldn't cost her the step after it
+        let caught: Rc<Cell<usize>> = Rc::new(Cell::new(0));
+        let mut agent = Agent::new("amy".into());
+        agent
+            .program()
+            .catch({
+                let caught = caught.clone();
+                move |_| {
+                    caught.set(caught.get() + 1);
+                    Recovery::Continue
+                }
+            })
+            .on_message_once("amy *", |_, payload| {
+                (payload == &parse::program("hello.").unwrap().1).then(|| (justact::Recipient::All, parse::program("ack.").unwrap().1))
+            })
+            .state(justact::Recipient::All, parse::program("hello.").unwrap().1)
+            .state(justact::Recipient::All, parse::program("bye.").unwrap().1);
+
+        // Poll it in an otherwise empty world
+        let mut world = EmptyWorld::new("amy");
+        with_scoped_event_handler(RefuseAcks, || {
+            let mut poll = |agent: &mut Agent| -> Poll<()> { world.poll_alone(agent).unwrap() };
+
+            // The catch and listener are installed, and then she says hello
+            for _ in 0..3 {
+                assert_eq!(poll(&mut agent), Poll::Pending);
+            }
+            assert_eq!(caught.get(), 0);
+
+            // The listener fails, but the next step is still there...
+            assert_eq!(poll(&mut agent), Poll::Pending);
+            assert_eq!(caught.get(), 1);
+            assert_eq!(agent.peek_next_step(), Some(StepKind::State));
+
+            // ...and runs on the next poll
+            assert_eq!(poll(&mut agent), Poll::Ready(()));
+            assert_eq!(caught.get(), 1);
+        });
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_agent_data_timeout() {
+        use crate::dataplane::StoreHandle;
+
+        register_event_handler(NopEventHandler);
+
+        // Amy waits for data that is never written
+        let mut agent = Agent::with_store("amy".into(), StoreHandle::new().scope("amy"));
+        agent.program().wait_for_data_within([VarId::new("amy", "data", "x")], 3).state(justact::Recipient::All, parse::program("foo.").unwrap().1);
+        let err = <crate::System<Program> as ::justact::runtime::System>::run(&mut crate::System::new(), [agent], Agent::new("consortium".into()))
+            .unwrap_err();
+        let crate::runtime::Error::Agent { id, err } = err else { panic!("Expected an agent error, got {err:?}") };
+        assert_eq!(id, "amy");
+        assert!(matches!(err.downcast_ref::<Error>(), Some(Error::DataTimeout { polls: 3, .. })));
+        assert_eq!(err.to_string(), "Dataset \"(amy data) x\" did not become available within 3 polls");
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_agent_verify() {
+        use crate::dataplane::StoreHandle;
+
+        register_event_handler(NopEventHandler);
+
+        // Some worker computed the wrong output
+        let store = StoreHandle::new();
+        let x = VarId::new("bob", "task", "x");
+        store.scope("bob").write(x.clone(), "bob 1", b"2".as_slice()).unwrap();
+
+        // Amy checks it twice, and only the correct expectation is verified
+        let mut agent = Agent::with_store("amy".into(), store.scope("amy"));
+        agent
+            .program()
+            .verify(
+                x.clone(),
+                "amy 2",
+                Box::new(|contents| contents == b"3"),
+                justact::Recipient::All,
+                parse::program("x verified.").unwrap().1,
+                parse::program("error (x incorrect).").unwrap().1,
+            )
+            .verify(
+                x,
+                "amy 2",
+                Box::new(|contents| contents == b"2"),
+                justact::Recipient::All,
+                parse::program("x verified.").unwrap().1,
+                parse::program("error (x incorrect).").unwrap().1,
+            );
+        assert_eq!(agent.peek_next_step(), Some(StepKind::Verify));
+
+        // Poll it in an otherwise empty world
+        let mut world = EmptyWorld::new("amy");
+        assert_eq!(world.poll_alone(&mut agent).unwrap(), Poll::Pending);
+        assert_eq!(world.said(), vec![parse::program("error (x incorrect).").unwrap().1]);
+        assert_eq!(world.poll_alone(&mut agent).unwrap(), Poll::Ready(()));
+        assert!(world.said().contains(&parse::program("x verified.").unwrap().1));
     }
 }
@@ -15,33 +15,428 @@
 use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
-use std::fmt::{Debug, Formatter, Result as FResult};
+use std::fmt::{Debug, Display, Formatter, Result as FResult};
 use std::hash::Hash;
+#[cfg(feature = "binary-trace")]
+use std::io::{ErrorKind, Read, Write};
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use ::justact::actions::Action as _;
 use ::justact::collections::set::InfallibleSet as _;
 use ::justact::policies::{Denotation as _, Extractor as _, Policy as _};
 use slick::text::Text;
 use slick::{GroundAtom, Program};
+#[cfg(feature = "binary-trace")]
+use thiserror::Error;
 
 use crate::codegen::impl_enum_with_custom_derive;
-use crate::policy::PolicyDeserialize;
-use crate::policy::slick::{AffectorAtom, Denotation, Effect, Extractor, PatternAtom, SyntaxError};
+#[cfg(feature = "dataplane")]
+use crate::dataplane::VarId;
+use crate::policy::{PolicyDeserialize, PolicySerialize};
+use crate::policy::slick::{AffectorAtom, Denotation, Effect, EffectKind, Extractor, PatternAtom, Policy as SlickPolicy, SyntaxError, parse_payload};
 use crate::wire::{Action, Message};
 
 mod justact {
     pub use ::justact::collections::Recipient;
+    pub use ::justact::policies::Effect;
+}
+
+
+/***** CONSTANTS *****/
+/// The number of derivation steps that [`AuditablePolicy::supports_of()`] follows back from a
+/// fact, at most.
+///
+/// This keeps explanations readable (and cheap to compute) for long derivation chains.
+pub const MAX_SUPPORT_DEPTH: usize = 16;
+
+
+
+
+
+/***** ERRORS *****/
+/// Defines errors that occur when reading or writing [binary traces](write_events_binary()).
+#[cfg(feature = "binary-trace")]
+#[derive(Debug, Error)]
+pub enum BinaryTraceError {
+    #[error("Failed to decode event")]
+    Decode(#[source] rmp_serde::decode::Error),
+    #[error("Failed to encode event")]
+    Encode(#[source] rmp_serde::encode::Error),
+    #[error("Event of {size} bytes is too large to fit in a binary trace")]
+    EventTooLarge { size: usize },
+    #[error("Input is not a binary trace (missing magic bytes)")]
+    MissingMagic,
+    #[error("Failed to read from binary trace")]
+    Read(#[source] std::io::Error),
+    #[error("Binary trace ends in the middle of a frame")]
+    Truncated,
+    #[error("Failed to write to binary trace")]
+    Write(#[source] std::io::Error),
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Checks whether a truth is an error, i.e., `error` or `error ...`.
+///
+/// # Arguments
+/// - `truth`: The [`GroundAtom`] to examine.
+///
+/// # Returns
+/// True if it's an error atom, or false otherwise.
+fn is_error_atom(truth: &GroundAtom) -> bool {
+    match truth {
+        GroundAtom::Constant(t) => format!("{t:?}") == "error",
+        GroundAtom::Tuple(ts) => !ts.is_empty() && format!("{:?}", ts[0]) == "error",
+    }
+}
+
+/// Returns the identifier of a message, which is what the [`Audit`] keys them on.
+///
+/// # Arguments
+/// - `msg`: The [`Message`] to identify.
+///
+/// # Returns
+/// An `(author, id)`-pair.
+#[inline]
+fn message_id<P: ?Sized + ToOwned>(msg: &Message<P>) -> (String, String) { (msg.author_id.clone(), msg.human_id.clone()) }
+
+/// Finds the first occurrence of every message and action in a trace.
+///
+/// # Arguments
+/// - `events`: The trace of [`Event`]s to scan.
+///
+/// # Returns
+/// A map of [`EventId`]s to the index of the first event stating, agreeing or enacting them.
+fn event_ids<P: ToOwned>(events: &[Event<P>]) -> HashMap<EventId, usize> {
+    let mut res: HashMap<EventId, usize> = HashMap::with_capacity(events.len());
+    for (i, event) in events.iter().enumerate() {
+        let msgs: Vec<&Arc<Message<P>>> = match event {
+            Event::Control { event: EventControl::StateMessage { msg, .. } } => vec![msg],
+            Event::Control { event: EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } } => {
+                agrees.iter().collect()
+            },
+            Event::Control { event: EventControl::EnactAction { action, .. } } => {
+                res.entry(EventId::Action { actor: action.actor_id.clone(), id: action.human_id.clone() }).or_insert(i);
+                continue;
+            },
+            _ => continue,
+        };
+        for msg in msgs {
+            res.entry(EventId::Message { author: msg.author_id.clone(), id: msg.human_id.clone() }).or_insert(i);
+        }
+    }
+    res
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Serializable representation of an effect as used when serializing [`Permission`]s.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct SerializableEffect {
+    /// The fact that is the effect, as a string in the policy's language.
+    fact:     String,
+    /// The agent doing the effect, as a string in the policy's language.
+    affector: String,
 }
 
+/// Serializable representation of a message identifier as used when serializing [`Permission`]s.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct SerializableMessageId<'a> {
+    /// The identifier of the agent who authored the message.
+    author: &'a str,
+    /// The (human-readable) identifier of the message.
+    id:     &'a str,
+}
+
+
+
+
 
 /***** AUXILLARY *****/
+/// Abstracts over the policy languages that an [`Audit`] can run over.
+///
+/// The audit itself only needs to know how to extract policy from an action, how to derive its
+/// truths and effects and whether it's valid. Everything else (what's stated, what's agreed, when)
+/// is language-agnostic.
+pub trait AuditablePolicy: Clone + Debug {
+    /// The payload of messages that carry this policy.
+    type Payload: Clone + Debug + Eq + Hash;
+    /// The denotation computed from this policy.
+    type Denotation;
+    /// The facts that are derived by this policy.
+    type Fact: Clone + Debug + Eq;
+    /// The effects that are derived by this policy.
+    type Effect: Clone + Debug + Ord + justact::Effect<Fact = Self::Fact>;
+    /// Any configuration needed to extract this policy, e.g., how to recognize effects.
+    type Config: Clone + Debug;
+    /// The error emitted when extraction fails.
+    type Error: Debug + Display;
+
+
+    /// Returns the configuration used by [`Audit::new()`].
+    ///
+    /// # Returns
+    /// A [`AuditablePolicy::Config`] that is sensible for most scenarios.
+    fn default_config() -> Self::Config;
+
+    /// Extracts the policy from the justification of the given action.
+    ///
+    /// # Arguments
+    /// - `config`: The [`AuditablePolicy::Config`] to extract with.
+    /// - `action`: The [`Action`] who's justification to extract.
+    ///
+    /// # Returns
+    /// A new instance of Self encoding the policy in the justification.
+    ///
+    /// # Errors
+    /// This function errors if the justification did not contain valid policy.
+    fn extract(config: &Self::Config, action: &Action<Self::Payload>) -> Result<Self, Self::Error>;
+
+    /// Computes the denotation of this policy.
+    ///
+    /// # Returns
+    /// A [`AuditablePolicy::Denotation`] describing what is true and what are effects.
+    fn truths(&self) -> Self::Denotation;
+
+    /// Checks whether a denotation is valid.
+    ///
+    /// # Arguments
+    /// - `denot`: The [`AuditablePolicy::Denotation`] to check.
+    ///
+    /// # Returns
+    /// True if it is, or false if it derived errors.
+    fn is_valid(denot: &Self::Denotation) -> bool;
+
+    /// Checks whether a denotation contains any facts of which the truth is unknown.
+    ///
+    /// # Arguments
+    /// - `denot`: The [`AuditablePolicy::Denotation`] to check.
+    ///
+    /// # Returns
+    /// True if it does, or false if every fact is either true or false.
+    fn has_unknowns(denot: &Self::Denotation) -> bool;
+
+    /// Collects the facts that are true in a denotation.
+    ///
+    /// # Arguments
+    /// - `denot`: The [`AuditablePolicy::Denotation`] to collect from.
+    ///
+    /// # Returns
+    /// A list of [`AuditablePolicy::Fact`]s, in no particular order.
+    fn facts(denot: &Self::Denotation) -> Vec<Self::Fact>;
+
+    /// Collects the effects in a denotation.
+    ///
+    /// # Arguments
+    /// - `denot`: The [`AuditablePolicy::Denotation`] to collect from.
+    ///
+    /// # Returns
+    /// A list of [`AuditablePolicy::Effect`]s, in no particular order.
+    fn effects(denot: &Self::Denotation) -> Vec<Self::Effect>;
+
+    /// Checks whether a fact marks the policy as invalid.
+    ///
+    /// # Arguments
+    /// - `fact`: The [`AuditablePolicy::Fact`] to examine.
+    ///
+    /// # Returns
+    /// True if it's an error, or false otherwise.
+    fn is_error(fact: &Self::Fact) -> bool;
+
+    /// Checks whether a denotation could not be computed at all.
+    ///
+    /// Policies that fail inference are still [invalid](AuditablePolicy::is_valid()), but this
+    /// allows auditors to tell them apart from policies that derive an error themselves.
+    ///
+    /// # Arguments
+    /// - `denot`: The [`AuditablePolicy::Denotation`] to check.
+    ///
+    /// # Returns
+    /// True if inference failed, or false otherwise. By default, always false.
+    #[inline]
+    fn inference_failed(denot: &Self::Denotation) -> bool {
+        let _ = denot;
+        false
+    }
+
+    /// Finds the facts that a fact was derived from.
+    ///
+    /// This is used to explain why an action is not valid, by listing what led to each error.
+    /// Implementations follow the derivation back through the supports of the supports, up to
+    /// [`MAX_SUPPORT_DEPTH`] steps.
+    ///
+    /// # Arguments
+    /// - `denot`: The [`AuditablePolicy::Denotation`] computed from this policy.
+    /// - `fact`: The [`AuditablePolicy::Fact`] to find the support of.
+    ///
+    /// # Returns
+    /// A list of the (true) [`AuditablePolicy::Fact`]s that `fact` is derived from, directly or
+    /// indirectly, without `fact` itself and without duplicates. Direct supports come before
+    /// indirect ones. By default, always empty.
+    #[inline]
+    fn supports_of(&self, denot: &Self::Denotation, fact: &Self::Fact) -> Vec<Self::Fact> {
+        let _ = (denot, fact);
+        Vec::new()
+    }
+}
+
+// Policy impls
+impl AuditablePolicy for SlickPolicy {
+    type Payload = Program;
+    type Denotation = Denotation;
+    type Fact = GroundAtom;
+    type Effect = Effect;
+    type Config = Vec<(PatternAtom, AffectorAtom)>;
+    type Error = SyntaxError;
+
+
+    /// Recognizes effects as facts of the shape `Worker reads Variable`,
+    /// `Worker writes Variable` or `Worker deletes Variable`.
+    ///
+    /// These are the facts constructed by
+    /// [`effect_reads()`](crate::policy::slick::effect_reads()),
+    /// [`effect_writes()`](crate::policy::slick::effect_writes()) and
+    /// [`effect_deletes()`](crate::policy::slick::effect_deletes()).
+    #[inline]
+    fn default_config() -> Self::Config {
+        vec![(
+            PatternAtom::Tuple(vec![
+                PatternAtom::Variable(Text::from_str("Worker")),
+                PatternAtom::ConstantSet(EffectKind::ALL.iter().map(|kind| Text::from_str(kind.verb())).collect()),
+                PatternAtom::Variable(Text::from_str("Variable")),
+            ]),
+            AffectorAtom::Variable(Text::from_str("Worker")),
+        )]
+    }
+
+    #[inline]
+    fn extract(config: &Self::Config, action: &Action<Self::Payload>) -> Result<Self, Self::Error> {
+        let mut pol: Self = Extractor::new().extract(&action.payload())?;
+        pol.update_effect_patterns(config.clone());
+        Ok(pol)
+    }
+
+    #[inline]
+    fn truths(&self) -> Self::Denotation { <Self as ::justact::policies::Policy>::truths(self) }
+
+    #[inline]
+    fn is_valid(denot: &Self::Denotation) -> bool { denot.is_valid() }
+
+    #[inline]
+    fn has_unknowns(denot: &Self::Denotation) -> bool { denot.has_unknowns() }
+
+    #[inline]
+    fn facts(denot: &Self::Denotation) -> Vec<Self::Fact> { denot.iter_truths().cloned().collect() }
+
+    #[inline]
+    fn effects(denot: &Self::Denotation) -> Vec<Self::Effect> { denot.iter_effects().cloned().collect() }
+
+    #[inline]
+    fn is_error(fact: &Self::Fact) -> bool { is_error_atom(fact) }
+
+    #[inline]
+    fn inference_failed(denot: &Self::Denotation) -> bool { denot.inference_failed() }
+
+    /// Collects the antecedents of every rule that derives `fact` (see
+    /// [`Denotation::proof_of()`](crate::policy::slick::Denotation::proof_of())), and then those
+    /// of the antecedents, and so on.
+    ///
+    /// Facts that were already visited are not expanded again, so cyclic derivations terminate.
+    fn supports_of(&self, denot: &Self::Denotation, fact: &Self::Fact) -> Vec<Self::Fact> {
+        let rules = &self.program().rules;
+        let mut supports: Vec<GroundAtom> = Vec::new();
+        let mut frontier: Vec<GroundAtom> = vec![fact.clone()];
+        for _ in 0..MAX_SUPPORT_DEPTH {
+            let mut next: Vec<GroundAtom> = Vec::new();
+            for current in &frontier {
+                for support in denot.proof_of(rules, current).map(|proof| proof.supports).unwrap_or_default() {
+                    for antecedent in support.antecedents {
+                        if antecedent != *fact && !supports.contains(&antecedent) {
+                            supports.push(antecedent.clone());
+                            next.push(antecedent);
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+        supports
+    }
+}
+#[cfg(feature = "datalog")]
+impl AuditablePolicy for crate::policy::datalog::Policy<'static, 'static> {
+    type Payload = datalog::ast::Spec<(&'static str, &'static str)>;
+    type Denotation = crate::policy::datalog::Denotation<'static, 'static>;
+    type Fact = datalog::ir::GroundAtom<(&'static str, &'static str)>;
+    type Effect = crate::policy::datalog::Effect<'static, 'static>;
+    /// An optional pattern and affector to recognize effects with. See
+    /// [`Policy::update_effect_pattern()`](crate::policy::datalog::Policy::update_effect_pattern()).
+    type Config = Option<(datalog::ir::Atom<(&'static str, &'static str)>, datalog::ir::Ident<(&'static str, &'static str)>)>;
+    type Error = crate::policy::datalog::SyntaxError<'static>;
+
+
+    /// Uses the Datalog policy's default pattern, i.e., `effect(Affector, Effect)`.
+    #[inline]
+    fn default_config() -> Self::Config { None }
+
+    #[inline]
+    fn extract(config: &Self::Config, action: &Action<Self::Payload>) -> Result<Self, Self::Error> {
+        // NOTE: Datalog has no reflection of the actor, so we only extract the justification
+        let msgs: ::justact::messages::MessageSet<Arc<Message<Self::Payload>>> =
+            std::iter::once(action.basis.clone()).chain(action.extra.iter().cloned()).collect();
+        let mut pol: Self = crate::policy::datalog::Extractor.extract(&msgs)?;
+        if let Some((pat, affector)) = config {
+            pol.update_effect_pattern(pat.clone(), affector.clone());
+        }
+        Ok(pol)
+    }
+
+    #[inline]
+    fn truths(&self) -> Self::Denotation { <Self as ::justact::policies::Policy>::truths(self) }
+
+    #[inline]
+    fn is_valid(denot: &Self::Denotation) -> bool {
+        let error: Self::Fact = datalog::ir::GroundAtom { ident: datalog::ir::Ident::new("error".into(), None), args: Vec::new() };
+        denot.truth_of(&error) != Some(true)
+    }
+
+    #[inline]
+    fn has_unknowns(denot: &Self::Denotation) -> bool { denot.has_unknowns() }
+
+    #[inline]
+    fn facts(denot: &Self::Denotation) -> Vec<Self::Fact> { denot.iter_truths().cloned().collect() }
+
+    #[inline]
+    fn effects(denot: &Self::Denotation) -> Vec<Self::Effect> { denot.iter_effects().cloned().collect() }
+
+    #[inline]
+    fn is_error(fact: &Self::Fact) -> bool { fact.ident == datalog::ir::Ident::new("error".into(), None) }
+}
+
+
+
 /// Defines how we describe the validity of an action.
 ///
 /// Corresponds to Definition 3.5 of the paper:
 /// > $$permitted(c: config, a: action) := valid-act(a) \wedge sourced(c, a) \wedge based(c, a).$$
+///
+/// With the `serde`-feature enabled, this can be serialized for consumption by external tools.
+/// Next to the three properties, this includes a `permitted`-field with the conclusion. Note that
+/// the truths and effects are serialized lossily, as strings in the policy's language.
 #[derive(Clone, Debug)]
-pub struct Permission {
+pub struct Permission<P: AuditablePolicy = SlickPolicy> {
     /// Definition 3.7
     /// > $$valid-act(a: action) := valid(payload(extract(a))).$$
     ///
@@ -52,39 +447,327 @@ pub struct Permission {
     ///
     /// I.e., the chosen set of messages in the act are all stated.
     pub sourced:   bool,
+    /// The messages in the justification that were never stated nor agreed at all, as
+    /// `(author, identifier)`-pairs.
+    ///
+    /// If this is non-empty, the action is not [sourced](Permission::sourced). Usually, this
+    /// points to a typo in the script of an agent rather than to a message that was stated to
+    /// someone else.
+    pub missing_justifications: Vec<(String, String)>,
     /// Definition 3.10
     /// > $$based(c, a) := m \in payload(a) \wedge agreed(c, m)\text{ where }m := basis(a).$$
     ///
-    /// I.e., the justification includes an agreed message marked as the basis of the action.
+    /// I.e., the justification includes an agreed message marked as the basis of the action. As
+    /// such, actions of which the justification doesn't include the basis (e.g., because it is
+    /// empty) are never based.
     pub based:     bool,
+    /// Whether the action was enacted while its basis was in force.
+    ///
+    /// Agreements may only be valid within a window of time (see
+    /// [`EventControl::SetAgreementsWithin`]). If the action was enacted outside of it, then it is
+    /// not current. Agreements without a window are always current.
+    pub current:   bool,
+    /// Whether the action's basis is agreed to be in force only at a time after the one last
+    /// advanced to (see [`EventControl::AdvanceTime`]).
+    ///
+    /// Such actions are never [current](Permission::current), but this flags the more specific
+    /// case of an agent enacting against an agreement that isn't current _yet_. That's usually a
+    /// bug in the script of the agent rather than a stale agreement.
+    pub future_basis: bool,
+    /// Whether the policy of the action could not be computed at all.
+    ///
+    /// Such actions are never [valid](Permission::valid_act), but this flags that this is because
+    /// inference failed rather than because the policy derived an error.
+    pub inference_failed: bool,
 
     /// Describes the truths denoted by this action.
     ///
     /// For convenience, sorted by: errors first (alphabetically), then other truths
     /// (alphabetically).
-    pub truths:  Vec<GroundAtom>,
+    pub truths:  Vec<P::Fact>,
     /// Describes the effects denoted by this action.
     ///
-    /// For convenience, sorted by alphabet.
-    pub effects: Vec<Effect>,
+    /// For convenience, sorted by their [`Ord`]-implementation.
+    pub effects: Vec<P::Effect>,
+    /// Describes, for every error in [`Permission::truths`], the facts it was derived from.
+    ///
+    /// In the same order as the errors occur in the truths. Empty if the action is valid, if
+    /// inference failed or if the policy language cannot trace derivations.
+    pub error_supports: Vec<(P::Fact, Vec<P::Fact>)>,
 }
-impl Default for Permission {
+impl<P: AuditablePolicy> Default for Permission<P> {
     /// Initializes the default Permission.
     ///
     /// Note that it is initialized such that [`Permission::is_permitted()`] yields _true_, for
     /// convenience (one can simply conjunct a list of permissions).
     #[inline]
-    fn default() -> Self { Self { valid_act: true, sourced: true, based: true, truths: Vec::new(), effects: Vec::new() } }
+    fn default() -> Self {
+        Self {
+            valid_act: true,
+            sourced: true,
+            missing_justifications: Vec::new(),
+            based: true,
+            current: true,
+            future_basis: false,
+            inference_failed: false,
+            truths: Vec::new(),
+            effects: Vec::new(),
+            error_supports: Vec::new(),
+        }
+    }
 }
-impl Permission {
+impl<P: AuditablePolicy> Permission<P> {
     /// Checks whether the action represented by this permission is permitted.
     ///
     /// # Returns
     /// True if it's a correctly justified action, or false otherwise.
     #[inline]
-    pub const fn is_permitted(&self) -> bool { self.valid_act && self.sourced && self.based }
+    pub const fn is_permitted(&self) -> bool { self.valid_act && self.sourced && self.based && self.current && !self.future_basis }
+
+    /// Explains, in a human-readable fashion, why an action is (not) permitted.
+    ///
+    /// # Returns
+    /// A [`String`] that is `OK` if the action is permitted, or else `ILLEGAL (...)` where `...`
+    /// lists the properties that failed. If the action is not valid, any error truths that
+    /// caused it are listed as well.
+    pub fn explain(&self) -> String {
+        if self.is_permitted() {
+            return "OK".into();
+        }
+
+        let mut reasons: Vec<String> = Vec::with_capacity(4);
+        if self.inference_failed {
+            reasons.push("not valid (inference failed)".into());
+        } else if !self.valid_act {
+            let errors: Vec<String> = self.truths.iter().filter(|t| P::is_error(t)).map(|t| format!("{t:?}")).collect();
+            if errors.is_empty() { reasons.push("not valid".into()) } else { reasons.push(format!("not valid [{}]", errors.join("; "))) }
+        }
+        if !self.sourced {
+            if self.missing_justifications.is_empty() {
+                reasons.push("not sourced".into());
+            } else {
+                let missing: Vec<String> = self.missing_justifications.iter().map(|(_, id)| format!("{id:?}")).collect();
+                reasons.push(format!("not sourced [never stated: {}]", missing.join(", ")));
+            }
+        }
+        if !self.based {
+            reasons.push("not based".into());
+        }
+        if self.future_basis {
+            reasons.push("not current (basis in the future)".into());
+        } else if !self.current {
+            reasons.push("not current".into());
+        }
+        format!("ILLEGAL ({})", reasons.join(", "))
+    }
+}
+#[cfg(feature = "serde")]
+impl<P: AuditablePolicy> serde::Serialize for Permission<P>
+where
+    <P::Effect as ::justact::auxillary::Affectored>::AffectorId: Debug,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct as _;
+
+        let mut perm = serializer.serialize_struct("Permission", 11)?;
+        perm.serialize_field("permitted", &self.is_permitted())?;
+        perm.serialize_field("valid_act", &self.valid_act)?;
+        perm.serialize_field("sourced", &self.sourced)?;
+        perm.serialize_field(
+            "missing_justifications",
+            &self
+                .missing_justifications
+                .iter()
+                .map(|(author, id)| SerializableMessageId { author, id })
+                .collect::<Vec<SerializableMessageId>>(),
+        )?;
+        perm.serialize_field("based", &self.based)?;
+        perm.serialize_field("current", &self.current)?;
+        perm.serialize_field("future_basis", &self.future_basis)?;
+        perm.serialize_field("inference_failed", &self.inference_failed)?;
+        perm.serialize_field("truths", &self.truths.iter().map(|t| format!("{t:?}")).collect::<Vec<String>>())?;
+        perm.serialize_field(
+            "effects",
+            &self
+                .effects
+                .iter()
+                .map(|e| SerializableEffect { fact: format!("{:?}", e.fact()), affector: format!("{:?}", e.affector_id()) })
+                .collect::<Vec<SerializableEffect>>(),
+        )?;
+        perm.serialize_field(
+            "error_supports",
+            &self
+                .error_supports
+                .iter()
+                .map(|(error, supports)| (format!("{error:?}"), supports.iter().map(|s| format!("{s:?}")).collect()))
+                .collect::<Vec<(String, Vec<String>)>>(),
+        )?;
+        perm.end()
+    }
+}
+
+
+
+/// Summarizes what the [`Audit`] concluded about a particular event.
+///
+/// This is a more digestible version of [`Audit::permission_of()`].
+#[derive(Clone, Debug)]
+pub enum ActionOutcome<P: AuditablePolicy = SlickPolicy> {
+    /// The event in question was not an audited action.
+    NotAnAction,
+    /// The event was an action, but its justification did not result in a parsable policy. The
+    /// [`String`] describes the reason why.
+    PolicyError(String),
+    /// The event was an action and it was permitted.
+    Permitted(Permission<P>),
+    /// The event was an action, but it was not permitted.
+    Rejected(Permission<P>),
+}
+impl<P: AuditablePolicy> ActionOutcome<P> {
+    /// Checks whether this outcome is [`ActionOutcome::Permitted`].
+    ///
+    /// # Returns
+    /// True if the event was an action that was permitted, or false otherwise.
+    #[inline]
+    pub const fn is_permitted(&self) -> bool { matches!(self, Self::Permitted(_)) }
+
+    /// Returns the [`Permission`] computed for the action, if any.
+    ///
+    /// # Returns
+    /// A reference to the [`Permission`] if this outcome is either [`ActionOutcome::Permitted`]
+    /// or [`ActionOutcome::Rejected`], or else [`None`].
+    #[inline]
+    pub const fn permission(&self) -> Option<&Permission<P>> {
+        match self {
+            Self::Permitted(perm) | Self::Rejected(perm) => Some(perm),
+            Self::NotAnAction | Self::PolicyError(_) => None,
+        }
+    }
+}
+
+
+
+/// Identifies a message or an action across traces.
+///
+/// See [`diff_audits()`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum EventId {
+    /// A message, stated or agreed, identified by its author and ID.
+    Message { author: String, id: String },
+    /// An enacted action, identified by its actor and ID.
+    Action { actor: String, id: String },
+}
+impl Display for EventId {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::Message { author, id } => write!(f, "message {id:?} by {author:?}"),
+            Self::Action { actor, id } => write!(f, "action {id:?} by {actor:?}"),
+        }
+    }
+}
+
+/// Describes an action of which the permission differs between two traces.
+///
+/// See [`diff_audits()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PermissionChange {
+    /// The action in question.
+    pub id: EventId,
+    /// The index of the action in the first trace.
+    pub index_a: usize,
+    /// The index of the action in the second trace.
+    pub index_b: usize,
+    /// Whether the action was permitted in the first trace.
+    pub permitted_a: bool,
+    /// Whether the action was permitted in the second trace.
+    pub permitted_b: bool,
+}
+
+/// Describes the differences between two audited traces.
+///
+/// See [`diff_audits()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditDiff {
+    /// Messages and actions that only occur in the second trace, with their index in it.
+    pub added:   Vec<(EventId, usize)>,
+    /// Messages and actions that only occur in the first trace, with their index in it.
+    pub removed: Vec<(EventId, usize)>,
+    /// Actions that occur in both traces, but were permitted in one and not in the other.
+    pub changed: Vec<PermissionChange>,
+}
+impl AuditDiff {
+    /// Checks whether the two traces were the same as far as the diff is concerned.
+    ///
+    /// # Returns
+    /// True if nothing was added, removed or changed, or false otherwise.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() }
+}
+impl Display for AuditDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        for (id, i) in &self.removed {
+            writeln!(f, "- {id} (event {i})")?;
+        }
+        for (id, i) in &self.added {
+            writeln!(f, "+ {id} (event {i})")?;
+        }
+        for change in &self.changed {
+            let status = |permitted: bool| -> &'static str { if permitted { "permitted" } else { "not permitted" } };
+            writeln!(
+                f,
+                "~ {} (event {} -> {}): {} -> {}",
+                change.id,
+                change.index_a,
+                change.index_b,
+                status(change.permitted_a),
+                status(change.permitted_b)
+            )?;
+        }
+        Ok(())
+    }
 }
 
+/// A snapshot of the world as seen by an [`Audit`] at some point in a trace.
+///
+/// See [`Audit::world()`] and [`Audit::world_at()`].
+#[derive(Clone, Debug)]
+pub struct WorldState<P: AuditablePolicy = SlickPolicy> {
+    /// The current time, as last advanced by [`EventControl::AdvanceTime`].
+    pub now: u64,
+    /// The agreed messages, together with the window (`[at, until)`) in which they are valid.
+    ///
+    /// Sorted by the `(author, id)`-pairs of the messages.
+    pub agreed: Vec<(Arc<Message<P::Payload>>, u64, Option<u64>)>,
+    /// The stated messages, to anyone.
+    ///
+    /// Sorted by the `(author, id)`-pairs of the messages.
+    pub stated: Vec<Arc<Message<P::Payload>>>,
+    /// The variables that have been written and not deleted since, in sorted order.
+    #[cfg(feature = "dataplane")]
+    pub data:   Vec<VarId>,
+}
+impl<P: AuditablePolicy> WorldState<P> {
+    /// Checks whether an agreed message is in force at [`WorldState::now`].
+    ///
+    /// # Arguments
+    /// - `author`: The author of the message.
+    /// - `id`: The identifier of the message.
+    ///
+    /// # Returns
+    /// True if the message is agreed and its window (if any) includes the current time, or false
+    /// otherwise.
+    pub fn is_current(&self, author: &str, id: &str) -> bool {
+        self.agreed
+            .iter()
+            .find(|(msg, _, _)| msg.author_id == author && msg.human_id == id)
+            .map(|(_, at, until)| *at <= self.now && until.map(|until| self.now < until).unwrap_or(true))
+            .unwrap_or(false)
+    }
+}
 
 
 impl_enum_with_custom_derive! {
@@ -116,6 +799,22 @@ where
         }
     }
 }
+impl<'a, P: ?Sized + PolicySerialize + ToOwned> Event<'a, P>
+where
+    P::Owned: Eq + Hash,
+{
+    /// Converts this event into one carrying serialized policy instead.
+    ///
+    /// # Returns
+    /// A translated [`Event`] that has messages over [`str`]ings instead of `P`.
+    #[inline]
+    pub fn serialize(&self) -> Event<'a, str> {
+        match self {
+            Self::Control { event } => Event::Control { event: event.serialize() },
+            Self::Data { event } => Event::Data { event: event.clone() },
+        }
+    }
+}
 impl<'a> Event<'a, str> {
     /// Recovers some policy representation from a serialized version of it.
     ///
@@ -125,22 +824,134 @@ impl<'a> Event<'a, str> {
     /// # Returns
     /// A translated [`Event`] that has messages over `P` instead of [`str`]ings.
     pub fn deserialize<'s, P: ?Sized + PolicyDeserialize<'s> + ToOwned>(&'s self) -> Result<Event<'a, P>, P::Error>
+    where
+        P::Owned: Eq + Hash,
+    {
+        self.try_map_payloads(&mut |msg: &'s Message<str>| P::deserialize(&msg.payload))
+    }
+
+    /// Recovers the [Slick](crate::policy::slick) policy from a serialized version of it.
+    ///
+    /// This is like [`Event::deserialize::<Program>()`](Event::deserialize()), except that
+    /// payloads are parsed with [`parse_payload()`] such that errors name the message that
+    /// carried them. This is what traces written by the prototype are read back as before
+    /// [auditing](Audit) them.
+    ///
+    /// # Returns
+    /// A translated [`Event`] that has messages over [`Program`]s instead of [`str`]ings.
+    ///
+    /// # Errors
+    /// This function errors with a [`SyntaxError::Slick`] if any of the messages in this event did
+    /// not carry valid Slick.
+    #[inline]
+    pub fn deserialize_slick(&self) -> Result<Event<'a, Program>, SyntaxError> {
+        self.try_map_payloads(&mut |msg: &Message<str>| parse_payload(&msg.author_id, &msg.human_id, msg.payload.as_bytes()))
+    }
+
+    /// Recovers some policy representation by parsing the payloads of all messages with the
+    /// given function.
+    ///
+    /// # Arguments
+    /// - `parse`: Parses the payload of a single message, given the whole message for context.
+    ///
+    /// # Returns
+    /// A translated [`Event`] that has messages over `P` instead of [`str`]ings.
+    ///
+    /// # Errors
+    /// This function errors if `parse` failed for any of the messages.
+    fn try_map_payloads<'s, P: ?Sized + ToOwned, E>(&'s self, parse: &mut impl FnMut(&'s Message<str>) -> Result<P::Owned, E>) -> Result<Event<'a, P>, E>
     where
         P::Owned: Eq + Hash,
     {
         match self {
-            Self::Control { event } => Ok(Event::Control { event: event.deserialize()? }),
+            Self::Control { event } => Ok(Event::Control { event: event.try_map_payloads(parse)? }),
             Self::Data { event } => Ok(Event::Data { event: event.clone() }),
         }
     }
 }
 
-impl_enum_with_custom_derive! {
+/// Annotates a (serialized) [`Event`] with the wall-clock time at which it was emitted.
+///
+/// Handlers only see these if the `timestamps` feature is enabled (see
+/// [`EventHandler::handle_at()`](crate::io::EventHandler::handle_at())), and the
+/// [`JsonLinesEventHandler`](crate::io::JsonLinesEventHandler) then writes these instead of plain
+/// [`Event`]s.
+#[derive(Clone, Debug)]
+pub struct TimedEvent<'a> {
+    /// When the event was emitted.
+    pub emitted_at: SystemTime,
+    /// The event itself.
+    pub event: Event<'a, str>,
+}
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for TimedEvent<'a> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap as _;
+
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("emitted_at", &self.emitted_at)?;
+        map.serialize_entry("event", &self.event)?;
+        map.end()
+    }
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TimedEvent<'de> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        /// <https://serde.rs/deserialize-struct.html>
+        struct Visitor;
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = TimedEvent<'de>;
+
+            #[inline]
+            fn expecting(&self, f: &mut Formatter) -> FResult { write!(f, "a timed event") }
+
+            #[inline]
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut emitted_at: Option<SystemTime> = None;
+                let mut event: Option<Event<'de, str>> = None;
+                while let Some(key) = map.next_key::<&str>()? {
+                    match key {
+                        "emitted_at" if emitted_at.is_none() => emitted_at = Some(map.next_value()?),
+                        "event" if event.is_none() => event = Some(map.next_value()?),
+                        "emitted_at" | "event" => return Err(<A::Error as serde::de::Error>::custom(format!("Duplicate field '{key}'"))),
+                        other => return Err(<A::Error as serde::de::Error>::custom(format!("Unexpected field '{other}'"))),
+                    }
+                }
+                Ok(TimedEvent {
+                    emitted_at: emitted_at.ok_or_else(|| <A::Error as serde::de::Error>::custom("Missing field 'emitted_at'"))?,
+                    event:      event.ok_or_else(|| <A::Error as serde::de::Error>::custom("Missing field 'event'"))?,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+impl_enum_with_custom_derive! {
     #[derive(Clone, Debug, Deserialize, Serialize)]
     /// Defines what may be traced by the JustAct-part of the framework (governance).
     pub enum EventControl<'a, P: ToOwned> {
         /// Traces the addition of a new agreement.
         SetAgreements { agrees: Vec<Arc<Message<P>>> },
+        /// Traces the addition of new agreements that are only valid from `at` up to (but not
+        /// including) `until`.
+        SetAgreementsWithin { agrees: Vec<Arc<Message<P>>>, at: u64, until: u64 },
+        /// Traces the retraction of the agreement with the given `author` and `id`.
+        RetractAgreement { author: Cow<'a, str>, id: Cow<'a, str> },
+        /// Traces the progression of time to the given `timestamp`.
+        AdvanceTime { timestamp: u64 },
         /// Traces the enacting of an action.
         EnactAction { who: Cow<'a, str>, to: justact::Recipient<Cow<'a, str>>, action: Action<P> },
         /// States a new message.
@@ -161,6 +972,11 @@ where
     pub fn into_owned(self) -> EventControl<'static, P> {
         match self {
             Self::SetAgreements { agrees } => EventControl::SetAgreements { agrees },
+            Self::SetAgreementsWithin { agrees, at, until } => EventControl::SetAgreementsWithin { agrees, at, until },
+            Self::RetractAgreement { author, id } => {
+                EventControl::RetractAgreement { author: Cow::Owned(author.into_owned()), id: Cow::Owned(id.into_owned()) }
+            },
+            Self::AdvanceTime { timestamp } => EventControl::AdvanceTime { timestamp },
             Self::EnactAction { who, to, action } => EventControl::EnactAction {
                 who: Cow::Owned(who.into_owned()),
                 to: match to {
@@ -180,6 +996,29 @@ where
         }
     }
 }
+impl<'a, P: ?Sized + PolicySerialize + ToOwned> EventControl<'a, P>
+where
+    P::Owned: Eq + Hash,
+{
+    /// Converts this event into one carrying serialized policy instead.
+    ///
+    /// # Returns
+    /// A translated [`EventControl`] that has messages over [`str`]ings instead of `P`.
+    pub fn serialize(&self) -> EventControl<'a, str> {
+        match self {
+            Self::SetAgreements { agrees } => EventControl::SetAgreements { agrees: agrees.iter().map(|agree| Arc::new(agree.serialize())).collect() },
+            Self::SetAgreementsWithin { agrees, at, until } => EventControl::SetAgreementsWithin {
+                agrees: agrees.iter().map(|agree| Arc::new(agree.serialize())).collect(),
+                at:     *at,
+                until:  *until,
+            },
+            Self::RetractAgreement { author, id } => EventControl::RetractAgreement { author: author.clone(), id: id.clone() },
+            Self::AdvanceTime { timestamp } => EventControl::AdvanceTime { timestamp: *timestamp },
+            Self::EnactAction { who, to, action } => EventControl::EnactAction { who: who.clone(), to: to.clone(), action: action.serialize() },
+            Self::StateMessage { who, to, msg } => EventControl::StateMessage { who: who.clone(), to: to.clone(), msg: Arc::new(msg.serialize()) },
+        }
+    }
+}
 impl<'a> EventControl<'a, str> {
     /// Recovers some policy representation from a serialized version of it.
     ///
@@ -189,18 +1028,46 @@ impl<'a> EventControl<'a, str> {
     /// # Returns
     /// A translated [`EventControl`] that has messages over `P` instead of [`str`]ings.
     pub fn deserialize<'s, P: ?Sized + PolicyDeserialize<'s> + ToOwned>(&'s self) -> Result<EventControl<'a, P>, P::Error>
+    where
+        P::Owned: Eq + Hash,
+    {
+        self.try_map_payloads(&mut |msg: &'s Message<str>| P::deserialize(&msg.payload))
+    }
+
+    /// Recovers some policy representation by parsing the payloads of all messages with the
+    /// given function.
+    ///
+    /// # Arguments
+    /// - `parse`: Parses the payload of a single message, given the whole message for context.
+    ///
+    /// # Returns
+    /// A translated [`EventControl`] that has messages over `P` instead of [`str`]ings.
+    ///
+    /// # Errors
+    /// This function errors if `parse` failed for any of the messages.
+    fn try_map_payloads<'s, P: ?Sized + ToOwned, E>(
+        &'s self,
+        parse: &mut impl FnMut(&'s Message<str>) -> Result<P::Owned, E>,
+    ) -> Result<EventControl<'a, P>, E>
     where
         P::Owned: Eq + Hash,
     {
         match self {
             Self::SetAgreements { agrees } => Ok(EventControl::SetAgreements {
-                agrees: agrees.into_iter().map(|agree| Ok(Arc::new(agree.deserialize()?))).collect::<Result<_, _>>()?,
+                agrees: agrees.iter().map(|agree| Ok(Arc::new(agree.try_map_payload(&mut *parse)?))).collect::<Result<_, _>>()?,
             }),
+            Self::SetAgreementsWithin { agrees, at, until } => Ok(EventControl::SetAgreementsWithin {
+                agrees: agrees.iter().map(|agree| Ok(Arc::new(agree.try_map_payload(&mut *parse)?))).collect::<Result<_, _>>()?,
+                at:     *at,
+                until:  *until,
+            }),
+            Self::RetractAgreement { author, id } => Ok(EventControl::RetractAgreement { author: author.clone(), id: id.clone() }),
+            Self::AdvanceTime { timestamp } => Ok(EventControl::AdvanceTime { timestamp: *timestamp }),
             Self::EnactAction { who, to, action } => {
-                Ok(EventControl::EnactAction { who: who.clone(), to: to.clone(), action: action.deserialize()? })
+                Ok(EventControl::EnactAction { who: who.clone(), to: to.clone(), action: action.try_map_payloads(parse)? })
             },
             Self::StateMessage { who, to, msg } => {
-                Ok(EventControl::StateMessage { who: who.clone(), to: to.clone(), msg: Arc::new(msg.deserialize()?) })
+                Ok(EventControl::StateMessage { who: who.clone(), to: to.clone(), msg: Arc::new(msg.try_map_payload(parse)?) })
             },
         }
     }
@@ -213,9 +1080,20 @@ impl<'a> EventControl<'a, str> {
 #[cfg_attr(feature = "serde", serde(tag = "kind"))]
 pub enum EventData<'a> {
     /// Traces that somebody read from a variable.
-    Read { who: Cow<'a, str>, id: Cow<'a, ((String, String), String)>, context: Cow<'a, str>, contents: Option<Cow<'a, [u8]>> },
+    Read { who: Cow<'a, str>, id: Cow<'a, VarId>, context: Cow<'a, str>, contents: Option<Cow<'a, [u8]>> },
     /// Traces that somebody wrote to a variable.
-    Write { who: Cow<'a, str>, id: Cow<'a, ((String, String), String)>, context: Cow<'a, str>, new: bool, contents: Cow<'a, [u8]> },
+    Write {
+        who: Cow<'a, str>,
+        id: Cow<'a, VarId>,
+        context: Cow<'a, str>,
+        new: bool,
+        contents: Cow<'a, [u8]>,
+        /// Any labels given to the variable. Absent in older traces, hence the default.
+        #[cfg_attr(feature = "serde", serde(default))]
+        labels: Cow<'a, HashMap<String, String>>,
+    },
+    /// Traces that somebody deleted a variable.
+    Delete { who: Cow<'a, str>, id: Cow<'a, VarId>, context: Cow<'a, str> },
 }
 
 // Data management
@@ -237,12 +1115,18 @@ impl<'a> EventData<'a> {
                     None => None,
                 },
             },
-            Self::Write { who, id, context, new, contents } => EventData::Write {
+            Self::Write { who, id, context, new, contents, labels } => EventData::Write {
                 who: Cow::Owned(who.into_owned()),
                 id: Cow::Owned(id.into_owned()),
                 context: Cow::Owned(context.into_owned()),
                 new,
                 contents: Cow::Owned(contents.into_owned()),
+                labels: Cow::Owned(labels.into_owned()),
+            },
+            Self::Delete { who, id, context } => EventData::Delete {
+                who:     Cow::Owned(who.into_owned()),
+                id:      Cow::Owned(id.into_owned()),
+                context: Cow::Owned(context.into_owned()),
             },
         }
     }
@@ -253,71 +1137,286 @@ impl<'a> EventData<'a> {
 
 
 /***** LIBRARY *****/
+/// Shrinks a trace to a smaller one that still exhibits some behaviour.
+///
+/// This is useful when reporting bugs: e.g., when an example produces an unexpected illegal
+/// action, this can remove all events that are irrelevant to it. It works by greedily dropping
+/// events (last to first) as long as a fresh [`Audit`] over the remaining events still satisfies
+/// the `predicate`.
+///
+/// Note that dropping events shifts the indices of the ones after it. As such, the predicate
+/// should not refer to events by their index in the original trace.
+///
+/// # Arguments
+/// - `events`: The trace of [`Event`]s to minimize.
+/// - `predicate`: Decides whether an [`Audit`] over a candidate trace still exhibits the
+///   behaviour of interest.
+///
+/// # Returns
+/// A subsequence of `events` for which `predicate` holds and from which no single event can be
+/// dropped without it failing. If the predicate does not hold for the full trace, it is returned
+/// as-is.
+pub fn minimize<'a, P: AuditablePolicy>(events: &[Event<'a, P::Payload>], predicate: impl Fn(&Audit<P>) -> bool) -> Vec<Event<'a, P::Payload>> {
+    let holds = |events: &[Event<'a, P::Payload>]| -> bool {
+        let mut audit: Audit<P> = Audit::new();
+        audit.audit_all(events);
+        predicate(&audit)
+    };
+
+    // Don't bother if there's nothing to reproduce
+    let mut res: Vec<Event<'a, P::Payload>> = events.to_vec();
+    if !holds(&res) {
+        return res;
+    }
+
+    // Otherwise, attempt to drop every event once
+    for i in (0..res.len()).rev() {
+        let event: Event<'a, P::Payload> = res.remove(i);
+        if !holds(&res) {
+            res.insert(i, event);
+        }
+    }
+    res
+}
+
+/// Compares two traces, e.g., two variants of the same scenario.
+///
+/// Events are aligned by the identifiers of the messages and actions in them, not by their index;
+/// so inserting or dropping events doesn't throw off the rest of the comparison. If a message or
+/// action occurs more than once in a trace, only its first occurrence is considered.
+///
+/// # Arguments
+/// - `a`: The first trace of [`Event`]s.
+/// - `b`: The second trace of [`Event`]s.
+///
+/// # Returns
+/// An [`AuditDiff`] listing the messages and actions that were added in `b` or removed from `a`,
+/// and the actions of which the permission differs between them. All lists are sorted by
+/// [`EventId`].
+pub fn diff_audits<P: AuditablePolicy>(a: &[Event<P::Payload>], b: &[Event<P::Payload>]) -> AuditDiff {
+    let mut audit_a: Audit<P> = Audit::new();
+    audit_a.audit_all(a);
+    let mut audit_b: Audit<P> = Audit::new();
+    audit_b.audit_all(b);
+
+    // Align the events by ID
+    let ids_a: HashMap<EventId, usize> = event_ids(a);
+    let ids_b: HashMap<EventId, usize> = event_ids(b);
+    let mut diff = AuditDiff::default();
+    for (id, index_a) in &ids_a {
+        let Some(index_b) = ids_b.get(id) else {
+            diff.removed.push((id.clone(), *index_a));
+            continue;
+        };
+        if matches!(id, EventId::Action { .. }) {
+            let permitted_a: bool = audit_a.outcome_of(*index_a).is_permitted();
+            let permitted_b: bool = audit_b.outcome_of(*index_b).is_permitted();
+            if permitted_a != permitted_b {
+                diff.changed.push(PermissionChange { id: id.clone(), index_a: *index_a, index_b: *index_b, permitted_a, permitted_b });
+            }
+        }
+    }
+    diff.added.extend(ids_b.into_iter().filter(|(id, _)| !ids_a.contains_key(id)));
+
+    // Make it deterministic
+    diff.added.sort();
+    diff.removed.sort();
+    diff.changed.sort_by(|lhs, rhs| lhs.id.cmp(&rhs.id));
+    diff
+}
+
+
+
 /// Defines a so-called "audit" that is used to examine a [`Trace`] and properly asses action
 /// validity in the context of the system at the time of enacting.
+///
+/// The audit is generic over the [`AuditablePolicy`] language it audits; use [`SlickAudit`] for
+/// the Slick policies used throughout the prototype.
 #[derive(Debug)]
-pub struct Audit {
+pub struct Audit<P: AuditablePolicy> {
     /// The current number of events seen.
     i: usize,
-    /// The list of agreed messages up to this point.
-    agreed: HashSet<Program>,
-    /// The list of stated messages up to this point.
-    stated: HashSet<Program>,
+    /// The current time, as last advanced by [`EventControl::AdvanceTime`].
+    now: u64,
+    /// The list of agreed messages up to this point, together with the window (`[at, until)`) in
+    /// which they are valid.
+    ///
+    /// Messages are identified by their `(author, id)`-pairs.
+    agreed: HashMap<(String, String), (u64, Option<u64>)>,
+    /// The list of stated messages up to this point, together with who can see them. [`None`]
+    /// means that everybody can.
+    stated: HashMap<(String, String), HashSet<Option<String>>>,
+    /// The messages that are agreed or stated, by their `(author, id)`-pairs.
+    messages: HashMap<(String, String), Arc<Message<P::Payload>>>,
     /// A list of event indices mapping [`EventControl::EnactAction`]s to [`Permission`]s.
-    validity: HashMap<usize, Result<Permission, SyntaxError>>,
+    validity: HashMap<usize, Result<Permission<P>, P::Error>>,
+    /// The configuration used to extract the policy of actions (e.g., to recognize effects).
+    config: P::Config,
+    /// Whether actions of which the policy has unknown facts are considered invalid.
+    strict_unknowns: bool,
+    /// If given, only actions enacted by these actors are given a [`Permission`].
+    actors: Option<HashSet<String>>,
+    /// The index of the most recent [`EventData::Write`] to every variable.
+    #[cfg(feature = "dataplane")]
+    last_writes: HashMap<VarId, usize>,
+    /// A list of event indices mapping [`EventData::Read`]s to the [`EventData::Write`]s they
+    /// observed.
+    #[cfg(feature = "dataplane")]
+    read_sources: HashMap<usize, usize>,
+    /// The index of the first [`EventControl::EnactAction`] of every action, by its (actor,
+    /// identifier) pair.
+    #[cfg(feature = "dataplane")]
+    actions: HashMap<(String, String), usize>,
+    /// A list of event indices mapping dataplane events to the kind and fact of the effect they
+    /// had, together with the index of the action (enacted before them) that they named as their
+    /// context. Because data events only name the identifier of that action, it is resolved among
+    /// the actions enacted by the agent doing the access.
+    #[cfg(feature = "dataplane")]
+    data_accesses: HashMap<usize, (EffectKind, GroundAtom, Option<usize>)>,
 }
 
+/// An [`Audit`] over the [Slick](crate::policy::slick) policies used throughout the prototype.
+pub type SlickAudit = Audit<SlickPolicy>;
+
 // Constructors
-impl Default for Audit {
+impl<P: AuditablePolicy> Default for Audit<P> {
     #[inline]
     fn default() -> Self { Self::new() }
 }
-impl Audit {
+impl<P: AuditablePolicy> Audit<P> {
     /// Creates a new Audit that is initialized to not having seen any trace yet.
     ///
+    /// It uses the policy's [default configuration](AuditablePolicy::default_config()).
+    ///
+    /// # Returns
+    /// A new Audit ready for (wait for it) auditing.
+    #[inline]
+    pub fn new() -> Self { Self::with_config(P::default_config()) }
+
+    /// Creates a new Audit that extracts policy with a custom configuration.
+    ///
+    /// # Arguments
+    /// - `config`: The [`AuditablePolicy::Config`] to extract policy with.
+    ///
     /// # Returns
     /// A new Audit ready for (wait for it) auditing.
     #[inline]
-    pub fn new() -> Self {
-        Self { i: 0, agreed: HashSet::with_capacity(4), stated: HashSet::with_capacity(64), validity: HashMap::with_capacity(16) }
+    pub fn with_config(config: P::Config) -> Self {
+        Self {
+            i: 0,
+            now: 0,
+            agreed: HashMap::with_capacity(4),
+            stated: HashMap::with_capacity(64),
+            messages: HashMap::with_capacity(64),
+            validity: HashMap::with_capacity(16),
+            config,
+            strict_unknowns: false,
+            actors: None,
+            #[cfg(feature = "dataplane")]
+            last_writes: HashMap::with_capacity(16),
+            #[cfg(feature = "dataplane")]
+            read_sources: HashMap::with_capacity(16),
+            #[cfg(feature = "dataplane")]
+            actions: HashMap::with_capacity(16),
+            #[cfg(feature = "dataplane")]
+            data_accesses: HashMap::with_capacity(16),
+        }
+    }
+
+    /// Configures whether unknown facts make an action invalid.
+    ///
+    /// By default, only derived errors do. In strict mode, an action is also not
+    /// [valid](Permission::valid_act) if its policy has facts that are neither true nor false
+    /// (e.g., because it's not stratified).
+    ///
+    /// # Arguments
+    /// - `strict`: Whether to treat unknown facts as errors.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_strict_unknowns(mut self, strict: bool) -> Self {
+        self.strict_unknowns = strict;
+        self
+    }
+
+    /// Configures the Audit to only check the actions of some actors.
+    ///
+    /// Statements and agreements are still tracked for everyone, as they may justify the actions
+    /// of the chosen actors. But other actions are skipped, saving their inference, and won't have
+    /// a [`Permission`] (see [`Audit::permission_of()`]). Likewise, the dataplane events of other
+    /// agents are never [violations](Audit::is_data_violation()).
+    ///
+    /// # Arguments
+    /// - `actors`: The identifiers of the actors of which to check actions.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn with_actors(mut self, actors: HashSet<String>) -> Self {
+        self.actors = Some(actors);
+        self
     }
 }
+impl Audit<SlickPolicy> {
+    /// Creates a new Audit that recognizes effects with custom patterns.
+    ///
+    /// By default (see [`Audit::new()`]), effects are facts of the shape
+    /// `Worker reads Variable`, `Worker writes Variable` or `Worker deletes Variable`. If a scenario's policy uses another
+    /// vocabulary for its effects, then this constructor can be used to teach it to the Audit.
+    ///
+    /// # Arguments
+    /// - `patterns`: Pairs of patterns recognizing effects and how to find their affectors. See
+    ///   [`Policy::update_effect_pattern()`](crate::policy::slick::Policy::update_effect_pattern())
+    ///   for more information.
+    ///
+    /// # Returns
+    /// A new Audit ready for (wait for it) auditing.
+    #[inline]
+    pub fn with_effect_patterns(patterns: Vec<(PatternAtom, AffectorAtom)>) -> Self { Self::with_config(patterns) }
+}
 
 // Auditing
-impl Audit {
+impl<P: AuditablePolicy> Audit<P> {
     /// Audits a particular [`Event`].
     ///
     /// # Arguments
     /// - `event`: An [`Event`] to examine. Will update the "current state" of the system the audit
-    ///   keeps internally if it's an [`EventControl::AdvanceTime`] or an
-    ///   [`EventControl::StateMessage`]. If it's an [`EventControl::EnactAction`], will store its
-    ///   validity.
-    pub fn audit(&mut self, event: &Event<Program>) {
+    ///   keeps internally if it's an [`EventControl::AdvanceTime`], an
+    ///   [`EventControl::StateMessage`] or one that sets or retracts agreements. If it's an
+    ///   [`EventControl::EnactAction`], will store its validity. If it's an [`EventData::Read`],
+    ///   will remember which [`EventData::Write`] it observed.
+    pub fn audit(&mut self, event: &Event<P::Payload>) {
         match event {
             // We're only interested in control plane events
             Event::Control { event } => match event {
-                // We keep track of the stated messages
-                EventControl::StateMessage { who: _, to: _, msg } => {
-                    self.stated.insert(msg.payload.clone());
+                // We keep track of the stated messages and who can see them
+                EventControl::StateMessage { who, to, msg } => {
+                    self.messages.entry(message_id(msg)).or_insert_with(|| msg.clone());
+                    let visible: &mut HashSet<Option<String>> = self.stated.entry(message_id(msg)).or_default();
+                    visible.insert(Some(who.to_string()));
+                    visible.insert(match to {
+                        justact::Recipient::All => None,
+                        justact::Recipient::One(to) => Some(to.to_string()),
+                    });
                     self.i += 1;
                 },
 
                 // Enacting of actions triggers the "real" audit
-                EventControl::EnactAction { who: _, to: _, action } => {
-                    let mut validity: Permission = Default::default();
+                EventControl::EnactAction { who, to: _, action } => {
+                    let mut validity: Permission<P> = Default::default();
+                    if self.actors.as_ref().is_some_and(|actors| !actors.contains(&action.actor_id)) {
+                        self.i += 1;
+                        return;
+                    }
+                    #[cfg(feature = "dataplane")]
+                    self.actions.entry((action.actor_id.clone(), action.human_id.clone())).or_insert(self.i);
 
                     // Before we begin, compute the action's denotation
-                    let denot: Denotation = match Extractor.extract(&action.payload()) {
-                        Ok(mut pol) => {
-                            pol.update_effect_pattern(
-                                PatternAtom::Tuple(vec![
-                                    PatternAtom::Variable(Text::from_str("Worker")),
-                                    PatternAtom::ConstantSet(vec![Text::from_str("reads"), Text::from_str("writes")]),
-                                    PatternAtom::Variable(Text::from_str("Variable")),
-                                ]),
-                                AffectorAtom::Variable(Text::from_str("Worker")),
-                            );
-                            pol.truths()
+                    let (pol, denot): (P, P::Denotation) = match P::extract(&self.config, action) {
+                        Ok(pol) => {
+                            let denot: P::Denotation = pol.truths();
+                            (pol, denot)
                         },
                         Err(err) => {
                             // We failed to extract. Log the error.
@@ -326,20 +1425,12 @@ impl Audit {
                             return;
                         },
                     };
-                    let mut truths: Vec<(bool, GroundAtom)> = denot
-                        .iter_truths()
-                        .cloned()
+                    let mut truths: Vec<(bool, P::Fact)> = P::facts(&denot)
+                        .into_iter()
                         .map(|t| {
                             // First, find out which atoms are errors; then sort on that boolean
                             // first before we sort on the alphabet
-                            (
-                                match &t {
-                                    GroundAtom::Constant(t) if format!("{t:?}") == "error" => true,
-                                    GroundAtom::Tuple(ts) if !ts.is_empty() && format!("{:?}", ts[0]) == "error" => true,
-                                    _ => false,
-                                },
-                                t,
-                            )
+                            (P::is_error(&t), t)
                         })
                         .collect();
                     truths.sort_by(|lhs, rhs| match (lhs.0, rhs.0) {
@@ -348,24 +1439,59 @@ impl Audit {
                         _ => format!("{:?}", lhs.1).cmp(&format!("{:?}", rhs.1)),
                     });
                     validity.truths = truths.into_iter().map(|(_, t)| t).collect();
-                    validity.effects = denot.iter_effects().cloned().collect();
-                    validity.effects.sort_by_key(|e| format!("{e:?}"));
+                    validity.effects = P::effects(&denot);
+                    validity.effects.sort();
 
 
 
                     // First property: check whether the action is valid
                     // NOTE: Because we have sorted truths already, the search should be crazy fast
-                    validity.valid_act = denot.is_valid();
+                    validity.inference_failed = P::inference_failed(&denot);
+                    validity.valid_act = !validity.inference_failed && P::is_valid(&denot) && !(self.strict_unknowns && P::has_unknowns(&denot));
+                    if !validity.inference_failed {
+                        validity.error_supports = validity
+                            .truths
+                            .iter()
+                            .filter(|t| P::is_error(t))
+                            .map(|t| (t.clone(), pol.supports_of(&denot, t)))
+                            .collect();
+                    }
 
-                    // Second property: check whether everything in the justification is stated
+                    // Second property: check whether everything in the justification is stated (to
+                    // the actor, that is; agreements are always visible to everyone)
                     for msg in action.extra.iter() {
-                        validity.sourced &= self.stated.contains(&msg.payload);
+                        let id: (String, String) = message_id(msg);
+                        if !self.agreed.contains_key(&id) && !self.stated.contains_key(&id) {
+                            // Nobody ever saw this one
+                            validity.sourced = false;
+                            if !validity.missing_justifications.contains(&id) {
+                                validity.missing_justifications.push(id);
+                            }
+                            continue;
+                        }
+                        validity.sourced &= self.agreed.contains_key(&id)
+                            || self
+                                .stated
+                                .get(&id)
+                                .map(|visible| visible.contains(&None) || visible.contains(&Some(who.to_string())))
+                                .unwrap_or(false);
                     }
+                    validity.missing_justifications.sort();
 
                     // Third property: is the basis agreed?
                     // NOTE: By construction, everything in agreed is also stated, so we don't
                     // check that explicitly.
-                    validity.based = denot.is_valid();
+                    // NOTE: The basis only counts if the justification actually includes it; in
+                    // particular, an empty justification has no basis to speak of, regardless of
+                    // what the action names as one.
+                    let basis: (String, String) = message_id(&action.basis);
+                    validity.based = action.extra.iter().any(|msg| message_id(msg) == basis) && self.agreed.contains_key(&basis);
+
+                    // Fourth property: if the basis is only agreed for a while, are we in it?
+                    if let Some((at, until)) = self.agreed.get(&basis) {
+                        validity.current = *at <= self.now && until.map(|until| self.now < until).unwrap_or(true);
+                        validity.future_basis = *at > self.now;
+                    }
 
 
 
@@ -374,24 +1500,109 @@ impl Audit {
                     self.i += 1;
                 },
 
-                // Adding of agreements has no effect on us.
+                // We keep track of agreements and when they are valid
                 EventControl::SetAgreements { agrees } => {
                     for agree in agrees {
-                        self.agreed.insert(agree.payload.clone());
+                        self.messages.entry(message_id(agree)).or_insert_with(|| agree.clone());
+                        self.agreed.entry(message_id(agree)).or_insert((self.now, None));
                     }
                     self.i += 1
                 },
+                EventControl::SetAgreementsWithin { agrees, at, until } => {
+                    for agree in agrees {
+                        self.messages.entry(message_id(agree)).or_insert_with(|| agree.clone());
+                        self.agreed.insert(message_id(agree), (*at, Some(*until)));
+                    }
+                    self.i += 1
+                },
+                // Retracted agreements can no longer serve as a basis
+                EventControl::RetractAgreement { author, id } => {
+                    self.agreed.remove(&(author.to_string(), id.to_string()));
+                    self.i += 1
+                },
+
+                // We keep track of time
+                EventControl::AdvanceTime { timestamp } => {
+                    self.now = *timestamp;
+                    self.i += 1
+                },
             },
 
-            // Data events have no bearing on us
+            // Data events have no bearing on validity, but we do track their lineage
             #[cfg(feature = "dataplane")]
-            Event::Data { .. } => self.i += 1,
+            Event::Data { event } => {
+                let (kind, who, id, context): (EffectKind, &Cow<str>, &Cow<VarId>, &Cow<str>) = match event {
+                    EventData::Read { who, id, context, .. } => {
+                        if let Some(write) = self.last_writes.get(id.as_ref()) {
+                            self.read_sources.insert(self.i, *write);
+                        }
+                        (EffectKind::Read, who, id, context)
+                    },
+                    EventData::Write { who, id, context, .. } => {
+                        self.last_writes.insert(id.as_ref().clone(), self.i);
+                        (EffectKind::Write, who, id, context)
+                    },
+                    // Reads after a deletion no longer observe earlier writes
+                    EventData::Delete { who, id, context } => {
+                        self.last_writes.remove(id.as_ref());
+                        (EffectKind::Delete, who, id, context)
+                    },
+                };
+                // The actions of actors we don't audit can't justify anything, so neither do we check their accesses
+                if !self.actors.as_ref().is_some_and(|actors| !actors.contains(who.as_ref())) {
+                    let action: Option<usize> = self.actions.get(&(who.to_string(), context.to_string())).copied();
+                    self.data_accesses.insert(self.i, (kind, kind.fact(who, id), action));
+                }
+                self.i += 1
+            },
+        }
+    }
+
+    /// Audits a sequence of [`Event`]s in order.
+    ///
+    /// This is equivalent to calling [`Audit::audit()`] on every event.
+    ///
+    /// # Arguments
+    /// - `events`: The [`Event`]s to examine.
+    #[inline]
+    pub fn audit_all<'e, 'a: 'e>(&mut self, events: impl IntoIterator<Item = &'e Event<'a, P::Payload>>) {
+        for event in events {
+            self.audit(event);
         }
     }
+
+    /// Audits a sequence of [`Event`]s in order, but only checks the actions of some actors.
+    ///
+    /// This is like [`Audit::audit_all()`], except that actions enacted by anyone not in `actors`
+    /// are skipped (see [`Audit::with_actors()`]). This saves inference on large traces when
+    /// only the behaviour of some parties is of interest.
+    ///
+    /// # Arguments
+    /// - `events`: Some iterator yielding [`Event`]s to audit.
+    /// - `actors`: The identifiers of the actors of which to check actions.
+    pub fn audit_filtered<'e, 'a: 'e>(&mut self, events: impl IntoIterator<Item = &'e Event<'a, P::Payload>>, actors: &HashSet<String>) {
+        let prev: Option<HashSet<String>> = self.actors.replace(actors.clone());
+        self.audit_all(events);
+        self.actors = prev;
+    }
 }
 
 // Action retrieval
-impl Audit {
+impl<P: AuditablePolicy> Audit<P> {
+    /// Returns the number of events audited so far.
+    ///
+    /// # Returns
+    /// The number of events, which is also one more than the index of the last one.
+    #[inline]
+    pub const fn len(&self) -> usize { self.i }
+
+    /// Returns whether no events have been audited yet.
+    ///
+    /// # Returns
+    /// True if [`Audit::len()`] is zero, or false otherwise.
+    #[inline]
+    pub const fn is_empty(&self) -> bool { self.i == 0 }
+
     /// Attempts to find the action with the given index.
     ///
     /// # Returns
@@ -401,5 +1612,1304 @@ impl Audit {
     /// - [`Some(Err(_))`](Err) is returned, indicating that the given index points to an audited
     ///   action but its justification did not result in a parsable policy; or
     /// - [`None`] is returned, indicating that no action was audited at the given index.
-    pub fn permission_of(&self, index: usize) -> Option<&Result<Permission, SyntaxError>> { self.validity.get(&index) }
+    pub fn permission_of(&self, index: usize) -> Option<&Result<Permission<P>, P::Error>> { self.validity.get(&index) }
+
+    /// Summarizes the audit of the event with the given index.
+    ///
+    /// This is like [`Audit::permission_of()`], but wrapped in an [`ActionOutcome`] that doesn't
+    /// require any further matching to decide if the action was permitted.
+    ///
+    /// # Arguments
+    /// - `index`: The index of the event to find the outcome of.
+    ///
+    /// # Returns
+    /// An [`ActionOutcome`] describing the audit's conclusion.
+    pub fn outcome_of(&self, index: usize) -> ActionOutcome<P> {
+        match self.validity.get(&index) {
+            Some(Ok(perm)) if perm.is_permitted() => ActionOutcome::Permitted(perm.clone()),
+            Some(Ok(perm)) => ActionOutcome::Rejected(perm.clone()),
+            Some(Err(err)) => ActionOutcome::PolicyError(err.to_string()),
+            None => ActionOutcome::NotAnAction,
+        }
+    }
+}
+
+// World state
+impl<P: AuditablePolicy> Audit<P> {
+    /// Returns all messages agreed upon so far.
+    ///
+    /// Note that this includes agreements that are only valid within a window of time, regardless
+    /// of whether that window is current.
+    ///
+    /// # Returns
+    /// An iterator over the agreed [`Message`]s, in no particular order.
+    #[inline]
+    pub fn agreements(&self) -> impl '_ + Iterator<Item = &Message<P::Payload>> {
+        self.agreed.keys().filter_map(|id| self.messages.get(id)).map(|msg| &**msg)
+    }
+
+    /// Returns all messages stated so far, to anyone.
+    ///
+    /// # Returns
+    /// An iterator over the stated [`Message`]s, in no particular order.
+    #[inline]
+    pub fn statements(&self) -> impl '_ + Iterator<Item = &Message<P::Payload>> {
+        self.stated.keys().filter_map(|id| self.messages.get(id)).map(|msg| &**msg)
+    }
+
+    /// Takes a snapshot of the world as seen after all events audited so far.
+    ///
+    /// # Returns
+    /// A [`WorldState`] with the current time, agreements, statements and (if the `dataplane`
+    /// feature is enabled) existing variables.
+    pub fn world(&self) -> WorldState<P> {
+        let mut agreed: Vec<(&(String, String), (u64, Option<u64>))> = self.agreed.iter().map(|(id, window)| (id, *window)).collect();
+        agreed.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+        let mut stated: Vec<&(String, String)> = self.stated.keys().collect();
+        stated.sort();
+        #[cfg(feature = "dataplane")]
+        let mut data: Vec<VarId> = self.last_writes.keys().cloned().collect();
+        #[cfg(feature = "dataplane")]
+        data.sort();
+
+        WorldState {
+            now: self.now,
+            agreed: agreed.into_iter().filter_map(|(id, (at, until))| self.messages.get(id).map(|msg| (msg.clone(), at, until))).collect(),
+            stated: stated.into_iter().filter_map(|id| self.messages.get(id).cloned()).collect(),
+            #[cfg(feature = "dataplane")]
+            data,
+        }
+    }
+
+    /// Reconstructs the world as it was just before a particular event.
+    ///
+    /// The audit itself only keeps the latest state, so this replays the given trace up to (but
+    /// not including) `index` with a fresh audit that uses the same configuration. That makes it
+    /// possible to show the exact context in which an earlier action was evaluated.
+    ///
+    /// # Arguments
+    /// - `events`: The trace of [`Event`]s that this audit audited.
+    /// - `index`: The index of the event to reconstruct the world before. If it's beyond the end
+    ///   of the trace, the world after the full trace is returned.
+    ///
+    /// # Returns
+    /// A [`WorldState`] of the world just before the event at `index`.
+    pub fn world_at(&self, events: &[Event<P::Payload>], index: usize) -> WorldState<P> {
+        let mut audit: Self = Self::with_config(self.config.clone()).with_strict_unknowns(self.strict_unknowns);
+        audit.audit_all(&events[..index.min(events.len())]);
+        audit.world()
+    }
+}
+
+// Data lineage
+#[cfg(feature = "dataplane")]
+impl<P: AuditablePolicy> Audit<P> {
+    /// Finds the write that produced the value observed by a read.
+    ///
+    /// # Arguments
+    /// - `index`: The index of the [`EventData::Read`] to find the source of.
+    ///
+    /// # Returns
+    /// The index of the most recent [`EventData::Write`] to the same variable before the read, or
+    /// [`None`] if the given index isn't a read or the variable wasn't written to before.
+    #[inline]
+    pub fn read_source(&self, index: usize) -> Option<usize> { self.read_sources.get(&index).copied() }
+}
+#[cfg(feature = "dataplane")]
+impl Audit<SlickPolicy> {
+    /// Checks whether a dataplane event was justified.
+    ///
+    /// It is if the action it names as its context was enacted before it by the same agent, is
+    /// permitted and has the event's effect as one of its effects; i.e., an effect of the same
+    /// [kind](Effect::kind()) on the same variable by the same agent (see
+    /// [`EffectKind::fact()`]).
+    ///
+    /// # Arguments
+    /// - `index`: The index of the [`EventData`] to check.
+    ///
+    /// # Returns
+    /// True if the event at `index` is a dataplane event that wasn't justified, or false
+    /// otherwise (including if it's not a dataplane event at all, or if it's done by an agent
+    /// whose actions [aren't audited](Audit::with_actors())).
+    pub fn is_data_violation(&self, index: usize) -> bool {
+        let Some((kind, fact, action)) = self.data_accesses.get(&index) else { return false };
+        match action.and_then(|action| self.validity.get(&action)) {
+            Some(Ok(perm)) => !perm.is_permitted() || !perm.effects.iter().any(|e| e.kind() == Some(*kind) && &e.fact == fact),
+            Some(Err(_)) | None => true,
+        }
+    }
+
+    /// Finds all dataplane events that weren't justified.
+    ///
+    /// See [`Audit::is_data_violation()`] for when an event is.
+    ///
+    /// # Returns
+    /// The indices of the offending [`EventData`]s, in order.
+    pub fn data_violations(&self) -> Vec<usize> {
+        let mut res: Vec<usize> = self.data_accesses.keys().copied().filter(|i| self.is_data_violation(*i)).collect();
+        res.sort();
+        res
+    }
+}
+
+
+
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// The bytes every binary trace starts with.
+///
+/// Readers (like the inspector) can use these to tell binary traces apart from JSON ones.
+#[cfg(feature = "binary-trace")]
+pub const BINARY_TRACE_MAGIC: &[u8; 8] = b"JUSTACT\x01";
+
+/// Encodes a single [`Event`] as a frame in a binary trace.
+///
+/// Every frame is the length of the encoded event (as a little-endian [`u32`]), followed by the
+/// event as [MessagePack](https://msgpack.org). Note that, unlike formats like `bincode` or
+/// `postcard`, MessagePack is self-describing, which we need for the tagged enums in events.
+///
+/// # Arguments
+/// - `event`: The [`Event`] to encode.
+///
+/// # Returns
+/// The bytes of the frame.
+///
+/// # Errors
+/// This function errors if we failed to serialize the event, or if it's larger than 4 GiB.
+#[cfg(feature = "binary-trace")]
+pub fn encode_event_binary(event: &Event<str>) -> Result<Vec<u8>, BinaryTraceError> {
+    let raw: Vec<u8> = rmp_serde::to_vec_named(event).map_err(BinaryTraceError::Encode)?;
+    let len: u32 = raw.len().try_into().map_err(|_| BinaryTraceError::EventTooLarge { size: raw.len() })?;
+    let mut frame: Vec<u8> = Vec::with_capacity(4 + raw.len());
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend(raw);
+    Ok(frame)
+}
+
+/// Decodes the body of a single frame in a binary trace.
+///
+/// See [`encode_event_binary()`] for the format of frames.
+///
+/// # Arguments
+/// - `raw`: The bytes of the frame _after_ its length.
+///
+/// # Returns
+/// The decoded [`Event`].
+///
+/// # Errors
+/// This function errors if the bytes did not encode a valid event.
+#[cfg(feature = "binary-trace")]
+#[inline]
+pub fn decode_event_binary(raw: &[u8]) -> Result<Event<'static, str>, BinaryTraceError> { rmp_serde::from_slice(raw).map_err(BinaryTraceError::Decode) }
+
+/// Writes a binary trace of events.
+///
+/// Binary traces are a compact alternative to the JSON lines written by the
+/// [`JsonLinesEventHandler`](crate::io::JsonLinesEventHandler). They start with the
+/// [`BINARY_TRACE_MAGIC`], followed by one frame per event (see [`encode_event_binary()`]).
+///
+/// # Arguments
+/// - `writer`: Some [`Write`]r to write the trace to.
+/// - `events`: The [`Event`]s to write.
+///
+/// # Errors
+/// This function errors if we failed to encode any of the events or to write them.
+#[cfg(feature = "binary-trace")]
+pub fn write_events_binary<'e, 'a: 'e>(mut writer: impl Write, events: impl IntoIterator<Item = &'e Event<'a, str>>) -> Result<(), BinaryTraceError> {
+    writer.write_all(BINARY_TRACE_MAGIC).map_err(BinaryTraceError::Write)?;
+    for event in events {
+        writer.write_all(&encode_event_binary(event)?).map_err(BinaryTraceError::Write)?;
+    }
+    writer.flush().map_err(BinaryTraceError::Write)
+}
+
+/// Reads a binary trace of events written by [`write_events_binary()`].
+///
+/// # Arguments
+/// - `reader`: Some [`Read`]er to read the trace from.
+///
+/// # Returns
+/// The [`Event`]s in the trace, in order.
+///
+/// # Errors
+/// This function errors if the input did not start with the [`BINARY_TRACE_MAGIC`], if we failed
+/// to read from it, if it ended in the middle of a frame or if any of the frames did not encode a
+/// valid event.
+#[cfg(feature = "binary-trace")]
+pub fn read_events_binary(mut reader: impl Read) -> Result<Vec<Event<'static, str>>, BinaryTraceError> {
+    // Check it's a trace to begin with
+    let mut magic: [u8; 8] = [0; 8];
+    match reader.read_exact(&mut magic) {
+        Ok(_) if &magic == BINARY_TRACE_MAGIC => {},
+        Ok(_) => return Err(BinaryTraceError::MissingMagic),
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Err(BinaryTraceError::MissingMagic),
+        Err(err) => return Err(BinaryTraceError::Read(err)),
+    }
+
+    // Then read frames until we run out
+    let mut events: Vec<Event<'static, str>> = Vec::new();
+    let mut raw: Vec<u8> = Vec::new();
+    loop {
+        // Only running out at the start of a frame is a clean end
+        let mut len: [u8; 4] = [0; 4];
+        let mut n: usize = 0;
+        while n < len.len() {
+            match reader.read(&mut len[n..]) {
+                Ok(0) if n == 0 => return Ok(events),
+                Ok(0) => return Err(BinaryTraceError::Truncated),
+                Ok(read) => n += read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                Err(err) => return Err(BinaryTraceError::Read(err)),
+            }
+        }
+
+        // NOTE: We don't allocate the length up front, as it may be anything in a corrupt trace
+        let len: u32 = u32::from_le_bytes(len);
+        raw.clear();
+        (&mut reader).take(len as u64).read_to_end(&mut raw).map_err(BinaryTraceError::Read)?;
+        if raw.len() != len as usize {
+            return Err(BinaryTraceError::Truncated);
+        }
+        events.push(decode_event_binary(&raw)?);
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use slick::parse;
+
+    use super::*;
+
+
+    /// Generates a permission with the given booleans and some error truth.
+    #[inline]
+    fn make_permission(valid_act: bool, sourced: bool, based: bool) -> Permission {
+        Permission {
+            valid_act,
+            sourced,
+            missing_justifications: Vec::new(),
+            based,
+            current: true,
+            future_basis: false,
+            inference_failed: false,
+            truths: vec![parse::ground_atom("error (bad thing)").unwrap().1, parse::ground_atom("foo").unwrap().1],
+            effects: Vec::new(),
+            error_supports: Vec::new(),
+        }
+    }
+
+
+    #[test]
+    fn test_permission_explain() {
+        assert_eq!(make_permission(true, true, true).explain(), "OK");
+        assert_eq!(make_permission(false, true, true).explain(), "ILLEGAL (not valid [error (bad thing)])");
+        assert_eq!(make_permission(true, false, true).explain(), "ILLEGAL (not sourced)");
+        assert_eq!(make_permission(true, true, false).explain(), "ILLEGAL (not based)");
+        assert_eq!(make_permission(false, false, true).explain(), "ILLEGAL (not valid [error (bad thing)], not sourced)");
+        assert_eq!(make_permission(false, true, false).explain(), "ILLEGAL (not valid [error (bad thing)], not based)");
+        assert_eq!(make_permission(true, false, false).explain(), "ILLEGAL (not sourced, not based)");
+        assert_eq!(make_permission(false, false, false).explain(), "ILLEGAL (not valid [error (bad thing)], not sourced, not based)");
+
+        // Without error truths to blame
+        let perm = Permission { valid_act: false, ..Default::default() };
+        assert_eq!(perm.explain(), "ILLEGAL (not valid)");
+
+        // Outside of the agreement's window
+        let perm = Permission { current: false, ..Default::default() };
+        assert_eq!(perm.explain(), "ILLEGAL (not current)");
+        let perm = Permission { current: false, future_basis: true, ..Default::default() };
+        assert_eq!(perm.explain(), "ILLEGAL (not current (basis in the future))");
+
+        // When the policy couldn't be computed, its error truth isn't blamed
+        let perm = Permission { valid_act: false, inference_failed: true, truths: vec![parse::ground_atom("error (inference failure)").unwrap().1], ..Default::default() };
+        assert_eq!(perm.explain(), "ILLEGAL (not valid (inference failed))");
+    }
+
+    #[test]
+    fn test_audit_agreement_window() {
+        let agree = Arc::new(Message { human_id: "consortium 1".into(), author_id: "consortium".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let enact: Event<Program> = Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action {
+                    human_id: "amy 1".into(),
+                    actor_id: "amy".into(),
+                    basis:    agree.clone(),
+                    extra:    ::justact::messages::MessageSet::from_iter([agree.clone()]),
+                },
+            },
+        };
+        let current_at = |audit: &SlickAudit, i: usize| -> bool { audit.permission_of(i).unwrap().as_ref().unwrap().current };
+        let future_at = |audit: &SlickAudit, i: usize| -> bool { audit.permission_of(i).unwrap().as_ref().unwrap().future_basis };
+
+        // Agree on the message for `[5, 10)`
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } });
+        audit.audit(&Event::Control { event: EventControl::SetAgreementsWithin { agrees: vec![agree.clone()], at: 5, until: 10 } });
+
+        // Before the window, i.e., enacted before the relevant time advance
+        audit.audit(&enact);
+        assert!(!current_at(&audit, 2));
+        assert!(future_at(&audit, 2));
+        assert_eq!(audit.outcome_of(2).permission().unwrap().explain(), "ILLEGAL (not current (basis in the future))");
+
+        // Within the window
+        audit.audit(&Event::Control { event: EventControl::AdvanceTime { timestamp: 5 } });
+        audit.audit(&enact);
+        assert!(current_at(&audit, 4));
+        audit.audit(&Event::Control { event: EventControl::AdvanceTime { timestamp: 9 } });
+        audit.audit(&enact);
+        assert!(current_at(&audit, 6));
+
+        // After the window
+        audit.audit(&Event::Control { event: EventControl::AdvanceTime { timestamp: 10 } });
+        audit.audit(&enact);
+        assert!(!current_at(&audit, 8));
+        assert!(!future_at(&audit, 8));
+        assert!(!audit.outcome_of(8).is_permitted());
+
+        // Agreements without a window are always current
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control { event: EventControl::AdvanceTime { timestamp: 1000 } });
+        audit.audit(&enact);
+        assert!(current_at(&audit, 2));
+    }
+
+    #[test]
+    fn test_audit_retract_agreement() {
+        let agree = Arc::new(Message { human_id: "consortium 1".into(), author_id: "consortium".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let enact: Event<Program> = Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action {
+                    human_id: "amy 1".into(),
+                    actor_id: "amy".into(),
+                    basis:    agree.clone(),
+                    extra:    ::justact::messages::MessageSet::from_iter([agree.clone()]),
+                },
+            },
+        };
+        let based_at = |audit: &SlickAudit, i: usize| -> bool { audit.permission_of(i).unwrap().as_ref().unwrap().based };
+
+        // While agreed, the action is fine
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&enact);
+        assert!(based_at(&audit, 1));
+        assert!(audit.outcome_of(1).is_permitted());
+
+        // Retracting some other agreement changes nothing
+        audit.audit(&Event::Control { event: EventControl::RetractAgreement { author: Cow::Borrowed("consortium"), id: Cow::Borrowed("consortium 2") } });
+        audit.audit(&enact);
+        assert!(audit.outcome_of(3).is_permitted());
+
+        // But once retracted, it no longer is
+        audit.audit(&Event::Control { event: EventControl::RetractAgreement { author: Cow::Borrowed("consortium"), id: Cow::Borrowed("consortium 1") } });
+        audit.audit(&enact);
+        assert!(!based_at(&audit, 5));
+        assert!(!audit.outcome_of(5).is_permitted());
+        assert_eq!(audit.agreements().count(), 0);
+    }
+
+    #[test]
+    fn test_audit_raw_action() {
+        let agree = Arc::new(Message::<Program>::new_raw("consortium 1", "consortium", parse::program("foo.").unwrap().1));
+        let stated = Arc::new(Message::<Program>::new_raw("amy 1", "amy", parse::program("bar.").unwrap().1));
+        let unstated = Arc::new(Message::<Program>::new_raw("bob 1", "bob", parse::program("baz.").unwrap().1));
+        let enact = |id: &str, extra: Vec<Arc<Message<Program>>>| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action::new_raw(id, "amy", agree.clone(), extra),
+                },
+            }
+        };
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: stated.clone() } });
+        audit.audit(&enact("amy 2", vec![agree.clone(), stated.clone()]));
+        audit.audit(&enact("amy 3", vec![agree.clone(), unstated.clone()]));
+        assert!(audit.outcome_of(2).is_permitted());
+        assert!(!audit.outcome_of(3).is_permitted());
+        assert!(!audit.permission_of(3).unwrap().as_ref().unwrap().sourced);
+    }
+
+    #[test]
+    fn test_audit_error_supports() {
+        let agree = Arc::new(Message::<Program>::new_raw("consortium 1", "consortium", parse::program("foo. error if foo.").unwrap().1));
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action::new_raw("amy 1", "amy", agree.clone(), [agree.clone()]),
+            },
+        });
+        let perm: &Permission = audit.permission_of(1).unwrap().as_ref().unwrap();
+        assert!(!perm.valid_act);
+
+        // The error is explained by the antecedent that made it fire
+        let error: GroundAtom = parse::ground_atom("error").unwrap().1;
+        let (_, supports) = perm.error_supports.iter().find(|(e, _)| *e == error).unwrap();
+        assert!(supports.contains(&parse::ground_atom("foo").unwrap().1));
+    }
+
+    #[test]
+    fn test_audit_error_supports_transitive() {
+        let agree = Arc::new(Message::<Program>::new_raw(
+            "consortium 1",
+            "consortium",
+            parse::program("foo. bar if foo. baz if bar. baz if qux. qux if baz. error if baz.").unwrap().1,
+        ));
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action::new_raw("amy 1", "amy", agree.clone(), [agree.clone()]),
+            },
+        });
+        let perm: &Permission = audit.permission_of(1).unwrap().as_ref().unwrap();
+
+        // The error is explained all the way back to `foo`, even though `baz` and `qux` derive each other
+        let error: GroundAtom = parse::ground_atom("error").unwrap().1;
+        let (_, supports) = perm.error_supports.iter().find(|(e, _)| *e == error).unwrap();
+        assert_eq!(supports.first(), Some(&parse::ground_atom("baz").unwrap().1));
+        for fact in ["bar", "foo"] {
+            assert!(supports.contains(&parse::ground_atom(fact).unwrap().1), "{fact:?} missing from {supports:?}");
+        }
+        assert!(!supports.contains(&error));
+    }
+
+    #[test]
+    fn test_audit_missing_justifications() {
+        let agree = Arc::new(Message::<Program>::new_raw("consortium 1", "consortium", parse::program("foo.").unwrap().1));
+        let stated = Arc::new(Message::<Program>::new_raw("amy 1", "amy", parse::program("bar.").unwrap().1));
+        let secret = Arc::new(Message::<Program>::new_raw("bob 1", "bob", parse::program("baz.").unwrap().1));
+        // A typo in amy's script makes her refer to a message that doesn't exist
+        let dangling = Arc::new(Message::<Program>::new_raw("amy 11", "amy", parse::program("bar.").unwrap().1));
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: stated.clone() } });
+        audit.audit(&Event::Control {
+            event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::One(Cow::Borrowed("bob")), msg: secret.clone() },
+        });
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action::new_raw("amy 2", "amy", agree.clone(), [agree.clone(), stated.clone(), dangling.clone(), secret.clone()]),
+            },
+        });
+
+        // Only the message that was never stated is missing; the secret one is merely invisible
+        let perm: &Permission = audit.permission_of(3).unwrap().as_ref().unwrap();
+        assert!(!perm.sourced);
+        assert_eq!(perm.missing_justifications, vec![("amy".to_string(), "amy 11".to_string())]);
+        assert_eq!(perm.explain(), "ILLEGAL (not sourced [never stated: \"amy 11\"])");
+
+        // Without dangling references, there's nothing missing
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action::new_raw("amy 3", "amy", agree.clone(), [agree.clone(), stated.clone(), secret.clone()]),
+            },
+        });
+        let perm: &Permission = audit.permission_of(4).unwrap().as_ref().unwrap();
+        assert!(!perm.sourced);
+        assert!(perm.missing_justifications.is_empty());
+    }
+
+    #[test]
+    fn test_audit_empty_justification() {
+        let agree = Arc::new(Message::<Program>::new_raw("consortium 1", "consortium", parse::program("foo.").unwrap().1));
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action::new_raw("amy 1", "amy", agree.clone(), []),
+            },
+        });
+        let perm: &Permission = audit.permission_of(1).unwrap().as_ref().unwrap();
+        assert!(perm.valid_act);
+        assert!(perm.sourced);
+        assert!(!perm.based);
+        assert_eq!(perm.explain(), "ILLEGAL (not based)");
+    }
+
+    #[test]
+    fn test_audit_basis_not_justified() {
+        let agree = Arc::new(Message::<Program>::new_raw("consortium 1", "consortium", parse::program("foo.").unwrap().1));
+        let other = Arc::new(Message::<Program>::new_raw("amy 1", "amy", parse::program("bar.").unwrap().1));
+        let enact = |id: &str, extra: Vec<Arc<Message<Program>>>| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action::new_raw(id, "amy", agree.clone(), extra),
+                },
+            }
+        };
+
+        // Naming an agreed basis is not enough if the justification doesn't include it
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: other.clone() } });
+        audit.audit(&enact("amy 2", vec![other.clone()]));
+        audit.audit(&enact("amy 3", vec![other.clone(), agree.clone()]));
+        assert!(!audit.permission_of(2).unwrap().as_ref().unwrap().based);
+        assert!(audit.permission_of(3).unwrap().as_ref().unwrap().based);
+        assert!(audit.outcome_of(3).is_permitted());
+    }
+
+    #[test]
+    fn test_audit_sourced_recipients() {
+        let secret = Arc::new(Message { human_id: "amy 1".into(), author_id: "amy".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let enact = |who: &'static str| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed(who),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: format!("{who} 2"),
+                        actor_id: who.into(),
+                        basis:    secret.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([secret.clone()]),
+                    },
+                },
+            }
+        };
+        let sourced_at = |audit: &SlickAudit, i: usize| -> bool { audit.permission_of(i).unwrap().as_ref().unwrap().sourced };
+
+        // Amy tells only Bob
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control {
+            event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::One(Cow::Borrowed("bob")), msg: secret.clone() },
+        });
+        audit.audit(&enact("amy"));
+        audit.audit(&enact("bob"));
+        audit.audit(&enact("cho"));
+        assert!(sourced_at(&audit, 1));
+        assert!(sourced_at(&audit, 2));
+        assert!(!sourced_at(&audit, 3));
+        assert!(!audit.outcome_of(3).is_permitted());
+
+        // Once it's gossiped to everyone, Cho may use it too
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::All, msg: secret.clone() } });
+        audit.audit(&enact("cho"));
+        assert!(sourced_at(&audit, 5));
+    }
+
+    #[test]
+    fn test_audit_identical_payloads() {
+        let amys = Arc::new(Message { human_id: "amy 1".into(), author_id: "amy".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let bobs = Arc::new(Message { human_id: "bob 1".into(), author_id: "bob".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let enact = |msg: &Arc<Message<Program>>| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("cho"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: "cho 1".into(),
+                        actor_id: "cho".into(),
+                        basis:    msg.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([msg.clone()]),
+                    },
+                },
+            }
+        };
+        let sourced_at = |audit: &SlickAudit, i: usize| -> bool { audit.permission_of(i).unwrap().as_ref().unwrap().sourced };
+
+        // Amy only tells Bob, whereas Bob tells everyone the same thing
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control {
+            event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::One(Cow::Borrowed("bob")), msg: amys.clone() },
+        });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::All, msg: bobs.clone() } });
+        audit.audit(&enact(&amys));
+        audit.audit(&enact(&bobs));
+
+        // Both are stated, but only Bob's is visible to Cho
+        assert!(!sourced_at(&audit, 2));
+        assert!(sourced_at(&audit, 3));
+
+        // Similarly, agreeing on one doesn't agree on the other
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![bobs.clone()] } });
+        audit.audit(&enact(&amys));
+        audit.audit(&enact(&bobs));
+        assert!(!audit.permission_of(5).unwrap().as_ref().unwrap().based);
+        assert!(audit.permission_of(6).unwrap().as_ref().unwrap().based);
+        assert!(audit.outcome_of(6).is_permitted());
+    }
+
+    #[test]
+    fn test_audit_agreements_statements() {
+        let msg = |author: &str, id: &str, payload: &str| -> Arc<Message<Program>> {
+            Arc::new(Message { human_id: id.into(), author_id: author.into(), payload: parse::program(payload).unwrap().1, meta: BTreeMap::new() })
+        };
+        let agree = msg("consortium", "consortium 1", "foo.");
+        let amys = msg("amy", "amy 1", "bar.");
+        let bobs = msg("bob", "bob 1", "bar.");
+
+        let mut audit = SlickAudit::new();
+        assert_eq!(audit.agreements().count(), 0);
+        assert_eq!(audit.statements().count(), 0);
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } });
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: amys.clone() } });
+        audit.audit(&Event::Control {
+            event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::One(Cow::Borrowed("amy")), msg: bobs.clone() },
+        });
+        // Restating doesn't count twice
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::All, msg: bobs.clone() } });
+
+        assert_eq!(audit.agreements().map(|m| m.human_id.as_str()).collect::<Vec<&str>>(), ["consortium 1"]);
+        assert_eq!(audit.statements().count(), 3);
+        assert_eq!(audit.statements().map(|m| m.human_id.as_str()).collect::<HashSet<&str>>(), HashSet::from(["consortium 1", "amy 1", "bob 1"]));
+    }
+
+    #[test]
+    fn test_audit_world_at() {
+        let msg = |author: &str, id: &str, payload: &str| -> Arc<Message<Program>> {
+            Arc::new(Message { human_id: id.into(), author_id: author.into(), payload: parse::program(payload).unwrap().1, meta: BTreeMap::new() })
+        };
+        let agree = msg("consortium", "consortium 1", "foo.");
+        let amys = msg("amy", "amy 1", "bar.");
+        let events: Vec<Event<Program>> = vec![
+            Event::Control { event: EventControl::SetAgreementsWithin { agrees: vec![agree.clone()], at: 1, until: 3 } },
+            Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } },
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: amys.clone() } },
+            Event::Control { event: EventControl::AdvanceTime { timestamp: 3 } },
+        ];
+        let mut audit = SlickAudit::new();
+        audit.audit_all(&events);
+
+        // Before anything happened
+        let world = audit.world_at(&events, 0);
+        assert_eq!(world.now, 0);
+        assert!(world.agreed.is_empty());
+        assert!(world.stated.is_empty());
+
+        // Halfway
+        let world = audit.world_at(&events, 2);
+        assert_eq!(world.now, 1);
+        assert_eq!(world.agreed.iter().map(|(m, at, until)| (m.human_id.as_str(), *at, *until)).collect::<Vec<_>>(), [("consortium 1", 1, Some(3))]);
+        assert!(world.is_current("consortium", "consortium 1"));
+        assert!(world.stated.is_empty());
+
+        // At the end, which is the same as the live state
+        let world = audit.world_at(&events, 100);
+        assert_eq!(world.now, 3);
+        assert!(!world.is_current("consortium", "consortium 1"));
+        assert_eq!(world.stated.iter().map(|m| m.human_id.as_str()).collect::<Vec<&str>>(), ["amy 1"]);
+        assert_eq!(audit.world().stated.len(), 1);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let msg = |author: &str, payload: &str| -> Arc<Message<Program>> {
+            Arc::new(Message { human_id: format!("{author} 1"), author_id: author.into(), payload: parse::program(payload).unwrap().1, meta: BTreeMap::new() })
+        };
+        let state = |msg: &Arc<Message<Program>>| -> Event<'static, Program> {
+            Event::Control { event: EventControl::StateMessage { who: Cow::Owned(msg.author_id.clone()), to: justact::Recipient::All, msg: msg.clone() } }
+        };
+        let agree = msg("consortium", "error if bad.");
+        let bad = msg("amy", "bad.");
+        let events: Vec<Event<Program>> = vec![
+            state(&agree),
+            Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } },
+            state(&msg("bob", "foo.")),
+            state(&bad),
+            state(&msg("cho", "bar.")),
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: "amy 2".into(),
+                        actor_id: "amy".into(),
+                        basis:    agree.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([agree.clone(), bad.clone()]),
+                    },
+                },
+            },
+        ];
+
+        // Minimize while there's an action that is invalid _but_ otherwise justified
+        let min = minimize(&events, |audit: &SlickAudit| {
+            (0..audit.len()).any(|i| matches!(audit.permission_of(i), Some(Ok(perm)) if !perm.valid_act && perm.sourced))
+        });
+        assert_eq!(min.len(), 3);
+        assert!(matches!(&min[0], Event::Control { event: EventControl::StateMessage { msg, .. } } if msg.author_id == "consortium"));
+        assert!(matches!(&min[1], Event::Control { event: EventControl::StateMessage { msg, .. } } if msg.author_id == "amy"));
+        assert!(matches!(&min[2], Event::Control { event: EventControl::EnactAction { .. } }));
+
+        // If it doesn't hold in the first place, nothing is dropped
+        assert_eq!(minimize(&events, |_: &SlickAudit| false).len(), events.len());
+    }
+
+    #[cfg(feature = "datalog")]
+    #[test]
+    fn test_audit_datalog() {
+        type Spec = datalog::ast::Spec<(&'static str, &'static str)>;
+        let basis = Arc::new(Message {
+            human_id:  "amy 1".into(),
+            author_id: "amy".into(),
+            payload:   <Spec as PolicyDeserialize>::deserialize("foo. error :- bar.").unwrap(),
+            meta:      BTreeMap::new(),
+        });
+        let bad = Arc::new(Message { human_id: "bob 1".into(), author_id: "bob".into(), payload: <Spec as PolicyDeserialize>::deserialize("bar.").unwrap(), meta: BTreeMap::new() });
+        let enact = |extra: Vec<Arc<Message<Spec>>>| -> Event<'static, Spec> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: "amy 3".into(),
+                        actor_id: "amy".into(),
+                        basis:    basis.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter(extra),
+                    },
+                },
+            }
+        };
+
+        // The audit works the same as for Slick, just with another language
+        let mut audit: Audit<crate::policy::datalog::Policy<'static, 'static>> = Audit::new();
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: basis.clone() } });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::All, msg: bad.clone() } });
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![basis.clone()] } });
+        audit.audit(&enact(vec![basis.clone()]));
+        audit.audit(&enact(vec![basis.clone(), bad.clone()]));
+        assert!(audit.outcome_of(3).is_permitted());
+        let perm = audit.permission_of(4).unwrap().as_ref().unwrap();
+        assert!(!perm.valid_act);
+        assert!(perm.sourced);
+        assert!(perm.truths.iter().any(<crate::policy::datalog::Policy as AuditablePolicy>::is_error));
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_audit_read_source() {
+        let x = VarId::new("amy", "data", "x");
+        let y = VarId::new("amy", "data", "y");
+        let read = |id: &VarId| -> Event<'static, Program> {
+            Event::Data {
+                event: EventData::Read { who: Cow::Borrowed("bob"), id: Cow::Owned(id.clone()), context: Cow::Borrowed("bob 1"), contents: None },
+            }
+        };
+        let write = |id: &VarId| -> Event<'static, Program> {
+            Event::Data {
+                event: EventData::Write {
+                    who: Cow::Borrowed("amy"),
+                    id: Cow::Owned(id.clone()),
+                    context: Cow::Borrowed("amy 1"),
+                    new: true,
+                    contents: Cow::Borrowed(b"hello"),
+                    labels: Cow::Owned(HashMap::new()),
+                },
+            }
+        };
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&read(&x));
+        audit.audit(&write(&x));
+        audit.audit(&write(&y));
+        audit.audit(&read(&x));
+        audit.audit(&write(&x));
+        audit.audit(&read(&x));
+        audit.audit(&read(&y));
+
+        // Reads before any write have no source
+        assert_eq!(audit.read_source(0), None);
+        // Writes aren't reads
+        assert_eq!(audit.read_source(1), None);
+        // Reads observe the most recent write to the same variable
+        assert_eq!(audit.read_source(3), Some(1));
+        assert_eq!(audit.read_source(5), Some(4));
+        assert_eq!(audit.read_source(6), Some(2));
+
+        // Deleting a variable forgets its writes
+        audit.audit(&Event::Data { event: EventData::Delete { who: Cow::Borrowed("amy"), id: Cow::Owned(x.clone()), context: Cow::Borrowed("amy 1") } });
+        audit.audit(&read(&x));
+        audit.audit(&read(&y));
+        assert_eq!(audit.read_source(8), None);
+        assert_eq!(audit.read_source(9), Some(2));
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_audit_effect_reads_writes() {
+        use crate::policy::slick::{effect_deletes, effect_reads, effect_writes};
+
+        let x = VarId::new("amy", "data", "x");
+        let msg = Arc::new(Message {
+            human_id:  "amy 1".into(),
+            author_id: "amy".into(),
+            payload:   parse::program("amy reads ((amy data) x). bob writes ((amy data) x). cho deletes ((amy data) x).").unwrap().1,
+            meta:      BTreeMap::new(),
+        });
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action {
+                    human_id: "amy 2".into(),
+                    actor_id: "amy".into(),
+                    basis:    msg.clone(),
+                    extra:    ::justact::messages::MessageSet::from_iter([msg.clone()]),
+                },
+            },
+        });
+
+        // The default patterns should recognize exactly the atoms the helpers construct
+        let perm: &Permission = audit.permission_of(0).unwrap().as_ref().unwrap();
+        let effects: HashSet<(GroundAtom, GroundAtom)> = perm.effects.iter().map(|e| (e.fact.clone(), e.affector.clone())).collect();
+        assert_eq!(
+            effects,
+            HashSet::from([
+                (effect_reads("amy", &x), GroundAtom::Constant(Text::from_str("amy"))),
+                (effect_writes("bob", &x), GroundAtom::Constant(Text::from_str("bob"))),
+                (effect_deletes("cho", &x), GroundAtom::Constant(Text::from_str("cho"))),
+            ])
+        );
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_audit_data_violations() {
+        let x = VarId::new("amy", "data", "x");
+        let agree = Arc::new(Message {
+            human_id:  "consortium 1".into(),
+            author_id: "consortium".into(),
+            payload:   parse::program("amy reads ((amy data) x).").unwrap().1,
+            meta:      BTreeMap::new(),
+        });
+        let secret = Arc::new(Message {
+            human_id:  "amy 1".into(),
+            author_id: "amy".into(),
+            payload:   parse::program("amy reads ((amy data) x).").unwrap().1,
+            meta:      BTreeMap::new(),
+        });
+        let enact = |id: &str, basis: &Arc<Message<Program>>| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: id.into(),
+                        actor_id: "amy".into(),
+                        basis:    basis.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([basis.clone()]),
+                    },
+                },
+            }
+        };
+        let read = |context: &str| -> Event<'static, Program> {
+            Event::Data { event: EventData::Read { who: Cow::Borrowed("amy"), id: Cow::Owned(x.clone()), context: Cow::Owned(context.into()), contents: None } }
+        };
+
+        let mut audit = SlickAudit::new();
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } });
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: secret.clone() } });
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+
+        // Permitted accesses
+        audit.audit(&enact("amy 2", &agree));
+        assert!(audit.outcome_of(3).is_permitted());
+        audit.audit(&read("amy 2"));
+        assert!(!audit.is_data_violation(4));
+
+        // Effects the action doesn't have
+        audit.audit(&Event::Data {
+            event: EventData::Delete { who: Cow::Borrowed("amy"), id: Cow::Owned(x.clone()), context: Cow::Borrowed("amy 2") },
+        });
+        assert!(audit.is_data_violation(5));
+
+        // Actions that don't exist (yet)
+        audit.audit(&read("amy 3"));
+        assert!(audit.is_data_violation(6));
+        audit.audit(&enact("amy 3", &agree));
+        audit.audit(&read("amy 3"));
+        assert!(!audit.is_data_violation(8));
+
+        // Actions that are illegal
+        audit.audit(&enact("amy 4", &secret));
+        assert!(!audit.outcome_of(9).is_permitted());
+        audit.audit(&read("amy 4"));
+        assert!(audit.is_data_violation(10));
+
+        // Actions with the same identifier, but enacted by someone else
+        audit.audit(&Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("bob"),
+                to:     justact::Recipient::All,
+                action: Action {
+                    human_id: "amy 5".into(),
+                    actor_id: "bob".into(),
+                    basis:    agree.clone(),
+                    extra:    ::justact::messages::MessageSet::from_iter([agree.clone()]),
+                },
+            },
+        });
+        assert!(audit.outcome_of(11).is_permitted());
+        audit.audit(&read("amy 5"));
+        assert!(audit.is_data_violation(12));
+
+        // Non-data events are never violations
+        assert!(!audit.is_data_violation(3));
+        assert_eq!(audit.data_violations(), [5, 6, 10, 12]);
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_audit_data_violations_filtered() {
+        let x = VarId::new("amy", "data", "x");
+        let agree = Arc::new(Message {
+            human_id:  "consortium 1".into(),
+            author_id: "consortium".into(),
+            payload:   parse::program("amy reads ((amy data) x). bob reads ((amy data) x).").unwrap().1,
+            meta:      BTreeMap::new(),
+        });
+        let enact = |who: &'static str, id: &str| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed(who),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: id.into(),
+                        actor_id: who.into(),
+                        basis:    agree.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([agree.clone()]),
+                    },
+                },
+            }
+        };
+        let read = |who: &'static str, context: &str| -> Event<'static, Program> {
+            Event::Data { event: EventData::Read { who: Cow::Borrowed(who), id: Cow::Owned(x.clone()), context: Cow::Owned(context.into()), contents: None } }
+        };
+
+        // Only amy is audited
+        let mut audit = SlickAudit::new().with_actors(HashSet::from(["amy".into()]));
+        audit.audit(&Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } });
+        audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+        audit.audit(&enact("amy", "amy 1"));
+        audit.audit(&enact("bob", "bob 1"));
+        assert!(audit.permission_of(3).is_none());
+
+        // The accesses of bob aren't checked, not even if they name nothing
+        audit.audit(&read("bob", "bob 1"));
+        audit.audit(&read("bob", "bob 2"));
+        assert!(!audit.is_data_violation(4));
+        assert!(!audit.is_data_violation(5));
+
+        // While those of amy are
+        audit.audit(&read("amy", "amy 1"));
+        audit.audit(&read("amy", "amy 2"));
+        assert!(!audit.is_data_violation(6));
+        assert!(audit.is_data_violation(7));
+        assert_eq!(audit.data_violations(), [7]);
+    }
+
+    #[cfg(feature = "binary-trace")]
+    #[test]
+    fn test_events_binary_roundtrip() {
+        let msg = Arc::new(Message { human_id: "amy 1".into(), author_id: "amy".into(), payload: "foo.\nbar if foo.\n".to_string(), meta: BTreeMap::new() });
+        let events: Vec<Event<str>> = vec![
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::One(Cow::Borrowed("bob")), msg: msg.clone() } },
+            Event::Control { event: EventControl::SetAgreementsWithin { agrees: vec![msg.clone()], at: 1, until: 5 } },
+            Event::Control { event: EventControl::AdvanceTime { timestamp: 3 } },
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: "amy 2".into(),
+                        actor_id: "amy".into(),
+                        basis:    msg.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([msg.clone()]),
+                    },
+                },
+            },
+        ];
+
+        // Write it and read it back
+        let mut raw: Vec<u8> = Vec::new();
+        write_events_binary(&mut raw, &events).unwrap();
+        assert!(raw.starts_with(BINARY_TRACE_MAGIC));
+        let read: Vec<Event<str>> = read_events_binary(raw.as_slice()).unwrap();
+        assert_eq!(format!("{read:?}"), format!("{events:?}"));
+
+        // It's smaller than the JSON equivalent
+        let json: usize = events.iter().map(|e| serde_json::to_string(e).unwrap().len() + 1).sum();
+        assert!(raw.len() < json);
+
+        // Other inputs are rejected
+        assert!(matches!(read_events_binary(&b"{\"Control\":{}}"[..]), Err(BinaryTraceError::MissingMagic)));
+
+        // As are traces that end in the middle of a frame, be it in its length or its event
+        let last: usize = raw.len() - encode_event_binary(&events[3]).unwrap().len();
+        for cut in [last + 2, raw.len() - 1] {
+            assert!(matches!(read_events_binary(&raw[..cut]), Err(BinaryTraceError::Truncated)), "cut at {cut}");
+        }
+        let mut huge: Vec<u8> = BINARY_TRACE_MAGIC.to_vec();
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(read_events_binary(huge.as_slice()), Err(BinaryTraceError::Truncated)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_events_slick_roundtrip() {
+        let agree = Arc::new(Message {
+            human_id:  "consortium 1".into(),
+            author_id: "consortium".into(),
+            payload:   parse::program("foo. amy says hello if foo.").unwrap().1,
+            meta:      BTreeMap::new(),
+        });
+        let events: Vec<Event<Program>> = vec![
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } },
+            Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } },
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: "amy 1".into(),
+                        actor_id: "amy".into(),
+                        basis:    agree.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([agree.clone()]),
+                    },
+                },
+            },
+        ];
+
+        // Write them as JSON and read them back as Slick
+        let raw: Vec<String> = events.iter().map(|e| serde_json::to_string(&e.serialize()).unwrap()).collect();
+        let read: Vec<Event<str>> = raw.iter().map(|raw| serde_json::from_str(raw).unwrap()).collect();
+        let back: Vec<Event<Program>> = read.iter().map(|e| e.deserialize_slick().unwrap()).collect();
+        assert_eq!(format!("{back:?}"), format!("{events:?}"));
+
+        // Which audits the same as the original
+        let mut audit = SlickAudit::new();
+        for event in &back {
+            audit.audit(event);
+        }
+        assert!(audit.outcome_of(2).is_permitted());
+    }
+
+    #[test]
+    fn test_events_deserialize_slick_error() {
+        let good = Arc::new(Message::<str>::new_raw("consortium 1", "consortium", "foo.\n".into()));
+        let bad = Arc::new(Message::<str>::new_raw("amy 1", "amy", "foo. )".into()));
+        let enact: Event<str> = Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action::new_raw("amy 2", "amy", good.clone(), [good.clone(), bad.clone()]),
+            },
+        };
+
+        // The error points at the message in the justification that isn't Slick
+        let err = enact.deserialize_slick().unwrap_err();
+        assert!(matches!(&err, SyntaxError::Slick { author, id, .. } if author == "amy" && id == "amy 1"));
+        let agree: Event<str> = Event::Control { event: EventControl::SetAgreements { agrees: vec![good] } };
+        assert!(agree.deserialize_slick().is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_permission_serialize() {
+        // A permitted one
+        let mut perm = make_permission(true, true, true);
+        perm.effects = vec![Effect { fact: parse::ground_atom("amy reads x").unwrap().1, affector: parse::ground_atom("amy").unwrap().1 }];
+        assert_eq!(
+            serde_json::to_value(&perm).unwrap(),
+            serde_json::json!({
+                "permitted": true,
+                "valid_act": true,
+                "sourced": true,
+                "missing_justifications": [],
+                "based": true,
+                "current": true,
+                "future_basis": false,
+                "inference_failed": false,
+                "truths": ["error (bad thing)", "foo"],
+                "effects": [{ "fact": "amy reads x", "affector": "amy" }],
+                "error_supports": [],
+            })
+        );
+
+        // An illegal one
+        let mut perm = make_permission(false, false, false);
+        perm.missing_justifications = vec![("amy".into(), "amy 11".into())];
+        assert_eq!(
+            serde_json::to_value(&perm).unwrap(),
+            serde_json::json!({
+                "permitted": false,
+                "valid_act": false,
+                "sourced": false,
+                "missing_justifications": [{ "author": "amy", "id": "amy 11" }],
+                "based": false,
+                "current": true,
+                "future_basis": false,
+                "inference_failed": false,
+                "truths": ["error (bad thing)", "foo"],
+                "effects": [],
+                "error_supports": [],
+            })
+        );
+    }
+
+    #[test]
+    fn test_diff_audits() {
+        let agree = Arc::new(Message { human_id: "consortium 1".into(), author_id: "consortium".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let stmt = Arc::new(Message { human_id: "amy 1".into(), author_id: "amy".into(), payload: parse::program("bar.").unwrap().1, meta: BTreeMap::new() });
+        let enact: Event<Program> = Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed("amy"),
+                to:     justact::Recipient::All,
+                action: Action {
+                    human_id: "amy 2".into(),
+                    actor_id: "amy".into(),
+                    basis:    agree.clone(),
+                    extra:    ::justact::messages::MessageSet::from_iter([agree.clone(), stmt.clone()]),
+                },
+            },
+        };
+        let with: Vec<Event<Program>> = vec![
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } },
+            Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } },
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: stmt.clone() } },
+            enact.clone(),
+        ];
+        let without: Vec<Event<Program>> = vec![
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("amy"), to: justact::Recipient::All, msg: stmt.clone() } },
+            enact.clone(),
+        ];
+
+        // Identical traces don't differ
+        assert!(diff_audits::<SlickPolicy>(&with, &with).is_empty());
+
+        // Without the agreement, the action is no longer permitted
+        let diff = diff_audits::<SlickPolicy>(&with, &without);
+        assert_eq!(diff.added, vec![]);
+        assert_eq!(diff.removed, vec![(EventId::Message { author: "consortium".into(), id: "consortium 1".into() }, 0)]);
+        assert_eq!(diff.changed, vec![PermissionChange {
+            id: EventId::Action { actor: "amy".into(), id: "amy 2".into() },
+            index_a: 3,
+            index_b: 1,
+            permitted_a: true,
+            permitted_b: false,
+        }]);
+        assert_eq!(
+            diff.to_string(),
+            "- message \"consortium 1\" by \"consortium\" (event 0)\n~ action \"amy 2\" by \"amy\" (event 3 -> 1): permitted -> not permitted\n"
+        );
+
+        // And the other way around
+        let diff = diff_audits::<SlickPolicy>(&without, &with);
+        assert_eq!(diff.added, vec![(EventId::Message { author: "consortium".into(), id: "consortium 1".into() }, 0)]);
+        assert_eq!(diff.removed, vec![]);
+        assert!(!diff.changed[0].permitted_a && diff.changed[0].permitted_b);
+    }
+
+    #[test]
+    fn test_audit_strict_unknowns() {
+        let agree = Arc::new(Message { human_id: "consortium 1".into(), author_id: "consortium".into(), payload: parse::program("foo if not foo.").unwrap().1, meta: BTreeMap::new() });
+        let events: Vec<Event<Program>> = vec![
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("consortium"), to: justact::Recipient::All, msg: agree.clone() } },
+            Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } },
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed("amy"),
+                    to:     justact::Recipient::All,
+                    action: Action {
+                        human_id: "amy 1".into(),
+                        actor_id: "amy".into(),
+                        basis:    agree.clone(),
+                        extra:    ::justact::messages::MessageSet::from_iter([agree.clone()]),
+                    },
+                },
+            },
+        ];
+
+        // The policy is not stratified
+        let den = <SlickPolicy as ::justact::policies::Policy>::truths(&Extractor::new().extract(&agree.as_ref().clone()).unwrap());
+        assert!(den.has_unknowns());
+        assert!(!<SlickPolicy as ::justact::policies::Policy>::truths(&SlickPolicy::default()).has_unknowns());
+
+        // By default, that's fine
+        let mut audit = SlickAudit::new();
+        audit.audit_all(&events);
+        assert!(audit.outcome_of(2).is_permitted());
+
+        // But not when strict
+        let mut audit = SlickAudit::new().with_strict_unknowns(true);
+        audit.audit_all(&events);
+        let perm = audit.permission_of(2).unwrap().as_ref().unwrap();
+        assert!(!perm.valid_act);
+        assert_eq!(perm.explain(), "ILLEGAL (not valid)");
+    }
+
+    #[test]
+    fn test_audit_filtered() {
+        let agree = Arc::new(Message::<Program>::new_raw("consortium 1", "consortium", parse::program("foo.").unwrap().1));
+        let stated = Arc::new(Message::<Program>::new_raw("bob 1", "bob", parse::program("bar.").unwrap().1));
+        let enact = |who: &'static str, id: &str| -> Event<'static, Program> {
+            Event::Control {
+                event: EventControl::EnactAction {
+                    who:    Cow::Borrowed(who),
+                    to:     justact::Recipient::All,
+                    action: Action::new_raw(id, who, agree.clone(), [agree.clone(), stated.clone()]),
+                },
+            }
+        };
+        let events: Vec<Event<Program>> = vec![
+            Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } },
+            Event::Control { event: EventControl::StateMessage { who: Cow::Borrowed("bob"), to: justact::Recipient::All, msg: stated.clone() } },
+            enact("amy", "amy 1"),
+            enact("bob", "bob 2"),
+        ];
+
+        // Only Amy's action is checked, but Bob's statement still justifies it
+        let mut audit = SlickAudit::new();
+        audit.audit_filtered(&events, &HashSet::from(["amy".into()]));
+        assert_eq!(audit.len(), 4);
+        assert!(audit.outcome_of(2).is_permitted());
+        assert!(audit.permission_of(3).is_none());
+        assert!(matches!(audit.outcome_of(3), ActionOutcome::NotAnAction));
+
+        // The filter doesn't stick around
+        audit.audit(&enact("bob", "bob 3"));
+        assert!(audit.outcome_of(4).is_permitted());
+
+        // Unless configured as such
+        let mut audit = SlickAudit::new().with_actors(HashSet::from(["bob".into()]));
+        audit.audit_all(&events);
+        assert!(audit.permission_of(2).is_none());
+        assert!(audit.outcome_of(3).is_permitted());
+    }
 }
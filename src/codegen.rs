@@ -21,9 +21,12 @@ pub(crate) use len_ident_list;
 
 /// Implements standard ops (and some for serde) for types that have complicated bounds. In
 /// particular, they do some `P` generic that is `ToOwned`.
+///
+/// Fields may be given a default value by writing `field: Type = value`. Then, if that field is
+/// missing when deserializing, the value is used instead of erroring.
 macro_rules! impl_struct_with_custom_derive {
     /* Private implementors */
-    (impl Clone $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl Clone $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         impl<P: ?Sized + ToOwned> Clone for $name<P>
         where
             P::Owned: Clone,
@@ -33,9 +36,9 @@ macro_rules! impl_struct_with_custom_derive {
         }
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
-    (impl Debug $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl Debug $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         impl<P: ?Sized + ToOwned> std::fmt::Debug for $name<P>
         where
             P::Owned: std::fmt::Debug,
@@ -49,18 +52,18 @@ macro_rules! impl_struct_with_custom_derive {
         }
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
-    (impl Eq $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl Eq $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         impl<P: ?Sized + ToOwned> Eq for $name<P>
         where
             P::Owned: Eq,
         {}
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
-    (impl Hash $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl Hash $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         impl<P: ?Sized + ToOwned> std::hash::Hash for $name<P>
         where
             P::Owned: std::hash::Hash,
@@ -72,9 +75,9 @@ macro_rules! impl_struct_with_custom_derive {
         }
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
-    (impl PartialEq $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl PartialEq $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         impl<P: ?Sized + ToOwned> PartialEq for $name<P>
         where
             P::Owned: PartialEq,
@@ -87,9 +90,9 @@ macro_rules! impl_struct_with_custom_derive {
         }
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
-    (impl Deserialize $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl Deserialize $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         #[cfg(feature = "serde")]
         impl<'de, P: ?Sized + ToOwned> serde::Deserialize<'de> for $name<P>
         where
@@ -139,7 +142,7 @@ macro_rules! impl_struct_with_custom_derive {
                             $(
                                 $field: match $field {
                                     Some(res) => res,
-                                    None => return Err(<A::Error as serde::de::Error>::custom(concat!("Missing field '", stringify!($field), "'"))),
+                                    None => $crate::codegen::impl_struct_with_custom_derive!(missing $field $(= $default)?),
                                 },
                             )*
                         })
@@ -151,9 +154,9 @@ macro_rules! impl_struct_with_custom_derive {
         }
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
-    (impl Serialize $(, $trait:ident)* for $name:ident { $($field:ident),* }) => {
+    (impl Serialize $(, $trait:ident)* for $name:ident { $($field:ident $(= $default:expr)?),* }) => {
         #[cfg(feature = "serde")]
         impl<P: ?Sized + ToOwned> serde::Serialize for $name<P>
         where
@@ -173,9 +176,15 @@ macro_rules! impl_struct_with_custom_derive {
         }
 
         // Don't forget to continue to implement any others
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
+    };
+    (impl for $name:ident { $($field:ident $(= $default:expr)?),* }) => { /* Done implementing */ };
+    (missing $field:ident) => {
+        return Err(<A::Error as serde::de::Error>::custom(concat!("Missing field '", stringify!($field), "'")))
+    };
+    (missing $field:ident = $default:expr) => {
+        $default
     };
-    (impl for $name:ident { $($field:ident),* }) => { /* Done implementing */ };
 
 
 
@@ -186,7 +195,7 @@ macro_rules! impl_struct_with_custom_derive {
         $vis:vis struct $name:ident<P: ?Sized + ToOwned> {
             $(
                 $(#[$($field_attrs:tt)*])*
-                $field_vis:vis $field:ident: $field_ty:ty
+                $field_vis:vis $field:ident: $field_ty:ty $(= $default:expr)?
             ),*
             $(,)?
         }
@@ -199,7 +208,7 @@ macro_rules! impl_struct_with_custom_derive {
             ),*
         }
 
-        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field),* });
+        $crate::codegen::impl_struct_with_custom_derive!(impl $($trait),* for $name { $($field $(= $default)?),* });
     };
 }
 pub(crate) use impl_struct_with_custom_derive;
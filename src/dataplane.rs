@@ -17,12 +17,13 @@ use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::error;
+use std::fmt::{Display, Formatter, Result as FResult};
 use std::rc::Rc;
 
 use thiserror::Error;
 
 use crate::auditing::{Event, EventData};
-use crate::io::EVENT_HANDLER;
+use crate::io::emit;
 
 
 /***** ERRORS *****/
@@ -35,6 +36,38 @@ pub enum Error {
         #[source]
         err: Box<dyn 'static + Send + error::Error>,
     },
+    /// Two stores that were [merged](StoreHandle::merge()) disagree on a variable.
+    #[error("Variable {id} exists in both stores, but with different contents or labels")]
+    MergeConflict { id: VarId },
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// Represents a single variable in the store.
+#[derive(Clone, Debug)]
+struct Variable {
+    /// The contents of the variable.
+    contents: Vec<u8>,
+    /// Any labels that were given to the variable when it was written.
+    labels:   HashMap<String, String>,
+    /// The ID of the action that justified the write of the current contents.
+    context:  String,
+    /// The versions before the current one since the variable was (last) created, in order, as
+    /// pairs of the justifying context and the written contents. Only kept if the store
+    /// [keeps history](StoreHandle::with_history()).
+    history:  Vec<(String, Vec<u8>)>,
+}
+
+/// Represents the contents of a [`StoreHandle`], shared by all of its scopes.
+#[derive(Debug, Default)]
+struct Store {
+    /// The variables in the store.
+    vars: HashMap<VarId, Variable>,
+    /// Whether to keep the previous versions of variables when they are overwritten.
+    keep_history: bool,
 }
 
 
@@ -42,6 +75,67 @@ pub enum Error {
 
 
 /***** LIBRARY *****/
+/// Identifies a variable in the store.
+///
+/// Variables are always produced by some `dataset` (e.g., a task) owned by some agent, and
+/// have a `name` within that dataset. In Slick, this is written as `((owner dataset) name)`.
+///
+/// For backwards compatibility, identifiers can be converted from- and into tuples of the shape
+/// `((owner, dataset), name)`. This is also how they are serialized.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(from = "((String, String), String)", into = "((String, String), String)"))]
+pub struct VarId {
+    /// The agent owning the dataset.
+    pub owner:   String,
+    /// The dataset (or task) that produced the variable.
+    pub dataset: String,
+    /// The name of the variable within the dataset.
+    pub name:    String,
+}
+impl VarId {
+    /// Constructor for the VarId.
+    ///
+    /// # Arguments
+    /// - `owner`: The agent owning the dataset.
+    /// - `dataset`: The dataset (or task) that produced the variable.
+    /// - `name`: The name of the variable within the dataset.
+    ///
+    /// # Returns
+    /// A new VarId.
+    #[inline]
+    pub fn new(owner: impl Into<String>, dataset: impl Into<String>, name: impl Into<String>) -> Self {
+        Self { owner: owner.into(), dataset: dataset.into(), name: name.into() }
+    }
+}
+impl Display for VarId {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "({} {}) {}", self.owner, self.dataset, self.name) }
+}
+impl<S1: Into<String>, S2: Into<String>, S3: Into<String>> From<((S1, S2), S3)> for VarId {
+    #[inline]
+    fn from(((owner, dataset), name): ((S1, S2), S3)) -> Self { Self::new(owner, dataset, name) }
+}
+impl From<VarId> for ((String, String), String) {
+    #[inline]
+    fn from(value: VarId) -> Self { ((value.owner, value.dataset), value.name) }
+}
+
+
+
+/// Decides what happens when two [merged](StoreHandle::merge()) stores disagree on a variable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MergeConflict {
+    /// The merge fails with an [`Error::MergeConflict`] (and nothing is merged).
+    Error,
+    /// The variable in the store merged into is kept.
+    KeepSelf,
+    /// The variable in the store merged from overwrites it.
+    KeepOther,
+}
+
+
+
 /// Represents a [`StoreHandle`] but scoped to a particular agent.
 #[derive(Debug)]
 pub struct ScopedStoreHandle {
@@ -68,7 +162,51 @@ impl ScopedStoreHandle {
     /// True if the function exists, or false otherwise.
     #[inline]
     #[track_caller]
-    pub fn exists(&self, id: &((String, String), String)) -> bool { self.handle.exists(id) }
+    pub fn exists(&self, id: &VarId) -> bool { self.handle.exists(id) }
+
+    /// Returns the labels of a variable.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to get the labels of.
+    ///
+    /// # Returns
+    /// The labels given to the variable when it was last written, or [`None`] if the given
+    /// variable never existed in the first place.
+    #[inline]
+    #[track_caller]
+    pub fn labels(&self, id: &VarId) -> Option<HashMap<String, String>> { self.handle.labels(id) }
+
+    /// Returns every version a variable has had.
+    ///
+    /// See [`StoreHandle::history()`].
+    ///
+    /// # Arguments
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to get the history of.
+    ///
+    /// # Returns
+    /// Every (kept) write to the variable in order, as pairs of the justifying context and the
+    /// written contents. Empty if the variable doesn't exist.
+    #[inline]
+    #[track_caller]
+    pub fn history(&self, id: &VarId) -> Vec<(String, Vec<u8>)> { self.handle.history(id) }
+
+    /// Lists all variables currently in the store.
+    ///
+    /// # Returns
+    /// The identifiers of all variables in the store, in sorted order.
+    #[inline]
+    pub fn list(&self) -> Vec<VarId> { self.handle.list() }
+
+    /// Lists all variables currently in the store that were produced by a particular dataset.
+    ///
+    /// # Arguments
+    /// - `owner`: The agent owning the dataset.
+    /// - `dataset`: The dataset (or task) that produced the variables.
+    ///
+    /// # Returns
+    /// The identifiers of all variables in the given dataset, in sorted order.
+    #[inline]
+    pub fn list_dataset(&self, owner: &str, dataset: &str) -> Vec<VarId> { self.handle.list_dataset(owner, dataset) }
 
 
 
@@ -88,11 +226,7 @@ impl ScopedStoreHandle {
     /// This function can error if it failed to write a trace of what happened.
     #[inline]
     #[track_caller]
-    pub fn read<'a>(
-        &self,
-        id: ((impl Into<String>, impl Into<String>), impl Into<String>),
-        context: impl Into<Cow<'a, str>>,
-    ) -> Result<Option<Vec<u8>>, Error> {
+    pub fn read<'a>(&self, id: impl Into<VarId>, context: impl Into<Cow<'a, str>>) -> Result<Option<Vec<u8>>, Error> {
         self.handle.read(&self.agent, id, context)
     }
 
@@ -110,13 +244,64 @@ impl ScopedStoreHandle {
     /// This function can error if it failed to write a trace of what happened.
     #[inline]
     #[track_caller]
-    pub fn write<'a>(
+    pub fn write<'a>(&self, id: impl Into<VarId>, context: impl Into<Cow<'a, str>>, contents: impl Into<Vec<u8>>) -> Result<(), Error> {
+        self.handle.write(&self.agent, id, context, contents)
+    }
+
+    /// Writes the contents of a (new) variable, and labels it.
+    ///
+    /// See [`ScopedStoreHandle::write()`] for more information.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to write to.
+    /// - `context`: The ID of an enacted action that is supposed to justify this write.
+    /// - `contents`: Some bytes to write as payload.
+    /// - `labels`: Some key/value pairs to attach to the variable (e.g., `sensitivity=high`).
+    ///
+    /// # Errors
+    /// This function can error if it failed to write a trace of what happened.
+    #[inline]
+    #[track_caller]
+    pub fn write_labelled<'a>(
         &self,
-        id: ((impl Into<String>, impl Into<String>), impl Into<String>),
+        id: impl Into<VarId>,
         context: impl Into<Cow<'a, str>>,
         contents: impl Into<Vec<u8>>,
+        labels: HashMap<String, String>,
     ) -> Result<(), Error> {
-        self.handle.write(&self.agent, id, context, contents)
+        self.handle.write_labelled(&self.agent, id, context, contents, labels)
+    }
+
+    /// Deletes a variable.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to delete.
+    /// - `context`: The ID of an enacted action that is supposed to justify this deletion.
+    ///
+    /// # Returns
+    /// True if the variable existed before it was deleted, or false otherwise.
+    ///
+    /// # Errors
+    /// This function can error if it failed to write a trace of what happened.
+    #[inline]
+    #[track_caller]
+    pub fn delete<'a>(&self, id: impl Into<VarId>, context: impl Into<Cow<'a, str>>) -> Result<bool, Error> { self.handle.delete(&self.agent, id, context) }
+
+    /// Copies all variables of another store into this one.
+    ///
+    /// See [`StoreHandle::merge()`] for more information.
+    ///
+    /// # Arguments
+    /// - `other`: The [`StoreHandle`] to copy the variables of.
+    /// - `context`: The ID of an enacted action that is supposed to justify the writes.
+    /// - `on_conflict`: What to do with variables that exist in both stores, but differently.
+    ///
+    /// # Errors
+    /// This function can error if it failed to write a trace of what happened, or if there was a
+    /// conflict and `on_conflict` is [`MergeConflict::Error`].
+    #[inline]
+    pub fn merge<'a>(&self, other: &StoreHandle, context: impl Into<Cow<'a, str>>, on_conflict: MergeConflict) -> Result<(), Error> {
+        self.handle.merge(&self.agent, other, context, on_conflict)
     }
 }
 
@@ -130,7 +315,7 @@ impl ScopedStoreHandle {
 /// [scope](StoreHandle::scope()) it. Since the handles are done by shared pointers, you can safely
 /// drop the original after all scopes have been made.
 #[derive(Debug)]
-pub struct StoreHandle(Rc<RefCell<HashMap<((String, String), String), Vec<u8>>>>);
+pub struct StoreHandle(Rc<RefCell<Store>>);
 
 // Constructors
 impl Default for StoreHandle {
@@ -141,9 +326,29 @@ impl StoreHandle {
     /// Constructor for the StoreHandle.
     ///
     /// # Returns
-    /// A new StoreHandle with no variables and no contents.
+    /// A new StoreHandle with no variables and no contents. It does not keep the
+    /// [history](StoreHandle::history()) of variables until enabled with
+    /// [`StoreHandle::with_history()`].
+    #[inline]
+    pub fn new() -> Self { Self(Rc::new(RefCell::new(Store::default()))) }
+
+    /// Sets whether this store keeps the previous versions of variables when they are
+    /// overwritten.
+    ///
+    /// This is off by default, as the history of often-written variables grows without bounds.
+    /// It applies to all [scopes](StoreHandle::scope()) of this store, but only to writes after
+    /// it has been enabled.
+    ///
+    /// # Arguments
+    /// - `keep_history`: Whether to keep previous versions.
+    ///
+    /// # Returns
+    /// Self for chaining.
     #[inline]
-    pub fn new() -> Self { Self(Rc::new(RefCell::new(HashMap::new()))) }
+    pub fn with_history(self, keep_history: bool) -> Self {
+        self.0.borrow_mut().keep_history = keep_history;
+        self
+    }
 }
 
 // Scoping
@@ -171,7 +376,76 @@ impl StoreHandle {
     /// True if the function exists, or false otherwise.
     #[inline]
     #[track_caller]
-    pub fn exists(&self, id: &((String, String), String)) -> bool { self.0.borrow().contains_key(id) }
+    pub fn exists(&self, id: &VarId) -> bool { self.0.borrow().vars.contains_key(id) }
+
+    /// Returns the labels of a variable.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to get the labels of.
+    ///
+    /// # Returns
+    /// The labels given to the variable when it was last written, or [`None`] if the given
+    /// variable never existed in the first place.
+    #[inline]
+    #[track_caller]
+    pub fn labels(&self, id: &VarId) -> Option<HashMap<String, String>> {
+        self.0.borrow().vars.get(id).map(|var| var.labels.clone())
+    }
+
+    /// Returns every version a variable has had.
+    ///
+    /// Whereas [reading](StoreHandle::read()) a variable only gives its latest contents, this
+    /// gives every write to it, which is useful to debug how it evolved. Like
+    /// [`StoreHandle::exists()`], this does not produce a trace. Deleting a variable forgets its
+    /// history.
+    ///
+    /// Previous versions are only kept by stores [with history](StoreHandle::with_history()).
+    /// Otherwise, this only gives the current version.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to get the history of.
+    ///
+    /// # Returns
+    /// Every (kept) write to the variable since it was (last) created in order, as pairs of the ID
+    /// of the action that justified it and the written contents. Empty if the variable doesn't
+    /// exist.
+    #[track_caller]
+    pub fn history(&self, id: &VarId) -> Vec<(String, Vec<u8>)> {
+        let store = self.0.borrow();
+        let Some(var) = store.vars.get(id) else { return Vec::new() };
+        let mut history: Vec<(String, Vec<u8>)> = var.history.clone();
+        history.push((var.context.clone(), var.contents.clone()));
+        history
+    }
+
+    /// Lists all variables currently in the store.
+    ///
+    /// Note that, like [`StoreHandle::exists()`], this does not produce a trace.
+    ///
+    /// # Returns
+    /// The identifiers of all variables that have been written and not deleted since, in sorted
+    /// order.
+    #[inline]
+    pub fn list(&self) -> Vec<VarId> {
+        let mut ids: Vec<VarId> = self.0.borrow().vars.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Lists all variables currently in the store that were produced by a particular dataset.
+    ///
+    /// # Arguments
+    /// - `owner`: The agent owning the dataset.
+    /// - `dataset`: The dataset (or task) that produced the variables.
+    ///
+    /// # Returns
+    /// The identifiers of all variables in the given dataset, in sorted order.
+    #[inline]
+    pub fn list_dataset(&self, owner: &str, dataset: &str) -> Vec<VarId> {
+        let mut ids: Vec<VarId> = self.0.borrow().vars.keys().filter(|id| id.owner == owner && id.dataset == dataset).cloned().collect();
+        ids.sort();
+        ids
+    }
 
 
 
@@ -192,34 +466,24 @@ impl StoreHandle {
     /// This function can error if it failed to write a trace of what happened.
     #[inline]
     #[track_caller]
-    pub fn read<'a>(
-        &self,
-        who: impl AsRef<str>,
-        id: ((impl Into<String>, impl Into<String>), impl Into<String>),
-        context: impl Into<Cow<'a, str>>,
-    ) -> Result<Option<Vec<u8>>, Error> {
+    pub fn read<'a>(&self, who: impl AsRef<str>, id: impl Into<VarId>, context: impl Into<Cow<'a, str>>) -> Result<Option<Vec<u8>>, Error> {
         let who: &str = who.as_ref();
-        let id: ((String, String), String) = ((id.0.0.into(), id.0.1.into()), id.1.into());
+        let id: VarId = id.into();
         let context: Cow<'a, str> = context.into();
 
         // Perform the read
-        let contents: Option<Vec<u8>> = { self.0.borrow().get(&id).cloned() };
+        let contents: Option<Vec<u8>> = { self.0.borrow().vars.get(&id).map(|var| var.contents.clone()) };
 
         // Log it
-        EVENT_HANDLER
-            .get()
-            .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
-            .lock()
-            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
-            .handle(Event::Data {
-                event: EventData::Read {
-                    who: who.into(),
-                    id: Cow::Owned(id),
-                    context,
-                    contents: contents.as_ref().map(Vec::as_slice).map(Cow::Borrowed),
-                },
-            })
-            .map_err(|err| Error::TraceHandle { err })?;
+        emit(Event::Data {
+            event: EventData::Read {
+                who: who.into(),
+                id: Cow::Owned(id),
+                context,
+                contents: contents.as_ref().map(Vec::as_slice).map(Cow::Borrowed),
+            },
+        })
+        .map_err(|err| Error::TraceHandle { err })?;
 
         // OK, return the contents
         Ok(contents)
@@ -228,7 +492,8 @@ impl StoreHandle {
     /// Writes the contents of a (new) variable.
     ///
     /// Note that this completely overwrites the contents of a dataset. Agents are responsible for
-    /// reading, then writing the updated version if that behaviour is desired.
+    /// reading, then writing the updated version if that behaviour is desired. The same goes for
+    /// any labels the variable had; see [`StoreHandle::write_labelled()`] to give new ones.
     ///
     /// # Arguments
     /// - `who`: The agent who is writing the contents.
@@ -243,36 +508,293 @@ impl StoreHandle {
     pub fn write<'a>(
         &self,
         who: impl AsRef<str>,
-        id: ((impl Into<String>, impl Into<String>), impl Into<String>),
+        id: impl Into<VarId>,
         context: impl Into<Cow<'a, str>>,
         contents: impl Into<Vec<u8>>,
+    ) -> Result<(), Error> {
+        self.write_labelled(who, id, context, contents, HashMap::new())
+    }
+
+    /// Writes the contents of a (new) variable, and labels it.
+    ///
+    /// Labels are small key/value pairs that describe the variable (e.g., `sensitivity=high`).
+    /// They are part of the trace, such that auditors can reason about them. Like the contents,
+    /// they completely replace any labels the variable had before.
+    ///
+    /// # Arguments
+    /// - `who`: The agent who is writing the contents.
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to write to.
+    /// - `context`: The ID of an enacted action that is supposed to justify this write.
+    /// - `contents`: Some bytes to write as payload.
+    /// - `labels`: Some key/value pairs to attach to the variable.
+    ///
+    /// # Errors
+    /// This function can error if it failed to write a trace of what happened.
+    #[track_caller]
+    pub fn write_labelled<'a>(
+        &self,
+        who: impl AsRef<str>,
+        id: impl Into<VarId>,
+        context: impl Into<Cow<'a, str>>,
+        contents: impl Into<Vec<u8>>,
+        labels: HashMap<String, String>,
     ) -> Result<(), Error> {
         let who: &str = who.as_ref();
-        let id: ((String, String), String) = ((id.0.0.into(), id.0.1.into()), id.1.into());
+        let id: VarId = id.into();
         let context: Cow<'a, str> = context.into();
         let contents: Vec<u8> = contents.into();
 
         // Log it first, for efficiency purposes (it can't fail anyway*)
         // * Famous last words
+        let context_owned: String = context.to_string();
         let mut store = self.0.borrow_mut();
-        EVENT_HANDLER
-            .get()
-            .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
-            .lock()
-            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
-            .handle(Event::Data {
-                event: EventData::Write {
-                    who: Cow::Borrowed(who),
-                    id: Cow::Borrowed(&id),
-                    new: store.contains_key(&id),
-                    context,
-                    contents: Cow::Borrowed(&contents),
-                },
-            })
-            .map_err(|err| Error::TraceHandle { err })?;
-
-        // Perform the write and that's it
-        store.insert(id, contents);
+        emit(Event::Data {
+            event: EventData::Write {
+                who: Cow::Borrowed(who),
+                id: Cow::Borrowed(&id),
+                new: store.vars.contains_key(&id),
+                context,
+                contents: Cow::Borrowed(&contents),
+                labels: Cow::Borrowed(&labels),
+            },
+        })
+        .map_err(|err| Error::TraceHandle { err })?;
+
+        // Perform the write, keeping track of the previous versions if asked
+        let keep_history: bool = store.keep_history;
+        let history: Vec<(String, Vec<u8>)> = match store.vars.remove(&id) {
+            Some(mut prev) if keep_history => {
+                prev.history.push((prev.context, prev.contents));
+                prev.history
+            },
+            _ => Vec::new(),
+        };
+        store.vars.insert(id, Variable { contents, labels, context: context_owned, history });
         Ok(())
     }
+
+    /// Deletes a variable.
+    ///
+    /// Afterwards, reading it behaves as if it never existed. Deleting a variable that doesn't
+    /// exist is still traced, but otherwise does nothing.
+    ///
+    /// # Arguments
+    /// - `who`: The agent who is deleting the variable.
+    /// - `id`: The identifier (as a prefixed-by-author name) of the variable to delete.
+    /// - `context`: The ID of an enacted action that is supposed to justify this deletion.
+    ///
+    /// # Returns
+    /// True if the variable existed before it was deleted, or false otherwise.
+    ///
+    /// # Errors
+    /// This function can error if it failed to write a trace of what happened.
+    #[track_caller]
+    pub fn delete<'a>(&self, who: impl AsRef<str>, id: impl Into<VarId>, context: impl Into<Cow<'a, str>>) -> Result<bool, Error> {
+        let who: &str = who.as_ref();
+        let id: VarId = id.into();
+        let context: Cow<'a, str> = context.into();
+
+        // Log it
+        emit(Event::Data { event: EventData::Delete { who: Cow::Borrowed(who), id: Cow::Borrowed(&id), context } }).map_err(|err| Error::TraceHandle { err })?;
+
+        // Then perform the deletion
+        Ok(self.0.borrow_mut().vars.remove(&id).is_some())
+    }
+
+    /// Copies all variables of another store into this one.
+    ///
+    /// This is useful to compose a scenario out of smaller ones that each have their own store.
+    /// Every copied variable is [written](StoreHandle::write_labelled()) by `who`, such that the
+    /// trace stays consistent with the store's contents. Variables that exist in both stores with
+    /// the same contents and labels are left alone.
+    ///
+    /// # Arguments
+    /// - `who`: The agent who is writing the variables.
+    /// - `other`: The [`StoreHandle`] to copy the variables of.
+    /// - `context`: The ID of an enacted action that is supposed to justify the writes.
+    /// - `on_conflict`: What to do with variables that exist in both stores, but differently.
+    ///
+    /// # Errors
+    /// This function can error if it failed to write a trace of what happened, or if there was a
+    /// conflict and `on_conflict` is [`MergeConflict::Error`]. In the latter case, nothing is
+    /// written.
+    pub fn merge<'a>(&self, who: impl AsRef<str>, other: &StoreHandle, context: impl Into<Cow<'a, str>>, on_conflict: MergeConflict) -> Result<(), Error> {
+        let who: &str = who.as_ref();
+        let context: Cow<'a, str> = context.into();
+        if Rc::ptr_eq(&self.0, &other.0) {
+            // Merging with ourselves never changes anything
+            return Ok(());
+        }
+
+        // Decide what to write before writing anything, such that conflicts leave us untouched
+        let mut writes: Vec<(VarId, Variable)> = Vec::new();
+        {
+            let store = self.0.borrow();
+            let theirs = other.0.borrow();
+            let mut ids: Vec<&VarId> = theirs.vars.keys().collect();
+            ids.sort();
+            for id in ids {
+                let var: &Variable = &theirs.vars[id];
+                match (store.vars.get(id), on_conflict) {
+                    (Some(ours), _) if ours.contents == var.contents && ours.labels == var.labels => continue,
+                    (Some(_), MergeConflict::Error) => return Err(Error::MergeConflict { id: id.clone() }),
+                    (Some(_), MergeConflict::KeepSelf) => continue,
+                    (Some(_), MergeConflict::KeepOther) | (None, _) => writes.push((id.clone(), var.clone())),
+                }
+            }
+        }
+
+        // Then write them (which traces them)
+        for (id, var) in writes {
+            self.write_labelled(who, id, context.clone(), var.contents, var.labels)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::io::{EventHandler, NopEventHandler, register_event_handler};
+
+
+    /// Event handler that remembers the variables written.
+    struct WriteCollector(Arc<Mutex<Vec<VarId>>>);
+    impl EventHandler for WriteCollector {
+        #[inline]
+        fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+            if let Event::Data { event: EventData::Write { id, .. } } = trace {
+                self.0.lock().unwrap().push(id.into_owned());
+            }
+            Ok(())
+        }
+    }
+
+
+    #[test]
+    fn test_store_list() {
+        register_event_handler(NopEventHandler);
+
+        let store = StoreHandle::new();
+        let amy = store.scope("amy");
+        amy.write(VarId::new("amy", "task2", "result"), "amy 2", "1").unwrap();
+        amy.write(VarId::new("amy", "task1", "b"), "amy 1", "2").unwrap();
+        amy.write(VarId::new("amy", "task1", "a"), "amy 1", "3").unwrap();
+        store.write("bob", VarId::new("bob", "task1", "a"), "bob 1", "4").unwrap();
+
+        assert_eq!(store.list(), vec![
+            VarId::new("amy", "task1", "a"),
+            VarId::new("amy", "task1", "b"),
+            VarId::new("amy", "task2", "result"),
+            VarId::new("bob", "task1", "a"),
+        ]);
+        assert_eq!(amy.list_dataset("amy", "task1"), vec![VarId::new("amy", "task1", "a"), VarId::new("amy", "task1", "b")]);
+        assert!(store.list_dataset("cho", "task1").is_empty());
+
+        // Deleted variables are no longer listed
+        amy.delete(VarId::new("amy", "task1", "a"), "amy 1").unwrap();
+        assert_eq!(store.list_dataset("amy", "task1"), vec![VarId::new("amy", "task1", "b")]);
+    }
+
+    #[test]
+    fn test_store_history() {
+        register_event_handler(NopEventHandler);
+
+        let store = StoreHandle::new().with_history(true);
+        let amy = store.scope("amy");
+        let x = VarId::new("amy", "task1", "x");
+        amy.write(x.clone(), "amy 1", "1").unwrap();
+        amy.write(x.clone(), "amy 2", "2").unwrap();
+        amy.write(x.clone(), "amy 3", "3").unwrap();
+
+        // Reading gives the latest, the history all of them
+        assert_eq!(amy.read(x.clone(), "amy 4").unwrap(), Some(b"3".to_vec()));
+        assert_eq!(store.history(&x), vec![
+            ("amy 1".to_string(), b"1".to_vec()),
+            ("amy 2".to_string(), b"2".to_vec()),
+            ("amy 3".to_string(), b"3".to_vec()),
+        ]);
+        assert!(store.history(&VarId::new("amy", "task1", "y")).is_empty());
+
+        // Recreating a deleted variable starts anew
+        amy.delete(x.clone(), "amy 5").unwrap();
+        assert!(amy.history(&x).is_empty());
+        amy.write(x.clone(), "amy 6", "6").unwrap();
+        assert_eq!(amy.history(&x), vec![("amy 6".to_string(), b"6".to_vec())]);
+
+        // Without history, only the current version is known
+        let store = StoreHandle::new();
+        let amy = store.scope("amy");
+        amy.write(x.clone(), "amy 7", "7").unwrap();
+        amy.write(x.clone(), "amy 8", "8").unwrap();
+        assert_eq!(store.history(&x), vec![("amy 8".to_string(), b"8".to_vec())]);
+    }
+
+    #[test]
+    fn test_store_labels() {
+        let store = StoreHandle::new();
+        let amy = store.scope("amy");
+        let x = VarId::new("amy", "task1", "x");
+        let labels: HashMap<String, String> = HashMap::from([("sensitivity".into(), "high".into())]);
+
+        // Labels can be read back after writing...
+        let trace = Arc::new(Mutex::new(Vec::new()));
+        crate::io::with_scoped_event_handler(crate::io::CollectEventHandler(trace.clone()), || {
+            amy.write_labelled(x.clone(), "amy 1", "1", labels.clone()).unwrap();
+        });
+        assert_eq!(amy.labels(&x), Some(labels.clone()));
+        assert_eq!(store.labels(&VarId::new("amy", "task1", "y")), None);
+
+        // ...and are part of the trace
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        assert_eq!(trace.len(), 1);
+        let Event::Data { event: EventData::Write { labels: traced, .. } } = &trace[0] else { panic!("Expected a write, got {:?}", trace[0]) };
+        assert_eq!(**traced, labels);
+
+        // Plain writes replace them
+        crate::io::with_scoped_event_handler(crate::io::NopEventHandler, || amy.write(x.clone(), "amy 2", "2").unwrap());
+        assert_eq!(amy.labels(&x), Some(HashMap::new()));
+    }
+
+    #[test]
+    fn test_store_merge() {
+        register_event_handler(NopEventHandler);
+        let make = |contents: &str| -> StoreHandle {
+            let store = StoreHandle::new();
+            store.write("amy", VarId::new("amy", "task1", contents), "amy 1", contents).unwrap();
+            store.write("amy", VarId::new("amy", "task1", "shared"), "amy 1", contents).unwrap();
+            store
+        };
+        let read = |store: &StoreHandle, name: &str| -> Option<Vec<u8>> { store.0.borrow().vars.get(&VarId::new("amy", "task1", name)).map(|var| var.contents.clone()) };
+
+        // Conflicts can be an error, in which case nothing changes
+        let (ours, theirs) = (make("a"), make("b"));
+        assert!(matches!(ours.merge("amy", &theirs, "amy 2", MergeConflict::Error), Err(Error::MergeConflict { id }) if id.name == "shared"));
+        assert_eq!(ours.list(), vec![VarId::new("amy", "task1", "a"), VarId::new("amy", "task1", "shared")]);
+
+        // Or we keep our own version
+        ours.merge("amy", &theirs, "amy 2", MergeConflict::KeepSelf).unwrap();
+        assert_eq!(read(&ours, "b"), Some(b"b".to_vec()));
+        assert_eq!(read(&ours, "shared"), Some(b"a".to_vec()));
+
+        // Or theirs, which is traced like any write
+        let (ours, theirs) = (make("a"), make("b"));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        crate::io::with_scoped_event_handler(WriteCollector(written.clone()), || {
+            ours.scope("amy").merge(&theirs, "amy 2", MergeConflict::KeepOther).unwrap();
+        });
+        assert_eq!(read(&ours, "b"), Some(b"b".to_vec()));
+        assert_eq!(read(&ours, "shared"), Some(b"b".to_vec()));
+        assert_eq!(*written.lock().unwrap(), vec![VarId::new("amy", "task1", "b"), VarId::new("amy", "task1", "shared")]);
+
+        // Identical variables are no conflict (and aren't rewritten)
+        assert!(ours.merge("amy", &theirs, "amy 3", MergeConflict::Error).is_ok());
+    }
 }
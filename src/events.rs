@@ -11,41 +11,40 @@
 //  Description:
 //!   Implements a translation layer atop [`View`]s to create an
 //!   event-trigger-like interface for agents.
+//!
+//!   Scripts are built with a [`ScriptBuilder`], which chains [`ScriptBlock`]s
+//!   together using [`Composite`]s. Every block only starts once the previous
+//!   one is done, such that a script reads top to bottom:
+//!   ```ignore
+//!   let script = ScriptBuilder::new()
+//!       .wait_for(parse::ground_atom("foo").unwrap().1)
+//!       .state(Recipient::All, parse::program("bar.").unwrap().1)
+//!       .finish();
+//!   ```
 //
 
 use std::convert::Infallible;
-use std::error::Error;
+use std::error;
 use std::marker::PhantomData;
-use std::sync::Arc;
 use std::task::Poll;
 
-use justact::actions::ConstructableAction;
-use justact::actors::{Agent, View};
-use justact::collections::set::{Set, SetAsync, SetSync};
-use justact::messages::ConstructableMessage;
+use slick::{GroundAtom, Program};
 use thiserror::Error;
 
-#[cfg(feature = "dataplane")]
-use crate::dataplane::ScopedStoreHandle;
-use crate::wire::Message;
-
-
-// /***** ERRORS *****/
-// /// Extends [`Result<T, impl Error>`](Result) with the ability to be easily cast into an [`Error`].
-// pub trait ResultToError<T> {
-//     /// Casts this error into an [`Error`].
-//     ///
-//     /// # Returns
-//     /// An [`Error`] that implements [`Error`](error::Error).
-//     fn cast(self) -> Result<T, Error>;
-// }
-// impl<T, E: 'static + Send + error::Error> ResultToError<T> for Result<T, E> {
-//     #[inline]
-//     fn cast(self) -> Result<T, Error> { self.map_err(|err| Error(Box::new(err))) }
-// }
+use crate::agent::{Agent as ScriptedAgent, Error, cast};
+use crate::io::{Recipient, TracingView};
 
+mod justact {
+    pub use ::justact::actions::ConstructableAction;
+    pub use ::justact::actors::{Agent, View};
+    pub use ::justact::auxillary::Identifiable;
+    pub use ::justact::collections::Recipient;
+    pub use ::justact::collections::set::{Set, SetAsync};
+    pub use ::justact::messages::ConstructableMessage;
+}
 
 
+/***** ERRORS *****/
 /// A convenient error returned by the [`Composite`] datatype.
 #[derive(Debug, Error)]
 pub enum CompositeError<E1, E2> {
@@ -55,119 +54,224 @@ pub enum CompositeError<E1, E2> {
     Right(E2),
 }
 
-// /// An extremely generic error returned by the [`EventHandled`].
-// #[derive(Debug)]
-// pub struct Error(Box<dyn 'static + Send + error::Error>);
-// impl Error {
-//     /// Constructor for the Error from a generic [`Error`](error::Error).
-//     ///
-//     /// # Arguments
-//     /// - `err`: The [`Error`](error::Error) to wrap.
-//     ///
-//     /// # Returns
-//     /// A new Error, ready to wreak havoc.
-//     #[inline]
-//     #[allow(unused)]
-//     pub fn new(err: impl 'static + Send + error::Error) -> Self { Self(Box::new(err)) }
-// }
-// impl Display for Error {
-//     #[inline]
-//     fn fmt(&self, f: &mut Formatter<'_>) -> FResult { self.0.fmt(f) }
-// }
-// impl error::Error for Error {
-//     #[inline]
-//     fn source(&self) -> Option<&(dyn error::Error + 'static)> { self.0.source() }
-// }
+
+
+
 
 /***** SCRIPT BUILDING BLOCKS *****/
 /// Defines the abstract block.
 pub trait ScriptBlock {
-    type Error: Error;
+    type Error: 'static + Send + error::Error;
+
+    /// Clone of [`Agent::poll()`](justact::Agent::poll()) with more convenient bounds for us.
+    ///
+    /// # Arguments
+    /// - `view`: The [`TracingView`] to interact with the world with.
+    ///
+    /// # Returns
+    /// [`Poll::Ready`] if this block is done, or [`Poll::Pending`] if it needs to be polled again.
+    ///
+    /// # Errors
+    /// This function may error if the block failed to do its job.
+    fn poll<A, S, E, SM, SA>(&mut self, view: &mut TracingView<A, S, E>) -> Result<Poll<()>, Self::Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>;
+}
+
+// Default impls
+impl ScriptBlock for () {
+    type Error = Infallible;
+
+    #[inline]
+    fn poll<A, S, E, SM, SA>(&mut self, _view: &mut TracingView<A, S, E>) -> Result<Poll<()>, Self::Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
+    {
+        Ok(Poll::Ready(()))
+    }
+}
+
+
+
+/// Defines a building block for a script that runs some closure the moment it is reached.
+pub struct Immediate<F, E> {
+    /// The closure to run. Taken when it has.
+    closure: Option<F>,
+    /// Remembers the error type of the closure.
+    _error:  PhantomData<fn() -> E>,
+}
+impl<F, E> Immediate<F, E> {
+    /// Constructor for the Immediate script building block.
+    ///
+    /// # Arguments
+    /// - `closure`: Some [`FnOnce`] to run immediately when this scripting block is reached.
+    ///
+    /// # Returns
+    /// A new Immediate instance.
+    #[inline]
+    pub const fn new(closure: F) -> Self { Self { closure: Some(closure), _error: PhantomData } }
+}
+impl<F, E> ScriptBlock for Immediate<F, E>
+where
+    F: FnOnce() -> Result<(), E>,
+    E: 'static + Send + error::Error,
+{
+    type Error = E;
 
-    /// Clone of [`Agent::poll()`] with more convenient bounds for us.
-    fn poll<A, S, E, MS>(&mut self, view: &mut View<A, S, E>) -> Result<Poll<()>, Self::Error>
+    #[inline]
+    fn poll<A, S, E2, SM, SA>(&mut self, _view: &mut TracingView<A, S, E2>) -> Result<Poll<()>, Self::Error>
     where
-        S: SetAsync<str, MS>;
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E2: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
+    {
+        if let Some(closure) = self.closure.take() {
+            closure()?;
+        }
+
+        // We're never waiting, so always "ready"
+        Ok(Poll::Ready(()))
+    }
 }
 
 
 
 /// Defines a building block for a script that publishes a statement the moment it is reached.
-pub struct State<P: ?Sized + ToOwned> {
-    /// The message to publish like, immediately.
-    message: Option<Arc<Message<P>>>,
+pub struct State {
+    /// The message to publish like, immediately, together with who to send it to.
+    message: Option<(Recipient, Program)>,
 }
-impl<P: ?Sized + ToOwned> State<P> {
+impl State {
     /// Constructor for the State script building block.
     ///
     /// # Arguments
-    /// - `message`: Some [`Message`] to immediately publish when this scripting block is reached.
+    /// - `to`: The [`Recipient`] to gossip the message to after stating it.
+    /// - `message`: Some payload to immediately publish when this scripting block is reached.
     ///
     /// # Returns
     /// A new State instance.
     #[inline]
-    pub const fn new(message: Arc<Message<P>>) -> Self { Self { message: Some(message) } }
+    pub const fn new(to: Recipient, message: Program) -> Self { Self { message: Some((to, message)) } }
 }
-impl<P: ?Sized + ToOwned> ScriptBlock for State<P> {
-    type Error = Infallible;
+impl ScriptBlock for State {
+    type Error = Error;
 
     #[inline]
-    fn poll<A, S, E, MS>(&mut self, view: &mut View<A, S, E>) -> Result<Poll<()>, Self::Error>
+    fn poll<A, S, E, SM, SA>(&mut self, view: &mut TracingView<A, S, E>) -> Result<Poll<()>, Self::Error>
     where
-        S: SetAsync<str, MS>,
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
     {
-        if let Some(message) = self.message.take() {
-            if let Err(err) = view.state() {}
+        if let Some((to, message)) = self.message.take() {
+            let msg = SM::new(view.0.id.clone(), message);
+            view.state(msg.clone()).map_err(cast).map_err(Error::SetStated)?;
+            view.gossip(to, msg).map_err(cast).map_err(Error::SetStated)?;
         }
 
         // We're never waiting, so always "ready"
-        Poll::Ready(Ok(()))
+        Ok(Poll::Ready(()))
     }
 }
 
 
 
-/// Defines a compositional [`Handler`] that has some nice, custom syntax for building nodes.
+/// Defines a building block for a script that waits until some truths are known to the agent.
+///
+/// Like [`AgentProgrammer::wait_for_truths()`](crate::agent::AgentProgrammer::wait_for_truths()),
+/// a truth is known if at least one agreed or stated message derives it in isolation.
+pub struct WaitFor {
+    /// The truths to wait for.
+    truths: Vec<GroundAtom>,
+}
+impl WaitFor {
+    /// Constructor for the WaitFor script building block.
+    ///
+    /// # Arguments
+    /// - `truths`: The truths that must all be known before this block is done.
+    ///
+    /// # Returns
+    /// A new WaitFor instance.
+    #[inline]
+    pub fn new(truths: impl IntoIterator<Item = GroundAtom>) -> Self { Self { truths: truths.into_iter().collect() } }
+}
+impl ScriptBlock for WaitFor {
+    type Error = Error;
+
+    #[inline]
+    fn poll<A, S, E, SM, SA>(&mut self, view: &mut TracingView<A, S, E>) -> Result<Poll<()>, Self::Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
+    {
+        if ScriptedAgent::truths_known(view, &self.truths)? { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) }
+    }
+}
+
+
+
+/// Defines a compositional [`ScriptBlock`] that runs one block after the other.
 pub struct Composite<H1, H2> {
-    /// The thing to test
+    /// The block to run first. Becomes [`None`] once it's done.
     left:  Option<H1>,
-    /// The next one in the chain.
+    /// The block to run second. Becomes [`None`] once it's done.
     right: Option<H2>,
 }
 impl<H1, H2> Composite<H1, H2> {
     /// Constructor for the Composite.
     ///
     /// # Arguments
-    /// - `left`: The first handler to run.
-    /// - `right`: The second handler to run. Its condition will only ever apply once the `left`
-    ///   has had its condition meet.
+    /// - `left`: The first block to run.
+    /// - `right`: The second block to run. It will only ever be polled once the `left` is done.
     ///
     /// # Returns
-    /// A new Composite that will first check for `H1`'s event, then check for `H2`'s event.
+    /// A new Composite that will first run `H1` to completion, then `H2`.
     #[inline]
     pub const fn new(left: H1, right: H2) -> Self { Self { left: Some(left), right: Some(right) } }
 }
-impl<C, H1: Handler<Context = C>, H2: Handler<Context = C>> Handler for Composite<H1, H2> {
-    type Context = C;
+impl<H1: ScriptBlock, H2: ScriptBlock> ScriptBlock for Composite<H1, H2> {
     type Error = CompositeError<H1::Error, H2::Error>;
 
     #[inline]
-    fn poll(&mut self, context: &mut Self::Context) -> Poll<Result<(), Self::Error>> {
-        // First, poll the left handler, and yield it's result when any
-        let res = if let Some(left) = &mut self.left { left.poll(context) } else { Poll::Pending };
-        if let Poll::Ready(res) = res {
+    fn poll<A, S, E, SM, SA>(&mut self, view: &mut TracingView<A, S, E>) -> Result<Poll<()>, Self::Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<str, SM>,
+        E: justact::SetAsync<str, SA>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = str, Message = SM>,
+    {
+        // First, run the left block until it's done
+        if let Some(left) = &mut self.left {
+            if left.poll(view).map_err(CompositeError::Left)?.is_pending() {
+                return Ok(Poll::Pending);
+            }
             self.left = None;
-            return Poll::Ready(res.map_err(CompositeError::Left));
         }
 
-        // Then, poll the other handler, doing the same thing.
-        let res = if let Some(right) = &mut self.right { right.poll(context) } else { Poll::Pending };
-        if let Poll::Ready(res) = res {
+        // Then, do the same for the right one
+        if let Some(right) = &mut self.right {
+            if right.poll(view).map_err(CompositeError::Right)?.is_pending() {
+                return Ok(Poll::Pending);
+            }
             self.right = None;
-            Poll::Ready(res.map_err(CompositeError::Right))
-        } else {
-            Poll::Pending
         }
+        Ok(Poll::Ready(()))
     }
 }
 
@@ -179,6 +283,10 @@ impl<C, H1: Handler<Context = C>, H2: Handler<Context = C>> Handler for Composit
 /// Convenient builder for building a script.
 #[derive(Debug)]
 pub struct ScriptBuilder<H>(H);
+impl Default for ScriptBuilder<()> {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
 impl ScriptBuilder<()> {
     /// Constructor for the ScriptBuilder that initializes it as new.
     ///
@@ -188,6 +296,16 @@ impl ScriptBuilder<()> {
     pub const fn new() -> Self { Self(()) }
 }
 impl<H> ScriptBuilder<H> {
+    /// Programs some arbitrary block to run when the script gets here.
+    ///
+    /// # Arguments
+    /// - `block`: The [`ScriptBlock`] to run.
+    ///
+    /// # Returns
+    /// A ScriptBuilder that adds this step to the script.
+    #[inline]
+    pub fn then<B: ScriptBlock>(self, block: B) -> ScriptBuilder<Composite<H, B>> { ScriptBuilder(Composite::new(self.0, block)) }
+
     /// Programs some action to run immediately when the script gets here.
     ///
     /// # Arguments
@@ -196,13 +314,48 @@ impl<H> ScriptBuilder<H> {
     /// # Returns
     /// A ScriptBuilder that adds this step to the script.
     #[inline]
-    pub fn run<F: FnOnce(&mut A) -> Result<(), E>, A, E>(self, closure: F) -> ScriptBuilder<Composite<H, Immediate<F, A, E>>> {
+    pub fn run<F: FnOnce() -> Result<(), E>, E>(self, closure: F) -> ScriptBuilder<Composite<H, Immediate<F, E>>> {
         ScriptBuilder(Composite::new(self.0, Immediate::new(closure)))
     }
 
+    /// Programs a message to be stated immediately when the script gets here.
+    ///
+    /// # Arguments
+    /// - `to`: The recipient to gossip the message to.
+    /// - `msg`: The payload of the message to state.
+    ///
+    /// # Returns
+    /// A ScriptBuilder that adds this step to the script.
+    #[inline]
+    pub fn state(self, to: justact::Recipient<String>, msg: Program) -> ScriptBuilder<Composite<H, State>> {
+        ScriptBuilder(Composite::new(self.0, State::new(to.into(), msg)))
+    }
+
+    /// Programs the script to wait until a certain truth is known before continuing.
+    ///
+    /// # Arguments
+    /// - `fact`: The truth to wait for.
+    ///
+    /// # Returns
+    /// A ScriptBuilder that adds this step to the script.
+    #[inline]
+    pub fn wait_for(self, fact: GroundAtom) -> ScriptBuilder<Composite<H, WaitFor>> { ScriptBuilder(Composite::new(self.0, WaitFor::new([fact]))) }
+
+    /// Programs the script to wait until zero or more truths are all known before continuing.
+    ///
+    /// # Arguments
+    /// - `facts`: The truths to wait for.
+    ///
+    /// # Returns
+    /// A ScriptBuilder that adds this step to the script.
+    #[inline]
+    pub fn wait_for_all(self, facts: impl IntoIterator<Item = GroundAtom>) -> ScriptBuilder<Composite<H, WaitFor>> {
+        ScriptBuilder(Composite::new(self.0, WaitFor::new(facts)))
+    }
+
 
 
-    /// Completes the script, returning a [`Handler`] that you can [`Handler::poll()`].
+    /// Completes the script, returning a [`ScriptBlock`] that you can [`ScriptBlock::poll()`].
     ///
     /// # Returns
     /// The inner `H`andler, ready to be polled.
@@ -213,343 +366,90 @@ impl<H> ScriptBuilder<H> {
 
 
 
+
 /***** LIBRARY *****/
+/// An [`Agent`](justact::Agent) that runs a script built with a [`ScriptBuilder`].
+#[derive(Debug)]
+pub struct Script<H> {
+    /// The ID of this agent.
+    id:    String,
+    /// The script to run.
+    block: H,
+}
+impl<H> Script<H> {
+    /// Constructor for the Script.
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the agent running the script.
+    /// - `block`: The [finished](ScriptBuilder::finish()) script to run.
+    ///
+    /// # Returns
+    /// A new Script that can be given to a [`System`](crate::System).
+    #[inline]
+    pub fn new(id: impl Into<String>, block: H) -> Self { Self { id: id.into(), block } }
+}
+
+// JustAct
+impl<H> justact::Identifiable for Script<H> {
+    type Id = str;
+
+    #[inline]
+    fn id(&self) -> &Self::Id { &self.id }
+}
+impl<H: ScriptBlock> justact::Agent<Program> for Script<H> {
+    type Error = H::Error;
+
+    #[inline]
+    fn poll<A, S, E, SM, SA>(&mut self, mut view: justact::View<Self::Id, A, S, E>) -> Result<Poll<()>, Self::Error>
+    where
+        A: justact::Set<SM>,
+        S: justact::SetAsync<Self::Id, SM>,
+        E: justact::SetAsync<Self::Id, SA>,
+        SM: justact::ConstructableMessage<AuthorId = Self::Id, Payload = Program>,
+        SA: justact::ConstructableAction<ActorId = Self::Id, Message = SM>,
+    {
+        self.block.poll(&mut TracingView(&mut view))
+    }
+}
+
 
 
 
 
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
 
-// /***** LIBRARY *****/
-// /// Implements a translation layer atop a [`View`] such that agents can write their scripts as
-// /// event handlers.
-// pub struct EventHandler {
-//     /// Whether or not we did the first one.
-//     on_start_handled: bool,
-//     /// The set of agreements & statements for which we check collectively.
-//     on_agreed_and_stated_handled: HashSet<((String, u32), Vec<(String, u32)>)>,
-//     /// The set of agreements that we've already handled.
-//     on_agreed_handled: HashSet<(String, u32)>,
-//     /// The set of statements that we've already handled.
-//     on_stated_handled: HashSet<(String, u32)>,
-//     /// The set of facts that we've already handled.
-//     on_truth_handled: HashSet<GroundAtom>,
-//     /// The set of fact sets that we've already handled.
-//     on_truths_handled: HashSet<Vec<GroundAtom>>,
-//     /// The set of enactments that we've already handled.
-//     on_enacted_handled: HashSet<(String, char)>,
-//     /// The set of data creations (writes) we've already handled.
-//     #[cfg(feature = "dataplane")]
-//     on_data_created_handled: HashSet<((String, String), String)>,
-//     /// The set of sets of data creations (writes) we've already handled.
-//     #[cfg(feature = "dataplane")]
-//     on_datas_created_handled: HashSet<Vec<((String, String), String)>>,
-//     /// The set of agreements & sets of data creations (writes) we've already handled.
-//     #[cfg(feature = "dataplane")]
-//     on_enacted_and_datas_created_handled: HashSet<((String, char), Vec<((String, String), String)>)>,
-// }
-// impl Default for EventHandler {
-//     #[inline]
-//     fn default() -> Self { Self::new() }
-// }
-// impl EventHandler {
-//     /// Constructor for the EventHandler that initializes it as new.
-//     ///
-//     /// # Returns
-//     /// A new EventHandler that can be used to (wait for it) handle events.
-//     #[inline]
-//     pub fn new() -> Self {
-//         Self {
-//             on_start_handled: false,
-//             on_agreed_and_stated_handled: HashSet::new(),
-//             on_agreed_handled: HashSet::new(),
-//             on_stated_handled: HashSet::new(),
-//             on_truth_handled: HashSet::new(),
-//             on_truths_handled: HashSet::new(),
-//             on_enacted_handled: HashSet::new(),
-//             #[cfg(feature = "dataplane")]
-//             on_data_created_handled: HashSet::new(),
-//             #[cfg(feature = "dataplane")]
-//             on_datas_created_handled: HashSet::new(),
-//             #[cfg(feature = "dataplane")]
-//             on_enacted_and_datas_created_handled: HashSet::new(),
-//         }
-//     }
-// }
-// impl EventHandler {
-//     /// Processes events in the given [`View`] and returns an [`EventHandled`] that can be used to
-//     /// run triggers.
-//     ///
-//     /// # Arguments
-//     /// - `view`: Some [`View`] to process.
-//     ///
-//     /// # Returns
-//     /// An [`EventHandled`] that can be used to process triggers.
-//     #[inline]
-//     pub const fn handle<A, S, E>(&mut self, view: View<A, S, E>) -> EventHandled<'_, A, S, E> {
-//         EventHandled {
-//             handler: self,
-//             view,
-//             #[cfg(feature = "dataplane")]
-//             store: None,
-//             ready: true,
-//         }
-//     }
-
-//     /// Processes events in the given [`View`] and [`ScopedStoreHandle`] and returns an
-//     /// [`EventHandled`] that can be used to run triggers.
-//     ///
-//     /// # Arguments
-//     /// - `view`: Some [`View`] to process.
-//     /// - `handle`: Some kind of [`StoreHandle`] that is used to trigger on dataplane events.
-//     ///
-//     /// # Returns
-//     /// An [`EventHandled`] that can be used to process triggers.
-//     #[inline]
-//     #[cfg(feature = "dataplane")]
-//     pub const fn handle_with_store<A, S, E>(&mut self, view: View<A, S, E>, store: ScopedStoreHandle) -> EventHandled<'_, A, S, E> {
-//         EventHandled { handler: self, view, store: Some(store), ready: true }
-//     }
-// }
-
-// /// Implements the state of the [`EventHandler`] after a [`View`] is added.
-// pub struct EventHandled<'h, A, S, E> {
-//     /// The handler (that contains some state).
-//     handler: &'h mut EventHandler,
-//     /// The view to process.
-//     view:    View<A, S, E>,
-//     /// The store to use for dataplane access. Since not all agents need that, it's optional.
-//     #[cfg(feature = "dataplane")]
-//     store:   Option<ScopedStoreHandle>,
-//     /// Whether all handles registered for this view are triggered (i.e., the agent is done).
-//     ready:   bool,
-// }
-// impl<'h, A, S, E> EventHandled<'h, A, S, E> {
-//     /// Adds a new handler for when the scenario starts.
-//     ///
-//     /// # Arguments
-//     /// - `closure`: Some [`FnOnce`] that will be executed initially.
-//     ///
-//     /// # Returns
-//     /// Self for chaining.
-//     ///
-//     /// # Errors
-//     /// This function may error if the closure errors.
-//     pub fn on_start<ERR>(mut self, closure: impl FnOnce(&mut View<A, S, E>) -> Result<(), ERR>) -> Result<Self, Error>
-//     where
-//         ERR: 'static + Send + error::Error,
-//     {
-//         // Don't run it if already started, lol
-//         if self.handler.on_start_handled {
-//             return Ok(self);
-//         }
-
-//         // Simply run the closure
-//         self.handler.on_start_handled = true;
-//         closure(&mut self.view).cast()?;
-//         Ok(self)
-//     }
-
-//     /// Adds a new handler for when a new fact has become available to this agent.
-//     ///
-//     /// # Arguments
-//     /// - `fact`: The fact to wait for.
-//     /// - `closure`: Some [`FnOnce`] that will be executed when the fact has become available.
-//     ///
-//     /// # Returns
-//     /// Self for chaining.
-//     ///
-//     /// # Errors
-//     /// This function may error if something went wrong with interacting with the sets in the
-//     /// internal view.
-//     pub fn on_truth<SM, ERR>(mut self, fact: GroundAtom, closure: impl FnOnce(&mut View<A, S, E>) -> Result<(), ERR>) -> Result<Self, Error>
-//     where
-//         S: Set<SM>,
-//         SM: Message<AuthorId = str, Payload = Program>,
-//         ERR: 'static + Send + error::Error,
-//     {
-//         // Don't do anything if we've already handled it
-//         if self.handler.on_truth_handled.contains(&fact) {
-//             return Ok(self);
-//         }
-
-//         // Else, consider messages until it is there
-//         let mut found: bool = false;
-//         for msg in self.view.stated.iter().cast()? {
-//             // Compute the denotation of this message
-//             let set = Singleton::new(msg);
-//             let truths: Denotation = Extractor.extract(&set).cast()?.truths();
-//             if <Denotation as InfallibleSet<GroundAtom>>::contains(&truths, &fact) {
-//                 found = true;
-//                 break;
-//             }
-//         }
-
-//         // Handle the case we found it (we do it here for borrowing-of-view purposes)
-//         if found {
-//             // Handled it
-//             self.handler.on_truth_handled.insert(fact);
-
-//             // Call the closure
-//             closure(&mut self.view).cast()?;
-//         } else {
-//             self.ready = false;
-//         }
-
-//         // Done
-//         Ok(self)
-//     }
-
-//     /// Adds a new handler for when a specific set of facts has become available to this agent.
-//     ///
-//     /// # Arguments
-//     /// - `facts`: The set of facts to wait for.
-//     /// - `closure`: Some [`FnOnce`] that will be executed when the facts have become available.
-//     ///
-//     /// # Returns
-//     /// Self for chaining.
-//     ///
-//     /// # Errors
-//     /// This function may error if something went wrong with interacting with the sets in the
-//     /// internal view.
-//     pub fn on_truths<SM, ERR>(
-//         mut self,
-//         facts: impl IntoIterator<Item = GroundAtom>,
-//         closure: impl FnOnce(&mut View<A, S, E>) -> Result<(), ERR>,
-//     ) -> Result<Self, Error>
-//     where
-//         S: Set<SM>,
-//         SM: Message<AuthorId = str, Payload = Program>,
-//         ERR: 'static + Send + error::Error,
-//     {
-//         let facts: Vec<GroundAtom> = facts.into_iter().collect();
-
-//         // Don't do anything if we've already handled it
-//         if self.handler.on_truths_handled.contains(&facts) {
-//             return Ok(self);
-//         }
-
-//         // Else, consider messages until it is there
-//         for fact in &facts {
-//             let mut found: bool = false;
-//             for msg in self.view.stated.iter().cast()? {
-//                 // Compute the denotation of this message
-//                 let set = Singleton::new(msg);
-//                 let truths: Denotation = Extractor.extract(&set).cast()?.truths();
-//                 if <Denotation as InfallibleSet<GroundAtom>>::contains(&truths, &fact) {
-//                     found = true;
-//                     break;
-//                 }
-//             }
-//             if !found {
-//                 self.ready = false;
-//                 return Ok(self);
-//             }
-//         }
-
-//         // Call the closure
-//         self.handler.on_truths_handled.insert(facts);
-//         closure(&mut self.view).cast()?;
-
-//         // Done
-//         Ok(self)
-//     }
-
-//     /// Triggers on a certain dataset existing.
-//     ///
-//     /// # Arguments
-//     /// - `id`: The identifier of the dataset to wait for.
-//     /// - `closure`: Some [`FnOnce`] that will be triggered once the dataset exists.
-//     ///
-//     /// # Returns
-//     /// Self for chaining.
-//     ///
-//     /// # Errors
-//     /// This function may error if something went wrong with interacting with the sets in the
-//     /// internal view.
-//     #[cfg(feature = "dataplane")]
-//     #[inline]
-//     pub fn on_data_created<ERR>(
-//         mut self,
-//         id: ((impl Into<String>, impl Into<String>), impl Into<String>),
-//         closure: impl FnOnce(&mut View<A, S, E>) -> Result<(), ERR>,
-//     ) -> Result<Self, Error>
-//     where
-//         ERR: 'static + Send + error::Error,
-//     {
-//         let store: &ScopedStoreHandle =
-//             self.store.as_ref().unwrap_or_else(|| panic!("Cannot call EventHandled::on_data_creates() if not handling with a store"));
-//         let id: ((String, String), String) = ((id.0.0.into(), id.0.1.into()), id.1.into());
-
-//         // Don't do anything if we've already handled it
-//         if self.handler.on_data_created_handled.contains(&id) {
-//             return Ok(self);
-//         }
-
-//         // Otherwise, check the internal handle for when it's there
-//         if store.exists(&id) {
-//             self.handler.on_data_created_handled.insert(id);
-//             closure(&mut self.view).cast()?;
-//         } else {
-//             self.ready = false;
-//         }
-//         Ok(self)
-//     }
-
-//     /// Triggers on a set of certain datasets existing.
-//     ///
-//     /// # Arguments
-//     /// - `ids`: The identifiers of the datasets to wait for.
-//     /// - `closure`: Some [`FnOnce`] that will be triggered once the dataset exists.
-//     ///
-//     /// # Returns
-//     /// Self for chaining.
-//     ///
-//     /// # Errors
-//     /// This function may error if something went wrong with interacting with the sets in the
-//     /// internal view.
-//     #[cfg(feature = "dataplane")]
-//     #[inline]
-//     pub fn on_datas_created<ERR>(
-//         mut self,
-//         ids: impl IntoIterator<Item = ((impl Into<String>, impl Into<String>), impl Into<String>)>,
-//         closure: impl FnOnce(&mut View<A, S, E>) -> Result<(), ERR>,
-//     ) -> Result<Self, Error>
-//     where
-//         ERR: 'static + Send + error::Error,
-//     {
-//         let store: &ScopedStoreHandle =
-//             self.store.as_ref().unwrap_or_else(|| panic!("Cannot call EventHandled::on_data_creates() if not handling with a store"));
-//         let ids: Vec<((String, String), String)> = ids.into_iter().map(|id| ((id.0.0.into(), id.0.1.into()), id.1.into())).collect();
-
-//         // Don't do anything if we've already handled it
-//         if self.handler.on_datas_created_handled.contains(&ids) {
-//             return Ok(self);
-//         }
-
-//         // Otherwise, check the internal handle for when it's there
-//         for id in &ids {
-//             if !store.exists(&id) {
-//                 self.ready = false;
-//                 return Ok(self);
-//             }
-//         }
-
-//         // They all exist at this point!
-//         self.handler.on_datas_created_handled.insert(ids);
-//         closure(&mut self.view).cast()?;
-//         Ok(self)
-//     }
-
-//     /// Finishes the handling.
-//     ///
-//     /// Basically just examines whether all called triggers are actually triggered (or have been in
-//     /// the past), which means the agent can die happily.
-//     ///
-//     /// # Returns
-//     /// [`Poll::Ready`] when all triggers (have been) triggered, or [`Poll::Pending`] otherwise.
-//     ///
-//     /// # Errors
-//     /// This function actually never errors. Just here to be more convenient to agents.
-//     #[inline]
-//     pub fn finish(self) -> Result<Poll<()>, Error> { if self.ready { Ok(Poll::Ready(())) } else { Ok(Poll::Pending) } }
-// }
+    use slick::parse;
+
+    use super::*;
+    use crate::System;
+    use crate::io::{NopEventHandler, register_event_handler};
+
+
+    #[test]
+    fn test_script_builder() {
+        register_event_handler(NopEventHandler);
+
+        // A script that only continues after the agreement is there
+        let ran: Rc<Cell<bool>> = Rc::new(Cell::new(false));
+        let script = {
+            let ran = ran.clone();
+            ScriptBuilder::new()
+                .wait_for(parse::ground_atom("foo").unwrap().1)
+                .run(move || -> Result<(), Infallible> {
+                    ran.set(true);
+                    Ok(())
+                })
+                .state(justact::Recipient::All, parse::program("bar.").unwrap().1)
+                .finish()
+        };
+
+        let mut sync = ScriptedAgent::new("consortium".into());
+        sync.program().agree(parse::program("foo.").unwrap().1);
+        <System<Program> as ::justact::runtime::System>::run(&mut System::new(), [Script::new("amy", script)], sync).unwrap();
+        assert!(ran.get());
+    }
+}
@@ -16,13 +16,25 @@
 //
 
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
 use std::error;
 use std::fmt::{Debug, Display, Formatter, Result as FResult};
-use std::sync::{Arc, Mutex, OnceLock};
+use std::future::Future;
+#[cfg(feature = "serde")]
+use std::io::Write;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::task::{Poll, Waker};
+use std::time::SystemTime;
 
-use ::justact::collections::set::InfallibleSet as _;
+use ::justact::auxillary::Authored as _;
+use ::justact::collections::set::{InfallibleSet as _, Set as _};
+use ::justact::messages::Message as _;
 use slick::Program;
 
+#[cfg(feature = "serde")]
+use crate::auditing::TimedEvent;
 use crate::auditing::{Event, EventControl};
 use crate::wire::{Message, into_prototype_action, into_prototype_message};
 
@@ -39,6 +51,16 @@ mod justact {
 /// Defines \*some\* [`EventHandler`] that will handle trace callbacks.
 pub(crate) static EVENT_HANDLER: OnceLock<Mutex<Box<dyn EventHandler>>> = OnceLock::new();
 
+thread_local! {
+    /// If set, an [`EventHandler`] that handles the events emitted on this thread instead of the
+    /// [registered one](EVENT_HANDLER). See [`with_scoped_event_handler()`].
+    static SCOPED_EVENT_HANDLER: RefCell<Option<Box<dyn EventHandler>>> = const { RefCell::new(None) };
+
+    /// If set, the [`Registry`] of the [`System`](crate::System) that is polling on this thread.
+    /// See [`with_scoped_registry()`].
+    static SCOPED_REGISTRY: RefCell<Option<Arc<Mutex<Registry>>>> = const { RefCell::new(None) };
+}
+
 
 
 
@@ -51,6 +73,12 @@ pub enum Error<E> {
     Inner(E),
     /// Failed to handle a trace.
     EventHandle { err: Box<dyn 'static + Send + error::Error> },
+    /// Failed to read the current agreements.
+    Agreements { err: Box<dyn 'static + Send + error::Error> },
+    /// Attempted to address a group that was never defined.
+    UnknownGroup { name: String },
+    /// Attempted to address a group with a member that isn't an agent in the system.
+    UnknownMember { group: String, member: String },
 }
 impl<E: Display> Display for Error<E> {
     #[inline]
@@ -58,6 +86,9 @@ impl<E: Display> Display for Error<E> {
         match self {
             Self::Inner(err) => err.fmt(f),
             Self::EventHandle { .. } => write!(f, "Failed to handle trace with registered handler"),
+            Self::Agreements { .. } => write!(f, "Failed to read the current agreements"),
+            Self::UnknownGroup { name } => write!(f, "Unknown group {name:?}"),
+            Self::UnknownMember { group, member } => write!(f, "Member {member:?} of group {group:?} is not an agent in the system"),
         }
     }
 }
@@ -66,8 +97,215 @@ impl<E: error::Error> error::Error for Error<E> {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             Self::Inner(err) => err.source(),
-            Self::EventHandle { err } => Some(&**err),
+            Self::EventHandle { err } | Self::Agreements { err } => Some(&**err),
+            Self::UnknownGroup { .. } | Self::UnknownMember { .. } => None,
+        }
+    }
+}
+
+/// Emitted by a [`ChannelEventHandler`] when its [`EventReceiver`] is gone.
+#[derive(Debug)]
+pub struct ReceiverDropped;
+impl Display for ReceiverDropped {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { write!(f, "Failed to send event because the receiving end of the channel was dropped") }
+}
+impl error::Error for ReceiverDropped {}
+
+
+
+
+
+/***** AUXILLARY *****/
+/// Defines who to gossip a message to.
+///
+/// This extends [`justact::Recipient`] with groups of agents, which are defined on the
+/// [`System`](crate::System) with [`System::define_group()`](crate::System::define_group()).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Recipient {
+    /// Everybody.
+    All,
+    /// One particular agent.
+    One(String),
+    /// Every member of the group with the given name.
+    Group(String),
+}
+impl Recipient {
+    /// Expands this Recipient to the [`justact::Recipient`]s that it refers to.
+    ///
+    /// This is a no-op for everybody or one agent, but groups are resolved to their members using
+    /// the [registry](with_scoped_registry()) of the current thread.
+    ///
+    /// # Returns
+    /// The [`justact::Recipient`]s to actually gossip to.
+    ///
+    /// # Errors
+    /// This function errors if this is a group that was never defined or if any of its members
+    /// isn't an agent in the system.
+    pub fn resolve<E>(self) -> Result<Vec<justact::Recipient<String>>, Error<E>> {
+        match self {
+            Self::All => Ok(vec![justact::Recipient::All]),
+            Self::One(id) => Ok(vec![justact::Recipient::One(id)]),
+            Self::Group(name) => with_registry(|registry| {
+                let registry: &Registry = registry.ok_or_else(|| Error::UnknownGroup { name: name.clone() })?;
+                let members: &Vec<String> = registry.groups.get(&name).ok_or_else(|| Error::UnknownGroup { name: name.clone() })?;
+                match members.iter().find(|member| !registry.agents.contains(*member)) {
+                    Some(member) => Err(Error::UnknownMember { group: name.clone(), member: member.clone() }),
+                    None => Ok(members.iter().map(|member| justact::Recipient::One(member.clone())).collect()),
+                }
+            }),
+        }
+    }
+}
+impl From<justact::Recipient<String>> for Recipient {
+    #[inline]
+    fn from(value: justact::Recipient<String>) -> Self {
+        match value {
+            justact::Recipient::All => Self::All,
+            justact::Recipient::One(id) => Self::One(id),
+        }
+    }
+}
+
+
+
+/// Something to wait on until an [`EventHandler`] is ready for the next event.
+///
+/// See [`EventHandler::backpressure()`].
+pub struct Backpressure(Box<dyn Send + FnOnce()>);
+impl Backpressure {
+    /// Constructor for the Backpressure.
+    ///
+    /// # Arguments
+    /// - `wait`: A closure that blocks until the handler is ready for the next event.
+    ///
+    /// # Returns
+    /// A new Backpressure that can be [waited](Backpressure::wait()) on.
+    #[inline]
+    pub fn new(wait: impl 'static + Send + FnOnce()) -> Self { Self(Box::new(wait)) }
+
+    /// Blocks until the handler is ready for the next event.
+    #[inline]
+    pub fn wait(self) { (self.0)() }
+}
+impl Debug for Backpressure {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult { f.debug_tuple("Backpressure").finish_non_exhaustive() }
+}
+
+
+
+/// Defines a barrier at which agents wait for each other.
+#[derive(Debug, Default)]
+pub(crate) struct Barrier {
+    /// The agents that will meet at this barrier.
+    pub(crate) parties: HashSet<String>,
+    /// The agents that have reached this barrier.
+    pub(crate) reached: HashSet<String>,
+}
+impl Barrier {
+    /// Returns the agents that will meet at this barrier, but haven't reached it yet.
+    ///
+    /// # Returns
+    /// The (sorted) identifiers of the agents the barrier is waiting for.
+    #[inline]
+    pub(crate) fn waiting(&self) -> Vec<String> {
+        let mut waiting: Vec<String> = self.parties.difference(&self.reached).cloned().collect();
+        waiting.sort();
+        waiting
+    }
+}
+
+/// Defines what a [`System`](crate::System) knows about its agents beyond their views.
+///
+/// While the System polls an agent, its registry is [scoped](with_scoped_registry()) to the
+/// current thread. That's how, e.g., a [`TracingView`] can resolve groups without agents having to
+/// know which System runs them.
+#[derive(Debug, Default)]
+pub(crate) struct Registry {
+    /// The identifiers of all agents registered with the System.
+    pub(crate) agents:   HashSet<String>,
+    /// The groups of agents that can be addressed with [`Recipient::Group`], by name.
+    pub(crate) groups:   HashMap<String, Vec<String>>,
+    /// The barriers that agents meet at, by name.
+    pub(crate) barriers: HashMap<String, Barrier>,
+    /// The agents that are waiting at a barrier, mapped to the name of that barrier.
+    pub(crate) blocked:  HashMap<String, String>,
+    /// Whether every agent has been polled at least once. Until then, not all parties of a
+    /// barrier may have been declared, so no barrier is passed.
+    pub(crate) settled:  bool,
+    /// The time that was last [advanced](TracingView::advance_time()) to, which is what the
+    /// clocks of agents follow.
+    pub(crate) now:      u64,
+}
+impl Registry {
+    /// Declares the barriers that an agent will meet at.
+    ///
+    /// Agents do this every time they are polled, which also marks that they're no longer waiting
+    /// at any barrier (until they [reach](Registry::reach_barrier()) one again).
+    ///
+    /// # Arguments
+    /// - `id`: The identifier of the agent.
+    /// - `names`: The names of the barriers in the agent's remaining steps.
+    pub(crate) fn declare_barriers<'n>(&mut self, id: &str, names: impl IntoIterator<Item = &'n str>) {
+        for name in names {
+            self.barriers.entry(name.into()).or_default().parties.insert(id.into());
         }
+        self.blocked.remove(id);
+    }
+
+    /// Marks an agent as having reached a barrier.
+    ///
+    /// Barriers are one-off: once every party has reached one, it stays open. As such, every
+    /// barrier name should only be used once per System.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the barrier.
+    /// - `id`: The identifier of the agent reaching it.
+    ///
+    /// # Returns
+    /// Whether the agent may pass the barrier. If not, it's considered blocked on it.
+    pub(crate) fn reach_barrier(&mut self, name: &str, id: &str) -> bool {
+        let barrier: &mut Barrier = self.barriers.entry(name.into()).or_default();
+        barrier.parties.insert(id.into());
+        barrier.reached.insert(id.into());
+        if self.settled && barrier.waiting().is_empty() {
+            self.blocked.remove(id);
+            true
+        } else {
+            self.blocked.insert(id.into(), name.into());
+            false
+        }
+    }
+
+    /// Finds out whether the running agents are deadlocked at their barriers.
+    ///
+    /// This is the case if every remaining agent is blocked at a barrier that waits for somebody
+    /// who will never reach it, i.e., an agent that is done or that is itself blocked.
+    ///
+    /// # Arguments
+    /// - `agents`: The identifiers of the agents that are not yet done.
+    /// - `synchronizer`: The identifier of the synchronizer, if it's not yet done.
+    ///
+    /// # Returns
+    /// A list of every agent, the barrier it's blocked at and who that barrier waits for, or
+    /// [`None`] if the agents are not deadlocked.
+    pub(crate) fn deadlock<'a>(&self, agents: impl IntoIterator<Item = &'a str>, synchronizer: Option<&str>) -> Option<Vec<(String, String, Vec<String>)>> {
+        if !self.settled {
+            return None;
+        }
+
+        let mut deadlock: Vec<(String, String, Vec<String>)> = Vec::new();
+        for id in agents {
+            let name: &String = self.blocked.get(id)?;
+            let waiting: Vec<String> = self.barriers.get(name).map(Barrier::waiting).unwrap_or_default();
+            // The barrier is passable, or the synchronizer may still reach it
+            if waiting.is_empty() || synchronizer.is_some_and(|sync| !self.blocked.contains_key(sync) && waiting.iter().any(|id| id == sync)) {
+                return None;
+            }
+            deadlock.push((id.into(), name.clone(), waiting));
+        }
+        if deadlock.is_empty() { None } else { Some(deadlock) }
     }
 }
 
@@ -88,12 +326,330 @@ pub trait EventHandler: 'static + Send + Sync {
     /// # Errors
     /// This trace is allowed to error, but it should return it as a dynamic (`'static`) object.
     fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>>;
+
+    /// Handles the occurrance of a trace that was emitted at a particular wall-clock time.
+    ///
+    /// This is called instead of [`EventHandler::handle()`] if the `timestamps` feature is
+    /// enabled. By default, it simply ignores the time.
+    ///
+    /// # Arguments
+    /// - `trace`: The [`Event`] to handle.
+    /// - `emitted_at`: The time at which the `trace` was emitted.
+    ///
+    /// # Errors
+    /// This trace is allowed to error, but it should return it as a dynamic (`'static`) object.
+    #[inline]
+    fn handle_at(&mut self, trace: Event<str>, emitted_at: SystemTime) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        let _ = emitted_at;
+        self.handle(trace)
+    }
+
+    /// Returns something to wait on until this handler is ready for the next event.
+    ///
+    /// Events are emitted while the handler is locked. Handlers that apply backpressure (e.g., the
+    /// [`ChannelEventHandler`]) should therefore not wait in [`EventHandler::handle()`], but return
+    /// a [`Backpressure`] here instead. It is waited on _before_ the handler is locked to handle the
+    /// next event, s.t. other threads can keep using the handler in the meantime.
+    ///
+    /// By default, handlers are always ready.
+    ///
+    /// # Returns
+    /// A [`Backpressure`] to wait on, or [`None`] if the handler is ready.
+    #[inline]
+    fn backpressure(&self) -> Option<Backpressure> { None }
+}
+
+/// Defines a trace handler that handles events asynchronously.
+///
+/// Unlike an [`EventHandler`], such a handler can apply backpressure (e.g., when writing to a slow
+/// network connection) by simply not completing until it's ready for the next event. As events are
+/// emitted synchronously, async handlers are fed through a [`ChannelEventHandler`]. Use a
+/// [`SyncEventHandler`] to use a normal [`EventHandler`] where an async one is expected.
+pub trait AsyncEventHandler: Send {
+    /// Handles the occurrance of a trace.
+    ///
+    /// # Arguments
+    /// - `trace`: The [`Event`] to handle.
+    ///
+    /// # Errors
+    /// This trace is allowed to error, but it should return it as a dynamic (`'static`) object.
+    fn handle(&mut self, trace: Event<'static, str>) -> impl Send + Future<Output = Result<(), Box<dyn 'static + Send + error::Error>>>;
 }
 
 // Blanket impls
 impl<T: EventHandler> EventHandler for Box<T> {
     #[inline]
     fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> { <T as EventHandler>::handle(self, trace) }
+
+    #[inline]
+    fn handle_at(&mut self, trace: Event<str>, emitted_at: SystemTime) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        <T as EventHandler>::handle_at(self, trace, emitted_at)
+    }
+
+    #[inline]
+    fn backpressure(&self) -> Option<Backpressure> { <T as EventHandler>::backpressure(self) }
+}
+
+
+
+
+
+/***** HANDLERS *****/
+/// An [`EventHandler`] that writes every event as a single line of JSON to some writer.
+///
+/// Events are flushed as soon as they are handled, so the resulting stream can be piped straight
+/// into the inspector while the system is still running.
+#[cfg(feature = "serde")]
+#[derive(Debug)]
+pub struct JsonLinesEventHandler<W> {
+    /// The writer to stream the events to.
+    writer: W,
+}
+#[cfg(feature = "serde")]
+impl<W> JsonLinesEventHandler<W> {
+    /// Constructor for the JsonLinesEventHandler.
+    ///
+    /// # Arguments
+    /// - `writer`: Some [`Write`]r to stream the events to (e.g., [`std::io::Stdout`] or a
+    ///   [`File`](std::fs::File)).
+    ///
+    /// # Returns
+    /// A new JsonLinesEventHandler that can be [registered](register_event_handler()).
+    #[inline]
+    pub const fn new(writer: W) -> Self { Self { writer } }
+}
+#[cfg(feature = "serde")]
+impl<W: 'static + Send + Sync + Write> EventHandler for JsonLinesEventHandler<W> {
+    #[inline]
+    fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        serde_json::to_writer(&mut self.writer, &trace).map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })?;
+        self.writer.write_all(b"\n").map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })?;
+        self.writer.flush().map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })
+    }
+
+    /// Writes the event as a [`TimedEvent`] instead of a plain [`Event`].
+    #[inline]
+    fn handle_at(&mut self, trace: Event<str>, emitted_at: SystemTime) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        serde_json::to_writer(&mut self.writer, &TimedEvent { emitted_at, event: trace })
+            .map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })?;
+        self.writer.write_all(b"\n").map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })?;
+        self.writer.flush().map_err(|err| -> Box<dyn 'static + Send + error::Error> { Box::new(err) })
+    }
+}
+
+
+
+/// An [`EventHandler`] that forwards every event to a list of other handlers, in order.
+///
+/// As only one handler can be [registered](register_event_handler()), this is the way to, e.g.,
+/// both write a trace to a file and collect it in memory.
+pub struct TeeEventHandler(pub Vec<Box<dyn EventHandler>>);
+impl EventHandler for TeeEventHandler {
+    /// Forwards the event to every child handler.
+    ///
+    /// # Errors
+    /// This function errors with the first error returned by any of the children. The children
+    /// after it will not see the event.
+    #[inline]
+    fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        for handler in &mut self.0 {
+            handler.handle(trace.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Forwards the event to every child handler, together with when it was emitted.
+    ///
+    /// # Errors
+    /// This function errors with the first error returned by any of the children. The children
+    /// after it will not see the event.
+    #[inline]
+    fn handle_at(&mut self, trace: Event<str>, emitted_at: SystemTime) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        for handler in &mut self.0 {
+            handler.handle_at(trace.clone(), emitted_at)?;
+        }
+        Ok(())
+    }
+
+    /// Waits until every child handler is ready.
+    fn backpressure(&self) -> Option<Backpressure> {
+        let waits: Vec<Backpressure> = self.0.iter().filter_map(|handler| handler.backpressure()).collect();
+        if waits.is_empty() { None } else { Some(Backpressure::new(move || waits.into_iter().for_each(Backpressure::wait))) }
+    }
+}
+
+
+
+/// An [`EventHandler`] that ignores all events.
+///
+/// Used by tests that need some handler to be registered, but don't care about the trace.
+#[cfg(test)]
+pub(crate) struct NopEventHandler;
+#[cfg(test)]
+impl EventHandler for NopEventHandler {
+    #[inline]
+    fn handle(&mut self, _trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> { Ok(()) }
+}
+
+/// An [`EventHandler`] that collects all events in memory.
+///
+/// Used by tests that assert something about the trace. Use it with
+/// [`with_scoped_event_handler()`] to only collect the events of the current thread.
+#[cfg(test)]
+pub(crate) struct CollectEventHandler(pub(crate) Arc<Mutex<Vec<Event<'static, str>>>>);
+#[cfg(test)]
+impl EventHandler for CollectEventHandler {
+    #[inline]
+    fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        self.0.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}")).push(trace.into_owned());
+        Ok(())
+    }
+}
+
+
+
+
+
+/// Bridges a (synchronous) [`EventHandler`] to an [`AsyncEventHandler`].
+///
+/// Events are handled immediately, so this never applies any backpressure.
+#[derive(Debug)]
+pub struct SyncEventHandler<H>(pub H);
+impl<H: EventHandler> AsyncEventHandler for SyncEventHandler<H> {
+    #[inline]
+    fn handle(&mut self, trace: Event<'static, str>) -> impl Send + Future<Output = Result<(), Box<dyn 'static + Send + error::Error>>> {
+        std::future::ready(self.0.handle(trace))
+    }
+}
+
+
+
+/// The state shared by a [`ChannelEventHandler`] and its [`EventReceiver`].
+struct Channel {
+    /// The events sent but not yet received.
+    queue:    VecDeque<Event<'static, str>>,
+    /// The maximum number of events in the `queue`.
+    capacity: usize,
+    /// Wakes the receiver when something's sent, or when the sender is dropped.
+    waker:    Option<Waker>,
+    /// Whether the [`ChannelEventHandler`] was dropped.
+    closed:   bool,
+    /// Whether the [`EventReceiver`] was dropped.
+    dropped:  bool,
+}
+
+/// Blocks until a [`Channel`] has room for another event, or until its receiver is gone.
+///
+/// # Arguments
+/// - `shared`: The [`Channel`] to wait on and the [`Condvar`] that signals room in it.
+fn wait_for_room(shared: &(Mutex<Channel>, Condvar)) {
+    let (lock, room) = shared;
+    let mut chan = lock.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+    while chan.queue.len() >= chan.capacity && !chan.dropped {
+        chan = room.wait(chan).unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+    }
+}
+
+/// An [`EventHandler`] that sends events over a bounded channel to an [`AsyncEventHandler`].
+///
+/// If the channel is full, emitting the next event blocks until the [`EventReceiver`] has caught
+/// up. This slows down the system to the pace of (e.g.) a streaming sink, instead of buffering an
+/// unbounded number of events. Create one with [`channel_event_handler()`].
+///
+/// The waiting happens through [`EventHandler::backpressure()`], i.e., without holding the lock on
+/// the registered handler. Still, **the [`EventReceiver`] must be driven on another OS thread than
+/// the one that runs the [`System`](crate::System)**. Blocking that thread for room in the channel
+/// otherwise means that nobody ever makes room, which deadlocks as soon as the channel is full.
+pub struct ChannelEventHandler(Arc<(Mutex<Channel>, Condvar)>);
+impl EventHandler for ChannelEventHandler {
+    /// Sends the event to the [`EventReceiver`], waiting for room in the channel if necessary.
+    ///
+    /// Events emitted by the System will have waited for room already (see
+    /// [`ChannelEventHandler::backpressure()`]). So this only waits if another thread filled the
+    /// channel in the meantime, or if the handler is called directly.
+    ///
+    /// # Errors
+    /// This function errors with a [`ReceiverDropped`] if the [`EventReceiver`] is gone.
+    fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        wait_for_room(&self.0);
+        let mut chan = self.0.0.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+        if chan.dropped {
+            return Err(Box::new(ReceiverDropped));
+        }
+        chan.queue.push_back(trace.into_owned());
+        if let Some(waker) = chan.waker.take() {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Waits until there's room in the channel (or until the [`EventReceiver`] is gone).
+    #[inline]
+    fn backpressure(&self) -> Option<Backpressure> {
+        let shared: Arc<(Mutex<Channel>, Condvar)> = self.0.clone();
+        Some(Backpressure::new(move || wait_for_room(&shared)))
+    }
+}
+impl Drop for ChannelEventHandler {
+    #[inline]
+    fn drop(&mut self) {
+        let mut chan = self.0.0.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+        chan.closed = true;
+        if let Some(waker) = chan.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Receives the events sent by a [`ChannelEventHandler`].
+pub struct EventReceiver(Arc<(Mutex<Channel>, Condvar)>);
+impl EventReceiver {
+    /// Waits for the next event.
+    ///
+    /// # Returns
+    /// The next [`Event`], or [`None`] if the [`ChannelEventHandler`] was dropped and every event
+    /// it sent has been received.
+    pub fn recv(&mut self) -> impl '_ + Send + Future<Output = Option<Event<'static, str>>> {
+        std::future::poll_fn(move |cx| {
+            let (lock, room) = &*self.0;
+            let mut chan = lock.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+            if let Some(event) = chan.queue.pop_front() {
+                room.notify_all();
+                return Poll::Ready(Some(event));
+            }
+            if chan.closed {
+                return Poll::Ready(None);
+            }
+            chan.waker = Some(cx.waker().clone());
+            Poll::Pending
+        })
+    }
+
+    /// Forwards every event received to an [`AsyncEventHandler`] until the channel closes.
+    ///
+    /// The next event is only received once the handler is done with the previous one, which is
+    /// how it can apply backpressure.
+    ///
+    /// # Arguments
+    /// - `handler`: The [`AsyncEventHandler`] to forward the events to.
+    ///
+    /// # Errors
+    /// This function errors with the first error returned by the `handler`. Events after it are
+    /// not forwarded.
+    pub async fn forward_to(mut self, handler: &mut impl AsyncEventHandler) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        while let Some(event) = self.recv().await {
+            handler.handle(event).await?;
+        }
+        Ok(())
+    }
+}
+impl Drop for EventReceiver {
+    #[inline]
+    fn drop(&mut self) {
+        let (lock, room) = &*self.0;
+        lock.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}")).dropped = true;
+        room.notify_all();
+    }
 }
 
 
@@ -101,12 +657,146 @@ impl<T: EventHandler> EventHandler for Box<T> {
 
 
 /***** LIBRARY FUNCTIONS *****/
+/// Creates a bounded channel for handling events asynchronously.
+///
+/// Register the returned [`ChannelEventHandler`] (see [`register_event_handler()`]), and then
+/// [forward](EventReceiver::forward_to()) the events from the [`EventReceiver`] to an
+/// [`AsyncEventHandler`] on **another OS thread** (e.g., in a multi-threaded async runtime). Doing
+/// so on the thread that runs the [`System`](crate::System) deadlocks once the channel is full.
+///
+/// # Arguments
+/// - `capacity`: The maximum number of events buffered before the system is slowed down. Must be
+///   at least 1.
+///
+/// # Returns
+/// A pair of a [`ChannelEventHandler`] that sends events and an [`EventReceiver`] that receives
+/// them.
+///
+/// # Panics
+/// This function panics if `capacity` is 0.
+pub fn channel_event_handler(capacity: usize) -> (ChannelEventHandler, EventReceiver) {
+    assert!(capacity > 0, "Cannot create a channel event handler with a capacity of 0");
+    let chan = Channel { queue: VecDeque::with_capacity(capacity), capacity, waker: None, closed: false, dropped: false };
+    let shared = Arc::new((Mutex::new(chan), Condvar::new()));
+    (ChannelEventHandler(shared.clone()), EventReceiver(shared))
+}
+
 /// Registers a particular [`EventHandler`] such that it handles traces.
 ///
 /// # Arguments
 /// - `handler`: The [`EventHandler`] to register.
 pub fn register_event_handler(handler: impl EventHandler) { let _ = EVENT_HANDLER.set(Mutex::new(Box::new(handler))); }
 
+/// Has the registered [`EventHandler`] handle a particular [`Event`].
+///
+/// If the `timestamps` feature is enabled, this passes the current wall-clock time along (see
+/// [`EventHandler::handle_at()`]). If the handler applies [backpressure](EventHandler::backpressure()),
+/// this first waits until it's ready.
+///
+/// # Arguments
+/// - `event`: The [`Event`] to handle.
+///
+/// # Errors
+/// This function errors if the registered handler failed to handle the event.
+///
+/// # Panics
+/// This function panics if no handler was registered yet (and none is [scoped](with_scoped_event_handler())).
+pub(crate) fn emit(event: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+    // Wait until the handler is ready, without keeping it locked while we do
+    if let Some(backpressure) = with_event_handler(|handler| handler.backpressure()) {
+        backpressure.wait();
+    }
+    with_event_handler(|handler| if cfg!(feature = "timestamps") { handler.handle_at(event, SystemTime::now()) } else { handler.handle(event) })
+}
+
+/// Runs a closure on the [scoped](with_scoped_event_handler()) or [registered](register_event_handler())
+/// [`EventHandler`], whichever applies.
+///
+/// # Arguments
+/// - `f`: The closure to run. The handler is locked while it runs.
+///
+/// # Returns
+/// Whatever `f` returns.
+///
+/// # Panics
+/// This function panics if no handler was registered yet (and none is scoped).
+fn with_event_handler<R>(f: impl FnOnce(&mut dyn EventHandler) -> R) -> R {
+    SCOPED_EVENT_HANDLER.with_borrow_mut(|scoped| {
+        let mut global;
+        let handler: &mut dyn EventHandler = match scoped {
+            Some(handler) => handler.as_mut(),
+            None => {
+                global = EVENT_HANDLER
+                    .get()
+                    .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
+                    .lock()
+                    .unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+                &mut **global
+            },
+        };
+        f(handler)
+    })
+}
+
+/// Runs a closure while all events emitted on the current thread go to the given handler instead
+/// of the [registered](register_event_handler()) one.
+///
+/// # Arguments
+/// - `handler`: The [`EventHandler`] to temporarily use.
+/// - `f`: The closure to run.
+///
+/// # Returns
+/// Whatever `f` returns.
+pub(crate) fn with_scoped_event_handler<R>(handler: impl EventHandler, f: impl FnOnce() -> R) -> R {
+    /// Unsets the scoped handler again, even if `f` panics.
+    struct Unscope;
+    impl Drop for Unscope {
+        #[inline]
+        fn drop(&mut self) { SCOPED_EVENT_HANDLER.with_borrow_mut(|scoped| *scoped = None); }
+    }
+
+    SCOPED_EVENT_HANDLER.with_borrow_mut(|scoped| *scoped = Some(Box::new(handler)));
+    let _unscope = Unscope;
+    f()
+}
+
+/// Runs a closure while the given [`Registry`] is the one of the [`System`](crate::System)
+/// polling on the current thread.
+///
+/// # Arguments
+/// - `registry`: The [`Registry`] to temporarily use.
+/// - `f`: The closure to run.
+///
+/// # Returns
+/// Whatever `f` returns.
+pub(crate) fn with_scoped_registry<R>(registry: &Arc<Mutex<Registry>>, f: impl FnOnce() -> R) -> R {
+    /// Restores the previously scoped registry, even if `f` panics.
+    struct Unscope(Option<Arc<Mutex<Registry>>>);
+    impl Drop for Unscope {
+        #[inline]
+        fn drop(&mut self) { SCOPED_REGISTRY.with_borrow_mut(|scoped| *scoped = self.0.take()); }
+    }
+
+    let _unscope = Unscope(SCOPED_REGISTRY.with_borrow_mut(|scoped| scoped.replace(registry.clone())));
+    f()
+}
+
+/// Runs a closure on the [`Registry`] of the [`System`](crate::System) polling on the current
+/// thread.
+///
+/// # Arguments
+/// - `f`: The closure to run. It is given [`None`] if there is no such System, e.g., because an
+///   agent is polled by hand.
+///
+/// # Returns
+/// Whatever `f` returns.
+pub(crate) fn with_registry<R>(f: impl FnOnce(Option<&mut Registry>) -> R) -> R {
+    SCOPED_REGISTRY.with_borrow(|scoped| match scoped {
+        Some(registry) => f(Some(&mut *registry.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}")))),
+        None => f(None),
+    })
+}
+
 
 
 
@@ -127,28 +817,44 @@ impl<'v, 's, 'i, A, S, E> TracingView<'v, A, S, E> {
     /// the new message, or if the agent attempted to publish a message not theirs.
     #[inline]
     pub fn state<SM>(&mut self, msg: SM) -> Result<(), Error<::justact::actors::Error<String, S::Error>>>
+    where
+        S: justact::SetAsync<str, SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        self.state_with_meta(msg, BTreeMap::new())
+    }
+
+    /// Have the agent state a message to their own view, attaching some metadata to it.
+    ///
+    /// The metadata is not part of the message as the agents see it; it only ends up in the
+    /// traced [`Message`](crate::wire::Message).
+    ///
+    /// # Arguments
+    /// - `msg`: The message to state.
+    /// - `meta`: Key/value pairs giving out-of-band context about the message.
+    ///
+    /// # Errors
+    /// This function errors if adding the message to the internal `S`tatements-set fails to add
+    /// the new message, or if the agent attempted to publish a message not theirs.
+    pub fn state_with_meta<SM>(&mut self, msg: SM, meta: BTreeMap<String, String>) -> Result<(), Error<::justact::actors::Error<String, S::Error>>>
     where
         S: justact::SetAsync<str, SM>,
         SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
     {
         // State the message, first
-        let pmsg = into_prototype_message(&msg);
+        let mut pmsg = into_prototype_message(&msg);
+        Arc::make_mut(&mut pmsg).meta = meta;
         self.0.state(msg).map_err(Error::Inner)?;
 
         // Then log that it happened
-        EVENT_HANDLER
-            .get()
-            .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
-            .lock()
-            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
-            .handle(Event::Control {
-                event: EventControl::StateMessage {
-                    who: Cow::Borrowed(&self.0.id),
-                    to:  justact::Recipient::One(Cow::Borrowed(&self.0.id)),
-                    msg: pmsg,
-                },
-            })
-            .map_err(|err| Error::EventHandle { err })
+        emit(Event::Control {
+            event: EventControl::StateMessage {
+                who: Cow::Borrowed(&self.0.id),
+                to:  justact::Recipient::One(Cow::Borrowed(&self.0.id)),
+                msg: pmsg,
+            },
+        })
+        .map_err(|err| Error::EventHandle { err })
     }
 
     /// Have the agent enact an action to their own view.
@@ -171,19 +877,14 @@ impl<'v, 's, 'i, A, S, E> TracingView<'v, A, S, E> {
         self.0.enact(act).map_err(Error::Inner)?;
 
         // Then log that it happened
-        EVENT_HANDLER
-            .get()
-            .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
-            .lock()
-            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
-            .handle(Event::Control {
-                event: EventControl::EnactAction {
-                    who:    Cow::Borrowed(&self.0.id),
-                    to:     justact::Recipient::One(Cow::Borrowed(&self.0.id)),
-                    action: pact,
-                },
-            })
-            .map_err(|err| Error::EventHandle { err })
+        emit(Event::Control {
+            event: EventControl::EnactAction {
+                who:    Cow::Borrowed(&self.0.id),
+                to:     justact::Recipient::One(Cow::Borrowed(&self.0.id)),
+                action: pact,
+            },
+        })
+        .map_err(|err| Error::EventHandle { err })
     }
 
     /// Agree on a new agreement.
@@ -208,13 +909,98 @@ impl<'v, 's, 'i, A, S, E> TracingView<'v, A, S, E> {
         self.0.agree(agrees).map_err(Error::Inner)?;
 
         // Then log that it happened
-        EVENT_HANDLER
-            .get()
-            .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
-            .lock()
-            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
-            .handle(Event::Control { event: EventControl::SetAgreements { agrees: pagrees } })
-            .map_err(|err| Error::EventHandle { err })
+        emit(Event::Control { event: EventControl::SetAgreements { agrees: pagrees } }).map_err(|err| Error::EventHandle { err })
+    }
+
+    /// Agree on new agreements that are only current for a while.
+    ///
+    /// Like [`TracingView::agree()`], this replaces all of the agreements with the given list. But
+    /// the audit only considers actions based on them current from `at` up to (but not including)
+    /// `until`, as set by [advancing the time](TracingView::advance_time()).
+    ///
+    /// # Arguments
+    /// - `agrees`: An iterator yielding agreements to put in the list.
+    /// - `at`: The first timestamp at which the agreements are current.
+    /// - `until`: The first timestamp at which the agreements are no longer current.
+    ///
+    /// # Errors
+    /// This function errors if we failed to clear the existing list or if we added any of the
+    /// agreements yielded by `agrees`.
+    #[inline]
+    pub fn agree_within<SM>(
+        &mut self,
+        agrees: impl IntoIterator<Item = SM>,
+        at: u64,
+        until: u64,
+    ) -> Result<(), Error<::justact::actors::Error<String, A::Error>>>
+    where
+        A: justact::SetSync<SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        // Update the agreements, first
+        let agrees: Vec<SM> = agrees.into_iter().collect();
+        let pagrees: Vec<Arc<Message<str>>> = agrees.iter().map(into_prototype_message).collect();
+        self.0.agree(agrees).map_err(Error::Inner)?;
+
+        // Then log that it happened, with the window
+        emit(Event::Control { event: EventControl::SetAgreementsWithin { agrees: pagrees, at, until } }).map_err(|err| Error::EventHandle { err })
+    }
+
+    /// Retracts a previously made agreement.
+    ///
+    /// Specifically, replaces all of the agreements with the current ones minus the one authored by
+    /// `author` with the given `id`. Actions based on it will no longer be considered based after
+    /// this.
+    ///
+    /// # Arguments
+    /// - `author`: The author of the agreement to retract.
+    /// - `id`: The (human) identifier of the agreement to retract.
+    ///
+    /// # Errors
+    /// This function errors if we failed to read the existing list, to clear it or to add back any
+    /// of the remaining agreements.
+    pub fn retract_agreement<SM>(&mut self, author: &str, id: &str) -> Result<(), Error<::justact::actors::Error<String, A::Error>>>
+    where
+        A: justact::SetSync<SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        // Update the agreements, first
+        let agrees: Vec<SM> = self
+            .0
+            .agreed
+            .iter()
+            .map_err(|err| Error::Agreements { err: Box::new(err) })?
+            .filter(|agree| agree.author_id() != author || agree.human_id() != id)
+            .cloned()
+            .collect();
+        self.0.agree(agrees).map_err(Error::Inner)?;
+
+        // Then log that it happened
+        emit(Event::Control { event: EventControl::RetractAgreement { author: Cow::Owned(author.into()), id: Cow::Owned(id.into()) } })
+        .map_err(|err| Error::EventHandle { err })
+    }
+
+    /// Advances the time to the given timestamp.
+    ///
+    /// Note that the view itself has no notion of time. Instead, this logs the advance, which is
+    /// what decides whether time-bound agreements are current, and tells the
+    /// [`System`](crate::System) polling this view (if any), such that the
+    /// [clocks](crate::agent::Agent::time()) of its agents follow it.
+    ///
+    /// # Arguments
+    /// - `timestamp`: The new current time.
+    ///
+    /// # Errors
+    /// This function errors if the registered event handler failed to handle the event.
+    #[inline]
+    pub fn advance_time(&mut self, timestamp: u64) -> Result<(), Error<Infallible>> {
+        emit(Event::Control { event: EventControl::AdvanceTime { timestamp } }).map_err(|err| Error::EventHandle { err })?;
+        with_registry(|registry| {
+            if let Some(registry) = registry {
+                registry.now = timestamp;
+            }
+        });
+        Ok(())
     }
 
 
@@ -223,40 +1009,92 @@ impl<'v, 's, 'i, A, S, E> TracingView<'v, A, S, E> {
     ///
     /// Note, though, that the message must already be in the agent's view for this to be allowed.
     ///
+    /// Groups are expanded to their members first, which are then gossiped to one-by-one.
+    ///
+    /// # Arguments
+    /// - `to`: Some [`Recipient`] to gossip the message to.
+    /// - `message`: The message to gossip.
+    ///
+    /// # Errors
+    /// This function errors if we failed to access the list of stated messages, if the current
+    /// agent did not know the `message`, if `to` is a group that was never defined or if any of
+    /// its members isn't an agent in the system.
+    #[inline]
+    pub fn gossip<SM>(&mut self, to: Recipient, message: SM) -> Result<(), Error<::justact::actors::Error<String, S::Error>>>
+    where
+        S: justact::SetAsync<str, SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        self.gossip_with_meta(to, message, BTreeMap::new())
+    }
+
+    /// Gossips a particular message to a new recipient, attaching some metadata to it.
+    ///
+    /// See [`TracingView::state_with_meta()`] for what the metadata is for.
+    ///
     /// # Arguments
     /// - `to`: Some [`Recipient`] to gossip the message to.
     /// - `message`: The message to gossip.
+    /// - `meta`: Key/value pairs giving out-of-band context about the message.
+    ///
+    /// # Errors
+    /// This function errors if we failed to access the list of stated messages, if the current
+    /// agent did not know the `message`, if `to` is a group that was never defined or if any of
+    /// its members isn't an agent in the system.
+    pub fn gossip_with_meta<SM>(
+        &mut self,
+        to: Recipient,
+        message: SM,
+        meta: BTreeMap<String, String>,
+    ) -> Result<(), Error<::justact::actors::Error<String, S::Error>>>
+    where
+        S: justact::SetAsync<str, SM>,
+        SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
+    {
+        for to in to.resolve()? {
+            self.gossip_to(to, message.clone(), meta.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Gossips a particular message to a new [`justact::Recipient`].
+    ///
+    /// # Arguments
+    /// - `to`: Some [`justact::Recipient`] to gossip the message to.
+    /// - `message`: The message to gossip.
+    /// - `meta`: Key/value pairs giving out-of-band context about the message.
     ///
     /// # Errors
     /// This function errors if we failed to access the list of stated messages or if the current
     /// agent did not know the `message`.
     #[inline]
-    pub fn gossip<SM>(&mut self, to: justact::Recipient<String>, message: SM) -> Result<(), Error<::justact::actors::Error<String, S::Error>>>
+    fn gossip_to<SM>(
+        &mut self,
+        to: justact::Recipient<String>,
+        message: SM,
+        meta: BTreeMap<String, String>,
+    ) -> Result<(), Error<::justact::actors::Error<String, S::Error>>>
     where
         S: justact::SetAsync<str, SM>,
         SM: justact::ConstructableMessage<AuthorId = str, Payload = Program>,
     {
         // Gossip first
-        let pmsg = into_prototype_message(&message);
+        let mut pmsg = into_prototype_message(&message);
+        Arc::make_mut(&mut pmsg).meta = meta;
         self.0.gossip(to.clone(), message).map_err(Error::Inner)?;
 
         // Then log that it happened
-        EVENT_HANDLER
-            .get()
-            .unwrap_or_else(|| panic!("No trace handler was registered; call `register_trace_handler()` first"))
-            .lock()
-            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
-            .handle(Event::Control {
-                event: EventControl::StateMessage {
-                    who: Cow::Borrowed(&self.0.id),
-                    to:  match to {
-                        justact::Recipient::All => justact::Recipient::All,
-                        justact::Recipient::One(id) => justact::Recipient::One(Cow::Owned(id)),
-                    },
-                    msg: pmsg,
+        emit(Event::Control {
+            event: EventControl::StateMessage {
+                who: Cow::Borrowed(&self.0.id),
+                to:  match to {
+                    justact::Recipient::All => justact::Recipient::All,
+                    justact::Recipient::One(id) => justact::Recipient::One(Cow::Owned(id)),
                 },
-            })
-            .map_err(|err| Error::EventHandle { err })
+                msg: pmsg,
+            },
+        })
+        .map_err(|err| Error::EventHandle { err })
     }
 }
 
@@ -444,3 +1282,116 @@ impl<'v, 's, 'i, A, S, E> TracingView<'v, A, S, E> {
 //         Ok(())
 //     }
 // }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+
+    /// Event handler that remembers the timestamps of the events it sees.
+    struct TimestampEventHandler(Arc<Mutex<Vec<u64>>>);
+    impl EventHandler for TimestampEventHandler {
+        #[inline]
+        fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+            if let Event::Control { event: EventControl::AdvanceTime { timestamp } } = trace {
+                self.0.lock().unwrap().push(timestamp);
+            }
+            Ok(())
+        }
+    }
+
+
+    /// Runs a future to completion on the current thread.
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        struct Unpark(std::thread::Thread);
+        impl std::task::Wake for Unpark {
+            #[inline]
+            fn wake(self: Arc<Self>) { self.0.unpark(); }
+        }
+
+        let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+        let mut cx = std::task::Context::from_waker(&waker);
+        let mut fut = std::pin::pin!(fut);
+        loop {
+            match fut.as_mut().poll(&mut cx) {
+                Poll::Ready(res) => return res,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+
+    #[test]
+    fn test_channel_event_handler() {
+        let (mut sender, receiver) = channel_event_handler(2);
+        let producer = std::thread::spawn(move || {
+            // This outpaces the channel, and so has to wait for the receiver
+            for timestamp in 1..=5 {
+                sender.handle(Event::Control { event: EventControl::AdvanceTime { timestamp } }).unwrap();
+            }
+        });
+
+        let seen: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(Vec::new()));
+        block_on(receiver.forward_to(&mut SyncEventHandler(TimestampEventHandler(seen.clone())))).unwrap();
+        producer.join().unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3, 4, 5]);
+
+        // Without a receiver, sending fails rather than blocking forever
+        let (mut sender, receiver) = channel_event_handler(1);
+        drop(receiver);
+        assert!(sender.handle(Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } }).is_err());
+    }
+
+    #[test]
+    fn test_channel_event_handler_emit() {
+        // Emit events on another thread, reporting every one that got through
+        let (sender, mut receiver) = channel_event_handler(1);
+        let (sent, emitted) = std::sync::mpsc::channel::<u64>();
+        let producer = std::thread::spawn(move || {
+            with_scoped_event_handler(sender, || {
+                for timestamp in 1..=3 {
+                    emit(Event::Control { event: EventControl::AdvanceTime { timestamp } }).unwrap();
+                    sent.send(timestamp).unwrap();
+                }
+            })
+        });
+
+        // The first event fits in the channel, but the second has to wait until we make room
+        assert_eq!(emitted.recv().unwrap(), 1);
+        assert!(emitted.recv_timeout(Duration::from_millis(100)).is_err());
+        assert!(matches!(block_on(receiver.recv()), Some(Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } })));
+        assert_eq!(emitted.recv().unwrap(), 2);
+
+        // The rest arrives in order once the producer is done
+        let seen: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(Vec::new()));
+        block_on(receiver.forward_to(&mut SyncEventHandler(TimestampEventHandler(seen.clone())))).unwrap();
+        producer.join().unwrap();
+        assert_eq!(*seen.lock().unwrap(), vec![2, 3]);
+
+        // If the receiver is dropped while the channel is full, emitting fails instead of blocking
+        let (sender, receiver) = channel_event_handler(1);
+        with_scoped_event_handler(sender, || {
+            emit(Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } }).unwrap();
+            drop(receiver);
+            assert!(emit(Event::Control { event: EventControl::AdvanceTime { timestamp: 2 } }).is_err());
+        });
+    }
+
+    #[test]
+    fn test_tee_event_handler() {
+        let lhs: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(Vec::new()));
+        let rhs: Arc<Mutex<Vec<u64>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut tee = TeeEventHandler(vec![Box::new(TimestampEventHandler(lhs.clone())), Box::new(TimestampEventHandler(rhs.clone()))]);
+        tee.handle(Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } }).unwrap();
+        tee.handle(Event::Control { event: EventControl::AdvanceTime { timestamp: 2 } }).unwrap();
+        assert_eq!(*lhs.lock().unwrap(), vec![1, 2]);
+        assert_eq!(*rhs.lock().unwrap(), vec![1, 2]);
+    }
+}
@@ -17,7 +17,7 @@ pub mod auditing;
 mod codegen;
 #[cfg(feature = "dataplane")]
 pub mod dataplane;
-// pub mod events;
+pub mod events;
 pub mod agent;
 pub mod io;
 pub mod policy;
@@ -28,3 +28,86 @@ pub mod wire;
 // Use some of it
 pub use justact as spec;
 pub use runtime::{Error, System};
+
+// Imports
+use std::error;
+
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Unifies the errors of all the modules in the prototype.
+///
+/// The errors of the individual modules are still around; this one merely wraps them such that
+/// embedders can use `?` across the whole crate. Note that it is distinct from [`Error`] (i.e.,
+/// [`runtime::Error`]), which only describes agents and synchronizers failing while a [`System`]
+/// runs them (but it can be wrapped by this one, too).
+///
+/// # Example
+/// ```no_run
+/// # #[cfg(all(feature = "dataplane", feature = "slick"))]
+/// # fn main() -> Result<(), justact_prototype::PrototypeError> {
+/// use std::borrow::Cow;
+/// use std::sync::Arc;
+///
+/// use justact_prototype::auditing::{Event, EventControl, SlickAudit};
+/// use justact_prototype::dataplane::{StoreHandle, VarId};
+/// use justact_prototype::policy::slick::{Extractor, Program, parse_payload};
+/// use justact_prototype::spec::collections::Recipient;
+/// use justact_prototype::spec::policies::Policy as _;
+/// use justact_prototype::wire::{Action, Message};
+///
+/// // Policy that doesn't parse or extract is a SyntaxError...
+/// let payload: Program = parse_payload("consortium", "consortium 1", b"amy may write.")?;
+/// let agree = Arc::new(Message::new_raw("consortium 1", "consortium", payload));
+/// if !Extractor::new().extract_with_actor("amy", &*agree)?.is_valid() {
+///     return Ok(());
+/// }
+///
+/// // ...the audit decides whether acting on it is permitted...
+/// let mut audit = SlickAudit::new();
+/// audit.audit(&Event::Control { event: EventControl::SetAgreements { agrees: vec![agree.clone()] } });
+/// audit.audit(&Event::Control {
+///     event: EventControl::EnactAction {
+///         who:    Cow::Borrowed("amy"),
+///         to:     Recipient::All,
+///         action: Action::new_raw("amy 1", "amy", agree.clone(), [agree]),
+///     },
+/// });
+///
+/// // ...whereas failing to write is a dataplane Error, but all of them end up as this one
+/// if audit.outcome_of(1).is_permitted() {
+///     StoreHandle::new().scope("amy").write(VarId::new("amy", "checks", "ok"), "amy 1", b"yes".as_slice())?;
+/// }
+/// # Ok(())
+/// # }
+/// # #[cfg(not(all(feature = "dataplane", feature = "slick")))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Error)]
+pub enum PrototypeError {
+    #[error("Agent failed to execute a step")]
+    Agent(#[from] agent::Error),
+    #[cfg(feature = "binary-trace")]
+    #[error("Failed to read or write a binary trace")]
+    BinaryTrace(#[from] auditing::BinaryTraceError),
+    #[error("Failed to handle an event")]
+    Io {
+        #[source]
+        err: Box<dyn 'static + Send + error::Error>,
+    },
+    #[error("Failed to run the system")]
+    Runtime(#[from] runtime::Error),
+    #[cfg(feature = "dataplane")]
+    #[error("Failed to access the dataplane")]
+    Store(#[from] dataplane::Error),
+    #[cfg(feature = "slick")]
+    #[error("Failed to extract policy")]
+    Syntax(#[from] policy::slick::SyntaxError),
+    #[error("Failed to render a policy template")]
+    Template(#[from] policy::TemplateError),
+}
+impl<E: 'static + Send + error::Error> From<io::Error<E>> for PrototypeError {
+    #[inline]
+    fn from(value: io::Error<E>) -> Self { Self::Io { err: Box::new(value) } }
+}
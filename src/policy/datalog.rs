@@ -12,6 +12,7 @@
 //!   Implements JustAct traits for the [`datalog`]-crate.
 //
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::convert::Infallible;
 use std::error::Error;
@@ -23,7 +24,7 @@ use datalog::{ast, ir};
 use error_trace::toplevel;
 use thiserror::Error;
 
-use super::{PolicyDeserialize, PolicySerialize};
+use super::{PolicyDeserialize, PolicyEngine, PolicySerialize};
 mod justact {
     pub use ::justact::auxillary::{Affectored, Identifiable};
     pub use ::justact::collections::map::Map;
@@ -43,6 +44,43 @@ pub enum SyntaxError<'m> {
     Iter { what: &'static str, err: Box<dyn 'm + Error> },
 }
 
+/// Defines errors that may occur when finding the affector of [`Effect`]s.
+#[derive(Debug, Error)]
+pub enum EffectError<'f, 's> {
+    #[error("Fact {fact:?} matches the effect pattern, but its affector is ambiguous (both {first:?} and {second:?})")]
+    AmbiguousAffector { fact: ir::GroundAtom<(&'f str, &'s str)>, first: ir::GroundAtom<(&'f str, &'s str)>, second: ir::GroundAtom<(&'f str, &'s str)> },
+    #[error("Affector variable {affector:?} does not occur in effect pattern {pat:?}")]
+    UnknownAffector { pat: ir::Atom<(&'f str, &'s str)>, affector: ir::Ident<(&'f str, &'s str)> },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Orders two [`GroundAtom`](ir::GroundAtom)s structurally.
+///
+/// Atoms are ordered by their identifiers first, and then lexicographically by their arguments.
+///
+/// # Arguments
+/// - `lhs`: The first atom to compare.
+/// - `rhs`: The second atom to compare.
+///
+/// # Returns
+/// The [`Ordering`] of `lhs` with respect to `rhs`.
+fn cmp_ground_atoms(lhs: &ir::GroundAtom<(&str, &str)>, rhs: &ir::GroundAtom<(&str, &str)>) -> Ordering {
+    if lhs.ident != rhs.ident {
+        return format!("{:?}", lhs.ident).cmp(&format!("{:?}", rhs.ident));
+    }
+    for (lhs, rhs) in lhs.args.iter().zip(rhs.args.iter()) {
+        let ord: Ordering = cmp_ground_atoms(lhs, rhs);
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    lhs.args.len().cmp(&rhs.args.len())
+}
+
 
 
 
@@ -53,11 +91,22 @@ pub enum SyntaxError<'m> {
 pub struct Effect<'f, 's> {
     /// The truth wrapped by this effect.
     pub fact:     ir::GroundAtom<(&'f str, &'s str)>,
-    /// The identifier of the affector.
-    pub affector: ir::Ident<(&'f str, &'s str)>,
+    /// The atom identifying the affector.
+    ///
+    /// This is usually a plain identifier, but may be a nested atom if the effect pattern's
+    /// affector variable matched one.
+    pub affector: ir::GroundAtom<(&'f str, &'s str)>,
+}
+impl<'f, 's> Ord for Effect<'f, 's> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering { cmp_ground_atoms(&self.fact, &other.fact).then_with(|| cmp_ground_atoms(&self.affector, &other.affector)) }
+}
+impl<'f, 's> PartialOrd for Effect<'f, 's> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
 }
 impl<'f, 's> justact::Affectored for Effect<'f, 's> {
-    type AffectorId = ir::Ident<(&'f str, &'s str)>;
+    type AffectorId = ir::GroundAtom<(&'f str, &'s str)>;
 
     #[inline]
     fn affector_id(&self) -> &Self::AffectorId { &self.affector }
@@ -90,6 +139,10 @@ impl<'f, 's> Default for Denotation<'f, 's> {
 impl<'f, 's> Denotation<'f, 's> {
     /// Creates a new Denotation from a Datalog [`Interpretation`].
     ///
+    /// This function is lenient: facts for which the affector cannot be determined are not
+    /// considered effects (but are still truths). Use [`Denotation::try_from_interpretation()`]
+    /// to learn about these cases instead.
+    ///
     /// # Arguments
     /// - `int`: The [`Interpretation`] to build this Denotation from.
     /// - `pat`: An [`Atom`] that describes a pattern for recognizing effects.
@@ -103,45 +156,109 @@ impl<'f, 's> Denotation<'f, 's> {
         pat: ir::Atom<(&'f str, &'s str)>,
         affector: ir::Ident<(&'f str, &'s str)>,
     ) -> Self {
-        fn matched_by<S: Clone>(atom: &ir::GroundAtom<S>, pat: &ir::Atom<S>, affector: &ir::Ident<S>) -> Option<ir::Ident<S>> {
+        let (den, _errs): (Self, Vec<EffectError<'f, 's>>) = Self::from_interpretation_with_errors(int, &pat, &affector);
+        #[cfg(feature = "log")]
+        for err in _errs {
+            log::warn!("Not considering fact as effect: {err}");
+        }
+        den
+    }
+
+    /// Creates a new Denotation from a Datalog [`Interpretation`], failing if the affector of any
+    /// effect cannot be determined.
+    ///
+    /// # Arguments
+    /// - `int`: The [`Interpretation`] to build this Denotation from.
+    /// - `pat`: An [`Atom`] that describes a pattern for recognizing effects.
+    /// - `affector`: An [`Atom`] that describes how to extract the affector from the effect.
+    ///
+    /// # Returns
+    /// A new Denotation that is JustAct^{TM} compliant.
+    ///
+    /// # Errors
+    /// This function errors if the `affector` does not occur in the `pat`tern, or if it matched
+    /// different atoms in the same effect.
+    #[inline]
+    pub fn try_from_interpretation(
+        int: KnowledgeBase<(&'f str, &'s str)>,
+        pat: ir::Atom<(&'f str, &'s str)>,
+        affector: ir::Ident<(&'f str, &'s str)>,
+    ) -> Result<Self, EffectError<'f, 's>> {
+        let (den, errs): (Self, Vec<EffectError<'f, 's>>) = Self::from_interpretation_with_errors(int, &pat, &affector);
+        match errs.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(den),
+        }
+    }
+
+    /// Implements both [`Denotation::from_interpretation()`] and
+    /// [`Denotation::try_from_interpretation()`].
+    ///
+    /// # Arguments
+    /// - `int`: The [`Interpretation`] to build this Denotation from.
+    /// - `pat`: An [`Atom`] that describes a pattern for recognizing effects.
+    /// - `affector`: An [`Atom`] that describes how to extract the affector from the effect.
+    ///
+    /// # Returns
+    /// A new Denotation with only the effects of which the affector could be found, together with
+    /// the problems encountered for the others.
+    fn from_interpretation_with_errors(
+        int: KnowledgeBase<(&'f str, &'s str)>,
+        pat: &ir::Atom<(&'f str, &'s str)>,
+        affector: &ir::Ident<(&'f str, &'s str)>,
+    ) -> (Self, Vec<EffectError<'f, 's>>) {
+        /// Checks whether the given variable occurs anywhere in the given pattern.
+        fn occurs_in<'f, 's>(pat: &ir::Atom<(&'f str, &'s str)>, var: &ir::Ident<(&'f str, &'s str)>) -> bool {
+            match pat {
+                ir::Atom::Fact(ir::Fact { args, .. }) => args.iter().any(|arg| occurs_in(arg, var)),
+                ir::Atom::Var(pat) => pat == var,
+            }
+        }
+
+        /// Matches the atom to the pattern, collecting everything bound to the affector variable.
+        fn matched_by<'a, 'f, 's>(
+            atom: &'a ir::GroundAtom<(&'f str, &'s str)>,
+            pat: &ir::Atom<(&'f str, &'s str)>,
+            affector: &ir::Ident<(&'f str, &'s str)>,
+            found: &mut Vec<&'a ir::GroundAtom<(&'f str, &'s str)>>,
+        ) -> bool {
             match pat {
-                // Check if arity matches
+                // Check if arity matches and then if the pattern checks out
                 ir::Atom::Fact(ir::Fact { ident, args }) if &atom.ident == ident && atom.args.len() == args.len() => {
-                    // Check if the pattern checks out
-                    let mut res: Option<ir::Ident<S>> = None;
-                    for (atom, pat) in atom.args.iter().zip(args.iter()) {
-                        if let Some(aff) = matched_by(atom, pat, affector) {
-                            if res.is_none() {
-                                res = Some(aff);
-                            } else {
-                                panic!("Duplicate affector!");
-                            }
-                        }
-                    }
-                    res
+                    atom.args.iter().zip(args.iter()).all(|(atom, pat)| matched_by(atom, pat, affector, found))
                 },
-                ir::Atom::Fact(_) => None,
+                ir::Atom::Fact(_) => false,
 
-                // Variables always match, but not necessarily the affector
-                ir::Atom::Var(var) if var == affector => {
-                    // We can only do something if the fact is an ident
-                    if !atom.args.is_empty() {
-                        panic!("Can only match affector with identifier, not nested thing!");
+                // Variables always match, but only the affector is interesting. Note that it can
+                // bind to nested atoms just fine.
+                ir::Atom::Var(var) => {
+                    if var == affector {
+                        found.push(atom);
                     }
-                    Some(atom.ident.clone())
+                    true
                 },
-                ir::Atom::Var(_) => None,
             }
         }
 
 
         let mut truths: HashMap<ir::GroundAtom<(&'f str, &'s str)>, Option<bool>> = HashMap::new();
         let mut effects: HashMap<ir::GroundAtom<(&'f str, &'s str)>, Effect<'f, 's>> = HashMap::new();
+        let mut errs: Vec<EffectError<'f, 's>> = Vec::new();
+        let known: bool = occurs_in(pat, affector);
+        if !known {
+            errs.push(EffectError::UnknownAffector { pat: pat.clone(), affector: affector.clone() });
+        }
         for fact in int.truths() {
             // See if the fact matches the pattern
-            if let Some(affector) = matched_by(&fact, &pat, &affector) {
-                // Insert it!
-                effects.insert(fact.clone(), Effect { fact: fact.clone(), affector });
+            let mut found: Vec<&ir::GroundAtom<(&'f str, &'s str)>> = Vec::new();
+            if known && matched_by(&fact, pat, affector, &mut found) {
+                // The affector must have matched the same thing everywhere
+                let first: &ir::GroundAtom<(&'f str, &'s str)> = found[0];
+                if let Some(other) = found.iter().find(|atom| **atom != first) {
+                    errs.push(EffectError::AmbiguousAffector { fact: fact.clone(), first: first.clone(), second: (*other).clone() });
+                } else {
+                    effects.insert(fact.clone(), Effect { fact: fact.clone(), affector: first.clone() });
+                }
             }
 
             // Always add the truth as such
@@ -149,8 +266,15 @@ impl<'f, 's> Denotation<'f, 's> {
         }
 
         // OK, return the denotation!
-        Self { truths, effects }
+        (Self { truths, effects }, errs)
     }
+
+    /// Checks if this denotation contains any facts of which the truth is unknown.
+    ///
+    /// # Returns
+    /// True if at least one fact is neither true nor false, or false otherwise.
+    #[inline]
+    pub fn has_unknowns(&self) -> bool { self.truths.values().any(Option::is_none) }
 }
 impl<'f, 's> justact::Denotation for Denotation<'f, 's> {
     type Effect = Effect<'f, 's>;
@@ -257,6 +381,20 @@ impl<'f, 's> Policy<'f, 's> {
     /// The internal [`Spec`].
     #[inline]
     pub fn into_spec(self) -> ir::Spec<ir::Atom<(&'f str, &'s str)>> { self.spec }
+
+    /// Computes the truths of this policy like [`Policy::truths()`](justact::Policy::truths()),
+    /// but fails instead of ignoring effects of which the affector cannot be found.
+    ///
+    /// # Returns
+    /// A [`Denotation`] with the truths and effects of this policy.
+    ///
+    /// # Errors
+    /// This function errors if the affector variable does not occur in the effect pattern, or if
+    /// it matched different atoms in the same effect.
+    #[inline]
+    pub fn try_truths(&self) -> Result<Denotation<'f, 's>, EffectError<'f, 's>> {
+        Denotation::try_from_interpretation(self.spec.alternating_fixpoint(), self.pat.clone(), self.affector.clone())
+    }
 }
 impl<'f, 's> justact::Policy for Policy<'f, 's> {
     type Denotation = Denotation<'f, 's>;
@@ -395,6 +533,38 @@ impl<'m> justact::Extractor<str, ast::Spec<(&'m str, &'m str)>> for Extractor {
 
 
 
+/// Implements a [`PolicyEngine`] for Datalog.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Engine;
+impl PolicyEngine for Engine {
+    type Denotation<'s> = Denotation<'static, 's>;
+    type Error<'s> = datalog::parser::Error<'s, (&'static str, &'s str)>;
+
+    fn denote<'s>(&self, program: &'s str) -> Result<Self::Denotation<'s>, Self::Error<'s>> {
+        let spec: ast::Spec<(&'static str, &'s str)> = parse(("<raw>", program))?;
+
+        // Compile it like the extractor would
+        let mut pol: Policy<'static, 's> = Policy::default();
+        pol.spec.rules.reserve(spec.rules.len());
+        for rule in &spec.rules {
+            match rule.compile() {
+                Ok(rule) => pol.spec.rules.push(rule),
+                Err(_) => {
+                    pol.spec.rules = vec![ir::Rule {
+                        consequents:     vec![ir::Atom::Fact(ir::Fact { ident: ir::Ident::new("error".into(), None), args: Vec::new() })],
+                        pos_antecedents: Vec::new(),
+                        neg_antecedents: Vec::new(),
+                    }];
+                    break;
+                },
+            }
+        }
+        Ok(<Policy as justact::Policy>::truths(&pol))
+    }
+}
+
+
+
 impl<'a> PolicySerialize for ast::Spec<(&'a str, &'a str)> {
     #[inline]
     fn serialize(&self) -> String { format!("{self}") }
@@ -417,9 +587,9 @@ mod tests {
     use std::convert::Infallible;
 
     use datalog::ast::{Spec, datalog};
-    use datalog::ir::{GroundAtom, Ident};
+    use datalog::ir::{Atom, Fact, GroundAtom, Ident};
 
-    use super::{Denotation, Effect, Extractor, Policy};
+    use super::{Denotation, Effect, EffectError, Extractor, Policy};
     mod justact {
         pub use ::justact::auxillary::Authored;
         pub use ::justact::messages::MessageSet;
@@ -428,7 +598,18 @@ mod tests {
     }
 
 
-    /// Generates the effect pattern.
+    /// Generates a constant.
+    fn make_constant(name: &'static str) -> GroundAtom<(&'static str, &'static str)> { GroundAtom { ident: Ident::new(name.into(), None), args: Vec::new() } }
+
+    /// Generates the default effect pattern, i.e., `effect(A, E)`.
+    fn make_pattern() -> Atom<(&'static str, &'static str)> {
+        Atom::Fact(Fact {
+            ident: Ident::new("effect".into(), None),
+            args:  vec![Atom::Var(Ident::new("A".into(), None)), Atom::Var(Ident::new("E".into(), None))],
+        })
+    }
+
+    /// Generates an effect.
     fn make_effect(actor: &'static str, effect: &'static str) -> GroundAtom<(&'static str, &'static str)> {
         GroundAtom {
             ident: Ident::new("effect".into(), None),
@@ -529,8 +710,103 @@ mod tests {
             truths:  [make_effect("amy", "read")].into_iter().map(|a| (a, Some(true))).collect(),
             effects: [make_effect("amy", "read")]
                 .into_iter()
-                .map(|a| (a.clone(), Effect { fact: a, affector: Ident::new("amy".into(), None) }))
+                .map(|a| (a.clone(), Effect { fact: a, affector: make_constant("amy") }))
                 .collect(),
         })
     }
+
+    /// Tests whether the extraction of effects works as expected when there's nothing to extract.
+    #[test]
+    fn test_denotation_effects_none() {
+        // Empty program
+        let den = Denotation::from_interpretation(Policy::default().spec.alternating_fixpoint(), make_pattern(), Ident::new("A".into(), None));
+        assert!(den.effects.is_empty());
+
+        // Non-empty & non-effect program
+        let spec = datalog!( amy. reads(amy, effect). effect(amy). effect(amy, write, foo). ).compile().unwrap();
+        let den = Denotation::from_interpretation(spec.alternating_fixpoint(), make_pattern(), Ident::new("A".into(), None));
+        assert!(den.effects.is_empty());
+        assert_eq!(den.truths.len(), 4);
+    }
+    /// Tests whether the extraction of effects works as expected when there are effects to extract.
+    #[test]
+    fn test_denotation_effects_some() {
+        // Match a single effect, but with noise
+        let spec = datalog!( effect(amy, read). foo(bar, baz). effect(bob). ).compile().unwrap();
+        let den = Denotation::from_interpretation(spec.alternating_fixpoint(), make_pattern(), Ident::new("A".into(), None));
+        assert_eq!(
+            den.effects,
+            HashMap::from([(make_effect("amy", "read"), Effect { fact: make_effect("amy", "read"), affector: make_constant("amy") })])
+        );
+
+        // Match multiple effects
+        let spec = datalog!( effect(amy, read). effect(bob, write). ).compile().unwrap();
+        let den = Denotation::from_interpretation(spec.alternating_fixpoint(), make_pattern(), Ident::new("A".into(), None));
+        assert_eq!(
+            den.effects,
+            HashMap::from([
+                (make_effect("amy", "read"), Effect { fact: make_effect("amy", "read"), affector: make_constant("amy") }),
+                (make_effect("bob", "write"), Effect { fact: make_effect("bob", "write"), affector: make_constant("bob") }),
+            ])
+        );
+    }
+    /// Tests whether affectors can be nested atoms.
+    #[test]
+    fn test_denotation_effects_nested() {
+        let spec = datalog!( effect(worker(amy), read). ).compile().unwrap();
+        let den = Denotation::from_interpretation(spec.alternating_fixpoint(), make_pattern(), Ident::new("A".into(), None));
+        let fact = GroundAtom {
+            ident: Ident::new("effect".into(), None),
+            args:  vec![GroundAtom { ident: Ident::new("worker".into(), None), args: vec![make_constant("amy")] }, make_constant("read")],
+        };
+        let affector = fact.args[0].clone();
+        assert_eq!(den.effects, HashMap::from([(fact.clone(), Effect { fact, affector })]));
+    }
+    /// Tests whether unclear affectors are reported instead of panicking.
+    #[test]
+    fn test_effect_ord() {
+        let effect = |fact: GroundAtom<(&'static str, &'static str)>| -> Effect { Effect { affector: fact.args[0].clone(), fact } };
+        let nested = |args: Vec<GroundAtom<(&'static str, &'static str)>>| -> GroundAtom<(&'static str, &'static str)> {
+            GroundAtom { ident: Ident::new("effect".into(), None), args: vec![make_constant("amy"), GroundAtom { ident: Ident::new("read".into(), None), args }] }
+        };
+
+        // Arguments are compared one-by-one, and shorter ones come first
+        let mut effects: Vec<Effect> =
+            vec![effect(nested(vec![make_constant("b")])), effect(nested(vec![make_constant("a"), make_constant("b")])), effect(nested(vec![make_constant("a")]))];
+        effects.sort();
+        assert_eq!(effects, vec![
+            effect(nested(vec![make_constant("a")])),
+            effect(nested(vec![make_constant("a"), make_constant("b")])),
+            effect(nested(vec![make_constant("b")]))
+        ]);
+        assert_eq!(effect(make_effect("amy", "read")).cmp(&effect(make_effect("amy", "read"))), std::cmp::Ordering::Equal);
+        assert_eq!(effect(make_effect("amy", "read")).cmp(&effect(make_effect("bob", "read"))), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn test_denotation_effects_errors() {
+        // The affector occurs twice, and matches different things
+        let pat = Atom::Fact(Fact {
+            ident: Ident::new("effect".into(), None),
+            args:  vec![Atom::Var(Ident::new("A".into(), None)), Atom::Var(Ident::new("A".into(), None))],
+        });
+        let spec = datalog!( effect(amy, bob). effect(cho, cho). ).compile().unwrap();
+        assert!(matches!(
+            Denotation::try_from_interpretation(spec.alternating_fixpoint(), pat.clone(), Ident::new("A".into(), None)),
+            Err(EffectError::AmbiguousAffector { .. })
+        ));
+        let den = Denotation::from_interpretation(spec.alternating_fixpoint(), pat, Ident::new("A".into(), None));
+        assert_eq!(den.effects.len(), 1);
+        assert_eq!(den.truths.len(), 2);
+
+        // The affector does not occur at all
+        let spec = datalog!( effect(amy, read). ).compile().unwrap();
+        assert!(matches!(
+            Denotation::try_from_interpretation(spec.alternating_fixpoint(), make_pattern(), Ident::new("B".into(), None)),
+            Err(EffectError::UnknownAffector { .. })
+        ));
+        let den = Denotation::from_interpretation(spec.alternating_fixpoint(), make_pattern(), Ident::new("B".into(), None));
+        assert!(den.effects.is_empty());
+        assert_eq!(den.truths.len(), 1);
+    }
 }
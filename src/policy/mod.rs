@@ -20,9 +20,26 @@ pub mod datalog;
 pub mod slick;
 
 // Imports
+use std::collections::HashMap;
 use std::convert::Infallible;
 use std::error::Error;
 
+use thiserror::Error;
+
+
+/***** ERRORS *****/
+/// Defines errors that may occur when [rendering](render_template()) a policy template.
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Unclosed placeholder starting at character {pos} (missing '}}}}')")]
+    UnclosedPlaceholder { pos: usize },
+    #[error("Unknown placeholder {{{{{name}}}}} at character {pos}")]
+    UnknownPlaceholder { pos: usize, name: String },
+}
+
+
+
+
 
 /***** LIBRARY *****/
 /// Creates a policy snippet that reflects an actor.
@@ -88,3 +105,67 @@ impl<'a> PolicyDeserialize<'a> for str {
     #[inline]
     fn deserialize(raw: &'a str) -> Result<Self::Owned, Self::Error> { Ok(raw.to_string()) }
 }
+
+
+
+/// Abstracts over the inference engines of the various policy languages.
+///
+/// This is mostly useful for comparing them (e.g., in benchmarks), as it denotes raw policy
+/// text without any of the checks or reflection done by the language's
+/// [`Extractor`](justact::policies::Extractor).
+pub trait PolicyEngine {
+    /// The result of denoting a program.
+    type Denotation<'s>;
+    /// The error emitted when the program could not be parsed.
+    type Error<'s>: Error;
+
+    /// Parses and then denotes the given program.
+    ///
+    /// # Arguments
+    /// - `program`: The raw program text to denote.
+    ///
+    /// # Returns
+    /// A [`PolicyEngine::Denotation`] describing everything the program derives.
+    ///
+    /// # Errors
+    /// This function errors if the `program` was not valid in this engine's language.
+    fn denote<'s>(&self, program: &'s str) -> Result<Self::Denotation<'s>, Self::Error<'s>>;
+}
+
+
+
+
+
+/***** LIBRARY FUNCTIONS *****/
+/// Fills in the placeholders in a policy template.
+///
+/// Placeholders are written as `{{name}}` (e.g., `{{agent}} says read({{dataset}}).`), such that
+/// one policy file can be reused with different agents or datasets. This happens textually, so
+/// the result still has to be parsed by the policy language.
+///
+/// # Arguments
+/// - `src`: The template to render, e.g., as obtained with [`include_str!()`].
+/// - `vars`: A map of placeholder names to what they are replaced with.
+///
+/// # Returns
+/// The rendered policy text.
+///
+/// # Errors
+/// This function errors if a placeholder in `src` is not closed, or if it has no value in `vars`.
+pub fn render_template(src: &str, vars: &HashMap<&str, &str>) -> Result<String, TemplateError> {
+    let mut res: String = String::with_capacity(src.len());
+    let mut rem: &str = src;
+    while let Some(start) = rem.find("{{") {
+        let pos: usize = src.len() - rem.len() + start;
+        res.push_str(&rem[..start]);
+        let Some(len) = rem[start + 2..].find("}}") else { return Err(TemplateError::UnclosedPlaceholder { pos }) };
+        let name: &str = rem[start + 2..start + 2 + len].trim();
+        match vars.get(name) {
+            Some(value) => res.push_str(value),
+            None => return Err(TemplateError::UnknownPlaceholder { pos, name: name.into() }),
+        }
+        rem = &rem[start + 2 + len + 2..];
+    }
+    res.push_str(rem);
+    Ok(res)
+}
@@ -12,7 +12,9 @@
 //!   Implements JustAct traits for the [`slick`]-crate.
 //
 
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter, Result as FResult};
@@ -21,9 +23,11 @@ use std::ops::{Deref, DerefMut};
 use slick::infer::Config;
 pub use slick::text::Text;
 pub use slick::{Atom, GroundAtom, Program};
-use slick::{Rule, RuleBody};
+use slick::{Check, CheckKind, Rule, RuleBody};
 
-use super::{PolicyDeserialize, PolicyReflect, PolicySerialize};
+#[cfg(feature = "dataplane")]
+use crate::dataplane::VarId;
+use super::{PolicyDeserialize, PolicyEngine, PolicyReflect, PolicySerialize};
 mod justact {
     pub use ::justact::auxillary::{Affectored, Identifiable};
     pub use ::justact::collections::map::Map;
@@ -34,25 +38,336 @@ mod justact {
 use thiserror::Error;
 
 
+/***** GLOBALS *****/
+thread_local! {
+    /// Caches the [`Text`]s of names that were [pre-interned](intern_all()) on this thread.
+    ///
+    /// Note that this is a cache in front of Slick's own interner, not a second one: it only
+    /// saves [`Text::from_str()`] looking the name up again. It is thread-local such that
+    /// [`text()`], which is called for every name in every extraction, never has to wait on a
+    /// lock.
+    static INTERNED: RefCell<HashMap<String, Text>> = RefCell::new(HashMap::new());
+}
+
+
+
+
+
 /***** ERRORS *****/
 /// Defines errors that may occur when [extracting](Extractor::extract()) policy.
+///
+/// Every error that is caused by a particular message carries the `author` and `id` of that
+/// message, so that it can be found back in a larger justification.
 #[derive(Debug, Error)]
 pub enum SyntaxError {
+    #[error("Multiple messages with ID {id:?} by {author:?} carry a different payload")]
+    DuplicateMessageId { author: String, id: String },
+    #[error("Payload of message {id:?} by {author:?} is not valid UTF-8 (first invalid byte at offset {offset})")]
+    InvalidUtf8 { author: String, id: String, offset: usize },
     #[error("Failed to iterate over messages in {what}")]
     Iter {
         what: &'static str,
         #[source]
         err:  Box<dyn 'static + Send + Error>,
     },
-    #[error("Misplaced wildcard in rule \"{rule:?}\"")]
-    MisplacedWildcard { rule: Rule },
-    #[error("Failed to parse the input as valid Slick")]
+    #[error("Actor name {actor:?} is not a valid Slick constant")]
+    InvalidActor { actor: String },
+    #[error("Misplaced wildcard in rule \"{rule:?}\" in message {id:?} by {author:?}")]
+    MisplacedWildcard { author: String, id: String, rule: Rule },
+    #[error("Rule in message {id:?} by {author:?} nests tuples deeper than the maximum of {max}")]
+    TooDeeplyNested { author: String, id: String, max: usize },
+    #[error("Failed to parse message {id:?} by {author:?} as valid Slick")]
     Slick {
+        author: String,
+        id:     String,
         #[source]
-        err: nom::Err<nom::error::VerboseError<String>>,
+        err:    nom::Err<nom::error::VerboseError<String>>,
     },
-    #[error("Unsafe/Unbound variables {} in \"{rule:?}\"", PrettyDebugAndList(vars.iter()))]
-    UnboundVariables { vars: HashSet<Text>, rule: Rule },
+    #[error("Unsafe/Unbound variables {} in \"{rule:?}\" in message {id:?} by {author:?}", PrettyDebugAndList(vars.iter()))]
+    UnboundVariables { author: String, id: String, vars: HashSet<Text>, rule: Rule },
+    #[error(
+        "Unsupported check \"{check:?}\" in \"{rule:?}\" in message {id:?} by {author:?} (only (in)equality of atoms and ordering of integers are \
+         supported)"
+    )]
+    UnsupportedCheck { author: String, id: String, check: Check, rule: Rule },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Orders two [`GroundAtom`]s structurally.
+///
+/// Constants come before tuples. Constants are ordered by their names, and tuples
+/// lexicographically by their elements.
+///
+/// # Arguments
+/// - `lhs`: The first atom to compare.
+/// - `rhs`: The second atom to compare.
+///
+/// # Returns
+/// The [`Ordering`] of `lhs` with respect to `rhs`.
+fn cmp_ground_atoms(lhs: &GroundAtom, rhs: &GroundAtom) -> Ordering {
+    match (lhs, rhs) {
+        (GroundAtom::Constant(lhs), GroundAtom::Constant(rhs)) => {
+            if lhs == rhs {
+                Ordering::Equal
+            } else {
+                format!("{lhs:?}").cmp(&format!("{rhs:?}"))
+            }
+        },
+        (GroundAtom::Constant(_), GroundAtom::Tuple(_)) => Ordering::Less,
+        (GroundAtom::Tuple(_), GroundAtom::Constant(_)) => Ordering::Greater,
+        (GroundAtom::Tuple(lhs), GroundAtom::Tuple(rhs)) => {
+            for (lhs, rhs) in lhs.iter().zip(rhs.iter()) {
+                let ord: Ordering = cmp_ground_atoms(lhs, rhs);
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            lhs.len().cmp(&rhs.len())
+        },
+    }
+}
+
+/// Writes a [`GroundAtom`] such that it can be told apart from what surrounds it.
+///
+/// # Arguments
+/// - `atom`: The [`GroundAtom`] to write.
+/// - `f`: The [`Formatter`] to write to.
+///
+/// # Errors
+/// This function errors if we failed to write to `f`.
+fn fmt_nested_ground_atom(atom: &GroundAtom, f: &mut Formatter) -> FResult {
+    match atom {
+        GroundAtom::Constant(_) => write!(f, "{atom:?}"),
+        GroundAtom::Tuple(_) => write!(f, "({atom:?})"),
+    }
+}
+
+/// Reads a constant as an integer, if it is one.
+///
+/// Slick has no separate variant for integers; they are constants like any other, which happen to
+/// be named by a number. This is the one place that decides which constants those are.
+///
+/// # Arguments
+/// - `constant`: The name of the constant to read.
+///
+/// # Returns
+/// The integer named by the constant, or [`None`] if it isn't named by one.
+#[inline]
+fn int_constant(constant: &Text) -> Option<i64> { format!("{constant:?}").parse().ok() }
+
+/// Decides whether Slick is able to evaluate the given check.
+///
+/// Slick can compare any two atoms for (in)equality, but it can only order constants that are
+/// integers. Wildcards never make sense in a check.
+///
+/// # Arguments
+/// - `check`: The [`Check`] to examine.
+///
+/// # Returns
+/// True if the check can be evaluated, or false otherwise.
+fn is_supported_check(check: &Check) -> bool {
+    check.atoms.iter().all(|atom| match (&check.kind, atom) {
+        (_, Atom::Wildcard) => false,
+        (_, Atom::Variable(_)) => true,
+        (CheckKind::Eq, Atom::Constant(_) | Atom::Tuple(_)) => true,
+        (CheckKind::Lt, Atom::Constant(c)) => int_constant(c).is_some(),
+        (CheckKind::Lt, Atom::Tuple(_)) => false,
+    })
+}
+
+
+
+
+
+/// Matches a (possibly non-ground) atom against a ground one, extending some variable bindings.
+///
+/// # Arguments
+/// - `atom`: The [`Atom`] to match.
+/// - `fact`: The [`GroundAtom`] to match it against.
+/// - `bindings`: The values of variables bound so far. Newly bound variables are added to it,
+///   even if the match ultimately fails.
+///
+/// # Returns
+/// True if `atom` matches `fact` under (the extended) `bindings`, or false otherwise.
+fn unify(atom: &Atom, fact: &GroundAtom, bindings: &mut HashMap<Text, GroundAtom>) -> bool {
+    match (atom, fact) {
+        (Atom::Constant(lhs), GroundAtom::Constant(rhs)) => lhs == rhs,
+        (Atom::Tuple(lhs), GroundAtom::Tuple(rhs)) => lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter()).all(|(lhs, rhs)| unify(lhs, rhs, bindings)),
+        (Atom::Variable(var), fact) => match bindings.get(var) {
+            Some(value) => value == fact,
+            None => {
+                bindings.insert(*var, fact.clone());
+                true
+            },
+        },
+        (Atom::Wildcard, _) => true,
+        _ => false,
+    }
+}
+
+/// Substitutes the variables in an atom with their bound values.
+///
+/// # Arguments
+/// - `atom`: The [`Atom`] to ground.
+/// - `bindings`: The values of the variables in `atom`.
+///
+/// # Returns
+/// A [`GroundAtom`] version of `atom`, or [`None`] if it contains wildcards or unbound variables.
+fn ground(atom: &Atom, bindings: &HashMap<Text, GroundAtom>) -> Option<GroundAtom> {
+    match atom {
+        Atom::Constant(c) => Some(GroundAtom::Constant(*c)),
+        Atom::Tuple(atoms) => atoms.iter().map(|atom| ground(atom, bindings)).collect::<Option<Vec<GroundAtom>>>().map(GroundAtom::Tuple),
+        Atom::Variable(var) => bindings.get(var).cloned(),
+        Atom::Wildcard => None,
+    }
+}
+
+/// Finds bindings that make the antecedents and checks of a rule hold in a set of truths.
+///
+/// # Arguments
+/// - `body`: The [`RuleBody`] to satisfy.
+/// - `pos`: The positive antecedents of `body` that are still left to match.
+/// - `truths`: The facts that are true.
+/// - `bindings`: The values of variables bound so far.
+/// - `matched`: The ground versions of the positive antecedents matched so far.
+///
+/// # Returns
+/// The ground positive antecedents that make `body` hold, or [`None`] if there are none.
+fn satisfy(body: &RuleBody, pos: &[Atom], truths: &[&GroundAtom], bindings: &HashMap<Text, GroundAtom>, matched: &mut Vec<GroundAtom>) -> Option<Vec<GroundAtom>> {
+    let Some((next, rest)) = pos.split_first() else {
+        // All positives are matched; see if the negatives are false and the checks hold
+        for neg in &body.neg_antecedents {
+            if truths.iter().any(|fact| unify(neg, fact, &mut bindings.clone())) {
+                return None;
+            }
+        }
+        for check in &body.checks {
+            let lhs: GroundAtom = ground(&check.atoms[0], bindings)?;
+            let rhs: GroundAtom = ground(&check.atoms[1], bindings)?;
+            let holds: bool = match (&check.kind, &lhs, &rhs) {
+                (CheckKind::Eq, lhs, rhs) => lhs == rhs,
+                (CheckKind::Lt, GroundAtom::Constant(lhs), GroundAtom::Constant(rhs)) => match (int_constant(lhs), int_constant(rhs)) {
+                    (Some(lhs), Some(rhs)) => lhs < rhs,
+                    _ => false,
+                },
+                (CheckKind::Lt, _, _) => false,
+            };
+            if holds != check.positive {
+                return None;
+            }
+        }
+        return Some(matched.clone());
+    };
+
+    // Try every true fact for the next positive antecedent
+    for fact in truths {
+        let mut extended: HashMap<Text, GroundAtom> = bindings.clone();
+        if unify(next, fact, &mut extended) {
+            matched.push((*fact).clone());
+            if let Some(res) = satisfy(body, rest, truths, &extended, matched) {
+                return Some(res);
+            }
+            matched.pop();
+        }
+    }
+    None
+}
+
+/// Decides whether any atom in the given rule nests tuples deeper than some maximum.
+///
+/// This is implemented without recursion such that even absurdly deep atoms do not overflow the
+/// stack while checking them.
+///
+/// # Arguments
+/// - `rule`: The [`Rule`] to examine.
+/// - `max`: The maximum depth allowed. Constants, variables and wildcards have depth 0, and every
+///   tuple adds 1 to the depth of its deepest element.
+///
+/// # Returns
+/// True if any atom (in the consequents, antecedents or checks) is nested deeper than `max`.
+fn is_too_deeply_nested(rule: &Rule, max: usize) -> bool {
+    let mut todo: Vec<(&Atom, usize)> = rule
+        .consequents
+        .iter()
+        .chain(rule.rule_body.pos_antecedents.iter())
+        .chain(rule.rule_body.neg_antecedents.iter())
+        .chain(rule.rule_body.checks.iter().flat_map(|check| check.atoms.iter()))
+        .map(|atom| (atom, 0))
+        .collect();
+    while let Some((atom, depth)) = todo.pop() {
+        if let Atom::Tuple(atoms) = atom {
+            if depth >= max {
+                return true;
+            }
+            todo.extend(atoms.iter().map(|atom| (atom, depth + 1)));
+        }
+    }
+    false
+}
+
+/// Checks that a rule is safe to reason about.
+///
+/// Specifically, this rejects misplaced wildcards, unbound variables and unsupported checks. Any
+/// rule that ends up in a [`Policy`] must pass this, including those that we inject ourselves.
+///
+/// # Arguments
+/// - `rule`: The [`Rule`] to check.
+/// - `author`: The author of the message that the rule came from, for error reporting.
+/// - `id`: The identifier of the message that the rule came from, for error reporting.
+///
+/// # Errors
+/// This function errors with the [`SyntaxError`] describing the first problem found.
+fn check_rule(rule: &Rule, author: &str, id: &str) -> Result<(), SyntaxError> {
+    if rule.misplaced_wildcards() {
+        return Err(SyntaxError::MisplacedWildcard { author: author.into(), id: id.into(), rule: rule.clone() });
+    }
+    let mut unbound_vars = HashSet::default();
+    rule.unbound_variables(&mut unbound_vars);
+    if !unbound_vars.is_empty() {
+        return Err(SyntaxError::UnboundVariables {
+            author: author.into(),
+            id:     id.into(),
+            vars:   unbound_vars.into_iter().copied().collect(),
+            rule:   rule.clone(),
+        });
+    }
+    for check in &rule.rule_body.checks {
+        if !is_supported_check(check) {
+            return Err(SyntaxError::UnsupportedCheck { author: author.into(), id: id.into(), check: check.clone(), rule: rule.clone() });
+        }
+    }
+    Ok(())
+}
+
+/// Builds the `actor X`-fact injected by [`Extractor::extract_with_actor()`].
+///
+/// The name is only accepted if it would be read back as the same constant when written in Slick;
+/// otherwise, a name like `Amy` or `_` ends up in the policy as a constant that no rule written
+/// by a human can ever refer to.
+///
+/// # Arguments
+/// - `actor`: The name of the actor.
+///
+/// # Returns
+/// A [`Rule`] with the fact.
+///
+/// # Errors
+/// This function errors if `actor` is not a valid Slick constant, or if the resulting rule does
+/// not pass the same checks as any extracted one.
+fn actor_rule(actor: &str) -> Result<Rule, SyntaxError> {
+    if actor.starts_with(|c: char| c.is_uppercase() || c == '_') || !matches!(slick::parse::ground_atom(actor), Ok(("", GroundAtom::Constant(_)))) {
+        return Err(SyntaxError::InvalidActor { actor: actor.into() });
+    }
+    let rule = Rule {
+        consequents: vec![Atom::Tuple(vec![Atom::Constant(text("actor")), Atom::Constant(text(actor))])],
+        rule_body:   RuleBody { pos_antecedents: Vec::new(), neg_antecedents: Vec::new(), checks: Vec::new() },
+    };
+    check_rule(&rule, actor, "actor")?;
+    Ok(rule)
 }
 
 
@@ -121,12 +436,132 @@ pub enum AffectorAtom {
     Variable(Text),
 }
 
+/// Describes a possible mistake found by [`Policy::lint()`].
+///
+/// Rules are referred to by their index in the [`Policy`]'s [`Program`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Lint {
+    /// The rule at `index` is an exact copy of an earlier one (at `original`).
+    DuplicateRule { index: usize, original: usize },
+    /// The rule at `index` has no consequents, and so it never derives anything.
+    EmptyConsequents { index: usize },
+}
+impl Display for Lint {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        match self {
+            Self::DuplicateRule { index, original } => write!(f, "Rule {index} is a duplicate of rule {original}"),
+            Self::EmptyConsequents { index } => write!(f, "Rule {index} has no consequents and can never fire"),
+        }
+    }
+}
+
+/// Describes the size of a [`Program`], as computed by [`Policy::stats()`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PolicyStats {
+    /// The number of rules.
+    pub rules: usize,
+    /// The number of (top-level) atoms in all consequents, antecedents and checks.
+    pub atoms: usize,
+    /// The largest number of elements in any tuple, nested or not.
+    pub max_arity: usize,
+    /// The deepest nesting of tuples in any atom. Constants, variables and wildcards have depth 0,
+    /// and every tuple adds 1 to the depth of its deepest element.
+    pub max_depth: usize,
+}
+impl PolicyStats {
+    /// Computes the statistics of the given program.
+    ///
+    /// Like the [`Extractor`]'s depth check, this is implemented without recursion.
+    ///
+    /// # Arguments
+    /// - `program`: The [`Program`] to examine.
+    ///
+    /// # Returns
+    /// A new PolicyStats describing `program`.
+    pub fn of(program: &Program) -> Self {
+        let mut res = Self { rules: program.rules.len(), ..Default::default() };
+        for rule in &program.rules {
+            let mut todo: Vec<(&Atom, usize)> = rule
+                .consequents
+                .iter()
+                .chain(rule.rule_body.pos_antecedents.iter())
+                .chain(rule.rule_body.neg_antecedents.iter())
+                .chain(rule.rule_body.checks.iter().flat_map(|check| check.atoms.iter()))
+                .map(|atom| (atom, 0))
+                .collect();
+            res.atoms += todo.len();
+            while let Some((atom, depth)) = todo.pop() {
+                if let Atom::Tuple(atoms) = atom {
+                    res.max_arity = res.max_arity.max(atoms.len());
+                    res.max_depth = res.max_depth.max(depth + 1);
+                    todo.extend(atoms.iter().map(|atom| (atom, depth + 1)));
+                }
+            }
+        }
+        res
+    }
+}
+impl Display for PolicyStats {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        write!(f, "{} rules, {} atoms, max arity {}, max depth {}", self.rules, self.atoms, self.max_arity, self.max_depth)
+    }
+}
+
 
 
 
 
 /***** LIBRARY *****/
+/// Classifies what an [`Effect`] does to the dataplane.
+///
+/// See [`Effect::kind()`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum EffectKind {
+    /// Reading a variable.
+    Read,
+    /// Writing a variable.
+    Write,
+    /// Deleting a variable.
+    Delete,
+}
+impl EffectKind {
+    /// Every kind of effect, in the order they are listed in summaries.
+    pub const ALL: [Self; 3] = [Self::Read, Self::Write, Self::Delete];
+
+    /// The verb that identifies this kind of effect in facts.
+    ///
+    /// # Returns
+    /// `reads`, `writes` or `deletes`.
+    #[inline]
+    pub const fn verb(&self) -> &'static str {
+        match self {
+            Self::Read => "reads",
+            Self::Write => "writes",
+            Self::Delete => "deletes",
+        }
+    }
+
+    /// Constructs the fact that a policy should derive to allow `who` to do this to `var`.
+    ///
+    /// # Arguments
+    /// - `who`: The name of the agent doing the access.
+    /// - `var`: The [`VarId`] of the variable in question.
+    ///
+    /// # Returns
+    /// A [`GroundAtom`] encoding `<who> <verb> ((<owner> <dataset>) <name>)`, where `<verb>` is
+    /// this kind's [verb](EffectKind::verb()).
+    #[cfg(feature = "dataplane")]
+    pub fn fact(&self, who: &str, var: &VarId) -> GroundAtom {
+        GroundAtom::Tuple(vec![GroundAtom::Constant(text(who)), GroundAtom::Constant(text(self.verb())), var_atom(var)])
+    }
+}
+
 /// Wraps a Slick (truth, affector) pair as an [`Effect`](justact::Effect).
+///
+/// Effects are ordered by their fact first and their affector second, and are displayed in the
+/// same shape as they are derived by the policy; i.e., as `effect <fact> by <affector>`.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Effect {
     /// The truth wrapped.
@@ -134,6 +569,37 @@ pub struct Effect {
     /// The affector who does this effect.
     pub affector: GroundAtom,
 }
+impl Effect {
+    /// Classifies what this effect does to the dataplane.
+    ///
+    /// This recognizes the facts constructed by [`effect_reads()`], [`effect_writes()`] and
+    /// [`effect_deletes()`]; i.e., facts of the shape `Worker reads|writes|deletes Variable`.
+    ///
+    /// # Returns
+    /// The [`EffectKind`] of this effect, or [`None`] if its fact has any other shape.
+    pub fn kind(&self) -> Option<EffectKind> {
+        let GroundAtom::Tuple(atoms) = &self.fact else { return None };
+        let [_, GroundAtom::Constant(verb), _] = atoms.as_slice() else { return None };
+        EffectKind::ALL.into_iter().find(|kind| *verb == text(kind.verb()))
+    }
+}
+impl Display for Effect {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FResult {
+        write!(f, "effect ")?;
+        fmt_nested_ground_atom(&self.fact, f)?;
+        write!(f, " by ")?;
+        fmt_nested_ground_atom(&self.affector, f)
+    }
+}
+impl Ord for Effect {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering { cmp_ground_atoms(&self.fact, &other.fact).then_with(|| cmp_ground_atoms(&self.affector, &other.affector)) }
+}
+impl PartialOrd for Effect {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
 impl justact::Affectored for Effect {
     type AffectorId = GroundAtom;
 
@@ -153,6 +619,27 @@ impl justact::Effect for Effect {
     fn fact(&self) -> &Self::Fact { &self.fact }
 }
 
+/// Explains why a fact is true in a [`Denotation`].
+///
+/// See [`Denotation::proof_of()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proof {
+    /// The fact that is proven.
+    pub fact:     GroundAtom,
+    /// Every rule that derives the fact, together with what made it fire. Facts that are stated
+    /// directly have a support with a rule without antecedents.
+    pub supports: Vec<Support>,
+}
+
+/// Describes how a single rule derives the fact in a [`Proof`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Support {
+    /// The rule that derives the fact.
+    pub rule: Rule,
+    /// The (true) facts that matched the rule's positive antecedents.
+    pub antecedents: Vec<GroundAtom>,
+}
+
 /// Wraps a Slick denotation as a [`Denotation`](justact::Denotation).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Denotation {
@@ -160,10 +647,13 @@ pub struct Denotation {
     truths:  HashMap<GroundAtom, Option<bool>>,
     /// The set of effects computed from the slick denotation.
     effects: HashMap<GroundAtom, Effect>,
+    /// Whether Slick failed to compute the denotation at all, as opposed to the policy deriving
+    /// errors.
+    inference_failed: bool,
 }
 impl Default for Denotation {
     #[inline]
-    fn default() -> Self { Self { truths: HashMap::new(), effects: HashMap::new() } }
+    fn default() -> Self { Self { truths: HashMap::new(), effects: HashMap::new(), inference_failed: false } }
 }
 impl Denotation {
     /// Creates a new Denotation from a Datalog [`Interpretation`].
@@ -181,6 +671,24 @@ impl Denotation {
     /// A new Denotation that is JustAct^{TM} compliant.
     #[inline]
     pub fn from_interpretation(int: slick::infer::Denotation, pat: PatternAtom, affector: AffectorAtom) -> Self {
+        Self::from_interpretation_with_patterns(int, &[(pat, affector)])
+    }
+
+    /// Creates a new Denotation from a Datalog [`Interpretation`], recognizing effects using
+    /// multiple patterns.
+    ///
+    /// This is like [`Denotation::from_interpretation()`], except that a fact is an effect if it
+    /// matches _any_ of the given patterns. If it matches multiple, the affector is found using
+    /// the first one.
+    ///
+    /// # Arguments
+    /// - `int`: The [`Denotation`](slick::infer::Denotation) to build this Denotation from.
+    /// - `pats`: Pairs of [`PatternAtom`]s that describe a pattern for recognizing effects, and
+    ///   [`AffectorAtom`]s that describe how to extract the affector from effects matching it.
+    ///
+    /// # Returns
+    /// A new Denotation that is JustAct^{TM} compliant.
+    pub fn from_interpretation_with_patterns(int: slick::infer::Denotation, pats: &[(PatternAtom, AffectorAtom)]) -> Self {
         let mut truths: HashMap<GroundAtom, Option<bool>> = HashMap::new();
         let mut effects: HashMap<GroundAtom, Effect> = HashMap::new();
         for (fact, value) in int.trues.into_iter().map(|v| (v, Some(true))).chain(int.unknowns.into_iter().map(|v| (v, None))) {
@@ -240,11 +748,15 @@ impl Denotation {
                     },
                 }
             }
-            if match_effect(&fact, value, &pat) {
+            for (pat, affector) in pats {
+                if !match_effect(&fact, value, pat) {
+                    continue;
+                }
+
                 // See if we have a constant affector or can match
                 match affector {
                     AffectorAtom::Constant(c) => {
-                        effects.insert(fact.clone(), Effect { fact: fact.clone(), affector: GroundAtom::Constant(c) });
+                        effects.insert(fact.clone(), Effect { fact: fact.clone(), affector: GroundAtom::Constant(*c) });
                     },
                     AffectorAtom::Variable(v) => {
                         fn get_var_contents<'f>(fact: &'f GroundAtom, pat: &PatternAtom, affector_var: &Text) -> Option<&'f GroundAtom> {
@@ -271,7 +783,7 @@ impl Denotation {
                                 PatternAtom::Wildcard => Some(fact),
                             }
                         }
-                        match get_var_contents(&fact, &pat, &v) {
+                        match get_var_contents(&fact, pat, v) {
                             Some(affector) => {
                                 effects.insert(fact.clone(), Effect { fact: fact.clone(), affector: affector.clone() });
                             },
@@ -279,6 +791,7 @@ impl Denotation {
                         }
                     },
                 }
+                break;
             }
 
             // Always add the truth as such
@@ -286,16 +799,129 @@ impl Denotation {
         }
 
         // OK, return the denotation!
-        Self { truths, effects }
+        Self { truths, effects, inference_failed: false }
     }
 
+    /// Creates a new Denotation for a policy of which Slick failed to compute the denotation.
+    ///
+    /// It only has the truth `error (inference failure)`, such that anything that only looks at
+    /// the truths still finds something wrong with it. But use [`Denotation::inference_failed()`]
+    /// to tell it apart from a policy that derives that error itself.
+    ///
+    /// # Returns
+    /// A new Denotation that is never [valid](Denotation::is_valid()).
+    pub fn inference_failure() -> Self {
+        let atom = GroundAtom::Tuple(vec![
+            GroundAtom::Constant(Text::from_str("error")),
+            GroundAtom::Tuple(vec![GroundAtom::Constant(Text::from_str("inference")), GroundAtom::Constant(Text::from_str("failure"))]),
+        ]);
+        Self { truths: HashMap::from([(atom, Some(true))]), effects: HashMap::new(), inference_failed: true }
+    }
 
 
+
+    /// Checks if this interpretation contains any facts of which the truth is unknown.
+    ///
+    /// This happens when the policy is not stratified, e.g., for `foo if not foo.`.
+    ///
+    /// # Returns
+    /// True if at least one fact is neither true nor false, or false otherwise.
+    #[inline]
+    pub fn has_unknowns(&self) -> bool { self.truths.values().any(Option::is_none) }
+
+    /// Checks if Slick failed to compute this denotation.
+    ///
+    /// In that case, the truths don't say anything about the policy, and the policy is considered
+    /// invalid. This is different from the policy deriving some error.
+    ///
+    /// # Returns
+    /// True if inference failed, or false if this denotation is the actual one of the policy.
+    #[inline]
+    pub const fn inference_failed(&self) -> bool { self.inference_failed }
+
+    /// Checks if all of the given facts are true in this denotation.
+    ///
+    /// This is useful to assert on the part of a denotation one cares about, instead of comparing
+    /// it as a whole (including, e.g., every reflected `says`-fact).
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use justact::messages::MessageSet;
+    /// use justact::policies::{Extractor as _, Policy as _};
+    /// use justact_prototype::policy::slick::{Denotation, Extractor};
+    /// use justact_prototype::wire::Message;
+    /// use slick::parse;
+    ///
+    /// let msgs: MessageSet<Arc<Message<slick::Program>>> = MessageSet::from_iter([
+    ///     Arc::new(Message::new_raw("amy 1", "amy", parse::program("foo. (bar foo) if foo. baz X if bar X.").unwrap().1)),
+    ///     Arc::new(Message::new_raw("bob 1", "bob", parse::program("qux X if baz X.").unwrap().1)),
+    /// ]);
+    /// let denot: Denotation = Extractor::new().extract(&msgs).unwrap().truths();
+    ///
+    /// // Only the derivation chain matters here
+    /// assert!(denot.contains_all(["baz foo", "qux foo"].map(|fact| parse::ground_atom(fact).unwrap().1)));
+    /// assert!(!denot.contains_all(["baz foo", "qux bar"].map(|fact| parse::ground_atom(fact).unwrap().1)));
+    /// ```
+    ///
+    /// # Arguments
+    /// - `facts`: The facts to look for.
+    ///
+    /// # Returns
+    /// True if every fact in `facts` is true, or false if any of them is false or unknown.
+    #[inline]
+    pub fn contains_all(&self, facts: impl IntoIterator<Item = GroundAtom>) -> bool {
+        facts.into_iter().all(|fact| self.truths.get(&fact) == Some(&Some(true)))
+    }
+
+    /// Asserts that all of the given effects are in this denotation.
+    ///
+    /// Other effects may be present as well, so tests don't break when a policy grows.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use justact::messages::MessageSet;
+    /// use justact::policies::{Extractor as _, Policy as _};
+    /// use justact_prototype::policy::slick::{Denotation, Effect, Extractor};
+    /// use justact_prototype::wire::Message;
+    /// use slick::parse;
+    ///
+    /// let msgs: MessageSet<Arc<Message<slick::Program>>> = MessageSet::from_iter([
+    ///     Arc::new(Message::new_raw("amy 1", "amy", parse::program("foo. (bar foo) if foo. baz X if bar X.").unwrap().1)),
+    ///     Arc::new(Message::new_raw("bob 1", "bob", parse::program("qux X if baz X. effect (qux X) by bob if qux X.").unwrap().1)),
+    /// ]);
+    /// let denot: Denotation = Extractor::new().extract(&msgs).unwrap().truths();
+    ///
+    /// denot.assert_effects([Effect { fact: parse::ground_atom("qux foo").unwrap().1, affector: parse::ground_atom("bob").unwrap().1 }]);
+    /// ```
+    ///
+    /// # Arguments
+    /// - `expected`: The [`Effect`]s that should be in this denotation.
+    ///
+    /// # Panics
+    /// This function panics if any of the `expected` effects is missing, listing all of those.
+    #[track_caller]
+    pub fn assert_effects(&self, expected: impl IntoIterator<Item = Effect>) {
+        let missing: Vec<String> = expected.into_iter().filter(|effect| self.effects.get(&effect.fact) != Some(effect)).map(|effect| effect.to_string()).collect();
+        if !missing.is_empty() {
+            panic!("Denotation is missing expected effects: {}", missing.join(", "));
+        }
+    }
+
     /// Checks if this interpretation contains a fact that would make the policy invalid.
     ///
+    /// A policy for which [inference failed](Denotation::inference_failed()) is never valid.
+    ///
     /// # Returns
     /// True if the parent policy is valid, false otherwise.
     pub fn is_valid(&self) -> bool {
+        if self.inference_failed {
+            return false;
+        }
+
         // Check whether error is true in the truths
         for fact in <Denotation as justact::InfallibleSet<GroundAtom>>::iter(self) {
             match fact {
@@ -309,6 +935,134 @@ impl Denotation {
         }
         true
     }
+
+
+
+    /// Computes the union of this denotation and another.
+    ///
+    /// Facts true in either are true in the result. Facts that are unknown in one and absent or
+    /// unknown in the other remain unknown. If both have the same effect with a different
+    /// affector, then the one in `self` is kept. Inference failed for the result if it failed for
+    /// either.
+    ///
+    /// # Arguments
+    /// - `other`: Some other [`Denotation`] to unify with.
+    ///
+    /// # Returns
+    /// A new [`Denotation`] with everything in `self` and `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut truths: HashMap<GroundAtom, Option<bool>> = self.truths.clone();
+        for (fact, value) in &other.truths {
+            let entry: &mut Option<bool> = truths.entry(fact.clone()).or_insert(*value);
+            if *value == Some(true) {
+                *entry = Some(true);
+            }
+        }
+        let mut effects: HashMap<GroundAtom, Effect> = self.effects.clone();
+        for (fact, effect) in &other.effects {
+            effects.entry(fact.clone()).or_insert_with(|| effect.clone());
+        }
+        Self { truths, effects, inference_failed: self.inference_failed || other.inference_failed }
+    }
+
+    /// Computes the intersection of this denotation and another.
+    ///
+    /// Only facts that occur in both are kept. They are true if they are true in both, and
+    /// unknown otherwise. Effects are only kept if they are identical in both. Inference failed for
+    /// the result only if it failed for both.
+    ///
+    /// # Arguments
+    /// - `other`: Some other [`Denotation`] to intersect with.
+    ///
+    /// # Returns
+    /// A new [`Denotation`] with only what `self` and `other` have in common.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let truths: HashMap<GroundAtom, Option<bool>> = self
+            .truths
+            .iter()
+            .filter_map(|(fact, lhs)| {
+                let rhs: &Option<bool> = other.truths.get(fact)?;
+                Some((fact.clone(), if *lhs == Some(true) && *rhs == Some(true) { Some(true) } else { None }))
+            })
+            .collect();
+        let effects: HashMap<GroundAtom, Effect> =
+            self.effects.iter().filter(|(fact, effect)| other.effects.get(*fact) == Some(*effect)).map(|(fact, effect)| (fact.clone(), effect.clone())).collect();
+        Self { truths, effects, inference_failed: self.inference_failed && other.inference_failed }
+    }
+
+    /// Computes the difference of this denotation with another.
+    ///
+    /// This keeps every fact in `self` that does not occur in `other` with the same truth value,
+    /// and every effect in `self` that does not occur identically in `other`. As such, it answers
+    /// "what does `self` say that `other` doesn't?". Likewise, inference failed for the result if
+    /// it failed for `self` but not for `other`.
+    ///
+    /// # Arguments
+    /// - `other`: Some other [`Denotation`] to subtract from this one.
+    ///
+    /// # Returns
+    /// A new [`Denotation`] with only what is in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let truths: HashMap<GroundAtom, Option<bool>> =
+            self.truths.iter().filter(|(fact, value)| other.truths.get(*fact) != Some(*value)).map(|(fact, value)| (fact.clone(), *value)).collect();
+        let effects: HashMap<GroundAtom, Effect> =
+            self.effects.iter().filter(|(fact, effect)| other.effects.get(*fact) != Some(*effect)).map(|(fact, effect)| (fact.clone(), effect.clone())).collect();
+        Self { truths, effects, inference_failed: self.inference_failed && !other.inference_failed }
+    }
+
+    /// Finds everything that a particular author said.
+    ///
+    /// This relies on the `author says X`-facts reflected by the [`Extractor`], and so will not
+    /// find anything if those were disabled (see [`Extractor::with_says()`]).
+    ///
+    /// # Arguments
+    /// - `author`: The identifier of the author to find the statements of.
+    ///
+    /// # Returns
+    /// An iterator over every `X` for which `author says X` is true.
+    pub fn facts_stated_by<'s>(&'s self, author: &str) -> impl 's + Iterator<Item = &'s GroundAtom> {
+        let author: GroundAtom = GroundAtom::Constant(text(author));
+        let says: GroundAtom = GroundAtom::Constant(text("says"));
+        self.truths.iter().filter(|(_, value)| **value == Some(true)).filter_map(move |(fact, _)| match fact {
+            GroundAtom::Tuple(atoms) if atoms.len() == 3 && atoms[0] == author && atoms[1] == says => Some(&atoms[2]),
+            _ => None,
+        })
+    }
+
+    /// Explains why a fact is true in this denotation.
+    ///
+    /// Because the denotation does not know the rules it was computed from, these have to be
+    /// given. The proof is shallow: it lists which rules derive the fact from which other true
+    /// facts, but not why those are true in turn. Call this function again on the antecedents to
+    /// find out.
+    ///
+    /// # Arguments
+    /// - `rules`: The [`Rule`]s that this denotation was computed from.
+    /// - `fact`: The fact to explain.
+    ///
+    /// # Returns
+    /// A [`Proof`] with every rule deriving `fact`, or [`None`] if `fact` is not true.
+    pub fn proof_of(&self, rules: &[Rule], fact: &GroundAtom) -> Option<Proof> {
+        if self.truths.get(fact) != Some(&Some(true)) {
+            return None;
+        }
+
+        let truths: Vec<&GroundAtom> = self.truths.iter().filter(|(_, value)| **value == Some(true)).map(|(fact, _)| fact).collect();
+        let mut supports: Vec<Support> = Vec::new();
+        for rule in rules {
+            for cons in &rule.consequents {
+                let mut bindings: HashMap<Text, GroundAtom> = HashMap::new();
+                if !unify(cons, fact, &mut bindings) {
+                    continue;
+                }
+                if let Some(antecedents) = satisfy(&rule.rule_body, &rule.rule_body.pos_antecedents, &truths, &bindings, &mut Vec::new()) {
+                    supports.push(Support { rule: rule.clone(), antecedents });
+                    break;
+                }
+            }
+        }
+        Some(Proof { fact: fact.clone(), supports })
+    }
 }
 impl justact::Map<Effect> for Denotation {
     type Error = Infallible;
@@ -357,25 +1111,25 @@ impl justact::Denotation for Denotation {
 /// Wraps a [`Program`] in order to implement [`Policy`](justact::Policy).
 #[derive(Clone, Debug)]
 pub struct Policy {
-    /// The pattern to match effects.
-    pat:      PatternAtom,
-    /// How to find the affector from effects.
-    affector: AffectorAtom,
+    /// The patterns to match effects, together with how to find the affector from effects.
+    pats:    Vec<(PatternAtom, AffectorAtom)>,
     /// The program we wrap with actual policy.
-    program:  Program,
+    program: Program,
 }
 impl Default for Policy {
     #[inline]
     fn default() -> Self {
         Self {
-            pat:      PatternAtom::Tuple(vec![
-                PatternAtom::Constant(Text::from_str("effect")),
-                PatternAtom::Variable(Text::from_str("Effect")),
-                PatternAtom::Constant(Text::from_str("by")),
-                PatternAtom::Variable(Text::from_str("Affector")),
-            ]),
-            affector: AffectorAtom::Variable(Text::from_str("Affector")),
-            program:  Program { rules: Vec::new() },
+            pats:    vec![(
+                PatternAtom::Tuple(vec![
+                    PatternAtom::Constant(Text::from_str("effect")),
+                    PatternAtom::Variable(Text::from_str("Effect")),
+                    PatternAtom::Constant(Text::from_str("by")),
+                    PatternAtom::Variable(Text::from_str("Affector")),
+                ]),
+                AffectorAtom::Variable(Text::from_str("Affector")),
+            )],
+            program: Program { rules: Vec::new() },
         }
     }
 }
@@ -392,10 +1146,17 @@ impl Policy {
     /// - `pat`: The pattern used to match effects.
     /// - `affector`: What affector to provide for effects (given as a special [`AffectorAtom`]).
     #[inline]
-    pub fn update_effect_pattern(&mut self, pat: PatternAtom, affector: AffectorAtom) {
-        self.pat = pat;
-        self.affector = affector;
-    }
+    pub fn update_effect_pattern(&mut self, pat: PatternAtom, affector: AffectorAtom) { self.pats = vec![(pat, affector)]; }
+
+    /// Updates the patterns that match Slick atoms to match effects.
+    ///
+    /// Like [`Policy::update_effect_pattern()`], but then for multiple patterns at once. Atoms
+    /// are effects if they match any of them.
+    ///
+    /// # Arguments
+    /// - `pats`: Pairs of patterns used to match effects and what affector to provide for them.
+    #[inline]
+    pub fn update_effect_patterns(&mut self, pats: Vec<(PatternAtom, AffectorAtom)>) { self.pats = pats; }
 
     /// Returns the program.
     ///
@@ -415,6 +1176,92 @@ impl Policy {
     /// The internal [`Program`].
     #[inline]
     pub fn into_program(self) -> Program { self.program }
+
+
+
+    /// Finds everything that a particular author said in this policy.
+    ///
+    /// This is a shorthand for computing the [truths](justact::Policy::truths()) and then calling
+    /// [`Denotation::facts_stated_by()`] on them.
+    ///
+    /// # Arguments
+    /// - `author`: The identifier of the author to find the statements of.
+    ///
+    /// # Returns
+    /// Every `X` for which `author says X` is true, in no particular order.
+    #[inline]
+    pub fn facts_stated_by(&self, author: &str) -> Vec<GroundAtom> {
+        <Self as justact::Policy>::truths(self).facts_stated_by(author).cloned().collect()
+    }
+
+    /// Removes all rules from this policy that were contributed by a particular author.
+    ///
+    /// This is useful for counterfactual analysis, i.e., to find out what the policy would have
+    /// concluded if `author` hadn't said anything. Rules are recognized by their reflected
+    /// `author says ...`-consequents; so rules extracted without them (see
+    /// [`Extractor::with_says()`]) are never removed.
+    ///
+    /// # Arguments
+    /// - `author`: The identifier of the author of which to remove the rules.
+    ///
+    /// # Returns
+    /// The number of rules that were removed.
+    pub fn remove_rules_from(&mut self, author: &str) -> usize {
+        let (author, says): (Atom, Atom) = (Atom::Constant(text(author)), Atom::Constant(text("says")));
+        let before: usize = self.program.rules.len();
+        self.program
+            .rules
+            .retain(|rule| !rule.consequents.iter().any(|cons| matches!(cons, Atom::Tuple(atoms) if atoms.len() == 3 && atoms[0] == author && atoms[1] == says)));
+        before - self.program.rules.len()
+    }
+
+    /// Explains why a fact is true in this policy.
+    ///
+    /// This is a shorthand for computing the [truths](justact::Policy::truths()) and then calling
+    /// [`Denotation::proof_of()`] on them with the rules of this policy.
+    ///
+    /// # Arguments
+    /// - `fact`: The fact to explain.
+    ///
+    /// # Returns
+    /// A [`Proof`] with every rule deriving `fact`, or [`None`] if `fact` is not true.
+    #[inline]
+    pub fn proof_of(&self, fact: &GroundAtom) -> Option<Proof> { <Self as justact::Policy>::truths(self).proof_of(&self.program.rules, fact) }
+
+
+
+    /// Computes how big this policy is.
+    ///
+    /// This is cheap compared to computing the [truths](justact::Policy::truths()), and so can be
+    /// used to decide whether that's a good idea for large, composed policies.
+    ///
+    /// # Returns
+    /// A [`PolicyStats`] describing the program in this policy.
+    #[inline]
+    pub fn stats(&self) -> PolicyStats { PolicyStats::of(&self.program) }
+
+    /// Looks for common mistakes in the rules of this policy.
+    ///
+    /// In particular, this finds rules that are exact duplicates of earlier ones (typically the
+    /// result of copy/pasting between messages) and rules that can never fire because they have
+    /// no consequents.
+    ///
+    /// # Returns
+    /// A list of [`Lint`]s, ordered by the index of the rule they refer to. If it's empty, nothing
+    /// suspicious was found.
+    pub fn lint(&self) -> Vec<Lint> {
+        let rules: &[Rule] = &self.program.rules;
+        let mut lints: Vec<Lint> = Vec::new();
+        for (i, rule) in rules.iter().enumerate() {
+            if rule.consequents.is_empty() {
+                lints.push(Lint::EmptyConsequents { index: i });
+            }
+            if let Some(original) = rules[..i].iter().position(|other| other == rule) {
+                lints.push(Lint::DuplicateRule { index: i, original });
+            }
+        }
+        lints
+    }
 }
 impl justact::Policy for Policy {
     type Denotation = Denotation;
@@ -425,17 +1272,13 @@ impl justact::Policy for Policy {
 
     #[inline]
     fn truths(&self) -> Self::Denotation {
-        let atom = GroundAtom::Tuple(vec![
-            GroundAtom::Constant(Text::from_str("error")),
-            GroundAtom::Tuple(vec![GroundAtom::Constant(Text::from_str("inference")), GroundAtom::Constant(Text::from_str("failure"))]),
-        ]);
         match self.program.clone().denotation(&Config::default()) {
-            Ok(den) => Denotation::from_interpretation(den, self.pat.clone(), self.affector.clone()),
+            Ok(den) => Denotation::from_interpretation_with_patterns(den, &self.pats),
             #[allow(unused)]
             Err(err) => {
                 #[cfg(feature = "log")]
                 log::error!("Failed to compute denotation: {:?}\n\nProgram:\n{}\n{:?}\n{}\n", err, "-".repeat(80), self.program, "-".repeat(80));
-                Denotation { truths: HashMap::from([(atom, Some(true))]), effects: HashMap::new() }
+                Denotation::inference_failure()
             },
         }
     }
@@ -464,9 +1307,91 @@ impl DerefMut for Policy {
 
 
 /// Represents the [`Extractor`] for Slick's [`Program`].
+///
+/// Besides parsing, the extractor validates the rules in the messages it extracts from. Of note
+/// are the checks in rule bodies: these are evaluated by Slick during inference, and can be used
+/// to compare atoms (e.g., `X same Y`, `X diff Y`) or to order integer constants (e.g., `X < 5`,
+/// which is how numeric conditions on counts are expressed). Checks that Slick cannot evaluate,
+/// such as ordering non-numeric constants or tuples, are rejected with a
+/// [`SyntaxError::UnsupportedCheck`].
+///
+/// Further, to prevent adversarial policies from exhausting the stack during inference, atoms
+/// may only nest tuples up to a [maximum depth](Extractor::with_max_depth()). Deeper atoms are
+/// rejected with a [`SyntaxError::TooDeeplyNested`].
+///
+/// Finally, every consequent is (by default) reflected with an additional `author says ...`
+/// consequent, which roughly doubles the number of rules to derive. This can be turned off with
+/// [`Extractor::with_says(false)`](Extractor::with_says()) to make inference on large policies
+/// cheaper. Note that this changes the meaning of any policy that refers to `says`-facts: for
+/// example, rules that derive `error` when someone says something they shouldn't will no longer
+/// fire, and the audit can no longer tell who said what.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Extractor;
+pub struct Extractor {
+    /// The maximum depth to which tuples may be nested in any atom.
+    max_depth: usize,
+    /// Whether to reflect on who said what by adding `author says ...`-consequents.
+    says:      bool,
+}
+impl Default for Extractor {
+    #[inline]
+    fn default() -> Self { Self::new() }
+}
 impl Extractor {
+    /// The maximum depth of tuples used by [`Extractor::new()`].
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+
+    /// Constructor for the Extractor that uses the [default](Extractor::DEFAULT_MAX_DEPTH)
+    /// maximum nesting depth.
+    ///
+    /// # Returns
+    /// A new Extractor, ready to extract.
+    #[inline]
+    pub const fn new() -> Self { Self { max_depth: Self::DEFAULT_MAX_DEPTH, says: true } }
+
+    /// Constructor for the Extractor that uses a custom maximum nesting depth.
+    ///
+    /// # Arguments
+    /// - `max_depth`: The maximum number of tuples that may be nested in any atom.
+    ///
+    /// # Returns
+    /// A new Extractor, ready to extract.
+    #[inline]
+    pub const fn with_max_depth(max_depth: usize) -> Self { Self { max_depth, says: true } }
+
+    /// Configures whether this Extractor reflects on who said what.
+    ///
+    /// By default, every consequent `X` in a message authored by `author` is accompanied by an
+    /// additional `author says X`-consequent. Disabling this extracts the rules as-is, which is
+    /// useful for policy that already encodes its own provenance.
+    ///
+    /// # Arguments
+    /// - `says`: Whether to generate the additional `says`-consequents.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub const fn with_says(mut self, says: bool) -> Self {
+        self.says = says;
+        self
+    }
+
+    /// Returns the maximum depth of nested tuples accepted by this Extractor.
+    ///
+    /// # Returns
+    /// The maximum depth.
+    #[inline]
+    pub const fn max_depth(&self) -> usize { self.max_depth }
+
+    /// Returns whether this Extractor generates `author says ...`-consequents.
+    ///
+    /// # Returns
+    /// True if it does (the default), or false if it extracts rules as-is.
+    #[inline]
+    pub const fn says(&self) -> bool { self.says }
+
+
+
     /// Extracts some policy with the additional, special `actor`-rule.
     ///
     /// # Arguments
@@ -475,6 +1400,10 @@ impl Extractor {
     ///
     /// # Returns
     /// A new set of [`Extractor::Policy`].
+    ///
+    /// # Errors
+    /// Next to the errors of [extracting](justact::Extractor::extract()) normally, this function
+    /// errors if `actor` is not a valid Slick constant (e.g., `Amy` would be a variable).
     pub fn extract_with_actor<'a, M: justact::Message<AuthorId = str, Payload = Program>>(
         &self,
         actor: &str,
@@ -484,14 +1413,30 @@ impl Extractor {
         let mut pol = <Self as justact::Extractor<str, Program>>::extract(self, msgs)?;
 
         // Inject the actor fact
-        pol.program.rules.push(Rule {
-            consequents: vec![Atom::Tuple(vec![Atom::Constant(Text::from_str("actor")), Atom::Constant(Text::from_str(actor))])],
-            rule_body:   RuleBody { pos_antecedents: Vec::new(), neg_antecedents: Vec::new(), checks: Vec::new() },
-        });
+        pol.program.rules.push(actor_rule(actor)?);
 
         // OK done
         Ok(pol)
     }
+
+    /// Extracts policy without reflecting on who said what.
+    ///
+    /// This is like [extracting](justact::Extractor::extract()) with an Extractor that has
+    /// [`Extractor::with_says(false)`](Extractor::with_says()); i.e., no additional
+    /// `author says ...`-consequents are generated.
+    ///
+    /// # Arguments
+    /// - `msgs`: Something message-set(-like) to extract policy from.
+    ///
+    /// # Returns
+    /// A new set of [`Extractor::Policy`].
+    #[inline]
+    pub fn extract_raw<'a, M: justact::Message<AuthorId = str, Payload = Program>>(
+        &self,
+        msgs: &'a impl justact::Set<M>,
+    ) -> Result<<Self as justact::Extractor<str, Program>>::Policy<'a>, <Self as justact::Extractor<str, Program>>::Error<'a>> {
+        <Self as justact::Extractor<str, Program>>::extract(&self.clone().with_says(false), msgs)
+    }
 }
 impl justact::Extractor<str, Program> for Extractor {
     type Policy<'a> = Policy;
@@ -508,32 +1453,38 @@ impl justact::Extractor<str, Program> for Extractor {
 
         // Parse the policy in the messages one-by-one
         let mut policy = Policy::default();
+        let mut seen: HashMap<(&str, &str), &Program> = HashMap::new();
         for msg in iter {
+            // Catch messages that claim to be another
+            if let Some(prev) = seen.insert((msg.author_id(), msg.human_id()), msg.payload()) {
+                if prev != msg.payload() {
+                    return Err(SyntaxError::DuplicateMessageId { author: msg.author_id().into(), id: msg.human_id().into() });
+                }
+            }
+
             // Parse as UTF-8
             let mut msg_prog: Program = msg.payload().clone();
 
+            // Reject overly deep atoms before we do anything recursive with them
+            if msg_prog.rules.iter().any(|rule| is_too_deeply_nested(rule, self.max_depth)) {
+                return Err(SyntaxError::TooDeeplyNested { author: msg.author_id().into(), id: msg.human_id().into(), max: self.max_depth });
+            }
+
             // Remember to do the supposedly crucial preprocessing steps
             // FROM: <https://github.com/sirkibsirkib/slick/blob/f693f756b1425c5d0fea7a6fb520018cf9c30625/src/bin.rs#L40>
             msg_prog.preprocess();
-            let mut unbound_vars = HashSet::default();
             for rule in &msg_prog.rules {
-                if rule.misplaced_wildcards() {
-                    return Err(SyntaxError::MisplacedWildcard { rule: rule.clone() });
-                }
-                rule.unbound_variables(&mut unbound_vars);
-                if !unbound_vars.is_empty() {
-                    return Err(SyntaxError::UnboundVariables { vars: unbound_vars.into_iter().copied().collect(), rule: rule.clone() });
-                }
+                check_rule(rule, msg.author_id(), msg.human_id())?;
             }
 
             // Generate additional `says`-heads
-            for rule in &mut msg_prog.rules {
+            for rule in msg_prog.rules.iter_mut().filter(|_| self.says) {
                 let mut additional_cons = Vec::with_capacity(rule.consequents.len());
                 for cons in &rule.consequents {
                     let author: &str = msg.author_id();
                     additional_cons.push(Atom::Tuple(vec![
-                        Atom::Constant(Text::from_str(author)),
-                        Atom::Constant(Text::from_str("says")),
+                        Atom::Constant(text(author)),
+                        Atom::Constant(text("says")),
                         cons.clone(),
                     ]));
                 }
@@ -551,12 +1502,29 @@ impl justact::Extractor<str, Program> for Extractor {
 
 
 
+/// Implements a [`PolicyEngine`] for Slick.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Engine;
+impl PolicyEngine for Engine {
+    type Denotation<'s> = Denotation;
+    type Error<'s> = nom::Err<nom::error::VerboseError<&'s str>>;
+
+    #[inline]
+    fn denote<'s>(&self, program: &'s str) -> Result<Self::Denotation<'s>, Self::Error<'s>> {
+        let mut pol = Policy::default();
+        pol.program = slick::parse::program(program)?.1;
+        Ok(<Policy as justact::Policy>::truths(&pol))
+    }
+}
+
+
+
 impl PolicyReflect for Program {
     #[inline]
     fn reflect_actor(name: &str) -> Self {
         Self {
             rules: vec![Rule {
-                consequents: vec![Atom::Tuple(vec![Atom::Constant(Text::from_str("actor")), Atom::Constant(Text::from_str(name))])],
+                consequents: vec![Atom::Tuple(vec![Atom::Constant(text("actor")), Atom::Constant(text(name))])],
                 rule_body:   RuleBody { pos_antecedents: Vec::new(), neg_antecedents: Vec::new(), checks: Vec::new() },
             }],
         }
@@ -584,12 +1552,153 @@ impl<'a> PolicyDeserialize<'a> for Program {
 
 
 
+/***** LIBRARY FUNCTIONS *****/
+/// Parses a raw payload of a message as Slick.
+///
+/// Unlike [`PolicyDeserialize::deserialize()`], this accepts bytes, and explicitly checks they
+/// are valid UTF-8 instead of (lossily) converting them. Further, it requires the whole payload
+/// to be Slick, instead of ignoring anything that follows the last rule that parsed.
+///
+/// # Arguments
+/// - `author`: The author of the message, for error reporting.
+/// - `id`: The identifier of the message, for error reporting.
+/// - `raw`: The raw payload to parse.
+///
+/// # Returns
+/// The parsed [`Program`].
+///
+/// # Errors
+/// This function errors with a [`SyntaxError::InvalidUtf8`] if `raw` was not valid UTF-8, or
+/// with a [`SyntaxError::Slick`] if it was not valid Slick.
+pub fn parse_payload(author: &str, id: &str, raw: &[u8]) -> Result<Program, SyntaxError> {
+    let raw: &str =
+        std::str::from_utf8(raw).map_err(|err| SyntaxError::InvalidUtf8 { author: author.into(), id: id.into(), offset: err.valid_up_to() })?;
+    let err: nom::Err<nom::error::VerboseError<String>> = match slick::parse::program(raw) {
+        // NOTE: Only whitespace and comments may follow the last rule
+        Ok((rest, program)) if rest.lines().map(str::trim).all(|line| line.is_empty() || line.starts_with("//")) => return Ok(program),
+        Ok((rest, _)) => nom::Err::Error(nom::error::VerboseError {
+            errors: vec![(rest.to_string(), nom::error::VerboseErrorKind::Nom(nom::error::ErrorKind::Eof))],
+        }),
+        Err(err) => err.map(|err| nom::error::VerboseError { errors: err.errors.into_iter().map(|(src, err)| (src.to_string(), err)).collect() }),
+    };
+    Err(SyntaxError::Slick { author: author.into(), id: id.into(), err })
+}
+
+/// Pre-interns a batch of names, e.g., the agent names and common constants of a scenario.
+///
+/// Afterwards, [`text()`] returns the cached [`Text`] for these names instead of going through
+/// [`Text::from_str()`] again. This is used internally for every author, actor and effect name.
+///
+/// Note that the cache is kept per thread, so this only affects calls to [`text()`] on the
+/// calling thread. This is the thread that runs the [`System`](crate::runtime::System).
+///
+/// # Arguments
+/// - `names`: The names to intern. Names that are already interned are skipped.
+pub fn intern_all(names: &[&str]) {
+    INTERNED.with_borrow_mut(|interned| {
+        for name in names {
+            if !interned.contains_key(*name) {
+                interned.insert(name.to_string(), Text::from_str(name));
+            }
+        }
+    })
+}
+
+/// Returns the [`Text`] for a name, taking it from the cache if it was [pre-interned](intern_all()).
+///
+/// # Arguments
+/// - `name`: The name to get the [`Text`] of.
+///
+/// # Returns
+/// The same [`Text`] as [`Text::from_str()`] would return.
+#[inline]
+pub fn text(name: &str) -> Text { INTERNED.with_borrow(|interned| interned.get(name).cloned()).unwrap_or_else(|| Text::from_str(name)) }
+
+/// Returns how many names have been [pre-interned](intern_all()) on this thread.
+///
+/// Note that this only counts the names cached for [`text()`], not every name known to Slick's
+/// interner.
+///
+/// # Returns
+/// The number of names in the cache used by [`text()`] on the calling thread.
+#[inline]
+pub fn cache_size() -> usize { INTERNED.with_borrow(HashMap::len) }
+
+
+
+/// Constructs the [`GroundAtom`] encoding a variable.
+///
+/// # Arguments
+/// - `var`: The [`VarId`] of the variable in question.
+///
+/// # Returns
+/// A [`GroundAtom`] encoding `((<owner> <dataset>) <name>)`.
+#[cfg(feature = "dataplane")]
+pub fn var_atom(var: &VarId) -> GroundAtom {
+    GroundAtom::Tuple(vec![
+        GroundAtom::Tuple(vec![GroundAtom::Constant(text(&var.owner)), GroundAtom::Constant(text(&var.dataset))]),
+        GroundAtom::Constant(text(&var.name)),
+    ])
+}
+
+/// Constructs the [`GroundAtom`] representing the effect of reading a variable.
+///
+/// This is the fact that a policy should derive to allow `who` to read `var`.
+///
+/// # Arguments
+/// - `who`: The name of the agent doing the reading.
+/// - `var`: The [`VarId`] of the variable in question.
+///
+/// # Returns
+/// A [`GroundAtom`] encoding `<who> reads ((<owner> <dataset>) <name>)`.
+#[cfg(feature = "dataplane")]
+#[inline]
+pub fn effect_reads(who: &str, var: &VarId) -> GroundAtom {
+    EffectKind::Read.fact(who, var)
+}
+
+/// Constructs the [`GroundAtom`] representing the effect of writing a variable.
+///
+/// This is the fact that a policy should derive to allow `who` to write `var`.
+///
+/// # Arguments
+/// - `who`: The name of the agent doing the writing.
+/// - `var`: The [`VarId`] of the variable in question.
+///
+/// # Returns
+/// A [`GroundAtom`] encoding `<who> writes ((<owner> <dataset>) <name>)`.
+#[cfg(feature = "dataplane")]
+#[inline]
+pub fn effect_writes(who: &str, var: &VarId) -> GroundAtom {
+    EffectKind::Write.fact(who, var)
+}
+
+/// Constructs the [`GroundAtom`] representing the effect of deleting a variable.
+///
+/// This is the fact that a policy should derive to allow `who` to delete `var`.
+///
+/// # Arguments
+/// - `who`: The name of the agent doing the deleting.
+/// - `var`: The [`VarId`] of the variable in question.
+///
+/// # Returns
+/// A [`GroundAtom`] encoding `<who> deletes ((<owner> <dataset>) <name>)`.
+#[cfg(feature = "dataplane")]
+#[inline]
+pub fn effect_deletes(who: &str, var: &VarId) -> GroundAtom {
+    EffectKind::Delete.fact(who, var)
+}
+
+
+
+
+
 /***** TESTS *****/
 #[cfg(test)]
 mod tests {
     use humanlog::{DebugMode, HumanLogger};
     use slick::infer::Config;
-    use slick::{Rule, RuleBody, parse};
+    use slick::{Check, CheckKind, Rule, RuleBody, parse};
 
     use super::*;
     mod justact {
@@ -616,6 +1725,21 @@ mod tests {
     #[track_caller]
     fn make_flat_ground_atom_str(s: &str) -> GroundAtom { parse::ground_atom(s).unwrap().1 }
 
+    /// Generates a program with a single rule `enough if count N` that has the given check in it.
+    #[inline]
+    fn make_check_program(kind: CheckKind, lhs: Atom, rhs: Atom) -> Program {
+        Program {
+            rules: vec![Rule {
+                consequents: vec![Atom::Constant(Text::from_str("enough"))],
+                rule_body:   RuleBody {
+                    pos_antecedents: vec![Atom::Tuple(vec![Atom::Constant(Text::from_str("count")), Atom::Variable(Text::from_str("N"))])],
+                    neg_antecedents: vec![],
+                    checks: vec![Check { kind, positive: true, atoms: [lhs, rhs] }],
+                },
+            }],
+        }
+    }
+
     /// Implements a test message
     #[derive(Eq, Hash, PartialEq)]
     struct Message {
@@ -630,6 +1754,10 @@ mod tests {
     impl justact::Message for Message {
         type Payload = Program;
 
+        /// NOTE: Every author states at most one message in these tests, so we can use it as ID.
+        #[inline]
+        fn human_id(&self) -> &str { &self.author_id }
+
         #[inline]
         fn payload(&self) -> &Self::Payload { &self.payload }
     }
@@ -652,7 +1780,7 @@ mod tests {
     #[test]
     fn test_extract_policy_single() {
         let msg = Message { author_id: "amy".into(), payload: parse::program("foo. bar if baz A.").unwrap().1 };
-        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor, &msg).unwrap();
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg).unwrap();
         assert_eq!(pol.program, Program {
             rules: vec![
                 Rule {
@@ -692,7 +1820,7 @@ mod tests {
         let msgs = justact::MessageSet::from_iter([msg1, msg2]);
 
         // Extract the policy from it
-        let mut pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor, &msgs).unwrap();
+        let mut pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msgs).unwrap();
         // NOTE: MessageSet collects messages unordered, so the rules may be in any order
         // For consistency, we ensure they aren't.
         pol.rules.sort_by(|lhs, rhs| format!("{lhs:?}").cmp(&format!("{rhs:?}")));
@@ -729,6 +1857,209 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_extract_policy_raw() {
+        let msg = Message { author_id: "amy".into(), payload: parse::program("foo. bar if baz A. (amy says qux) if foo.").unwrap().1 };
+        let pol = Extractor::new().extract_raw(&msg).unwrap();
+        assert_eq!(pol.program, parse::program("foo. bar if baz A. (amy says qux) if foo.").unwrap().1);
+
+        // It's the same as configuring the extractor
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new().with_says(false), &msg).unwrap();
+        assert_eq!(pol.program, parse::program("foo. bar if baz A. (amy says qux) if foo.").unwrap().1);
+    }
+
+    #[test]
+    fn test_extract_policy_with_actor() {
+        let msg = Message { author_id: "amy".into(), payload: parse::program("foo if actor amy.").unwrap().1 };
+        let pol = Extractor::new().extract_with_actor("amy", &msg).unwrap();
+        assert_eq!(<Policy as justact::Policy>::truths(&pol).truths.get(&make_flat_ground_atom_str("foo")), Some(&Some(true)));
+
+        // Names that aren't constants in Slick are caught instead of injected
+        for actor in ["Amy", "_", "amy bob", "(amy)", ""] {
+            assert!(
+                matches!(Extractor::new().extract_with_actor(actor, &msg), Err(SyntaxError::InvalidActor { actor: ref a }) if a == actor),
+                "Actor {actor:?} was not rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_policy_no_provenance_validity() {
+        // A long chain that doesn't refer to `says`-facts should be equally valid with or without reflection
+        let mut chain: String = "fact0.\n".into();
+        for i in 1..1000 {
+            chain.push_str(&format!("fact{i} if fact{}.\n", i - 1));
+        }
+        for (payload, valid) in [(chain.clone(), true), (format!("{chain}error if fact999.\n"), false)] {
+            let msg = Message { author_id: "amy".into(), payload: parse::program(&payload).unwrap().1 };
+            let with = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new().with_says(true), &msg).unwrap();
+            let without = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new().with_says(false), &msg).unwrap();
+            assert_eq!(<Policy as justact::Policy>::is_valid(&with), valid);
+            assert_eq!(<Policy as justact::Policy>::is_valid(&without), valid);
+        }
+    }
+
+    #[test]
+    fn test_extract_policy_says_validity() {
+        // Policies that don't reflect on `says` are equally valid with or without it, whether they're stated by one agent or more
+        for (amy, bob, valid) in [
+            ("foo. bar if foo.", "baz if bar.", true),
+            ("error if foo. foo.", "bar.", false),
+            ("foo if not bar.", "error if foo.", false),
+            ("foo if not bar.", "bar. error if foo.", true),
+            ("error (X too large) if count X and diff { X zero }. count zero.", "qux.", true),
+            ("error (X too large) if count X and diff { X zero }. count one.", "qux.", false),
+        ] {
+            let msg1 = Message { author_id: "amy".into(), payload: parse::program(amy).unwrap().1 };
+            let msg2 = Message { author_id: "bob".into(), payload: parse::program(bob).unwrap().1 };
+            let msgs = justact::MessageSet::from_iter([msg1, msg2]);
+            let with = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new().with_says(true), &msgs).unwrap();
+            let without = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new().with_says(false), &msgs).unwrap();
+            assert_eq!(<Policy as justact::Policy>::is_valid(&with), valid, "Wrong validity with says for {amy:?} + {bob:?}");
+            assert_eq!(<Policy as justact::Policy>::is_valid(&without), valid, "Wrong validity without says for {amy:?} + {bob:?}");
+        }
+    }
+
+    #[test]
+    fn test_extract_policy_duplicate_ids() {
+        let msg1 = Message { author_id: "amy".into(), payload: parse::program("foo.").unwrap().1 };
+        let msg2 = Message { author_id: "amy".into(), payload: parse::program("bar.").unwrap().1 };
+        assert!(matches!(
+            <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg1, msg2])),
+            Err(SyntaxError::DuplicateMessageId { author, id }) if author == "amy" && id == "amy"
+        ));
+    }
+
+    #[test]
+    fn test_extract_policy_checks() {
+        // `N > 5`, expressed as `5 < N`
+        let msg = Message {
+            author_id: "amy".into(),
+            payload:   make_check_program(CheckKind::Lt, Atom::Constant(Text::from_str("5")), Atom::Variable(Text::from_str("N"))),
+        };
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg).unwrap();
+        assert_eq!(pol.program.rules[0].rule_body.checks.len(), 1);
+
+        // Equality works on anything
+        let msg = Message {
+            author_id: "amy".into(),
+            payload:   make_check_program(
+                CheckKind::Eq,
+                Atom::Variable(Text::from_str("N")),
+                Atom::Tuple(vec![Atom::Constant(Text::from_str("a")), Atom::Constant(Text::from_str("b"))]),
+            ),
+        };
+        assert!(<Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg).is_ok());
+    }
+    #[test]
+    fn test_extract_policy_unsupported_checks() {
+        // Ordering non-numeric constants
+        let msg = Message {
+            author_id: "amy".into(),
+            payload:   make_check_program(CheckKind::Lt, Atom::Constant(Text::from_str("five")), Atom::Variable(Text::from_str("N"))),
+        };
+        assert!(matches!(
+            <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg),
+            Err(SyntaxError::UnsupportedCheck { .. })
+        ));
+
+        // Ordering tuples
+        let msg = Message {
+            author_id: "amy".into(),
+            payload:   make_check_program(
+                CheckKind::Lt,
+                Atom::Variable(Text::from_str("N")),
+                Atom::Tuple(vec![Atom::Constant(Text::from_str("1")), Atom::Constant(Text::from_str("2"))]),
+            ),
+        };
+        assert!(matches!(
+            <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg),
+            Err(SyntaxError::UnsupportedCheck { .. })
+        ));
+    }
+
+    #[test]
+    fn test_extract_policy_too_deeply_nested() {
+        /// Generates an atom `((((foo))))` with `depth` tuples around it.
+        fn make_nested_atom(depth: usize) -> Atom {
+            let mut atom = Atom::Constant(Text::from_str("foo"));
+            for _ in 0..depth {
+                atom = Atom::Tuple(vec![atom]);
+            }
+            atom
+        }
+
+        // Something within bounds is fine
+        let msg = Message {
+            author_id: "amy".into(),
+            payload:   Program {
+                rules: vec![Rule {
+                    consequents: vec![make_nested_atom(4)],
+                    rule_body:   RuleBody { pos_antecedents: vec![], neg_antecedents: vec![], checks: vec![] },
+                }],
+            },
+        };
+        assert!(<Extractor as justact::Extractor<str, Program>>::extract(&Extractor::with_max_depth(4), &msg).is_ok());
+        assert!(matches!(
+            <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::with_max_depth(3), &msg),
+            Err(SyntaxError::TooDeeplyNested { max: 3, .. })
+        ));
+
+        // Something absurd is rejected gracefully, wherever it occurs in the rule
+        let msg = Message {
+            author_id: "amy".into(),
+            payload:   Program {
+                rules: vec![Rule {
+                    consequents: vec![Atom::Constant(Text::from_str("foo"))],
+                    rule_body:   RuleBody { pos_antecedents: vec![], neg_antecedents: vec![make_nested_atom(1000)], checks: vec![] },
+                }],
+            },
+        };
+        assert!(matches!(
+            <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg),
+            Err(SyntaxError::TooDeeplyNested { author, max: Extractor::DEFAULT_MAX_DEPTH, .. }) if author == "amy"
+        ));
+    }
+
+    #[test]
+    fn test_extract_policy_error_context() {
+        let msg1 = Message { author_id: "amy".into(), payload: parse::program("foo.").unwrap().1 };
+        let msg2 = Message { author_id: "bob".into(), payload: parse::program("bar X.").unwrap().1 };
+        let err = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg1, msg2])).unwrap_err();
+        assert!(matches!(&err, SyntaxError::UnboundVariables { author, id, .. } if author == "bob" && id == "bob"));
+        assert!(err.to_string().contains("in message \"bob\" by \"bob\""));
+    }
+
+    #[test]
+    fn test_lint() {
+        let mut pol = Policy::default();
+        assert_eq!(pol.lint(), vec![]);
+
+        // Duplicates
+        pol.program = parse::program("foo. bar if foo. baz. bar if foo. foo.").unwrap().1;
+        assert_eq!(pol.lint(), vec![Lint::DuplicateRule { index: 3, original: 1 }, Lint::DuplicateRule { index: 4, original: 0 }]);
+
+        // Empty rules
+        pol.program.rules.push(Rule {
+            consequents: vec![],
+            rule_body:   RuleBody { pos_antecedents: vec![Atom::Constant(Text::from_str("foo"))], neg_antecedents: vec![], checks: vec![] },
+        });
+        assert_eq!(pol.lint(), vec![
+            Lint::DuplicateRule { index: 3, original: 1 },
+            Lint::DuplicateRule { index: 4, original: 0 },
+            Lint::EmptyConsequents { index: 5 },
+        ]);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut pol = Policy::default();
+        assert_eq!(pol.stats(), PolicyStats::default());
+
+        pol.program = parse::program("foo. bar if baz A. a (b c) if qux d e f.").unwrap().1;
+        assert_eq!(pol.stats(), PolicyStats { rules: 3, atoms: 5, max_arity: 4, max_depth: 2 });
+    }
+
     #[test]
     fn test_is_valid() {
         let mut pol = Policy::default();
@@ -750,9 +2081,40 @@ mod tests {
         assert_eq!(den, Denotation {
             truths:  [GroundAtom::Constant(Text::from_str("foo"))].into_iter().map(|a| (a, Some(true))).collect(),
             effects: HashMap::new(),
+            inference_failed: false,
         })
     }
     #[test]
+    fn test_truths_checks() {
+        // `enough if count N, 5 < N` only derives `enough` if the count is large enough
+        let enough = GroundAtom::Constant(Text::from_str("enough"));
+        for (count, derived) in [("3", false), ("5", false), ("7", true)] {
+            let mut payload: Program = make_check_program(CheckKind::Lt, Atom::Constant(Text::from_str("5")), Atom::Variable(Text::from_str("N")));
+            payload.rules.extend(parse::program(&format!("count {count}.")).unwrap().1.rules);
+            let msg = Message { author_id: "amy".into(), payload };
+            let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg).unwrap();
+            let den = <Policy as justact::Policy>::truths(&pol);
+            assert_eq!(den.contains_all([enough.clone()]), derived, "count {count}");
+        }
+    }
+    #[test]
+    fn test_truths_inference_failure() {
+        // Slick gives up on programs that derive ever deeper atoms
+        let mut pol = Policy::default();
+        pol.program = parse::program("nat zero. nat (succ N) if nat N.").unwrap().1;
+        let den = <Policy as justact::Policy>::truths(&pol);
+        assert!(den.inference_failed());
+        assert!(!den.is_valid());
+        assert_eq!(den, Denotation::inference_failure());
+
+        // Whereas errors derived by the policy are just that
+        pol.program = parse::program("error (bad thing). error if error _.").unwrap().1;
+        let den = <Policy as justact::Policy>::truths(&pol);
+        assert!(!den.inference_failed());
+        assert!(!den.is_valid());
+        assert!(!Denotation::default().inference_failed());
+    }
+    #[test]
     fn test_effects() {
         let mut pol = Policy::default();
         pol.program = parse::program("effect read by amy. effect write by amy if baz A.").unwrap().1;
@@ -763,8 +2125,41 @@ mod tests {
                 .into_iter()
                 .map(|a| (a.clone(), Effect { fact: a, affector: GroundAtom::Constant(Text::from_str("amy")) }))
                 .collect(),
+            inference_failed: false,
         })
     }
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_effect_reads_writes() {
+        let x = VarId::new("amy", "data", "x");
+        let y = VarId::new("amy", "data", "y");
+        assert_eq!(effect_reads("bob", &x), make_flat_ground_atom_str("bob reads ((amy data) x)"));
+        assert_eq!(effect_writes("bob", &y), make_flat_ground_atom_str("bob writes ((amy data) y)"));
+
+        // They should also be what a policy emits as effects
+        let mut pol = Policy::default();
+        pol.program = parse::program("bob reads ((amy data) x). bob writes ((amy data) y) if bob reads ((amy data) x).").unwrap().1;
+        pol.update_effect_patterns(vec![(
+            PatternAtom::Tuple(vec![
+                PatternAtom::Variable(Text::from_str("Worker")),
+                PatternAtom::ConstantSet(vec![Text::from_str("reads"), Text::from_str("writes")]),
+                PatternAtom::Variable(Text::from_str("Variable")),
+            ]),
+            AffectorAtom::Variable(Text::from_str("Worker")),
+        )]);
+        let den = <Policy as justact::Policy>::truths(&pol);
+        let effects: HashSet<&GroundAtom> = den.effects.keys().collect();
+        assert_eq!(effects, HashSet::from([&effect_reads("bob", &x), &effect_writes("bob", &y)]));
+        assert!(den.effects.values().all(|e| e.affector == make_flat_ground_atom_str("bob")));
+
+        // And they can be told apart again
+        let effect = |fact: GroundAtom| -> Effect { Effect { fact, affector: make_flat_ground_atom_str("bob") } };
+        assert_eq!(effect(effect_reads("bob", &x)).kind(), Some(EffectKind::Read));
+        assert_eq!(effect(effect_writes("bob", &y)).kind(), Some(EffectKind::Write));
+        assert_eq!(effect(effect_deletes("bob", &y)).kind(), Some(EffectKind::Delete));
+        assert_eq!(effect(make_flat_ground_atom_str("bob eats ((amy data) x)")).kind(), None);
+        assert_eq!(effect(make_flat_ground_atom_str("reads")).kind(), None);
+    }
 
     /// Tests whether the extraction of effects works as expected when there's nothing to extract.
     #[test]
@@ -865,13 +2260,80 @@ mod tests {
         );
     }
 
+    /// Tests whether the extraction of effects works as expected with multiple patterns.
+    #[test]
+    fn test_denotation_effects_multiple_patterns() {
+        let pats = [
+            (make_pattern(), AffectorAtom::Variable(Text::from_str("Affector"))),
+            (
+                PatternAtom::Tuple(vec![PatternAtom::Variable(Text::from_str("Worker")), PatternAtom::Constant(Text::from_str("deletes")), PatternAtom::Wildcard]),
+                AffectorAtom::Variable(Text::from_str("Worker")),
+            ),
+        ];
+        let program = parse::program("effect read by amy. bob deletes foo. bob reads foo.").unwrap();
+        let int = program.1.denotation(&Config::default()).unwrap();
+        let den = Denotation::from_interpretation_with_patterns(int, &pats);
+        assert_eq!(
+            den.effects,
+            HashMap::from([
+                (make_flat_ground_atom_str("effect read by amy"), Effect {
+                    fact:     make_flat_ground_atom_str("effect read by amy"),
+                    affector: make_flat_ground_atom_str("amy"),
+                }),
+                (make_flat_ground_atom_str("bob deletes foo"), Effect {
+                    fact:     make_flat_ground_atom_str("bob deletes foo"),
+                    affector: make_flat_ground_atom_str("bob"),
+                })
+            ])
+        );
+    }
+
+    /// Tests the set operations on denotations.
+    #[test]
+    fn test_denotation_set_ops() {
+        let make_den = |raw: &str| -> Denotation {
+            let int = parse::program(raw).unwrap().1.denotation(&Config::default()).unwrap();
+            Denotation::from_interpretation(int, make_pattern(), AffectorAtom::Variable(Text::from_str("Affector")))
+        };
+        let a = make_den("foo. bar. effect read by amy.");
+        let b = make_den("bar. baz. effect read by amy. effect write by bob.");
+
+        // Subtracting something from itself leaves nothing
+        let diff = a.difference(&a);
+        assert!(diff.truths.is_empty());
+        assert!(diff.effects.is_empty());
+
+        // Intersection is commutative
+        let int = a.intersect(&b);
+        assert_eq!(int, b.intersect(&a));
+        assert_eq!(
+            int.truths,
+            HashMap::from([(make_flat_ground_atom_str("bar"), Some(true)), (make_flat_ground_atom_str("effect read by amy"), Some(true))])
+        );
+        assert_eq!(int.effects.keys().collect::<Vec<_>>(), vec![&make_flat_ground_atom_str("effect read by amy")]);
+
+        // Difference only keeps what's unique to the left
+        let diff = b.difference(&a);
+        assert_eq!(
+            diff.truths,
+            HashMap::from([(make_flat_ground_atom_str("baz"), Some(true)), (make_flat_ground_atom_str("effect write by bob"), Some(true))])
+        );
+        assert_eq!(diff.effects.keys().collect::<Vec<_>>(), vec![&make_flat_ground_atom_str("effect write by bob")]);
+
+        // Union has everything
+        let uni = a.union(&b);
+        assert_eq!(uni, b.union(&a));
+        assert_eq!(uni.truths.len(), 5);
+        assert_eq!(uni.effects.len(), 2);
+    }
+
     /// Tests if the author rules work as expected.
     #[test]
     fn test_reflection() {
         // First, see if the derivation works.
         let msg1 = Message { author_id: "amy".into(), payload: parse::program("foo. (bar foo) if foo. baz X if bar X.").unwrap().1 };
         let msg2 = Message { author_id: "bob".into(), payload: parse::program("qux X if baz X.").unwrap().1 };
-        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor, &justact::MessageSet::from_iter([msg1, msg2])).unwrap();
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg1, msg2])).unwrap();
         let den = <Policy as justact::Policy>::truths(&pol);
         assert_eq!(den, Denotation {
             truths:  [
@@ -888,6 +2350,135 @@ mod tests {
             .map(|a| (a.clone(), Some(true)))
             .collect(),
             effects: HashMap::new(),
+            inference_failed: false,
         });
+        assert!(den.contains_all([make_flat_ground_atom_str("qux foo"), make_flat_ground_atom_str("bob says (qux foo)")]));
+        assert!(!den.contains_all([make_flat_ground_atom_str("qux foo"), make_flat_ground_atom_str("amy says (qux foo)")]));
+        den.assert_effects([]);
+    }
+    #[test]
+    #[should_panic(expected = "effect foo by amy")]
+    fn test_assert_effects_missing() {
+        let msg = Message { author_id: "amy".into(), payload: parse::program("foo. effect bar by amy.").unwrap().1 };
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg])).unwrap();
+        let den = <Policy as justact::Policy>::truths(&pol);
+        den.assert_effects([Effect { fact: make_flat_ground_atom_str("bar"), affector: make_flat_ground_atom_str("amy") }]);
+        den.assert_effects([Effect { fact: make_flat_ground_atom_str("foo"), affector: make_flat_ground_atom_str("amy") }]);
+    }
+    #[test]
+    fn test_proof_of() {
+        let msg1 = Message { author_id: "amy".into(), payload: parse::program("foo. (bar foo) if foo. baz X if bar X.").unwrap().1 };
+        let msg2 = Message { author_id: "bob".into(), payload: parse::program("qux X if baz X.").unwrap().1 };
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg1, msg2])).unwrap();
+
+        // `baz foo` is derived by `baz X if bar X` from `bar foo`
+        let rule: Rule = parse::program("baz X if bar X.").unwrap().1.rules.remove(0);
+        let proof = pol.proof_of(&make_flat_ground_atom_str("baz foo")).unwrap();
+        assert_eq!(proof.fact, make_flat_ground_atom_str("baz foo"));
+        assert_eq!(proof.supports.len(), 1);
+        assert!(proof.supports[0].rule.consequents.contains(&rule.consequents[0]));
+        assert_eq!(proof.supports[0].rule.rule_body.pos_antecedents, rule.rule_body.pos_antecedents);
+        assert_eq!(proof.supports[0].antecedents, vec![make_flat_ground_atom_str("bar foo")]);
+
+        // Stated facts are supported by themselves
+        let proof = pol.proof_of(&make_flat_ground_atom_str("foo")).unwrap();
+        assert_eq!(proof.supports.len(), 1);
+        assert!(proof.supports[0].antecedents.is_empty());
+
+        // False facts have no proof
+        assert_eq!(pol.proof_of(&make_flat_ground_atom_str("qux bar")), None);
+    }
+    #[test]
+    fn test_facts_stated_by() {
+        let msg1 = Message { author_id: "amy".into(), payload: parse::program("foo. (bar foo) if foo. baz X if bar X.").unwrap().1 };
+        let msg2 = Message { author_id: "bob".into(), payload: parse::program("qux X if baz X.").unwrap().1 };
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg1, msg2])).unwrap();
+        let facts: HashSet<GroundAtom> = pol.facts_stated_by("amy").into_iter().collect();
+        assert_eq!(facts, HashSet::from([make_flat_ground_atom_str("foo"), make_flat_ground_atom_str("bar foo"), make_flat_ground_atom_str("baz foo")]));
+        assert_eq!(pol.facts_stated_by("bob"), vec![make_flat_ground_atom_str("qux foo")]);
+        assert!(pol.facts_stated_by("cho").is_empty());
+    }
+    #[test]
+    fn test_remove_rules_from() {
+        let msg1 = Message { author_id: "amy".into(), payload: parse::program("foo. (bar foo) if foo.").unwrap().1 };
+        let msg2 = Message { author_id: "bob".into(), payload: parse::program("qux. quux if foo.").unwrap().1 };
+        let mut pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &justact::MessageSet::from_iter([msg1, msg2])).unwrap();
+        assert_eq!(pol.remove_rules_from("amy"), 2);
+        assert_eq!(pol.remove_rules_from("cho"), 0);
+
+        // Only Bob's facts remain, and those that relied on Amy's are no longer derived
+        let den = <Policy as justact::Policy>::truths(&pol);
+        assert_eq!(den, Denotation {
+            truths:  [make_flat_ground_atom_str("qux"), make_flat_ground_atom_str("bob says qux")].into_iter().map(|a| (a, Some(true))).collect(),
+            effects: HashMap::new(),
+            inference_failed: false,
+        });
+        assert!(pol.facts_stated_by("amy").is_empty());
+    }
+    #[test]
+    fn test_intern_all() {
+        intern_all(&["amy", "says", "amy"]);
+        assert_eq!(cache_size(), 2);
+        assert_eq!(text("amy"), Text::from_str("amy"));
+
+        // Names are interned per thread
+        assert_eq!(std::thread::spawn(cache_size).join().unwrap(), 0);
+        assert_eq!(text("bob"), Text::from_str("bob"));
+
+        // Pre-interning doesn't change what is extracted
+        let msg = Message { author_id: "amy".into(), payload: parse::program("foo.").unwrap().1 };
+        let pol = <Extractor as justact::Extractor<str, Program>>::extract(&Extractor::new(), &msg).unwrap();
+        assert_eq!(pol.facts_stated_by("amy"), vec![make_flat_ground_atom_str("foo")]);
+    }
+    #[test]
+    fn test_effect_ord_display() {
+        let effect = |fact: &str, affector: &str| Effect { fact: make_flat_ground_atom_str(fact), affector: make_flat_ground_atom_str(affector) };
+        let effects = [effect("amy reads x", "amy"), effect("foo", "bob"), effect("amy reads y", "amy"), effect("foo", "amy"), effect("bob writes x", "bob")];
+
+        // The order in which they come in doesn't matter
+        let mut forward: Vec<Effect> = effects.to_vec();
+        forward.sort();
+        let mut backward: Vec<Effect> = effects.iter().rev().cloned().collect();
+        backward.sort();
+        assert_eq!(forward, backward);
+        assert_eq!(forward.iter().map(Effect::to_string).collect::<Vec<String>>(), [
+            "effect foo by amy",
+            "effect foo by bob",
+            "effect (amy reads x) by amy",
+            "effect (amy reads y) by amy",
+            "effect (bob writes x) by bob",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_payload() {
+        assert_eq!(parse_payload("amy", "amy 1", b"foo. bar if foo.").unwrap(), parse::program("foo. bar if foo.").unwrap().1);
+
+        // Invalid UTF-8 is reported with where it starts
+        let err = parse_payload("amy", "amy 1", b"foo. \xFF\xFEbar.").unwrap_err();
+        assert!(matches!(&err, SyntaxError::InvalidUtf8 { author, id, offset: 5 } if author == "amy" && id == "amy 1"));
+        assert_eq!(err.to_string(), "Payload of message \"amy 1\" by \"amy\" is not valid UTF-8 (first invalid byte at offset 5)");
+
+        // Even when only part of a character is there
+        assert!(matches!(parse_payload("amy", "amy 1", "foo. é".as_bytes().split_last().unwrap().1), Err(SyntaxError::InvalidUtf8 { offset: 5, .. })));
+
+        // Valid UTF-8 that isn't Slick names the message too
+        let err = parse_payload("amy", "amy 1", b"foo. )").unwrap_err();
+        assert!(matches!(&err, SyntaxError::Slick { author, id, .. } if author == "amy" && id == "amy 1"));
+        assert_eq!(err.to_string(), "Failed to parse message \"amy 1\" by \"amy\" as valid Slick");
+    }
+
+    #[test]
+    fn test_render_template() {
+        use crate::policy::{TemplateError, render_template};
+
+        let tmpl: &str = "(amy reads {{dataset}}) if ({{ agent }} says (amy may read {{dataset}})).";
+        let vars: HashMap<&str, &str> = HashMap::from([("agent", "st-antonius"), ("dataset", "patients")]);
+        let text: String = render_template(tmpl, &vars).unwrap();
+        assert_eq!(text, "(amy reads patients) if (st-antonius says (amy may read patients)).");
+        assert_eq!(parse::program(&text).unwrap().1, parse::program("(amy reads patients) if (st-antonius says (amy may read patients)).").unwrap().1);
+
+        assert!(matches!(render_template("foo {{bar", &vars), Err(TemplateError::UnclosedPlaceholder { pos: 4 })));
+        assert!(matches!(render_template("foo {{bar}}.", &vars), Err(TemplateError::UnknownPlaceholder { pos: 4, name }) if name == "bar"));
     }
 }
@@ -12,15 +12,24 @@
 //!   Defines the main runtime regarding the JustAct policy engine.
 //
 
+use std::collections::HashMap;
 use std::error;
 use std::fmt::Debug;
 use std::hash::Hash;
+#[cfg(feature = "serde")]
+use std::io::Write;
+use std::sync::{Arc, Mutex};
 use std::task::Poll;
 
+use ::justact::collections::set::InfallibleSet as _;
 #[cfg(feature = "log")]
 use log::debug;
 use thiserror::Error;
 
+use crate::auditing::{Event, EventControl};
+use crate::io::{EventHandler, Registry, emit, with_scoped_event_handler, with_scoped_registry};
+#[cfg(feature = "serde")]
+use crate::io::{JsonLinesEventHandler, register_event_handler};
 use crate::policy::{PolicyReflect, PolicySerialize};
 use crate::sets::{Actions, Agreements, Statements};
 
@@ -46,6 +55,133 @@ pub enum Error {
         #[source]
         err: Box<dyn 'static + Send + error::Error>,
     },
+    #[error("Failed to emit an injected event.")]
+    Inject {
+        #[source]
+        err: Box<dyn 'static + Send + error::Error>,
+    },
+    #[error("Every remaining agent waits at a barrier that can never be passed: {}", render_deadlock(.waiting))]
+    Deadlock { waiting: Vec<(String, String, Vec<String>)> },
+    #[error("Event {index} differs from the expected trace (first difference at character {column})\n  expected: {expected}\n       got: {got}")]
+    Mismatch { index: usize, column: usize, expected: String, got: String },
+}
+
+
+
+
+
+/***** HELPER FUNCTIONS *****/
+/// Renders the agents of an [`Error::Deadlock`] and what they're waiting for.
+///
+/// # Arguments
+/// - `waiting`: Every blocked agent, the barrier it's waiting at and who that barrier waits for.
+///
+/// # Returns
+/// A [`String`] like `amy at "meet" (waiting for ["bob"]), bob at ...`.
+fn render_deadlock(waiting: &[(String, String, Vec<String>)]) -> String {
+    waiting.iter().map(|(id, name, parties)| format!("{id} at {name:?} (waiting for {parties:?})")).collect::<Vec<String>>().join(", ")
+}
+
+/// Renders an event such that it can be compared to the same event emitted by another run.
+///
+/// Message and action identifiers are drawn from process-wide counters, so they differ between
+/// runs in the same process. Hence, they are replaced by `#<n>`, where `n` is the order in which
+/// they first appeared in the trace.
+///
+/// # Arguments
+/// - `event`: The [`Event`] to render.
+/// - `ids`: The identifiers seen so far in the trace, mapped to their order. Updated with any new
+///   ones in `event`.
+///
+/// # Returns
+/// A [`String`] that is equal for equal events.
+fn canonicalize(event: &Event<str>, ids: &mut HashMap<String, usize>) -> String {
+    let found: Vec<&str> = match event {
+        Event::Control { event } => match event {
+            EventControl::SetAgreements { agrees } | EventControl::SetAgreementsWithin { agrees, .. } => {
+                agrees.iter().map(|agree| agree.human_id.as_str()).collect()
+            },
+            EventControl::RetractAgreement { author: _, id } => vec![id.as_ref()],
+            EventControl::AdvanceTime { .. } => Vec::new(),
+            EventControl::EnactAction { who: _, to: _, action } => [action.human_id.as_str(), action.basis.human_id.as_str()]
+                .into_iter()
+                .chain(action.extra.iter().map(|msg| msg.human_id.as_str()))
+                .collect(),
+            EventControl::StateMessage { who: _, to: _, msg } => vec![msg.human_id.as_str()],
+        },
+        #[cfg(feature = "dataplane")]
+        Event::Data { event } => match event {
+            crate::auditing::EventData::Read { context, .. }
+            | crate::auditing::EventData::Write { context, .. }
+            | crate::auditing::EventData::Delete { context, .. } => vec![context.as_ref()],
+        },
+    };
+
+    let mut res: String = format!("{event:?}");
+    for id in found {
+        let next: usize = ids.len();
+        let n: usize = *ids.entry(id.into()).or_insert(next);
+        res = res.replace(&format!("{id:?}"), &format!("\"#{n}\""));
+    }
+    res
+}
+
+/// Builds the [`Error::Mismatch`] for two (canonicalized) events.
+///
+/// # Arguments
+/// - `index`: The index of the events in their traces.
+/// - `expected`: The expected event, or [`None`] if the expected trace ended.
+/// - `got`: The emitted event, or [`None`] if the run ended.
+///
+/// # Returns
+/// An [`Error::Mismatch`] pointing to the first character at which they differ.
+fn mismatch(index: usize, expected: Option<&str>, got: Option<&str>) -> Error {
+    let expected: &str = expected.unwrap_or("<end of trace>");
+    let got: &str = got.unwrap_or("<end of trace>");
+    let column: usize = expected.chars().zip(got.chars()).take_while(|(e, g)| e == g).count();
+    Error::Mismatch { index, column, expected: expected.into(), got: got.into() }
+}
+
+
+
+
+
+/***** HELPERS *****/
+/// The progress of a [`ValidatingEventHandler`].
+#[derive(Debug, Default)]
+struct Validation {
+    /// The index of the next event to validate.
+    next:     usize,
+    /// The index and (canonicalized) event of the first mismatch, if any.
+    mismatch: Option<(usize, String)>,
+}
+
+/// An [`EventHandler`] that checks emitted events against an expected trace.
+///
+/// Used by [`System::run_validating()`].
+struct ValidatingEventHandler {
+    /// The expected trace, [canonicalized](canonicalize()).
+    expected: Arc<Vec<String>>,
+    /// The identifiers seen in the emitted trace so far.
+    ids:      HashMap<String, usize>,
+    /// The progress so far, shared with the caller.
+    state:    Arc<Mutex<Validation>>,
+}
+impl EventHandler for ValidatingEventHandler {
+    fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + error::Error>> {
+        let got: String = canonicalize(&trace, &mut self.ids);
+        let mut state = self.state.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+        let index: usize = state.next;
+        state.next += 1;
+        if self.expected.get(index) == Some(&got) {
+            return Ok(());
+        }
+        let err = mismatch(index, self.expected.get(index).map(String::as_str), Some(&got));
+        if state.mismatch.is_none() {
+            state.mismatch = Some((index, got));
+        }
+        Err(Box::new(err))
+    }
 }
 
 
@@ -54,13 +190,25 @@ pub enum Error {
 
 /***** LIBRARY *****/
 /// Defines the prototype runtime that will do things in-memory.
+///
+/// Agents are polled round-robin, once per cycle, followed by the synchronizer. By default, they
+/// are polled in the order in which they are given to [`System::run()`](justact::System::run()).
+/// A System created with [`System::new_seeded()`] instead polls them in an order that only
+/// depends on the seed and the agents' identifiers.
 pub struct System<P: ?Sized + ToOwned> {
     /// Defines the set of all agreements.
-    agreed:  Agreements<P>,
+    agreed:   Agreements<P>,
     /// Defines the set of all stated messages.
-    stated:  Statements<P>,
+    stated:   Statements<P>,
     /// Defines the set of all enacted actions.
-    enacted: Actions<P>,
+    enacted:  Actions<P>,
+    /// If given, fixes the order in which agents are polled.
+    seed:     Option<u64>,
+    /// The index of the agent to poll next in [`System::poll_once()`]. If it's beyond the last
+    /// agent, the synchronizer is next.
+    next:     usize,
+    /// What agents can look up about the System while it polls them (e.g., groups and barriers).
+    registry: Arc<Mutex<Registry>>,
 }
 impl<P: ?Sized + ToOwned> Default for System<P> {
     #[inline]
@@ -72,7 +220,275 @@ impl<P: ?Sized + ToOwned> System<P> {
     /// # Returns
     /// An empty System, ready to [run](Runtime::run()).
     #[inline]
-    pub fn new() -> Self { Self { agreed: Agreements::new(), stated: Statements::new(), enacted: Actions::new() } }
+    pub fn new() -> Self {
+        Self {
+            agreed:   Agreements::new(),
+            stated:   Statements::new(),
+            enacted:  Actions::new(),
+            seed:     None,
+            next:     0,
+            registry: Arc::new(Mutex::new(Registry::default())),
+        }
+    }
+
+    /// Constructor for the System that polls agents in an order determined by the given seed.
+    ///
+    /// Two systems with the same seed poll the same agents in the same order, regardless of the
+    /// order in which the agents are given (or the Rust version this is compiled with). This makes
+    /// runs reproducible, e.g., for golden-file tests of examples. Note, however, that message and
+    /// action identifiers are drawn from process-wide counters; so traces are only identical if
+    /// they are produced by fresh processes.
+    ///
+    /// # Arguments
+    /// - `seed`: The seed that determines the poll order.
+    ///
+    /// # Returns
+    /// An empty System, ready to [run](justact::System::run()).
+    #[inline]
+    pub fn new_seeded(seed: u64) -> Self { Self { seed: Some(seed), ..Self::new() } }
+
+    /// Returns the seed that determines the poll order, if any.
+    ///
+    /// # Returns
+    /// The seed given to [`System::new_seeded()`], or [`None`] if this System polls agents in the
+    /// given order.
+    #[inline]
+    pub const fn seed(&self) -> Option<u64> { self.seed }
+
+    /// Defines a group of agents that can be addressed as one.
+    ///
+    /// Agents can then state messages to all of its members with
+    /// [`AgentProgrammer::state_to_group()`](crate::agent::AgentProgrammer::state_to_group()).
+    ///
+    /// Groups only exist in this System. Every member must be an agent run by it; stating to a
+    /// group with other members fails. Defining a group that already exists replaces its members.
+    ///
+    /// # Arguments
+    /// - `name`: The name of the group.
+    /// - `members`: The identifiers of the agents in the group.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[inline]
+    pub fn define_group(self, name: impl Into<String>, members: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.registry
+            .lock()
+            .unwrap_or_else(|err| panic!("Lock poisoned: {err}"))
+            .groups
+            .insert(name.into(), members.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Pre-interns the given names for the Slick policy extractor.
+    ///
+    /// Call this at startup with the names of the agents and of any other constants that are used
+    /// often, such that extraction doesn't have to intern them anew every time. See
+    /// [`intern_all()`](crate::policy::slick::intern_all()).
+    ///
+    /// # Arguments
+    /// - `names`: The names to intern.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[cfg(feature = "slick")]
+    #[inline]
+    pub fn intern_all(self, names: &[&str]) -> Self {
+        crate::policy::slick::intern_all(names);
+        self
+    }
+
+    /// Streams the trace produced by this System to the given writer.
+    ///
+    /// Every event is serialized as a single line of JSON and written as soon as it occurs, which
+    /// means that the trace can be piped live into the inspector (e.g., `inspector -`).
+    ///
+    /// Note that this registers a [`JsonLinesEventHandler`] as the process-wide event handler.
+    /// As such, it has no effect if another handler was already registered.
+    ///
+    /// # Arguments
+    /// - `writer`: Some [`Write`]r to stream the events to.
+    ///
+    /// # Returns
+    /// Self for chaining.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn stream_to(self, writer: impl 'static + Send + Sync + Write) -> Self {
+        register_event_handler(JsonLinesEventHandler::new(writer));
+        self
+    }
+
+    /// Emits an arbitrary event to the registered event handler.
+    ///
+    /// This is meant as test scaffolding: it allows one to construct precise traces (e.g., a read
+    /// of a variable before anybody wrote it) to exercise the [audit](crate::auditing::Audit) or
+    /// the inspector without having to program agents that produce them.
+    ///
+    /// Note that this intentionally bypasses everything agents would normally go through. The
+    /// event isn't checked in any way, nor does it change the agreements, statements or actions
+    /// known to this System.
+    ///
+    /// # Arguments
+    /// - `event`: The [`Event`] to emit.
+    ///
+    /// # Errors
+    /// This function errors if the registered handler failed to handle the event.
+    ///
+    /// # Panics
+    /// This function panics if no event handler was registered.
+    #[inline]
+    pub fn inject_event(&mut self, event: Event<str>) -> Result<(), Error> { emit(event).map_err(|err| Error::Inject { err }) }
+}
+// Stepping
+impl<P: ?Sized + PolicyReflect + PolicySerialize + ToOwned> System<P>
+where
+    P: 'static,
+    P::Owned: 'static + Clone + Debug + Eq + Hash + Send + Sync,
+{
+    /// Polls the next agent (or the synchronizer) once.
+    ///
+    /// This is lower-level than [`System::run()`](justact::System::run()), which simply calls this
+    /// until everything is done. Instead, it allows embedders to drive the System one poll at a
+    /// time; e.g., to implement a "step"-button in a debugger, or to assert something about the
+    /// events emitted to the registered handler after every poll.
+    ///
+    /// The agents and synchronizer are owned by the caller, and should be given in the same state
+    /// to every call. Agents that are done are removed from `agents`, and the synchronizer is
+    /// taken from `synchronizer` when it's done.
+    ///
+    /// # Arguments
+    /// - `agents`: The agents to poll.
+    /// - `synchronizer`: The synchronizer to poll after every agent has been polled once, if it's
+    ///   not yet done.
+    ///
+    /// # Returns
+    /// [`Poll::Ready`] if all agents and the synchronizer are done, or [`Poll::Pending`] if there
+    /// is more polling to do.
+    ///
+    /// # Errors
+    /// This function errors if the polled agent or synchronizer failed, or with an
+    /// [`Error::Deadlock`] if every remaining agent waits at a barrier that can never be passed.
+    pub fn poll_once<A, Y>(&mut self, agents: &mut Vec<A>, synchronizer: &mut Option<Y>) -> Result<Poll<()>, Error>
+    where
+        A: justact::Agent<P, Id = str>,
+        Y: justact::Synchronizer<P, Id = str>,
+    {
+        // At the start of every cycle, register any non-registered agents and fix their order
+        if self.next == 0 {
+            let mut registry = self.registry.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+            for id in agents.iter().map(|agent| agent.id()).chain(synchronizer.as_ref().map(|sync| sync.id())) {
+                self.stated.register(id);
+                self.enacted.register(id);
+                registry.agents.insert(id.into());
+            }
+            drop(registry);
+            if let Some(seed) = self.seed {
+                // NOTE: We use a 64-bit FNV-1a instead of the `DefaultHasher`, as the latter's
+                //       algorithm may change between Rust releases (and with it, the order)
+                agents.sort_by_cached_key(|agent| {
+                    let hash: u64 = seed
+                        .to_le_bytes()
+                        .into_iter()
+                        .chain(agent.id().bytes())
+                        .fold(0xCBF29CE484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001B3));
+                    (hash, agent.id().to_string())
+                });
+            }
+        }
+
+        if let Some(agent) = agents.get_mut(self.next) {
+            // Run the agent
+            let agent_id: String = agent.id().into();
+            match with_scoped_registry(&self.registry, || {
+                agent.poll(justact::View {
+                    id:      agent_id.clone(),
+                    agreed:  &self.agreed,
+                    stated:  self.stated.scope(&agent_id),
+                    enacted: self.enacted.scope(&agent_id),
+                })
+            }) {
+                Ok(Poll::Ready(_)) => {
+                    #[cfg(feature = "log")]
+                    debug!("Agent {agent_id:?} is complete.");
+                    agents.remove(self.next);
+                },
+                Ok(Poll::Pending) => self.next += 1,
+                Err(err) => return Err(Error::Agent { id: agent_id, err: Box::new(err) }),
+            }
+        } else {
+            // Now run an update cycle through the synchronizer
+            self.next = 0;
+            self.registry.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}")).settled = true;
+            if let Some(sync) = synchronizer {
+                let sync_id: String = sync.id().into();
+                match with_scoped_registry(&self.registry, || {
+                    sync.poll(justact::View {
+                        id:      sync_id.clone(),
+                        agreed:  &mut self.agreed,
+                        stated:  self.stated.scope(&sync_id),
+                        enacted: self.enacted.scope(&sync_id),
+                    })
+                }) {
+                    Ok(Poll::Ready(_)) => *synchronizer = None,
+                    Ok(Poll::Pending) => {},
+                    Err(err) => return Err(Error::Synchronizer { id: sync_id, err: Box::new(err) }),
+                }
+            }
+
+            // If everybody is waiting for somebody that'll never come, we'll never be done
+            let registry = self.registry.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}"));
+            if let Some(waiting) = registry.deadlock(agents.iter().map(|agent| agent.id()), synchronizer.as_ref().map(|sync| sync.id())) {
+                return Err(Error::Deadlock { waiting });
+            }
+        }
+
+        // We're done if everything is done
+        if agents.is_empty() && synchronizer.is_none() {
+            self.next = 0;
+            Ok(Poll::Ready(()))
+        } else {
+            Ok(Poll::Pending)
+        }
+    }
+
+    /// Runs the System like [`System::run()`](justact::System::run()), but checks that it emits
+    /// exactly the given trace.
+    ///
+    /// This is useful to regression-test scenarios against a recorded trace. Message and action
+    /// identifiers are compared by the order in which they appear, as their numbers depend on what
+    /// else ran in the same process. While running, the events are only validated, not given to
+    /// the [registered](crate::io::register_event_handler()) event handler.
+    ///
+    /// # Arguments
+    /// - `agents`: The agents to run.
+    /// - `synchronizer`: The synchronizer to run.
+    /// - `expected`: The trace that the run should emit, in order.
+    ///
+    /// # Errors
+    /// This function errors with an [`Error::Mismatch`] at the first emitted event that isn't the
+    /// next expected one, including when either trace ends early. Otherwise, it errors if any of
+    /// the agents or the synchronizer failed.
+    pub fn run_validating<A, Y>(&mut self, agents: impl IntoIterator<Item = A>, synchronizer: Y, expected: &[Event<str>]) -> Result<(), Error>
+    where
+        A: justact::Agent<P, Id = str>,
+        Y: justact::Synchronizer<P, Id = str>,
+    {
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let expected: Arc<Vec<String>> = Arc::new(expected.iter().map(|event| canonicalize(event, &mut ids)).collect());
+        let state: Arc<Mutex<Validation>> = Arc::new(Mutex::new(Validation::default()));
+        let handler = ValidatingEventHandler { expected: expected.clone(), ids: HashMap::new(), state: state.clone() };
+
+        // Run the System as usual, then see what the handler found
+        let res = with_scoped_event_handler(handler, || <Self as justact::System>::run(self, agents, synchronizer));
+        let state: Validation = std::mem::take(&mut *state.lock().unwrap_or_else(|err| panic!("Lock poisoned: {err}")));
+        if let Some((index, got)) = state.mismatch {
+            return Err(mismatch(index, expected.get(index).map(String::as_str), Some(&got)));
+        }
+        res?;
+        if state.next < expected.len() {
+            return Err(mismatch(state.next, Some(&expected[state.next]), None));
+        }
+        Ok(())
+    }
 }
 impl<P: ?Sized + PolicyReflect + PolicySerialize + ToOwned> justact::System for System<P>
 where
@@ -94,60 +510,258 @@ where
     where
         A: justact::Agent<Self::Payload, Id = Self::AgentId>,
     {
-        // First, register any non-registered agents
-        self.stated.register(synchronizer.id());
-        self.enacted.register(synchronizer.id());
+        // Poll until everything is done
         let mut agents: Vec<A> = agents.into_iter().collect();
-        for agent in &agents {
-            self.stated.register(agent.id());
-            self.enacted.register(agent.id());
+        let mut synchronizer: Option<_> = Some(synchronizer);
+        self.next = 0;
+        while self.poll_once(&mut agents, &mut synchronizer)?.is_pending() {}
+
+        // OK, done!
+        Ok(())
+    }
+}
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(all(test, feature = "slick"))]
+mod tests {
+    use std::cell::RefCell;
+    use std::convert::Infallible;
+    use std::rc::Rc;
+
+    use slick::Program;
+
+    use super::*;
+    use crate::agent::Agent;
+    use crate::io::{CollectEventHandler, TracingView};
+
+    mod justact {
+        pub use ::justact::actions::ConstructableAction;
+        pub use ::justact::actors::{Agent, Synchronizer, View};
+        pub use ::justact::auxillary::Identifiable;
+        pub use ::justact::collections::set::{Set, SetAsync, SetSync};
+        pub use ::justact::messages::ConstructableMessage;
+        pub use ::justact::runtime::System;
+    }
+
+
+    /// Agent that only logs when it is polled.
+    struct Recorder {
+        id:    String,
+        polls: usize,
+        log:   Rc<RefCell<Vec<String>>>,
+    }
+    impl justact::Identifiable for Recorder {
+        type Id = str;
+
+        #[inline]
+        fn id(&self) -> &Self::Id { &self.id }
+    }
+    impl justact::Agent<Program> for Recorder {
+        type Error = Infallible;
+
+        fn poll<A, S, E, SM, SA>(&mut self, _view: justact::View<Self::Id, A, S, E>) -> Result<Poll<()>, Self::Error>
+        where
+            A: justact::Set<SM>,
+            S: justact::SetAsync<Self::Id, SM>,
+            E: justact::SetAsync<Self::Id, SA>,
+            SM: justact::ConstructableMessage<AuthorId = Self::Id, Payload = Program>,
+            SA: justact::ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            self.log.borrow_mut().push(self.id.clone());
+            self.polls -= 1;
+            Ok(if self.polls == 0 { Poll::Ready(()) } else { Poll::Pending })
         }
+    }
 
-        // Enter a loop to execute agents
-        let mut synchronizer: Option<_> = Some(synchronizer);
-        while synchronizer.is_some() || !agents.is_empty() {
-            // Go through the agents and keep the ones that want to be kept
-            agents = agents
-                .into_iter()
-                .filter_map(|mut agent| {
-                    // Run the agent
-                    let agent_id: String = agent.id().into();
-                    match agent.poll(justact::View {
-                        id:      agent_id.clone(),
-                        agreed:  &self.agreed,
-                        stated:  self.stated.scope(&agent_id),
-                        enacted: self.enacted.scope(&agent_id),
-                    }) {
-                        Ok(Poll::Ready(_)) => {
-                            #[cfg(feature = "log")]
-                            debug!("Agent {agent_id:?} is complete.");
-                            None
-                        },
-                        Ok(Poll::Pending) => Some(Ok(agent)),
-                        Err(err) => Some(Err(Error::Agent { id: agent_id, err: Box::new(err) })),
-                    }
-                })
-                .collect::<Result<Vec<A>, Error>>()?;
+    /// Synchronizer that agrees on a message for a window of time, and then advances through it.
+    struct Windowed {
+        /// The agreement to make, or [`None`] once it has been.
+        agree: Option<(Program, u64, u64)>,
+        /// The timestamps to advance to afterwards, one per poll, in reverse order.
+        times: Vec<u64>,
+    }
+    impl justact::Identifiable for Windowed {
+        type Id = str;
 
-            // Now run an update cycle through the synchronizer
-            synchronizer = if let Some(mut sync) = synchronizer.take() {
-                let sync_id: String = sync.id().into();
-                match sync.poll(justact::View {
-                    id:      sync_id.clone(),
-                    agreed:  &mut self.agreed,
-                    stated:  self.stated.scope(&sync_id),
-                    enacted: self.enacted.scope(&sync_id),
-                }) {
-                    Ok(Poll::Ready(_)) => None,
-                    Ok(Poll::Pending) => Some(sync),
-                    Err(err) => return Err(Error::Synchronizer { id: sync_id, err: Box::new(err) }),
-                }
-            } else {
-                None
-            };
+        #[inline]
+        fn id(&self) -> &Self::Id { "consortium" }
+    }
+    impl justact::Synchronizer<Program> for Windowed {
+        type Error = crate::agent::Error;
+
+        fn poll<A, S, E, SM, SA>(&mut self, mut view: justact::View<Self::Id, A, S, E>) -> Result<Poll<()>, Self::Error>
+        where
+            A: justact::SetSync<SM>,
+            S: justact::SetAsync<Self::Id, SM>,
+            E: justact::SetAsync<Self::Id, SA>,
+            SM: justact::ConstructableMessage<AuthorId = Self::Id, Payload = Program>,
+            SA: justact::ConstructableAction<ActorId = Self::Id, Message = SM>,
+        {
+            let mut view = TracingView(&mut view);
+            if let Some((msg, at, until)) = self.agree.take() {
+                view.agree_within([SM::new("consortium".into(), msg)], at, until)
+                    .map_err(crate::agent::cast)
+                    .map_err(crate::agent::Error::SetAgreed)?;
+            } else if let Some(at) = self.times.pop() {
+                view.advance_time(at).map_err(crate::agent::cast).map_err(crate::agent::Error::AdvanceTime)?;
+            }
+            Ok(if self.agree.is_none() && self.times.is_empty() { Poll::Ready(()) } else { Poll::Pending })
         }
+    }
 
-        // OK, done!
-        Ok(())
+
+    #[test]
+    fn test_system_run_validating() {
+        /// Amy says something once the consortium agreed she may.
+        fn scenario(says: &str) -> (Agent, Agent) {
+            let mut amy = Agent::new("amy".into());
+            amy.program()
+                .wait_for_truth(slick::parse::ground_atom("amy may say hello").unwrap().1)
+                .state(::justact::collections::Recipient::All, slick::parse::program(says).unwrap().1);
+            let mut consortium = Agent::new("consortium".into());
+            consortium.program().agree(slick::parse::program("amy may say hello.").unwrap().1);
+            (amy, consortium)
+        }
+
+        // Record the original
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        let (amy, consortium) = scenario("hello.");
+        with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            <System<Program> as justact::System>::run(&mut System::new(), [amy], consortium)
+        })
+        .unwrap();
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        assert_eq!(trace.len(), 2);
+
+        // Running it again is fine, despite the identifiers being different
+        let (amy, consortium) = scenario("hello.");
+        System::new().run_validating([amy], consortium, &trace).unwrap();
+
+        // But if Amy says something else, it's not
+        let (amy, consortium) = scenario("goodbye.");
+        let err = System::new().run_validating([amy], consortium, &trace).unwrap_err();
+        let Error::Mismatch { index, column, expected, got } = &err else { panic!("Expected a mismatch, got {err:?}") };
+        assert_eq!(*index, 1);
+        assert!(expected.chars().take(*column).eq(got.chars().take(*column)));
+        assert!(expected.contains("hello") && got.contains("goodbye"));
+
+        // And neither is it if the trace is longer than the run
+        let (amy, consortium) = scenario("hello.");
+        let longer: Vec<Event<str>> = trace.iter().cloned().chain(trace.iter().cloned()).collect();
+        let err = System::new().run_validating([amy], consortium, &longer).unwrap_err();
+        assert!(matches!(&err, Error::Mismatch { index: 2, got, .. } if got == "<end of trace>"));
+    }
+
+    #[test]
+    fn test_system_run_agree_within() {
+        use crate::auditing::SlickAudit;
+
+        // Amy acts on the agreement as soon as it's there, and then again every cycle
+        let may_act = slick::parse::ground_atom("amy may act").unwrap().1;
+        let mut amy = Agent::new("amy".into());
+        amy.program().enact_on_truth(may_act.clone()).enact_on_truth(may_act.clone()).enact_on_truth(may_act);
+        let consortium = Windowed { agree: Some((slick::parse::program("amy may act.").unwrap().1, 1, 3)), times: vec![3, 1] };
+
+        // Run it, which should trace the window
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            <System<Program> as justact::System>::run(&mut System::new(), [amy], consortium)
+        })
+        .unwrap();
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        assert!(trace.iter().any(|event| matches!(event, Event::Control { event: EventControl::SetAgreementsWithin { at: 1, until: 3, .. } })));
+
+        // Amy acted before, in and after the window, which the audit should see
+        let mut audit = SlickAudit::new();
+        for event in &trace {
+            audit.audit(&event.deserialize_slick().unwrap());
+        }
+        let perms: Vec<(bool, bool)> = (0..trace.len())
+            .filter_map(|i| audit.permission_of(i))
+            .map(|perm| perm.as_ref().unwrap())
+            .map(|perm| (perm.current, perm.future_basis))
+            .collect();
+        assert_eq!(perms, [(false, true), (true, false), (false, false)]);
+    }
+
+    #[test]
+    fn test_system_seeded() {
+        /// Runs the given agents in a system, returning who was polled when.
+        fn run(mut system: System<Program>, ids: &[&str]) -> Vec<String> {
+            let log: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+            let agents = ids.iter().enumerate().map(|(i, id)| Recorder { id: id.to_string(), polls: 1 + i % 2, log: log.clone() });
+            <System<Program> as justact::System>::run(&mut system, agents, Agent::new("consortium".into())).unwrap();
+            log.take()
+        }
+
+        // Without a seed, we get the given order
+        assert_eq!(run(System::new(), &["amy", "bob", "cho"]), ["amy", "bob", "cho", "bob"]);
+
+        // With one, the given order doesn't matter
+        let order = run(System::new_seeded(42), &["amy", "bob", "cho", "dan"]);
+        assert_eq!(order, ["dan", "cho", "bob", "amy", "dan", "bob"]);
+        assert_eq!(run(System::new_seeded(42), &["amy", "bob", "cho", "dan"]), order);
+        assert_eq!(run(System::new_seeded(42), &["dan", "cho", "bob", "amy"])[..4], order[..4]);
+    }
+
+    #[test]
+    fn test_system_poll_once() {
+        let log: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut agents: Vec<Recorder> = vec![
+            Recorder { id: "amy".into(), polls: 1, log: log.clone() },
+            Recorder { id: "bob".into(), polls: 2, log: log.clone() },
+        ];
+        let mut sync: Option<Agent> = Some(Agent::new("consortium".into()));
+        let mut system: System<Program> = System::new();
+
+        // Every call polls exactly one agent, or the synchronizer at the end of the cycle
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        assert_eq!(log.borrow().as_slice(), ["amy"]);
+        assert_eq!(agents.len(), 1);
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        assert_eq!(log.borrow().as_slice(), ["amy", "bob"]);
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Pending);
+        assert_eq!(log.borrow().len(), 2);
+        assert!(sync.is_none());
+        assert_eq!(system.poll_once(&mut agents, &mut sync).unwrap(), Poll::Ready(()));
+        assert_eq!(log.borrow().as_slice(), ["amy", "bob", "bob"]);
+        assert!(agents.is_empty());
+    }
+
+    #[cfg(feature = "dataplane")]
+    #[test]
+    fn test_system_inject_event() {
+        use std::borrow::Cow;
+
+        use crate::auditing::{EventData, SlickAudit};
+        use crate::dataplane::VarId;
+
+        // Hand-craft a read that happens before anybody wrote the variable
+        let x = VarId::new("amy", "data", "x");
+        let trace: Arc<Mutex<Vec<Event<'static, str>>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut system: System<Program> = System::new();
+        with_scoped_event_handler(CollectEventHandler(trace.clone()), || {
+            system.inject_event(Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } })?;
+            system.inject_event(Event::Data {
+                event: EventData::Read { who: Cow::Borrowed("bob"), id: Cow::Borrowed(&x), context: Cow::Borrowed("bob 1"), contents: None },
+            })
+        })
+        .unwrap();
+
+        // The events reach the handler as-is
+        let trace: Vec<Event<str>> = std::mem::take(&mut *trace.lock().unwrap());
+        assert_eq!(trace.len(), 2);
+        assert!(matches!(&trace[0], Event::Control { event: EventControl::AdvanceTime { timestamp: 1 } }));
+
+        // And they can be audited like any other trace
+        let mut audit = SlickAudit::new();
+        for event in &trace {
+            audit.audit(&event.deserialize_slick().unwrap());
+        }
+        assert_eq!(audit.read_source(1), None);
     }
 }
@@ -12,9 +12,10 @@
 //!   Implements the four sets.
 //
 
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{BuildHasherDefault, Hash};
 use std::sync::Arc;
 
 use thiserror::Error;
@@ -81,9 +82,16 @@ impl<P: ?Sized + ToOwned> Agented for Action<P> {
 
 /***** LIBRARY *****/
 /// A generic _asynchronous set_, which offers each agent a unique view to it.
+///
+/// Elements only appear in the views of the agents they are addressed to (and of whoever added
+/// them). As such, [iterating](justact::Set::iter()) an agent's view never yields elements that
+/// were privately sent to somebody else.
 pub struct SetAsync<E> {
     /// A map of agents to what they see.
-    views: HashMap<String, HashSet<E>>,
+    ///
+    /// NOTE: We use a fixed hasher such that agents iterate over their view in the same order
+    /// every run.
+    views: HashMap<String, HashSet<E, BuildHasherDefault<DefaultHasher>>>,
 }
 impl<E> Default for SetAsync<E> {
     #[inline]
@@ -113,7 +121,7 @@ impl<E> SetAsync<E> {
         let id: String = id.into();
         let exists: bool = self.views.contains_key(&id);
         if !exists {
-            self.views.insert(id, HashSet::new());
+            self.views.insert(id, HashSet::default());
         }
         exists
     }
@@ -199,9 +207,54 @@ impl<'s, 'i, E: 'static + Agented<AgentId = str> + Clone + Debug + Eq + Hash + S
                 Ok(())
             },
             justact::Recipient::One(id) => {
+                // NOTE: The sender always knows what they've sent, so it lands in their view too
+                if id != self.id {
+                    self.parent.views.get_mut(self.id).unwrap_or_else(|| panic!("Cannot operate view for unregistered agent {:?}", self.id)).insert(elem.clone());
+                }
                 self.parent.views.get_mut(&id).unwrap_or_else(|| panic!("Cannot operate view for unregistered agent {id:?}")).insert(elem);
                 Ok(())
             },
         }
     }
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(all(test, feature = "slick"))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use slick::{Program, parse};
+
+    use super::*;
+
+
+    #[test]
+    fn test_statements_recipient_scoped() {
+        let mut stated: Statements<Program> = Statements::new();
+        stated.register("amy");
+        stated.register("bob");
+        stated.register("cho");
+        let public = Arc::new(Message { human_id: "amy 1".into(), author_id: "amy".into(), payload: parse::program("foo.").unwrap().1, meta: BTreeMap::new() });
+        let secret = Arc::new(Message { human_id: "amy 2".into(), author_id: "amy".into(), payload: parse::program("bar.").unwrap().1, meta: BTreeMap::new() });
+        justact::SetAsync::add(&mut stated.scope("amy"), justact::Recipient::All, public.clone()).unwrap();
+        justact::SetAsync::add(&mut stated.scope("amy"), justact::Recipient::One("bob".into()), secret.clone()).unwrap();
+
+        // Everyone sees the public message, but only Amy and Bob see the secret one
+        let view_of = |stated: &mut Statements<Program>, id: &str| -> HashSet<Arc<Message<Program>>> {
+            justact::Set::iter(&stated.scope(id)).unwrap().cloned().collect()
+        };
+        assert_eq!(view_of(&mut stated, "amy"), HashSet::from([public.clone(), secret.clone()]));
+        assert_eq!(view_of(&mut stated, "bob"), HashSet::from([public.clone(), secret.clone()]));
+        assert_eq!(view_of(&mut stated, "cho"), HashSet::from([public.clone()]));
+
+        // Which also means Cho cannot gossip it
+        assert!(matches!(
+            justact::SetAsync::add(&mut stated.scope("cho"), justact::Recipient::All, secret.clone()),
+            Err(Error::IllegalStatement { .. })
+        ));
+    }
+}
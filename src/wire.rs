@@ -14,9 +14,10 @@
 //
 
 use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashSet};
 use std::convert::Infallible;
 use std::fmt::{Formatter, Result as FResult};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Mutex};
 
 use ::justact::collections::set::{InfallibleSet as _, InfallibleSetSync as _};
@@ -58,7 +59,7 @@ where
     SM: justact::Message<AuthorId = str>,
     SM::Payload: PolicySerialize,
 {
-    Arc::new(Message { human_id: msg.human_id().into(), author_id: msg.author_id().into(), payload: msg.payload().serialize() })
+    Arc::new(Message { human_id: msg.human_id().into(), author_id: msg.author_id().into(), payload: msg.payload().serialize(), meta: BTreeMap::new() })
 }
 
 
@@ -94,8 +95,10 @@ where
 
 /***** LIBRARY *****/
 impl_struct_with_custom_derive! {
-    #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
     /// Implements a [`Action`](justact::Action) in the prototype.
+    ///
+    /// Actions are compared and hashed by identity, i.e., by their `(actor_id, human_id)`-pair.
     pub struct Action<P: ?Sized + ToOwned> {
         /// SECRET: An identifier for legibility.
         pub human_id: String,
@@ -107,6 +110,49 @@ impl_struct_with_custom_derive! {
         pub extra: justact::MessageSet<Arc<Message<P>>>,
     }
 }
+// Identity
+impl<P: ?Sized + ToOwned> Eq for Action<P> {}
+impl<P: ?Sized + ToOwned> Hash for Action<P> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.actor_id.hash(state);
+        self.human_id.hash(state);
+    }
+}
+impl<P: ?Sized + ToOwned> PartialEq for Action<P> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.actor_id == other.actor_id && self.human_id == other.human_id }
+}
+// Constructors
+impl<P: ?Sized + ToOwned> Action<P>
+where
+    P::Owned: Eq + Hash,
+{
+    /// Creates a new Action with a chosen identifier.
+    ///
+    /// Unlike [`ConstructableAction::new()`](justact::ConstructableAction::new()), this does not
+    /// generate the identifier, which makes it useful to fabricate actions outside of a running
+    /// system (e.g., to audit them directly).
+    ///
+    /// # Arguments
+    /// - `id`: The (human) identifier of the action.
+    /// - `actor`: The identifier of the agent enacting it.
+    /// - `basis`: The agreement that the action is based on.
+    /// - `justification`: The messages justifying the action. Note that, like in actions enacted
+    ///   by agents, this should include the `basis`.
+    ///
+    /// # Returns
+    /// A new Action.
+    #[inline]
+    pub fn new_raw(
+        id: impl Into<String>,
+        actor: impl Into<String>,
+        basis: Arc<Message<P>>,
+        justification: impl IntoIterator<Item = Arc<Message<P>>>,
+    ) -> Self {
+        Self { human_id: id.into(), actor_id: actor.into(), basis, extra: justification.into_iter().collect() }
+    }
+}
 // Data management
 impl<P: ?Sized + PolicySerialize + ToOwned> Action<P>
 where
@@ -139,21 +185,69 @@ impl Action<str> {
     /// This function can fail if the action contents were not valid for the chosen `P`olicy type.
     #[inline]
     pub fn deserialize<'a, P: ?Sized + PolicyDeserialize<'a> + ToOwned>(&'a self) -> Result<Action<P>, P::Error>
+    where
+        P::Owned: Eq + Hash,
+    {
+        self.try_map_payloads(&mut |msg: &'a Message<str>| P::deserialize(&msg.payload))
+    }
+
+    /// Returns an action of which the payloads of all messages are parsed by the given function.
+    ///
+    /// # Arguments
+    /// - `parse`: Parses the payload of a single message, given the whole message for context.
+    ///
+    /// # Returns
+    /// A new Action, but then one over `P` instead of [`str`]ings.
+    ///
+    /// # Errors
+    /// This function errors if `parse` failed for any of the messages.
+    pub(crate) fn try_map_payloads<'a, P: ?Sized + ToOwned, E>(
+        &'a self,
+        parse: &mut impl FnMut(&'a Message<str>) -> Result<P::Owned, E>,
+    ) -> Result<Action<P>, E>
     where
         P::Owned: Eq + Hash,
     {
         Ok(Action {
             human_id: self.human_id.clone(),
             actor_id: self.actor_id.clone(),
-            basis:    Arc::new(self.basis.deserialize()?),
+            basis:    Arc::new(self.basis.try_map_payload(&mut *parse)?),
             extra:    self
                 .extra
                 .iter()
-                .map(|m| Ok(Arc::new(m.deserialize()?)))
-                .collect::<Result<justact::MessageSet<Arc<Message<P>>>, P::Error>>()?,
+                .map(|m| Ok(Arc::new(m.try_map_payload(&mut *parse)?)))
+                .collect::<Result<justact::MessageSet<Arc<Message<P>>>, E>>()?,
         })
     }
 }
+// Inspection
+impl<P: ?Sized + ToOwned> Action<P>
+where
+    P::Owned: Eq + Hash,
+{
+    /// Returns the authors of all messages in this action's justification.
+    ///
+    /// This includes the author of the basis as well as the authors of the extra messages.
+    ///
+    /// # Returns
+    /// A [`HashSet`] with the identifiers of every agent that contributed to the justification.
+    #[inline]
+    pub fn authors(&self) -> HashSet<&str> {
+        let mut res: HashSet<&str> = HashSet::from([self.basis.author_id.as_str()]);
+        res.extend(self.extra.iter().map(|msg| msg.author_id.as_str()));
+        res
+    }
+
+    /// Checks whether this action is justified solely by messages its actor authored.
+    ///
+    /// Such actions are unilateral, in that no other agent contributed to their justification.
+    ///
+    /// # Returns
+    /// True if every message in the justification was authored by [`Action::actor_id`], or false
+    /// otherwise.
+    #[inline]
+    pub fn is_self_justified(&self) -> bool { self.authors().into_iter().all(|author| author == self.actor_id) }
+}
 // JustAct
 impl<P: ?Sized + PolicyReflect + ToOwned> justact::ConstructableAction for Action<P>
 where
@@ -191,7 +285,7 @@ where
         for msg in self.extra.iter() {
             res.add(msg.clone());
         }
-        res.add(Arc::new(Message { human_id: "TEMP".into(), author_id: self.actor_id.clone(), payload: P::reflect_actor(&self.actor_id) }));
+        res.add(Arc::new(Message { human_id: "TEMP".into(), author_id: self.actor_id.clone(), payload: P::reflect_actor(&self.actor_id), meta: BTreeMap::new() }));
         res
     }
 
@@ -208,8 +302,12 @@ impl<P: ?Sized + ToOwned> justact::Actored for Action<P> {
 
 
 impl_struct_with_custom_derive! {
-    #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+    #[derive(Clone, Debug, Deserialize, Serialize)]
     /// Implements a [`Message`](justact::Message) in the prototype.
+    ///
+    /// Messages are compared and hashed by identity, i.e., by their `(author_id, human_id)`-pair.
+    /// As such, two messages with the same payload but a different identifier are considered
+    /// different, whereas sets of messages will only ever keep one message per identifier.
     pub struct Message<P: ?Sized + ToOwned> {
         /// SECRET: An identifier for legibility.
         pub human_id: String,
@@ -217,6 +315,42 @@ impl_struct_with_custom_derive! {
         pub author_id:  String,
         /// The payload of the message.
         pub payload: P::Owned,
+        /// Out-of-band context about the message (e.g., `("purpose", "consent form")`). This is
+        /// never part of the policy, and only there to be read by humans.
+        pub meta: BTreeMap<String, String> = BTreeMap::new(),
+    }
+}
+// Identity
+impl<P: ?Sized + ToOwned> Eq for Message<P> {}
+impl<P: ?Sized + ToOwned> Hash for Message<P> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.author_id.hash(state);
+        self.human_id.hash(state);
+    }
+}
+impl<P: ?Sized + ToOwned> PartialEq for Message<P> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.author_id == other.author_id && self.human_id == other.human_id }
+}
+// Constructors
+impl<P: ?Sized + ToOwned> Message<P> {
+    /// Creates a new Message with a chosen identifier and no metadata.
+    ///
+    /// Unlike [`ConstructableMessage::new()`](justact::ConstructableMessage::new()), this does
+    /// not generate the identifier, which makes it useful to fabricate messages outside of a
+    /// running system (e.g., to audit them directly).
+    ///
+    /// # Arguments
+    /// - `id`: The (human) identifier of the message.
+    /// - `author`: The identifier of the agent authoring it.
+    /// - `payload`: The policy carried by the message.
+    ///
+    /// # Returns
+    /// A new Message.
+    #[inline]
+    pub fn new_raw(id: impl Into<String>, author: impl Into<String>, payload: P::Owned) -> Self {
+        Self { human_id: id.into(), author_id: author.into(), payload, meta: BTreeMap::new() }
     }
 }
 // Data management
@@ -227,7 +361,7 @@ impl<P: ?Sized + PolicySerialize + ToOwned> Message<P> {
     /// A new Message, but then one over [`str`]ings instead of `P`.
     #[inline]
     pub fn serialize(&self) -> Message<str> {
-        Message { human_id: self.human_id.clone(), author_id: self.author_id.clone(), payload: self.payload.borrow().serialize() }
+        Message { human_id: self.human_id.clone(), author_id: self.author_id.clone(), payload: self.payload.borrow().serialize(), meta: self.meta.clone() }
     }
 }
 impl Message<str> {
@@ -243,7 +377,55 @@ impl Message<str> {
     /// This function can fail if the message contents were not valid for the chosen `P`olicy type.
     #[inline]
     pub fn deserialize<'a, P: ?Sized + PolicyDeserialize<'a> + ToOwned>(&'a self) -> Result<Message<P>, P::Error> {
-        Ok(Message { human_id: self.human_id.clone(), author_id: self.author_id.clone(), payload: P::deserialize(&self.payload)? })
+        self.try_map_payload(|msg| P::deserialize(&msg.payload))
+    }
+
+    /// Returns a message of which the payload is parsed by the given function.
+    ///
+    /// # Arguments
+    /// - `parse`: Parses the payload, given the whole message for context (e.g., to report
+    ///   errors with its identifier).
+    ///
+    /// # Returns
+    /// A new Message, but then one over `P` instead of [`str`]ings.
+    ///
+    /// # Errors
+    /// This function errors if `parse` failed.
+    #[inline]
+    pub(crate) fn try_map_payload<'a, P: ?Sized + ToOwned, E>(&'a self, parse: impl FnOnce(&'a Self) -> Result<P::Owned, E>) -> Result<Message<P>, E> {
+        Ok(Message { human_id: self.human_id.clone(), author_id: self.author_id.clone(), payload: parse(self)?, meta: self.meta.clone() })
+    }
+
+
+
+    /// Returns the lines of the (serialized) payload of this message.
+    ///
+    /// # Returns
+    /// An iterator over the lines in the payload, without their line endings.
+    #[inline]
+    pub fn payload_lines(&self) -> impl Iterator<Item = &str> { self.payload.lines() }
+
+    /// Summarizes the payload of this message on a single line.
+    ///
+    /// Every line in the payload is trimmed, and then all non-empty ones are joined with spaces.
+    /// If the result exceeds `max` characters, it is truncated and ends in `...`.
+    ///
+    /// # Arguments
+    /// - `max`: The maximum number of characters in the summary.
+    ///
+    /// # Returns
+    /// A [`String`] with at most `max` characters.
+    pub fn summary(&self, max: usize) -> String {
+        let full: String = self.payload_lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<&str>>().join(" ");
+        if full.chars().count() <= max {
+            return full;
+        }
+        if max < 3 {
+            return full.chars().take(max).collect();
+        }
+        let mut res: String = full.chars().take(max - 3).collect();
+        res.push_str("...");
+        res
     }
 }
 // JustAct
@@ -268,7 +450,7 @@ where
             *lock += 1;
             value
         };
-        Self { human_id: format!("{author_id} {value}"), author_id: author_id.to_owned(), payload: payload.to_owned() }
+        Self { human_id: format!("{author_id} {value}"), author_id: author_id.to_owned(), payload: payload.to_owned(), meta: BTreeMap::new() }
     }
 }
 impl<P: ?Sized + ToOwned> justact::Message for Message<P>
@@ -303,3 +485,98 @@ where
     #[inline]
     fn len(&self) -> Result<usize, Self::Error> { Ok(1) }
 }
+
+
+
+
+
+/***** TESTS *****/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+
+    /// Creates a new [`Message`] with some dummy payload.
+    fn make_message(human_id: &str, author_id: &str) -> Arc<Message<str>> {
+        Arc::new(Message { human_id: human_id.into(), author_id: author_id.into(), payload: String::new(), meta: BTreeMap::new() })
+    }
+
+    /// Creates a new [`Action`] from the given actor and messages.
+    fn make_action(actor_id: &str, basis: Arc<Message<str>>, extra: impl IntoIterator<Item = Arc<Message<str>>>) -> Action<str> {
+        Action { human_id: format!("{actor_id} 1"), actor_id: actor_id.into(), basis, extra: justact::MessageSet::from_iter(extra) }
+    }
+
+
+    #[test]
+    fn test_message_payload_lines() {
+        let msg = Message::<str> {
+            human_id:  "amy 1".into(),
+            author_id: "amy".into(),
+            payload:   "// Statement (amy 1).\n(amy count-patients) has output num-patients.\n\n(amy count-patients) executed.\n".into(),
+            meta:      BTreeMap::new(),
+        };
+        assert_eq!(msg.payload_lines().count(), 4);
+        assert_eq!(msg.payload_lines().nth(1), Some("(amy count-patients) has output num-patients."));
+
+        // Summaries collapse the lines and truncate
+        assert_eq!(msg.summary(usize::MAX), "// Statement (amy 1). (amy count-patients) has output num-patients. (amy count-patients) executed.");
+        assert_eq!(msg.summary(15), "// Statement...");
+        assert_eq!(msg.summary(2), "//");
+    }
+
+    #[test]
+    fn test_message_identity() {
+        let msg = |human_id: &str, author_id: &str, payload: &str| -> Message<str> {
+            Message { human_id: human_id.into(), author_id: author_id.into(), payload: payload.into(), meta: BTreeMap::new() }
+        };
+
+        // Same payload, different identifiers
+        assert_ne!(msg("amy 1", "amy", "foo."), msg("bob 1", "bob", "foo."));
+        assert_eq!(HashSet::from([msg("amy 1", "amy", "foo."), msg("bob 1", "bob", "foo.")]).len(), 2);
+        // Same identifier, whatever the payload
+        assert_eq!(msg("amy 1", "amy", "foo."), msg("amy 1", "amy", "bar."));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_message_meta_serde() {
+        let msg = Message::<str> {
+            human_id:  "amy 1".into(),
+            author_id: "amy".into(),
+            payload:   "foo.".into(),
+            meta:      BTreeMap::from([("purpose".into(), "consent form".into())]),
+        };
+        let raw: String = serde_json::to_string(&msg).unwrap();
+        let back: Message<str> = serde_json::from_str(&raw).unwrap();
+        assert_eq!(back.meta, msg.meta);
+
+        // Traces from before metadata existed are still understood
+        let old: Message<str> = serde_json::from_str(r#"{ "human_id": "amy 1", "author_id": "amy", "payload": "foo." }"#).unwrap();
+        assert!(old.meta.is_empty());
+    }
+
+    #[test]
+    fn test_action_authors() {
+        let act = make_action("amy", make_message("consortium 1", "consortium"), []);
+        assert_eq!(act.authors(), HashSet::from(["consortium"]));
+
+        let act = make_action("amy", make_message("consortium 1", "consortium"), [make_message("amy 1", "amy"), make_message("bob 1", "bob")]);
+        assert_eq!(act.authors(), HashSet::from(["consortium", "amy", "bob"]));
+
+        let act = make_action("amy", make_message("amy 1", "amy"), [make_message("amy 2", "amy")]);
+        assert_eq!(act.authors(), HashSet::from(["amy"]));
+    }
+
+    #[test]
+    fn test_action_is_self_justified() {
+        // Single authorship
+        assert!(make_action("amy", make_message("amy 1", "amy"), []).is_self_justified());
+        assert!(make_action("amy", make_message("amy 1", "amy"), [make_message("amy 2", "amy"), make_message("amy 3", "amy")]).is_self_justified());
+        assert!(!make_action("amy", make_message("bob 1", "bob"), []).is_self_justified());
+        assert!(!make_action("amy", make_message("bob 1", "bob"), [make_message("bob 2", "bob")]).is_self_justified());
+
+        // Mixed authorship
+        assert!(!make_action("amy", make_message("amy 1", "amy"), [make_message("bob 1", "bob")]).is_self_justified());
+        assert!(!make_action("amy", make_message("consortium 1", "consortium"), [make_message("amy 1", "amy")]).is_self_justified());
+    }
+}
@@ -0,0 +1,103 @@
+//  MOD.rs
+//    by Lut99
+//
+//  Description:
+//!   Defines helpers shared by the regression tests that run the examples of section 6 of the
+//!   JustAct paper \[1\].
+//
+
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use justact::collections::Recipient;
+use justact_prototype::auditing::{Event, EventControl, SlickAudit};
+use justact_prototype::io::{EventHandler, register_event_handler};
+use slick::Program;
+
+
+/***** HELPERS *****/
+/// An [`EventHandler`] that simply collects all events for later examination.
+pub struct CollectingEventHandler(Arc<Mutex<Vec<Event<'static, str>>>>);
+impl EventHandler for CollectingEventHandler {
+    #[inline]
+    fn handle(&mut self, trace: Event<str>) -> Result<(), Box<dyn 'static + Send + Error>> {
+        self.0.lock().unwrap().push(trace.into_owned());
+        Ok(())
+    }
+}
+
+
+
+/// Summarizes the control events in a trace in a way that is easy to compare.
+///
+/// # Arguments
+/// - `events`: The trace to summarize.
+///
+/// # Returns
+/// One line per [`EventControl`] in the trace, listing who did what with which message or action
+/// and, for actions, whether a [`SlickAudit`] permits them.
+pub fn summarize(events: &[Event<'static, str>]) -> Vec<String> {
+    let mut audit = SlickAudit::new();
+    let mut res = Vec::with_capacity(events.len());
+    for (i, event) in events.iter().enumerate() {
+        audit.audit(&event.deserialize::<Program>().unwrap());
+        if let Event::Control { event } = event {
+            res.push(match event {
+                EventControl::SetAgreements { agrees } => {
+                    format!("agree {}", agrees.iter().map(|msg| format!("{:?}", msg.human_id)).collect::<Vec<String>>().join(", "))
+                },
+                EventControl::SetAgreementsWithin { agrees, at, until } => format!(
+                    "agree {} within [{at}, {until})",
+                    agrees.iter().map(|msg| format!("{:?}", msg.human_id)).collect::<Vec<String>>().join(", ")
+                ),
+                EventControl::RetractAgreement { author: _, id } => format!("retract {id:?}"),
+                EventControl::AdvanceTime { timestamp } => format!("advance time to {timestamp}"),
+                EventControl::StateMessage { who, to, msg } => format!("{who} states {:?} to {}", msg.human_id, match to {
+                    Recipient::All => "all",
+                    Recipient::One(to) => to.as_ref(),
+                }),
+                EventControl::EnactAction { who, to: _, action } => {
+                    format!("{who} enacts {:?} ({})", action.human_id, if audit.outcome_of(i).is_permitted() { "permitted" } else { "rejected" })
+                },
+            });
+        }
+    }
+    res
+}
+
+
+
+/// Registers a [`CollectingEventHandler`] as the process-wide event handler.
+///
+/// # Returns
+/// The list of events that it collects.
+pub fn collect_events() -> Arc<Mutex<Vec<Event<'static, str>>>> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    register_event_handler(CollectingEventHandler(events.clone()));
+    events
+}
+
+
+
+/// Summarizes only the audit outcome of the actions in a trace.
+///
+/// Unlike [`summarize()`], this doesn't depend on the order in which agents happen to be polled or
+/// on the identifiers they are given.
+///
+/// # Arguments
+/// - `events`: The trace to summarize.
+///
+/// # Returns
+/// One line per enacted action, saying who enacted it and whether a [`SlickAudit`] permits it,
+/// sorted alphabetically.
+pub fn outcomes(events: &[Event<'static, str>]) -> Vec<String> {
+    let mut res: Vec<String> = summarize(events)
+        .into_iter()
+        .filter_map(|line| {
+            let (who, rest) = line.split_once(" enacts ")?;
+            Some(format!("{who} enacts ({}", rest.rsplit_once(" (")?.1))
+        })
+        .collect();
+    res.sort();
+    res
+}
@@ -0,0 +1,61 @@
+//  SECTION 6.rs
+//    by Lut99
+//
+//  Description:
+//!   Runs the examples of section 6 of the JustAct paper \[1\] end-to-end and compares the
+//!   resulting trace with what we expect.
+//!
+//!   Note that, because the event handler is global and message IDs are generated from global
+//!   counters, every scenario has to live in its own test binary.
+//
+
+mod common;
+#[path = "../examples/section6/helpers.rs"]
+mod helpers;
+#[path = "../examples/section6/scenarios.rs"]
+mod scenarios;
+
+use justact::runtime::System as _;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::StoreHandle;
+use justact_prototype::runtime::System;
+
+
+/***** TESTS *****/
+#[test]
+fn test_section6_3_1() {
+    let events = common::collect_events();
+
+    // Run the scenario to completion
+    let dataplane = StoreHandle::new();
+    let (agents, sync) = scenarios::section6_3_1(&dataplane);
+    System::new().run::<Agent>(agents, sync).unwrap();
+
+    // Compare the trace with the golden one
+    let events = events.lock().unwrap();
+    assert_eq!(common::summarize(&events), [
+        "st-antonius states \"st-antonius 1\" to st-antonius",
+        "st-antonius states \"st-antonius 1\" to all",
+        "surf states \"surf 2\" to surf",
+        "surf states \"surf 2\" to all",
+        "agree \"consortium 3\"",
+        "st-antonius enacts \"st-antonius 1\" (permitted)",
+        "surf enacts \"surf 2\" (permitted)",
+        "amy states \"amy 4\" to amy",
+        "amy states \"amy 4\" to all",
+        "st-antonius states \"st-antonius 5\" to st-antonius",
+        "st-antonius states \"st-antonius 5\" to all",
+        "st-antonius enacts \"st-antonius 3\" (permitted)",
+        "amy states \"amy 6\" to amy",
+        "amy states \"amy 6\" to all",
+        "st-antonius states \"st-antonius 7\" to st-antonius",
+        "st-antonius states \"st-antonius 7\" to all",
+        "amy enacts \"amy 4\" (permitted)",
+    ]);
+    assert_eq!(common::outcomes(&events), [
+        "amy enacts (permitted)",
+        "st-antonius enacts (permitted)",
+        "st-antonius enacts (permitted)",
+        "surf enacts (permitted)",
+    ]);
+}
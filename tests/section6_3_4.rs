@@ -0,0 +1,36 @@
+//  SECTION 6.3.4.rs
+//    by Lut99
+//
+//  Description:
+//!   Runs the fourth example of section 6.3 of the JustAct paper \[1\] end-to-end and checks
+//!   the audit outcome of the resulting trace.
+//!
+//!   See `section6.rs` for why this lives in its own test binary.
+//
+
+mod common;
+#[path = "../examples/section6/helpers.rs"]
+mod helpers;
+#[path = "../examples/section6/scenarios.rs"]
+mod scenarios;
+
+use justact::runtime::System as _;
+use justact_prototype::agent::Agent;
+use justact_prototype::dataplane::StoreHandle;
+use justact_prototype::runtime::System;
+
+
+/***** TESTS *****/
+#[test]
+fn test_section6_3_4() {
+    let events = common::collect_events();
+
+    // Run the scenario to completion
+    let dataplane = StoreHandle::new();
+    let (agents, sync) = scenarios::section6_3_4(&dataplane);
+    System::new().run::<Agent>(agents, sync).unwrap();
+
+    // Every action in it should be permitted by the audit
+    let events = events.lock().unwrap();
+    assert_eq!(common::outcomes(&events), ["st-antonius enacts (permitted)", "surf enacts (permitted)"]);
+}